@@ -52,11 +52,12 @@ use state_processing::{
     },
 };
 use std::borrow::Cow;
+use std::collections::HashMap;
 use strum::AsRefStr;
 use tree_hash::TreeHash;
 use types::{
-    Attestation, BeaconCommittee, ChainSpec, CommitteeIndex, Epoch, EthSpec, ForkName, Hash256,
-    IndexedAttestation, SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
+    Attestation, AttestationData, BeaconCommittee, ChainSpec, CommitteeIndex, Epoch, EthSpec,
+    ForkName, Hash256, IndexedAttestation, SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
 };
 
 pub use batch::{batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations};
@@ -713,9 +714,13 @@ impl<'a, T: BeaconChainTypes> VerifiedAggregatedAttestation<'a, T> {
 
 impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
     /// Run the checks that happen before an indexed attestation is constructed.
+    ///
+    /// The checks which depend only upon the `AttestationData` are skipped if they have already
+    /// passed for another attestation in `data_cache`.
     pub fn verify_early_checks(
         attestation: &Attestation<T::EthSpec>,
         chain: &BeaconChain<T>,
+        data_cache: Option<&AttestationDataCache>,
     ) -> Result<(), Error> {
         let attestation_epoch = attestation.data.slot.epoch(T::EthSpec::slots_per_epoch());
 
@@ -740,6 +745,10 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
             return Err(Error::NotExactlyOneAggregationBitSet(num_aggregation_bits));
         }
 
+        if data_cache.map_or(false, |cache| cache.contains(&attestation.data)) {
+            return Ok(());
+        }
+
         // Attestations must be for a known block. If the block is unknown, we simply drop the
         // attestation and do not delay consideration for later.
         //
@@ -812,7 +821,18 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
         subnet_id: Option<SubnetId>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, Error> {
-        Self::verify_slashable(attestation, subnet_id, chain)
+        Self::verify_with_data_cache(attestation, subnet_id, chain, None)
+    }
+
+    /// As per `Self::verify`, but sharing the results of the checks which depend only upon the
+    /// `AttestationData` with other attestations via `data_cache`.
+    pub fn verify_with_data_cache(
+        attestation: &'a Attestation<T::EthSpec>,
+        subnet_id: Option<SubnetId>,
+        chain: &BeaconChain<T>,
+        data_cache: Option<&mut AttestationDataCache>,
+    ) -> Result<Self, Error> {
+        Self::verify_slashable(attestation, subnet_id, chain, data_cache)
             .map(|verified_unaggregated| {
                 if let Some(slasher) = chain.slasher.as_ref() {
                     slasher.accept_attestation(verified_unaggregated.indexed_attestation.clone());
@@ -827,20 +847,26 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
         attestation: &'a Attestation<T::EthSpec>,
         subnet_id: Option<SubnetId>,
         chain: &BeaconChain<T>,
+        data_cache: Option<&mut AttestationDataCache>,
     ) -> Result<Self, AttestationSlashInfo<'a, T, Error>> {
         use AttestationSlashInfo::*;
 
-        if let Err(e) = Self::verify_early_checks(attestation, chain) {
+        if let Err(e) = Self::verify_early_checks(attestation, chain, data_cache.as_deref()) {
             return Err(SignatureNotChecked(attestation, e));
         }
 
-        let (indexed_attestation, committees_per_slot) =
-            match obtain_indexed_attestation_and_committees_per_slot(chain, attestation) {
-                Ok(x) => x,
-                Err(e) => {
-                    return Err(SignatureNotChecked(attestation, e));
-                }
-            };
+        let indexed_result = match data_cache {
+            Some(data_cache) => {
+                data_cache.obtain_indexed_attestation_and_committees_per_slot(chain, attestation)
+            }
+            None => obtain_indexed_attestation_and_committees_per_slot(chain, attestation),
+        };
+        let (indexed_attestation, committees_per_slot) = match indexed_result {
+            Ok(x) => x,
+            Err(e) => {
+                return Err(SignatureNotChecked(attestation, e));
+            }
+        };
 
         let (validator_index, expected_subnet_id) = match Self::verify_middle_checks(
             attestation,
@@ -1255,6 +1281,66 @@ pub fn obtain_indexed_attestation_and_committees_per_slot<T: BeaconChainTypes>(
     })
 }
 
+/// Caches the results of the checks which depend only upon the `AttestationData` of unaggregated
+/// attestations.
+///
+/// All members of a committee attest to the same `AttestationData`, so the attestations in a
+/// batch typically share a small number of distinct `AttestationData`. Sharing this cache between
+/// them means that the fork choice and committee lookups are performed once for each distinct
+/// `AttestationData`, rather than once for each attestation.
+#[derive(Default)]
+pub struct AttestationDataCache {
+    /// The committee and committee count per slot for each `AttestationData` which has passed the
+    /// early checks.
+    committees: HashMap<AttestationData, (Vec<usize>, CommitteesPerSlot)>,
+}
+
+impl AttestationDataCache {
+    /// Returns `true` if an attestation with `data` has already passed the early checks.
+    pub fn contains(&self, data: &AttestationData) -> bool {
+        self.committees.contains_key(data)
+    }
+
+    /// Returns the number of distinct `AttestationData` held in the cache.
+    pub fn len(&self) -> usize {
+        self.committees.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.committees.is_empty()
+    }
+
+    /// As per `obtain_indexed_attestation_and_committees_per_slot`, but reading the committee from
+    /// the cache if possible.
+    ///
+    /// The caller must ensure that `attestation` has passed the early checks before calling this
+    /// function, since a cached committee implies that the early checks passed.
+    fn obtain_indexed_attestation_and_committees_per_slot<T: BeaconChainTypes>(
+        &mut self,
+        chain: &BeaconChain<T>,
+        attestation: &Attestation<T::EthSpec>,
+    ) -> Result<(IndexedAttestation<T::EthSpec>, CommitteesPerSlot), Error> {
+        if let Some((committee, committees_per_slot)) = self.committees.get(&attestation.data) {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_DATA_CACHE_HITS);
+            return get_indexed_attestation(committee, attestation)
+                .map(|indexed_attestation| (indexed_attestation, *committees_per_slot))
+                .map_err(Error::Invalid);
+        }
+
+        metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_DATA_CACHE_MISSES);
+        let (committee, committees_per_slot) =
+            map_attestation_committee(chain, attestation, |(committee, committees_per_slot)| {
+                Ok((committee.committee.to_vec(), committees_per_slot))
+            })?;
+        let indexed_attestation =
+            get_indexed_attestation(&committee, attestation).map_err(Error::Invalid)?;
+        self.committees
+            .insert(attestation.data.clone(), (committee, committees_per_slot));
+
+        Ok((indexed_attestation, committees_per_slot))
+    }
+}
+
 /// Runs the `map_fn` with the committee and committee count per slot for the given `attestation`.
 ///
 /// This function exists in this odd "map" pattern because efficiently obtaining the committee for
@@ -10,8 +10,8 @@
 //! supplied as input. Each result provides the exact success or failure result of the corresponding
 //! attestation, with no loss of fidelity when compared to individual verification.
 use super::{
-    CheckAttestationSignature, Error, IndexedAggregatedAttestation, IndexedUnaggregatedAttestation,
-    VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
+    AttestationDataCache, CheckAttestationSignature, Error, IndexedAggregatedAttestation,
+    IndexedUnaggregatedAttestation, VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
 };
 use crate::{
     beacon_chain::VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT, metrics, BeaconChain, BeaconChainError,
@@ -19,10 +19,12 @@ use crate::{
 };
 use bls::verify_signature_sets;
 use state_processing::signature_sets::{
-    indexed_attestation_signature_set_from_pubkeys, signed_aggregate_selection_proof_signature_set,
-    signed_aggregate_signature_set,
+    indexed_attestation_signature_set_from_pubkeys,
+    indexed_attestation_signature_set_from_pubkeys_and_message,
+    signed_aggregate_selection_proof_signature_set, signed_aggregate_signature_set,
 };
 use std::borrow::Cow;
+use std::collections::HashMap;
 use types::*;
 
 /// Verify aggregated attestations using batch BLS signature verification.
@@ -148,10 +150,19 @@ where
     let mut num_partially_verified = 0;
     let mut num_failed = 0;
 
+    // Attestations with identical `AttestationData` (e.g., from members of the same committee)
+    // share the results of their fork choice and committee lookups.
+    let mut data_cache = AttestationDataCache::default();
+
     // Perform partial verification of all attestations, collecting the results.
     let partial_results = attestations
         .map(|(attn, subnet_opt)| {
-            let result = IndexedUnaggregatedAttestation::verify(attn, subnet_opt, chain);
+            let result = IndexedUnaggregatedAttestation::verify_with_data_cache(
+                attn,
+                subnet_opt,
+                chain,
+                Some(&mut data_cache),
+            );
             if result.is_ok() {
                 num_partially_verified += 1;
             } else {
@@ -176,21 +187,33 @@ where
             .ok_or(BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
 
         let mut signature_sets = Vec::with_capacity(num_partially_verified);
+        // The signing root is shared by all attestations with the same `AttestationData`, so only
+        // compute it once for each.
+        let mut signing_roots = HashMap::with_capacity(data_cache.len());
 
         // Iterate, flattening to get only the `Ok` values.
         for partially_verified in partial_results.iter().flatten() {
             let indexed_attestation = &partially_verified.indexed_attestation;
-            let fork = chain
-                .spec
-                .fork_at_epoch(indexed_attestation.data.target.epoch);
-
-            let signature_set = indexed_attestation_signature_set_from_pubkeys(
+            let message = *signing_roots
+                .entry(&indexed_attestation.data)
+                .or_insert_with(|| {
+                    let fork = chain
+                        .spec
+                        .fork_at_epoch(indexed_attestation.data.target.epoch);
+                    let domain = chain.spec.get_domain(
+                        indexed_attestation.data.target.epoch,
+                        Domain::BeaconAttester,
+                        &fork,
+                        chain.genesis_validators_root,
+                    );
+                    indexed_attestation.data.signing_root(domain)
+                });
+
+            let signature_set = indexed_attestation_signature_set_from_pubkeys_and_message(
                 |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
                 &indexed_attestation.signature,
                 indexed_attestation,
-                &fork,
-                chain.genesis_validators_root,
-                &chain.spec,
+                message,
             )
             .map_err(BeaconChainError::SignatureSetError)?;
 
@@ -198,6 +221,10 @@ where
         }
 
         metrics::stop_timer(signature_setup_timer);
+        metrics::observe(
+            &metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_DISTINCT_DATA,
+            signing_roots.len() as f64,
+        );
 
         let _signature_verification_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_TIMES);
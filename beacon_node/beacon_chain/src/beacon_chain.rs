@@ -8,6 +8,7 @@ use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckCaches};
 use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::blob_verification::{GossipBlobError, GossipVerifiedBlob};
+use crate::block_simulation::{BlockSimulation, OperationCounts};
 use crate::block_times_cache::BlockTimesCache;
 use crate::block_verification::POS_PANDA_BANNER;
 use crate::block_verification::{
@@ -72,6 +73,7 @@ use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
+use eth2::lighthouse::BlockSimulationQuery;
 use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
 use execution_layer::{
     BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
@@ -85,6 +87,7 @@ use futures::channel::mpsc::Sender;
 use itertools::process_results;
 use itertools::Itertools;
 use kzg::Kzg;
+use logging::{set_block_trace_root, BLOCK_TRACE_TARGET};
 use operation_pool::{AttestationRef, OperationPool, PersistedOperationPool, ReceivedPreCapella};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DoNotReOrg, ProposerHeadError};
@@ -119,6 +122,7 @@ use store::{
 };
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio_stream::Stream;
+use tracing::{info_span, Instrument, Span};
 use tree_hash::TreeHash;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::payload::BlockProductionVersion;
@@ -337,6 +341,47 @@ struct PartialBeaconBlock<E: EthSpec> {
     bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
 }
 
+impl<E: EthSpec> PartialBeaconBlock<E> {
+    /// Count the operations that were selected for inclusion in this block.
+    fn operation_counts(&self) -> OperationCounts {
+        OperationCounts {
+            attestations: self.attestations.len(),
+            proposer_slashings: self.proposer_slashings.len(),
+            attester_slashings: self.attester_slashings.len(),
+            voluntary_exits: self.voluntary_exits.len(),
+            bls_to_execution_changes: self.bls_to_execution_changes.len(),
+            sync_aggregate_participants: self
+                .sync_aggregate
+                .as_ref()
+                .map(|aggregate| aggregate.num_set_bits()),
+        }
+    }
+
+    /// Remove the operation types requested by a block simulation `query`.
+    fn exclude_operations(&mut self, query: &BlockSimulationQuery) {
+        if query.exclude_attestations {
+            self.attestations.clear();
+        }
+        if query.exclude_proposer_slashings {
+            self.proposer_slashings.clear();
+        }
+        if query.exclude_attester_slashings {
+            self.attester_slashings.clear();
+        }
+        if query.exclude_voluntary_exits {
+            self.voluntary_exits.clear();
+        }
+        if query.exclude_bls_to_execution_changes {
+            self.bls_to_execution_changes.clear();
+        }
+        if query.exclude_sync_aggregate {
+            if let Some(sync_aggregate) = self.sync_aggregate.as_mut() {
+                *sync_aggregate = SyncAggregate::new();
+            }
+        }
+    }
+}
+
 pub enum BlockProcessStatus<E: EthSpec> {
     /// Block is not in any pre-import cache. Block may be in the data-base or in the fork-choice.
     Unknown,
@@ -481,6 +526,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
     /// A cache used to produce light_client server messages
     pub light_client_server_cache: LightClientServerCache<T>,
+    /// A cache of the Merkle tree layers used to serve state proofs.
+    pub state_proof_cache: StateProofCache,
     /// Sender to signal the light_client server to produce new updates
     pub light_client_server_tx: Option<Sender<LightClientProducerEvent<T::EthSpec>>>,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
@@ -701,6 +748,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn persist_op_pool(&self) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::PERSIST_OP_POOL);
 
+        // Unaggregated attestations and sync committee messages are only held in memory by the
+        // naive aggregation pools, so move them into the op pool to ensure they are included in
+        // the persisted copy.
+        self.import_naive_aggregation_pool_to_op_pool(&self.head_snapshot().beacon_state);
+        self.import_naive_sync_aggregation_pool_to_op_pool();
+
         self.store.put_item(
             &OP_POOL_DB_KEY,
             &PersistedOperationPool::from_operation_pool(&self.op_pool),
@@ -709,6 +762,43 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Insert all attestations from the naive aggregation pool into the op pool, using `state` to
+    /// determine their attesting indices.
+    ///
+    /// Errors are logged rather than returned, since an attestation which cannot be imported
+    /// should not prevent the others from being imported.
+    pub fn import_naive_aggregation_pool_to_op_pool(&self, state: &BeaconState<T::EthSpec>) {
+        for attestation in self.naive_aggregation_pool.read().iter() {
+            let import = |attestation: &Attestation<T::EthSpec>| {
+                let attesting_indices = get_attesting_indices_from_state(state, attestation)?;
+                self.op_pool
+                    .insert_attestation(attestation.clone(), attesting_indices)
+            };
+            if let Err(e) = import(attestation) {
+                error!(
+                    self.log,
+                    "Attestation did not transfer to op pool";
+                    "reason" => ?e
+                );
+            }
+        }
+    }
+
+    /// Insert all contributions from the naive sync aggregation pool into the op pool.
+    ///
+    /// Errors are logged rather than returned, as in `import_naive_aggregation_pool_to_op_pool`.
+    pub fn import_naive_sync_aggregation_pool_to_op_pool(&self) {
+        for contribution in self.naive_sync_aggregation_pool.read().iter() {
+            if let Err(e) = self.op_pool.insert_sync_contribution(contribution.clone()) {
+                error!(
+                    self.log,
+                    "Sync contribution did not transfer to op pool";
+                    "reason" => ?e
+                );
+            }
+        }
+    }
+
     /// Persists `self.eth1_chain` and its caches to disk.
     pub fn persist_eth1_cache(&self) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::PERSIST_ETH1_CACHE);
@@ -2874,10 +2964,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
     ) -> Result<GossipVerifiedBlock<T>, BlockError<T::EthSpec>> {
         let chain = self.clone();
+        let span = info_span!(target: BLOCK_TRACE_TARGET, "verify_block_for_gossip");
         self.task_executor
             .clone()
             .spawn_blocking_handle(
                 move || {
+                    let _span_guard = span.enter();
                     let slot = block.slot();
                     let graffiti_string = block.message().body().graffiti().as_utf8_lossy();
 
@@ -3060,14 +3152,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         }
 
+        // Blocks arriving from the network are already covered by a span keyed by their root;
+        // blocks from other sources (e.g. the HTTP API) start their trace here.
+        let span = info_span!(
+            target: BLOCK_TRACE_TARGET,
+            "process_block",
+            slot = %block_slot,
+            source = %block_source
+        );
+        if !span.is_disabled() && Span::current().is_none() {
+            set_block_trace_root(&span, block_root.as_fixed_bytes());
+        }
+
         // A small closure to group the verification and import errors.
         let chain = self.clone();
         let import_block = async move {
-            let execution_pending = unverified_block.into_execution_pending_block(
-                block_root,
-                &chain,
-                notify_execution_layer,
-            )?;
+            let execution_pending = info_span!(target: BLOCK_TRACE_TARGET, "block_verification")
+                .in_scope(|| {
+                    unverified_block.into_execution_pending_block(
+                        block_root,
+                        &chain,
+                        notify_execution_layer,
+                    )
+                })?;
             publish_fn()?;
             let executed_block = chain.into_executed_block(execution_pending).await?;
             // Record the time it took to ask the execution layer.
@@ -3087,7 +3194,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     self.check_block_availability_and_import(block).await
                 }
             }
-        };
+        }
+        .instrument(span);
 
         // Verify and import the block.
         match import_block.await {
@@ -3210,9 +3318,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: AvailabilityPendingExecutedBlock<T::EthSpec>,
     ) -> Result<AvailabilityProcessingStatus, BlockError<T::EthSpec>> {
         let slot = block.block.slot();
-        let availability = self
-            .data_availability_checker
-            .put_pending_executed_block(block)?;
+        let availability = info_span!(target: BLOCK_TRACE_TARGET, "data_availability_check")
+            .in_scope(|| {
+                self.data_availability_checker
+                    .put_pending_executed_block(block)
+            })?;
         self.process_availability(slot, availability).await
     }
 
@@ -3226,7 +3336,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         if let Some(slasher) = self.slasher.as_ref() {
             slasher.accept_block_header(blob.signed_block_header());
         }
-        let availability = self.data_availability_checker.put_gossip_blob(blob)?;
+        let availability = info_span!(target: BLOCK_TRACE_TARGET, "data_availability_check")
+            .in_scope(|| self.data_availability_checker.put_gossip_blob(blob))?;
 
         self.process_availability(slot, availability).await
     }
@@ -3262,9 +3373,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }
             }
         }
-        let availability = self
-            .data_availability_checker
-            .put_rpc_blobs(block_root, blobs)?;
+        let availability = info_span!(target: BLOCK_TRACE_TARGET, "data_availability_check")
+            .in_scope(|| {
+                self.data_availability_checker
+                    .put_rpc_blobs(block_root, blobs)
+            })?;
 
         self.process_availability(slot, availability).await
     }
@@ -3319,9 +3432,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // import
         let chain = self.clone();
+        let span = info_span!(target: BLOCK_TRACE_TARGET, "import_block");
         let block_root = self
             .spawn_blocking_handle(
                 move || {
+                    let _span_guard = span.enter();
                     chain.import_block(
                         block,
                         block_root,
@@ -3427,16 +3542,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .seconds_from_current_slot_start()
                 .ok_or(Error::UnableToComputeTimeAtSlot)?;
 
-            fork_choice
-                .on_block(
-                    current_slot,
-                    block,
-                    block_root,
-                    block_delay,
-                    &state,
-                    payload_verification_status,
-                    &self.spec,
-                )
+            info_span!(target: BLOCK_TRACE_TARGET, "fork_choice_on_block")
+                .in_scope(|| {
+                    fork_choice.on_block(
+                        current_slot,
+                        block,
+                        block_root,
+                        block_delay,
+                        &state,
+                        payload_verification_status,
+                        &self.spec,
+                    )
+                })
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
         }
 
@@ -4152,7 +4269,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let (state, state_root_opt) = self
             .task_executor
             .spawn_blocking_handle(
-                move || chain.load_state_for_block_production(slot),
+                move || chain.load_state_for_block_production(slot, false),
                 "load_state_for_block_production",
             )
             .ok_or(BlockProductionError::ShuttingDown)?
@@ -4177,15 +4294,21 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Load a beacon state from the database for block production. This is a long-running process
     /// that should not be performed in an `async` context.
+    ///
+    /// If `simulate` is `true` then block production metrics are not recorded and the re-org
+    /// decision is logged at the debug level.
     fn load_state_for_block_production(
         self: &Arc<Self>,
         slot: Slot,
+        simulate: bool,
     ) -> Result<(BeaconState<T::EthSpec>, Option<Hash256>), BlockProductionError> {
-        let fork_choice_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_FORK_CHOICE_TIMES);
+        let fork_choice_timer =
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_FORK_CHOICE_TIMES, simulate);
         self.wait_for_fork_choice_before_block_production(slot)?;
         drop(fork_choice_timer);
 
-        let state_load_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES);
+        let state_load_timer =
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES, simulate);
 
         // Atomically read some values from the head whilst avoiding holding cached head `Arc` any
         // longer than necessary.
@@ -4200,14 +4323,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let (state, state_root_opt) = if head_slot < slot {
             // Attempt an aggressive re-org if configured and the conditions are right.
             if let Some((re_org_state, re_org_state_root)) =
-                self.get_state_for_re_org(slot, head_slot, head_block_root)
+                self.get_state_for_re_org(slot, head_slot, head_block_root, simulate)
             {
-                info!(
-                    self.log,
-                    "Proposing block to re-org current head";
-                    "slot" => slot,
-                    "head_to_reorg" => %head_block_root,
-                );
+                if simulate {
+                    debug!(
+                        self.log,
+                        "Simulating block to re-org current head";
+                        "slot" => slot,
+                        "head_to_reorg" => %head_block_root,
+                    );
+                } else {
+                    info!(
+                        self.log,
+                        "Proposing block to re-org current head";
+                        "slot" => slot,
+                        "head_to_reorg" => %head_block_root,
+                    );
+                }
                 (re_org_state, Some(re_org_state_root))
             } else {
                 // Fetch the head state advanced through to `slot`, which should be present in the
@@ -4220,12 +4352,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 (state, Some(state_root))
             }
         } else {
-            warn!(
-                self.log,
-                "Producing block that conflicts with head";
-                "message" => "this block is more likely to be orphaned",
-                "slot" => slot,
-            );
+            if simulate {
+                debug!(
+                    self.log,
+                    "Simulating block that conflicts with head";
+                    "slot" => slot,
+                );
+            } else {
+                warn!(
+                    self.log,
+                    "Producing block that conflicts with head";
+                    "message" => "this block is more likely to be orphaned",
+                    "slot" => slot,
+                );
+            }
             let state = self
                 .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
                 .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
@@ -4246,6 +4386,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         head_slot: Slot,
         canonical_head: Hash256,
+        simulate: bool,
     ) -> Option<(BeaconState<T::EthSpec>, Hash256)> {
         let re_org_head_threshold = self.config.re_org_head_threshold?;
         let re_org_parent_threshold = self.config.re_org_parent_threshold?;
@@ -4297,8 +4438,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         // Is the current head weak and appropriate for re-orging?
-        let proposer_head_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_GET_PROPOSER_HEAD_TIMES);
+        let proposer_head_timer = start_block_production_timer(
+            &metrics::BLOCK_PRODUCTION_GET_PROPOSER_HEAD_TIMES,
+            simulate,
+        );
         let proposer_head = self
             .canonical_head
             .fork_choice_read_lock()
@@ -4342,14 +4485,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 None
             })?;
 
-        info!(
-            self.log,
-            "Attempting re-org due to weak head";
-            "weak_head" => ?canonical_head,
-            "parent" => ?re_org_parent_block,
-            "head_weight" => proposer_head.head_node.weight,
-            "threshold_weight" => proposer_head.re_org_head_weight_threshold
-        );
+        if simulate {
+            debug!(
+                self.log,
+                "Simulating re-org due to weak head";
+                "weak_head" => ?canonical_head,
+                "parent" => ?re_org_parent_block,
+                "head_weight" => proposer_head.head_node.weight,
+                "threshold_weight" => proposer_head.re_org_head_weight_threshold
+            );
+        } else {
+            info!(
+                self.log,
+                "Attempting re-org due to weak head";
+                "weak_head" => ?canonical_head,
+                "parent" => ?re_org_parent_block,
+                "head_weight" => proposer_head.head_node.weight,
+                "threshold_weight" => proposer_head.re_org_head_weight_threshold
+            );
+        }
 
         Some((state, state_root))
     }
@@ -4736,7 +4890,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .graffiti_calculator
             .get_graffiti(validator_graffiti)
             .await;
-        let mut partial_beacon_block = self
+        let partial_beacon_block = self
             .task_executor
             .spawn_blocking_handle(
                 move || {
@@ -4748,6 +4902,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         graffiti,
                         builder_boost_factor,
                         block_production_version,
+                        false,
                     )
                 },
                 "produce_partial_beacon_block",
@@ -4755,6 +4910,91 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .ok_or(BlockProductionError::ShuttingDown)?
             .await
             .map_err(BlockProductionError::TokioJoin)??;
+
+        self.complete_block_production(partial_beacon_block, verification, false)
+            .await
+    }
+
+    /// Produce an unsigned block for `slot` without publishing or importing it, reporting the
+    /// rewards it would earn and the operations selected from the op pool.
+    ///
+    /// The block is produced upon the same state and via the same code path as a real proposal,
+    /// except that the RANDAO reveal is left empty (and unverified) and the operation types listed
+    /// in `query` are dropped prior to completing the block.
+    pub async fn simulate_block_production(
+        self: &Arc<Self>,
+        slot: Slot,
+        query: BlockSimulationQuery,
+    ) -> Result<BlockSimulation<T::EthSpec>, BlockProductionError> {
+        let chain = self.clone();
+        let (state, state_root_opt) = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || chain.load_state_for_block_production(slot, true),
+                "load_state_for_block_simulation",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+
+        let chain = self.clone();
+        let graffiti = self.graffiti_calculator.get_graffiti(query.graffiti).await;
+        let builder_boost_factor = query.builder_boost_factor;
+        let mut partial_beacon_block = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    chain.produce_partial_beacon_block(
+                        state,
+                        state_root_opt,
+                        slot,
+                        Signature::empty(),
+                        graffiti,
+                        builder_boost_factor,
+                        BlockProductionVersion::V3,
+                        true,
+                    )
+                },
+                "produce_partial_beacon_block_simulation",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+
+        let selected = partial_beacon_block.operation_counts();
+        partial_beacon_block.exclude_operations(&query);
+        // Keep a copy of the pre-state so that rewards can be computed once the block is complete.
+        let pre_state = partial_beacon_block.state.clone();
+
+        let block_response = self
+            .complete_block_production(
+                partial_beacon_block,
+                ProduceBlockVerification::NoVerification,
+                true,
+            )
+            .await?;
+
+        let chain = self.clone();
+        self.task_executor
+            .spawn_blocking_handle(
+                move || chain.compute_block_simulation(block_response, pre_state, selected),
+                "compute_block_simulation",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)?
+    }
+
+    /// Complete the production of a partial block, waiting for the execution layer to return a
+    /// payload if one is required.
+    ///
+    /// If `simulate` is `true` then block production metrics are not recorded.
+    async fn complete_block_production(
+        self: &Arc<Self>,
+        mut partial_beacon_block: PartialBeaconBlock<T::EthSpec>,
+        verification: ProduceBlockVerification,
+        simulate: bool,
+    ) -> Result<BeaconBlockResponseWrapper<T::EthSpec>, BlockProductionError> {
         // Part 2/3 (async)
         //
         // Wait for the execution layer to return an execution payload (if one is required).
@@ -4783,6 +5023,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                     partial_beacon_block,
                                     Some(block_contents),
                                     verification,
+                                    simulate,
                                 )
                             },
                             "complete_partial_beacon_block",
@@ -4803,6 +5044,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                                     partial_beacon_block,
                                     Some(block_contents),
                                     verification,
+                                    simulate,
                                 )
                             },
                             "complete_partial_beacon_block",
@@ -4824,6 +5066,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                             partial_beacon_block,
                             None,
                             verification,
+                            simulate,
                         )
                     },
                     "complete_partial_beacon_block",
@@ -4846,6 +5089,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         graffiti: Graffiti,
         builder_boost_factor: Option<u64>,
         block_production_version: BlockProductionVersion,
+        simulate: bool,
     ) -> Result<PartialBeaconBlock<T::EthSpec>, BlockProductionError> {
         let eth1_chain = self
             .eth1_chain
@@ -4860,7 +5104,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
-        let slot_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
+        let slot_timer =
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES, simulate);
 
         // Ensure the state has performed a complete transition into the required slot.
         complete_state_advance(&mut state, state_root_opt, produce_at_slot, &self.spec)?;
@@ -4931,26 +5176,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Iterate through the naive aggregation pool and ensure all the attestations from there
         // are included in the operation pool.
         let unagg_import_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_UNAGGREGATED_TIMES);
-        for attestation in self.naive_aggregation_pool.read().iter() {
-            let import = |attestation: &Attestation<T::EthSpec>| {
-                let attesting_indices = get_attesting_indices_from_state(&state, attestation)?;
-                self.op_pool
-                    .insert_attestation(attestation.clone(), attesting_indices)
-            };
-            if let Err(e) = import(attestation) {
-                // Don't stop block production if there's an error, just create a log.
-                error!(
-                    self.log,
-                    "Attestation did not transfer to op pool";
-                    "reason" => ?e
-                );
-            }
-        }
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_UNAGGREGATED_TIMES, simulate);
+        self.import_naive_aggregation_pool_to_op_pool(&state);
         drop(unagg_import_timer);
 
         let attestation_packing_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES);
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES, simulate);
 
         // Epoch cache and total balance cache are required for op pool packing.
         state.build_total_active_balance_cache(&self.spec)?;
@@ -5093,6 +5324,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         partial_beacon_block: PartialBeaconBlock<T::EthSpec>,
         block_contents: Option<BlockProposalContents<T::EthSpec, Payload>>,
         verification: ProduceBlockVerification,
+        simulate: bool,
     ) -> Result<BeaconBlockResponse<T::EthSpec, Payload>, BlockProductionError> {
         let PartialBeaconBlock {
             mut state,
@@ -5312,13 +5544,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "block_size" => block_size,
         );
 
-        metrics::observe(&metrics::BLOCK_SIZE, block_size as f64);
+        if !simulate {
+            metrics::observe(&metrics::BLOCK_SIZE, block_size as f64);
+        }
 
         if block_size > self.config.max_network_size {
             return Err(BlockProductionError::BlockTooLarge(block_size));
         }
 
-        let process_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
+        let process_timer =
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES, simulate);
         let signature_strategy = match verification {
             ProduceBlockVerification::VerifyRandao => BlockSignatureStrategy::VerifyRandao,
             ProduceBlockVerification::NoVerification => BlockSignatureStrategy::NoVerification,
@@ -5342,15 +5577,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         )?;
         drop(process_timer);
 
-        let state_root_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
+        let state_root_timer =
+            start_block_production_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES, simulate);
         let state_root = state.update_tree_hash_cache()?;
         drop(state_root_timer);
 
         let (mut block, _) = block.deconstruct();
         *block.state_root_mut() = state_root;
 
-        let blobs_verification_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_BLOBS_VERIFICATION_TIMES);
+        let blobs_verification_timer = start_block_production_timer(
+            &metrics::BLOCK_PRODUCTION_BLOBS_VERIFICATION_TIMES,
+            simulate,
+        );
         let blob_items = match maybe_blobs_and_proofs {
             Some((blobs, proofs)) => {
                 let expected_kzg_commitments =
@@ -5390,7 +5628,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         drop(blobs_verification_timer);
 
-        metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
+        if !simulate {
+            metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
+        }
 
         trace!(
             self.log,
@@ -6694,6 +6934,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     }
 }
 
+/// Start a timer for a stage of block production, unless the block is only being simulated.
+fn start_block_production_timer(
+    histogram: &metrics::Result<metrics::Histogram>,
+    simulate: bool,
+) -> Option<metrics::HistogramTimer> {
+    if simulate {
+        None
+    } else {
+        metrics::start_timer(histogram)
+    }
+}
+
 impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
     fn drop(&mut self) {
         let drop = || -> Result<(), Error> {
@@ -0,0 +1,135 @@
+use crate::{
+    BeaconBlockResponseWrapper, BeaconChain, BeaconChainError, BeaconChainTypes,
+    BlockProductionError,
+};
+use eth2::lighthouse::{
+    BlockSimulationPacking, OperationPacking, PackedAttestation, StandardBlockReward,
+    SyncAggregatePacking,
+};
+use operation_pool::RewardCache;
+use types::{AbstractExecPayload, BeaconBlock, BeaconState, EthSpec};
+
+/// The number of operations of each type selected from the op pool for a block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OperationCounts {
+    pub attestations: usize,
+    pub proposer_slashings: usize,
+    pub attester_slashings: usize,
+    pub voluntary_exits: usize,
+    pub bls_to_execution_changes: usize,
+    /// The number of participants in the sync aggregate, or `None` prior to Altair.
+    pub sync_aggregate_participants: Option<usize>,
+}
+
+/// The outcome of simulating block production via `BeaconChain::simulate_block_production`.
+pub struct BlockSimulation<E: EthSpec> {
+    /// The unsigned block, which has neither been published nor imported.
+    pub block_response: BeaconBlockResponseWrapper<E>,
+    /// The consensus rewards that the block would pay to its proposer.
+    pub reward: StandardBlockReward,
+    /// The operations available in the op pool and those which made it into the block.
+    pub packing: BlockSimulationPacking,
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Compute the rewards and packing summary for a simulated block.
+    ///
+    /// The `pre_state` must be the state which the block was produced upon, advanced to the
+    /// block's slot.
+    pub(crate) fn compute_block_simulation(
+        &self,
+        block_response: BeaconBlockResponseWrapper<T::EthSpec>,
+        mut pre_state: BeaconState<T::EthSpec>,
+        selected: OperationCounts,
+    ) -> Result<BlockSimulation<T::EthSpec>, BlockProductionError> {
+        let (reward, packing) = match &block_response {
+            BeaconBlockResponseWrapper::Full(response) => {
+                self.compute_block_simulation_packing(&response.block, &mut pre_state, selected)
+            }
+            BeaconBlockResponseWrapper::Blinded(response) => {
+                self.compute_block_simulation_packing(&response.block, &mut pre_state, selected)
+            }
+        }
+        .map_err(BlockProductionError::BeaconChain)?;
+
+        Ok(BlockSimulation {
+            block_response,
+            reward,
+            packing,
+        })
+    }
+
+    fn compute_block_simulation_packing<Payload: AbstractExecPayload<T::EthSpec>>(
+        &self,
+        block: &BeaconBlock<T::EthSpec, Payload>,
+        state: &mut BeaconState<T::EthSpec>,
+        selected: OperationCounts,
+    ) -> Result<(StandardBlockReward, BlockSimulationPacking), BeaconChainError> {
+        let block_root = block.canonical_root();
+
+        // Builds the committee and epoch caches required by `compute_block_reward`.
+        let reward = self.compute_beacon_block_reward(block.to_ref(), block_root, state)?;
+
+        let mut reward_cache = RewardCache::default();
+        let block_reward =
+            self.compute_block_reward(block.to_ref(), block_root, state, &mut reward_cache, false)?;
+
+        let body = block.body();
+        let packed_attestations = body
+            .attestations()
+            .iter()
+            .zip(block_reward.attestation_rewards.per_attestation_rewards)
+            .map(
+                |(attestation, fresh_validators_rewards)| PackedAttestation {
+                    data: attestation.data.clone(),
+                    num_attesters: attestation.aggregation_bits.num_set_bits(),
+                    num_fresh_attesters: fresh_validators_rewards.len(),
+                    reward: fresh_validators_rewards.values().sum(),
+                },
+            )
+            .collect();
+
+        let sync_aggregate = selected
+            .sync_aggregate_participants
+            .map(|selected_participants| SyncAggregatePacking {
+                selected_participants,
+                included_participants: body
+                    .sync_aggregate()
+                    .map_or(0, |sync_aggregate| sync_aggregate.num_set_bits()),
+            });
+
+        let packing = BlockSimulationPacking {
+            attestations: OperationPacking {
+                available: self.op_pool.num_attestations(),
+                selected: selected.attestations,
+                included: body.attestations().len(),
+            },
+            proposer_slashings: OperationPacking {
+                available: self.op_pool.num_proposer_slashings(),
+                selected: selected.proposer_slashings,
+                included: body.proposer_slashings().len(),
+            },
+            attester_slashings: OperationPacking {
+                available: self.op_pool.num_attester_slashings(),
+                selected: selected.attester_slashings,
+                included: body.attester_slashings().len(),
+            },
+            voluntary_exits: OperationPacking {
+                available: self.op_pool.num_voluntary_exits(),
+                selected: selected.voluntary_exits,
+                included: body.voluntary_exits().len(),
+            },
+            bls_to_execution_changes: OperationPacking {
+                available: self.op_pool.num_bls_to_execution_changes(),
+                selected: selected.bls_to_execution_changes,
+                included: body
+                    .bls_to_execution_changes()
+                    .map_or(0, |changes| changes.len()),
+            },
+            sync_aggregate,
+            packed_attestations,
+        };
+
+        Ok((reward, packing))
+    }
+}
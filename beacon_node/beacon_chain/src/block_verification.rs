@@ -70,6 +70,7 @@ use derivative::Derivative;
 use eth2::types::{EventKind, PublishBlockRequest};
 use execution_layer::PayloadStatus;
 pub use fork_choice::{AttestationFromBlock, PayloadVerificationStatus};
+use logging::BLOCK_TRACE_TARGET;
 use parking_lot::RwLockReadGuard;
 use proto_array::Block as ProtoBlock;
 use safe_arith::ArithError;
@@ -93,6 +94,7 @@ use std::io::Write;
 use std::sync::Arc;
 use store::{Error as DBError, HotStateSummary, KeyValueStore, StoreOp};
 use task_executor::JoinHandle;
+use tracing::{info_span, Instrument};
 use tree_hash::TreeHash;
 use types::{
     BeaconBlockRef, BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, ExecutionBlockHash,
@@ -1363,7 +1365,8 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
                 payload_verification_status,
                 is_valid_merge_transition_block,
             })
-        };
+        }
+        .instrument(info_span!(target: BLOCK_TRACE_TARGET, "new_payload"));
         // Spawn the payload verification future as a new task, but don't wait for it to complete.
         // The `payload_verification_future` will be awaited later to ensure verification completed
         // successfully.
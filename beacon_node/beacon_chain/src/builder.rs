@@ -30,7 +30,7 @@ use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, o, Logger};
+use slog::{crit, debug, error, info, o, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::{per_slot_processing, AllCaches};
 use std::marker::PhantomData;
@@ -949,6 +949,7 @@ where
             early_attester_cache: <_>::default(),
             reqresp_pre_import_cache: <_>::default(),
             light_client_server_cache: LightClientServerCache::new(),
+            state_proof_cache: <_>::default(),
             light_client_server_tx: self.light_client_server_tx,
             shutdown_sender: self
                 .shutdown_sender
@@ -982,6 +983,41 @@ where
             )
             .map_err(|e| format!("Failed to prime attester cache: {:?}", e))?;
 
+        // Drop any operations from the persisted op pool which became invalid whilst the node was
+        // offline (e.g., attestations which are too old to be included or exits which have
+        // already been processed).
+        let current_slot = beacon_chain
+            .slot()
+            .unwrap_or_else(|_| head.beacon_block.slot());
+        beacon_chain.op_pool.prune_all(
+            &head.beacon_block,
+            &head.beacon_state,
+            current_slot.epoch(E::slots_per_epoch()),
+            &beacon_chain.spec,
+        );
+        beacon_chain.op_pool.prune_sync_contributions(current_slot);
+
+        // Prime the reward cache with the head state, so that the first block produced after a
+        // restart doesn't have to build it.
+        if let Err(e) = beacon_chain.op_pool.update_reward_cache(&head.beacon_state) {
+            warn!(
+                log,
+                "Unable to prime reward cache";
+                "error" => ?e,
+            );
+        }
+
+        debug!(
+            log,
+            "Restored operation pool";
+            "attestations" => beacon_chain.op_pool.num_attestations(),
+            "sync_contributions" => beacon_chain.op_pool.num_sync_contributions(),
+            "attester_slashings" => beacon_chain.op_pool.num_attester_slashings(),
+            "proposer_slashings" => beacon_chain.op_pool.num_proposer_slashings(),
+            "voluntary_exits" => beacon_chain.op_pool.num_voluntary_exits(),
+            "bls_to_execution_changes" => beacon_chain.op_pool.num_bls_to_execution_changes(),
+        );
+
         // Only perform the check if it was configured.
         if let Some(wss_checkpoint) = beacon_chain.config.weak_subjectivity_checkpoint {
             if let Err(e) = beacon_chain.verify_weak_subjectivity_checkpoint(
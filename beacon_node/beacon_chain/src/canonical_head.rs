@@ -32,6 +32,7 @@
 //! stack.
 
 use crate::beacon_chain::ATTESTATION_CACHE_LOCK_TIMEOUT;
+use crate::fork_choice_snapshot::ForkChoiceSnapshot;
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::shuffling_cache::BlockShufflingIds;
 use crate::{
@@ -48,6 +49,7 @@ use fork_choice::{
     ResetPayloadStatuses,
 };
 use itertools::process_results;
+use logging::BLOCK_TRACE_TARGET;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
@@ -56,6 +58,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use store::{iter::StateRootsIterator, KeyValueStoreOp, StoreItem};
 use task_executor::{JoinHandle, ShutdownReason};
+use tracing::{info_span, Span};
 use types::*;
 
 /// Simple wrapper around `RwLock` that uses private visibility to prevent any other modules from
@@ -497,10 +500,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_TIMES);
 
+        // Only trace head recomputations triggered by the import of a traced block.
+        let span = if Span::current().is_none() {
+            Span::none()
+        } else {
+            info_span!(target: BLOCK_TRACE_TARGET, "recompute_head", slot = %current_slot)
+        };
+
         let chain = self.clone();
         match self
             .spawn_blocking_handle(
-                move || chain.recompute_head_at_slot_internal(current_slot),
+                move || {
+                    let _span_guard = span.enter();
+                    chain.recompute_head_at_slot_internal(current_slot)
+                },
                 "recompute_head_internal",
             )
             .await
@@ -576,9 +589,32 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let mut fork_choice_write_lock = self.canonical_head.fork_choice_write_lock();
 
+        // Note the head prior to recomputation so that a re-org can be detected whilst still
+        // holding the write-lock.
+        let pre_recompute_head_root = fork_choice_write_lock
+            .cached_fork_choice_view()
+            .head_block_root;
+
         // Recompute the current head via the fork choice algorithm.
         fork_choice_write_lock.get_head(current_slot, &self.spec)?;
 
+        // If the head moved to a block which does not descend from the previous head, capture
+        // fork choice before any other writer can modify it, so that the re-org can be replayed.
+        // Copying fork choice is expensive, so this is only done for re-orgs and when snapshots
+        // are enabled.
+        let fork_choice_snapshot = if self.config.fork_choice_snapshot_dir.is_some() {
+            let new_head_root = fork_choice_write_lock
+                .cached_fork_choice_view()
+                .head_block_root;
+            let is_reorg = new_head_root != pre_recompute_head_root
+                && !fork_choice_write_lock
+                    .proto_array()
+                    .is_descendant(pre_recompute_head_root, new_head_root);
+            is_reorg.then(|| ForkChoiceSnapshot::from_fork_choice(&fork_choice_write_lock))
+        } else {
+            None
+        };
+
         // Downgrade the fork choice write-lock to a read lock, without allowing access to any
         // other writers.
         let fork_choice_read_lock = RwLockWriteGuard::downgrade(fork_choice_write_lock);
@@ -727,9 +763,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // If the head changed, perform some updates.
         if new_snapshot.beacon_block_root != old_snapshot.beacon_block_root {
-            if let Err(e) =
-                self.after_new_head(&old_cached_head, &new_cached_head, new_head_proto_block)
-            {
+            if let Err(e) = self.after_new_head(
+                &old_cached_head,
+                &new_cached_head,
+                new_head_proto_block,
+                fork_choice_snapshot,
+            ) {
                 crit!(
                     self.log,
                     "Error updating canonical head";
@@ -775,6 +814,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         old_cached_head: &CachedHead<T::EthSpec>,
         new_cached_head: &CachedHead<T::EthSpec>,
         new_head_proto_block: ProtoBlock,
+        fork_choice_snapshot: Option<ForkChoiceSnapshot>,
     ) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_AFTER_NEW_HEAD_TIMES);
         let old_snapshot = &old_cached_head.snapshot;
@@ -862,6 +902,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.op_pool.prune_attestations(self.epoch()?);
         }
 
+        // Store the snapshot of fork choice so that the re-org can be replayed offline. Writing
+        // the file is slow, so it happens on a blocking task rather than delaying the head update.
+        if let (Some(_), Some(snapshot), Some(dir)) = (
+            reorg_distance,
+            fork_choice_snapshot,
+            self.config.fork_choice_snapshot_dir.clone(),
+        ) {
+            let chain = self.clone();
+            self.task_executor.spawn_blocking(
+                move || chain.write_fork_choice_snapshot(&dir, &snapshot),
+                "fork_choice_snapshot",
+            );
+        }
+
         // Register server-sent-events for a new head.
         if let Some(event_handler) = self
             .event_handler
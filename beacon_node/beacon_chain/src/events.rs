@@ -1,28 +1,142 @@
-pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+pub use eth2::types::{EventKind, EventTopic, SseBlock, SseFinalizedCheckpoint, SseHead};
+use parking_lot::Mutex;
 use slog::{trace, Logger};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
 use types::EthSpec;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 16;
 
+/// The time for which events are retained so that reconnecting clients can be sent those they
+/// missed.
+const HISTORY_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// Returns the ID of the first event issued by a new handler.
+///
+/// The ID is taken from the wall clock in microseconds so that IDs keep increasing across
+/// restarts, provided that fewer than a million events are issued per second.
+fn initial_event_id() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| u64::try_from(now.as_micros()).unwrap_or(u64::MAX))
+}
+
+/// An event along with its ID, which is unique and increases monotonically across all topics.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent<E: EthSpec> {
+    pub id: u64,
+    pub kind: EventKind<E>,
+}
+
+/// A broadcast channel for a single topic, along with a history of the most recent events so that
+/// reconnecting clients can be sent the events they missed.
+///
+/// The history is bounded by both `history_size` and `HISTORY_RETENTION`.
+struct EventChannel<E: EthSpec> {
+    tx: Sender<SseEvent<E>>,
+    history: Mutex<VecDeque<(Instant, SseEvent<E>)>>,
+    history_size: usize,
+    /// Set once the topic has been subscribed to, after which events keep being produced for the
+    /// history even whilst no client is connected.
+    retain_history: AtomicBool,
+}
+
+impl<E: EthSpec> EventChannel<E> {
+    fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            history: Mutex::new(VecDeque::with_capacity(capacity)),
+            history_size: capacity,
+            retain_history: AtomicBool::new(false),
+        }
+    }
+
+    /// Remove events which were sent more than `HISTORY_RETENTION` before `now`.
+    fn prune_history(history: &mut VecDeque<(Instant, SseEvent<E>)>, now: Instant) {
+        while history
+            .front()
+            .is_some_and(|(sent_at, _)| now.saturating_duration_since(*sent_at) > HISTORY_RETENTION)
+        {
+            history.pop_front();
+        }
+    }
+
+    fn send(
+        &self,
+        next_event_id: &AtomicU64,
+        kind: EventKind<E>,
+    ) -> Result<usize, SendError<SseEvent<E>>> {
+        // Hold the history lock whilst sending so that `subscribe_after` never observes an event
+        // both in the history and on its receiver.
+        let mut history = self.history.lock();
+        let event = SseEvent {
+            id: next_event_id.fetch_add(1, Ordering::Relaxed),
+            kind,
+        };
+        let now = Instant::now();
+        Self::prune_history(&mut history, now);
+        if self.history_size > 0 {
+            if history.len() >= self.history_size {
+                history.pop_front();
+            }
+            history.push_back((now, event.clone()));
+        }
+        self.tx.send(event)
+    }
+
+    fn subscribe(&self) -> Receiver<SseEvent<E>> {
+        self.retain_history.store(true, Ordering::Relaxed);
+        self.tx.subscribe()
+    }
+
+    fn subscribe_after(
+        &self,
+        last_event_id: u64,
+        next_event_id: &AtomicU64,
+    ) -> (Vec<SseEvent<E>>, Receiver<SseEvent<E>>) {
+        self.retain_history.store(true, Ordering::Relaxed);
+        let mut history = self.history.lock();
+        Self::prune_history(&mut history, Instant::now());
+        let is_known_id = last_event_id < next_event_id.load(Ordering::Relaxed);
+        let missed = history
+            .iter()
+            .map(|(_, event)| event)
+            .filter(|event| !is_known_id || event.id > last_event_id)
+            .cloned()
+            .collect();
+        (missed, self.tx.subscribe())
+    }
+
+    /// Returns `true` if events for this topic should be produced, either because a client is
+    /// connected or because a client may reconnect and ask for the events it missed.
+    fn has_subscribers(&self) -> bool {
+        self.tx.receiver_count() > 0
+            || (self.history_size > 0 && self.retain_history.load(Ordering::Relaxed))
+    }
+}
+
 pub struct ServerSentEventHandler<E: EthSpec> {
-    attestation_tx: Sender<EventKind<E>>,
-    block_tx: Sender<EventKind<E>>,
-    blob_sidecar_tx: Sender<EventKind<E>>,
-    finalized_tx: Sender<EventKind<E>>,
-    head_tx: Sender<EventKind<E>>,
-    exit_tx: Sender<EventKind<E>>,
-    chain_reorg_tx: Sender<EventKind<E>>,
-    contribution_tx: Sender<EventKind<E>>,
-    payload_attributes_tx: Sender<EventKind<E>>,
-    late_head: Sender<EventKind<E>>,
-    light_client_finality_update_tx: Sender<EventKind<E>>,
-    light_client_optimistic_update_tx: Sender<EventKind<E>>,
-    block_reward_tx: Sender<EventKind<E>>,
-    proposer_slashing_tx: Sender<EventKind<E>>,
-    attester_slashing_tx: Sender<EventKind<E>>,
-    bls_to_execution_change_tx: Sender<EventKind<E>>,
+    attestation_tx: EventChannel<E>,
+    block_tx: EventChannel<E>,
+    blob_sidecar_tx: EventChannel<E>,
+    finalized_tx: EventChannel<E>,
+    head_tx: EventChannel<E>,
+    exit_tx: EventChannel<E>,
+    chain_reorg_tx: EventChannel<E>,
+    contribution_tx: EventChannel<E>,
+    payload_attributes_tx: EventChannel<E>,
+    late_head: EventChannel<E>,
+    light_client_finality_update_tx: EventChannel<E>,
+    light_client_optimistic_update_tx: EventChannel<E>,
+    block_reward_tx: EventChannel<E>,
+    proposer_slashing_tx: EventChannel<E>,
+    attester_slashing_tx: EventChannel<E>,
+    bls_to_execution_change_tx: EventChannel<E>,
+    next_event_id: AtomicU64,
     log: Logger,
 }
 
@@ -35,22 +149,22 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     }
 
     pub fn new_with_capacity(log: Logger, capacity: usize) -> Self {
-        let (attestation_tx, _) = broadcast::channel(capacity);
-        let (block_tx, _) = broadcast::channel(capacity);
-        let (blob_sidecar_tx, _) = broadcast::channel(capacity);
-        let (finalized_tx, _) = broadcast::channel(capacity);
-        let (head_tx, _) = broadcast::channel(capacity);
-        let (exit_tx, _) = broadcast::channel(capacity);
-        let (chain_reorg_tx, _) = broadcast::channel(capacity);
-        let (contribution_tx, _) = broadcast::channel(capacity);
-        let (payload_attributes_tx, _) = broadcast::channel(capacity);
-        let (late_head, _) = broadcast::channel(capacity);
-        let (light_client_finality_update_tx, _) = broadcast::channel(capacity);
-        let (light_client_optimistic_update_tx, _) = broadcast::channel(capacity);
-        let (block_reward_tx, _) = broadcast::channel(capacity);
-        let (proposer_slashing_tx, _) = broadcast::channel(capacity);
-        let (attester_slashing_tx, _) = broadcast::channel(capacity);
-        let (bls_to_execution_change_tx, _) = broadcast::channel(capacity);
+        let attestation_tx = EventChannel::new(capacity);
+        let block_tx = EventChannel::new(capacity);
+        let blob_sidecar_tx = EventChannel::new(capacity);
+        let finalized_tx = EventChannel::new(capacity);
+        let head_tx = EventChannel::new(capacity);
+        let exit_tx = EventChannel::new(capacity);
+        let chain_reorg_tx = EventChannel::new(capacity);
+        let contribution_tx = EventChannel::new(capacity);
+        let payload_attributes_tx = EventChannel::new(capacity);
+        let late_head = EventChannel::new(capacity);
+        let light_client_finality_update_tx = EventChannel::new(capacity);
+        let light_client_optimistic_update_tx = EventChannel::new(capacity);
+        let block_reward_tx = EventChannel::new(capacity);
+        let proposer_slashing_tx = EventChannel::new(capacity);
+        let attester_slashing_tx = EventChannel::new(capacity);
+        let bls_to_execution_change_tx = EventChannel::new(capacity);
 
         Self {
             attestation_tx,
@@ -69,207 +183,243 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
             proposer_slashing_tx,
             attester_slashing_tx,
             bls_to_execution_change_tx,
+            next_event_id: AtomicU64::new(initial_event_id()),
             log,
         }
     }
 
     pub fn register(&self, kind: EventKind<E>) {
-        let log_count = |name, count| {
-            trace!(
+        let (channel, name) = self.channel_for_kind(&kind);
+        match channel.send(&self.next_event_id, kind) {
+            Ok(count) => trace!(
                 self.log,
                 "Registering server-sent event";
                 "kind" => name,
                 "receiver_count" => count
-            );
-        };
-        let result = match &kind {
-            EventKind::Attestation(_) => self
-                .attestation_tx
-                .send(kind)
-                .map(|count| log_count("attestation", count)),
-            EventKind::Block(_) => self
-                .block_tx
-                .send(kind)
-                .map(|count| log_count("block", count)),
-            EventKind::BlobSidecar(_) => self
-                .blob_sidecar_tx
-                .send(kind)
-                .map(|count| log_count("blob sidecar", count)),
-            EventKind::FinalizedCheckpoint(_) => self
-                .finalized_tx
-                .send(kind)
-                .map(|count| log_count("finalized checkpoint", count)),
-            EventKind::Head(_) => self
-                .head_tx
-                .send(kind)
-                .map(|count| log_count("head", count)),
-            EventKind::VoluntaryExit(_) => self
-                .exit_tx
-                .send(kind)
-                .map(|count| log_count("exit", count)),
-            EventKind::ChainReorg(_) => self
-                .chain_reorg_tx
-                .send(kind)
-                .map(|count| log_count("chain reorg", count)),
-            EventKind::ContributionAndProof(_) => self
-                .contribution_tx
-                .send(kind)
-                .map(|count| log_count("contribution and proof", count)),
-            EventKind::PayloadAttributes(_) => self
-                .payload_attributes_tx
-                .send(kind)
-                .map(|count| log_count("payload attributes", count)),
-            EventKind::LateHead(_) => self
-                .late_head
-                .send(kind)
-                .map(|count| log_count("late head", count)),
-            EventKind::LightClientFinalityUpdate(_) => self
-                .light_client_finality_update_tx
-                .send(kind)
-                .map(|count| log_count("light client finality update", count)),
-            EventKind::LightClientOptimisticUpdate(_) => self
-                .light_client_optimistic_update_tx
-                .send(kind)
-                .map(|count| log_count("light client optimistic update", count)),
-            EventKind::BlockReward(_) => self
-                .block_reward_tx
-                .send(kind)
-                .map(|count| log_count("block reward", count)),
-            EventKind::ProposerSlashing(_) => self
-                .proposer_slashing_tx
-                .send(kind)
-                .map(|count| log_count("proposer slashing", count)),
-            EventKind::AttesterSlashing(_) => self
-                .attester_slashing_tx
-                .send(kind)
-                .map(|count| log_count("attester slashing", count)),
-            EventKind::BlsToExecutionChange(_) => self
-                .bls_to_execution_change_tx
-                .send(kind)
-                .map(|count| log_count("bls to execution change", count)),
-        };
-        if let Err(SendError(event)) = result {
-            trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
+            ),
+            Err(SendError(event)) => {
+                trace!(self.log, "No receivers registered to listen for event"; "event" => ?event)
+            }
+        }
+    }
+
+    fn channel_for_kind(&self, kind: &EventKind<E>) -> (&EventChannel<E>, &'static str) {
+        match kind {
+            EventKind::Attestation(_) => (&self.attestation_tx, "attestation"),
+            EventKind::Block(_) => (&self.block_tx, "block"),
+            EventKind::BlobSidecar(_) => (&self.blob_sidecar_tx, "blob sidecar"),
+            EventKind::FinalizedCheckpoint(_) => (&self.finalized_tx, "finalized checkpoint"),
+            EventKind::Head(_) => (&self.head_tx, "head"),
+            EventKind::VoluntaryExit(_) => (&self.exit_tx, "exit"),
+            EventKind::ChainReorg(_) => (&self.chain_reorg_tx, "chain reorg"),
+            EventKind::ContributionAndProof(_) => (&self.contribution_tx, "contribution and proof"),
+            EventKind::PayloadAttributes(_) => (&self.payload_attributes_tx, "payload attributes"),
+            EventKind::LateHead(_) => (&self.late_head, "late head"),
+            EventKind::LightClientFinalityUpdate(_) => (
+                &self.light_client_finality_update_tx,
+                "light client finality update",
+            ),
+            EventKind::LightClientOptimisticUpdate(_) => (
+                &self.light_client_optimistic_update_tx,
+                "light client optimistic update",
+            ),
+            EventKind::BlockReward(_) => (&self.block_reward_tx, "block reward"),
+            EventKind::ProposerSlashing(_) => (&self.proposer_slashing_tx, "proposer slashing"),
+            EventKind::AttesterSlashing(_) => (&self.attester_slashing_tx, "attester slashing"),
+            EventKind::BlsToExecutionChange(_) => {
+                (&self.bls_to_execution_change_tx, "bls to execution change")
+            }
+        }
+    }
+
+    fn channel_for_topic(&self, topic: EventTopic) -> &EventChannel<E> {
+        match topic {
+            EventTopic::Attestation => &self.attestation_tx,
+            EventTopic::Block => &self.block_tx,
+            EventTopic::BlobSidecar => &self.blob_sidecar_tx,
+            EventTopic::FinalizedCheckpoint => &self.finalized_tx,
+            EventTopic::Head => &self.head_tx,
+            EventTopic::VoluntaryExit => &self.exit_tx,
+            EventTopic::ChainReorg => &self.chain_reorg_tx,
+            EventTopic::ContributionAndProof => &self.contribution_tx,
+            EventTopic::PayloadAttributes => &self.payload_attributes_tx,
+            EventTopic::LateHead => &self.late_head,
+            EventTopic::LightClientFinalityUpdate => &self.light_client_finality_update_tx,
+            EventTopic::LightClientOptimisticUpdate => &self.light_client_optimistic_update_tx,
+            EventTopic::BlockReward => &self.block_reward_tx,
+            EventTopic::ProposerSlashing => &self.proposer_slashing_tx,
+            EventTopic::AttesterSlashing => &self.attester_slashing_tx,
+            EventTopic::BlsToExecutionChange => &self.bls_to_execution_change_tx,
         }
     }
 
-    pub fn subscribe_attestation(&self) -> Receiver<EventKind<E>> {
+    /// Subscribe to all future events for `topic`.
+    pub fn subscribe(&self, topic: EventTopic) -> Receiver<SseEvent<E>> {
+        self.channel_for_topic(topic).subscribe()
+    }
+
+    /// Subscribe to all future events for `topic`, returning any retained events with an ID
+    /// greater than `last_event_id` which should be sent before those from the receiver.
+    ///
+    /// Since event IDs increase across restarts, an ID issued prior to a restart also results in
+    /// all retained events being returned, as does an ID which has not yet been issued.
+    pub fn subscribe_after(
+        &self,
+        topic: EventTopic,
+        last_event_id: u64,
+    ) -> (Vec<SseEvent<E>>, Receiver<SseEvent<E>>) {
+        self.channel_for_topic(topic)
+            .subscribe_after(last_event_id, &self.next_event_id)
+    }
+
+    pub fn subscribe_attestation(&self) -> Receiver<SseEvent<E>> {
         self.attestation_tx.subscribe()
     }
 
-    pub fn subscribe_block(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_block(&self) -> Receiver<SseEvent<E>> {
         self.block_tx.subscribe()
     }
 
-    pub fn subscribe_blob_sidecar(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_blob_sidecar(&self) -> Receiver<SseEvent<E>> {
         self.blob_sidecar_tx.subscribe()
     }
 
-    pub fn subscribe_finalized(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_finalized(&self) -> Receiver<SseEvent<E>> {
         self.finalized_tx.subscribe()
     }
 
-    pub fn subscribe_head(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_head(&self) -> Receiver<SseEvent<E>> {
         self.head_tx.subscribe()
     }
 
-    pub fn subscribe_exit(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_exit(&self) -> Receiver<SseEvent<E>> {
         self.exit_tx.subscribe()
     }
 
-    pub fn subscribe_reorgs(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_reorgs(&self) -> Receiver<SseEvent<E>> {
         self.chain_reorg_tx.subscribe()
     }
 
-    pub fn subscribe_contributions(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_contributions(&self) -> Receiver<SseEvent<E>> {
         self.contribution_tx.subscribe()
     }
 
-    pub fn subscribe_payload_attributes(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_payload_attributes(&self) -> Receiver<SseEvent<E>> {
         self.payload_attributes_tx.subscribe()
     }
 
-    pub fn subscribe_late_head(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_late_head(&self) -> Receiver<SseEvent<E>> {
         self.late_head.subscribe()
     }
 
-    pub fn subscribe_light_client_finality_update(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_light_client_finality_update(&self) -> Receiver<SseEvent<E>> {
         self.light_client_finality_update_tx.subscribe()
     }
 
-    pub fn subscribe_light_client_optimistic_update(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_light_client_optimistic_update(&self) -> Receiver<SseEvent<E>> {
         self.light_client_optimistic_update_tx.subscribe()
     }
 
-    pub fn subscribe_block_reward(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_block_reward(&self) -> Receiver<SseEvent<E>> {
         self.block_reward_tx.subscribe()
     }
 
-    pub fn subscribe_attester_slashing(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_attester_slashing(&self) -> Receiver<SseEvent<E>> {
         self.attester_slashing_tx.subscribe()
     }
 
-    pub fn subscribe_proposer_slashing(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_proposer_slashing(&self) -> Receiver<SseEvent<E>> {
         self.proposer_slashing_tx.subscribe()
     }
 
-    pub fn subscribe_bls_to_execution_change(&self) -> Receiver<EventKind<E>> {
+    pub fn subscribe_bls_to_execution_change(&self) -> Receiver<SseEvent<E>> {
         self.bls_to_execution_change_tx.subscribe()
     }
 
     pub fn has_attestation_subscribers(&self) -> bool {
-        self.attestation_tx.receiver_count() > 0
+        self.attestation_tx.has_subscribers()
     }
 
     pub fn has_block_subscribers(&self) -> bool {
-        self.block_tx.receiver_count() > 0
+        self.block_tx.has_subscribers()
     }
 
     pub fn has_blob_sidecar_subscribers(&self) -> bool {
-        self.blob_sidecar_tx.receiver_count() > 0
+        self.blob_sidecar_tx.has_subscribers()
     }
 
     pub fn has_finalized_subscribers(&self) -> bool {
-        self.finalized_tx.receiver_count() > 0
+        self.finalized_tx.has_subscribers()
     }
 
     pub fn has_head_subscribers(&self) -> bool {
-        self.head_tx.receiver_count() > 0
+        self.head_tx.has_subscribers()
     }
 
     pub fn has_exit_subscribers(&self) -> bool {
-        self.exit_tx.receiver_count() > 0
+        self.exit_tx.has_subscribers()
     }
 
     pub fn has_reorg_subscribers(&self) -> bool {
-        self.chain_reorg_tx.receiver_count() > 0
+        self.chain_reorg_tx.has_subscribers()
     }
 
     pub fn has_contribution_subscribers(&self) -> bool {
-        self.contribution_tx.receiver_count() > 0
+        self.contribution_tx.has_subscribers()
     }
 
     pub fn has_payload_attributes_subscribers(&self) -> bool {
-        self.payload_attributes_tx.receiver_count() > 0
+        self.payload_attributes_tx.has_subscribers()
     }
 
     pub fn has_late_head_subscribers(&self) -> bool {
-        self.late_head.receiver_count() > 0
+        self.late_head.has_subscribers()
     }
 
     pub fn has_block_reward_subscribers(&self) -> bool {
-        self.block_reward_tx.receiver_count() > 0
+        self.block_reward_tx.has_subscribers()
     }
 
     pub fn has_proposer_slashing_subscribers(&self) -> bool {
-        self.proposer_slashing_tx.receiver_count() > 0
+        self.proposer_slashing_tx.has_subscribers()
     }
 
     pub fn has_attester_slashing_subscribers(&self) -> bool {
-        self.attester_slashing_tx.receiver_count() > 0
+        self.attester_slashing_tx.has_subscribers()
     }
 
     pub fn has_bls_to_execution_change_subscribers(&self) -> bool {
-        self.bls_to_execution_change_tx.receiver_count() > 0
+        self.bls_to_execution_change_tx.has_subscribers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::types::SseBlock;
+    use types::{Hash256, MainnetEthSpec, Slot};
+
+    #[test]
+    fn history_expires_after_retention() {
+        let block_event = |slot: u64| SseEvent::<MainnetEthSpec> {
+            id: slot,
+            kind: EventKind::Block(SseBlock {
+                slot: Slot::new(slot),
+                block: Hash256::from_low_u64_be(slot),
+                execution_optimistic: false,
+            }),
+        };
+        let now = Instant::now();
+        let mut history = VecDeque::from(vec![
+            (now, block_event(1)),
+            (now + HISTORY_RETENTION, block_event(2)),
+        ]);
+
+        EventChannel::prune_history(&mut history, now + HISTORY_RETENTION);
+        assert_eq!(history.len(), 2);
+
+        EventChannel::prune_history(&mut history, now + HISTORY_RETENTION * 2);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].1, block_event(2));
     }
 }
@@ -0,0 +1,90 @@
+//! Provides a point-in-time copy of fork choice which can be written to disk and replayed offline
+//! using `lcli replay-fork-choice`.
+//!
+//! Snapshots are taken on demand via the `lighthouse/fork_choice/snapshot` HTTP endpoint, or
+//! automatically whenever a re-org is detected if `ChainConfig::fork_choice_snapshot_dir` is set.
+//! Re-org snapshots are captured under the same fork choice lock as the head recomputation which
+//! caused the re-org, so that replaying them reproduces that decision.
+use crate::beacon_chain::BeaconForkChoice;
+use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::{BeaconChain, BeaconChainTypes};
+use fork_choice::ForkChoiceStore;
+use slog::{error, info};
+use ssz::Encode;
+use ssz_derive::{Decode, Encode};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use types::{Hash256, Slot};
+
+/// The state of fork choice at the time a snapshot was taken.
+///
+/// The `persisted_fork_choice` contains the proto-array, the attestations which were queued for
+/// processing in a future slot and the fork choice store (including the justified balances).
+#[derive(Encode, Decode)]
+pub struct ForkChoiceSnapshot {
+    /// The current slot of the fork choice store.
+    pub current_slot: Slot,
+    /// The head block root most recently selected by fork choice.
+    pub head_block_root: Hash256,
+    pub persisted_fork_choice: PersistedForkChoice,
+}
+
+impl ForkChoiceSnapshot {
+    /// Take a snapshot of `fork_choice`, including its queued attestations.
+    pub fn from_fork_choice<T: BeaconChainTypes>(fork_choice: &BeaconForkChoice<T>) -> Self {
+        Self {
+            current_slot: fork_choice.fc_store().get_current_slot(),
+            head_block_root: fork_choice.cached_fork_choice_view().head_block_root,
+            persisted_fork_choice: PersistedForkChoice {
+                fork_choice: fork_choice.to_persisted(),
+                fork_choice_store: fork_choice.fc_store().to_persisted(),
+            },
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Take a snapshot of fork choice, including its queued attestations.
+    ///
+    /// Takes a read-lock on fork choice.
+    pub fn fork_choice_snapshot(&self) -> ForkChoiceSnapshot {
+        ForkChoiceSnapshot::from_fork_choice(&self.canonical_head.fork_choice_read_lock())
+    }
+
+    /// Write `snapshot` to `dir` as an SSZ file named after its slot and head block root.
+    ///
+    /// Errors are logged rather than returned, this function is intended to be used on a
+    /// best-effort basis for debugging.
+    pub fn write_fork_choice_snapshot(&self, dir: &Path, snapshot: &ForkChoiceSnapshot) {
+        let path = dir.join(format!(
+            "fork_choice_{}_{:?}.ssz",
+            snapshot.current_slot, snapshot.head_block_root
+        ));
+
+        let write_result = fs::create_dir_all(dir)
+            .and_then(|()| {
+                fs::OpenOptions::new()
+                    .create_new(true)
+                    .write(true)
+                    .open(&path)
+            })
+            .and_then(|mut file| file.write_all(&snapshot.as_ssz_bytes()));
+
+        match write_result {
+            Ok(()) => info!(
+                self.log,
+                "Stored fork choice snapshot";
+                "path" => ?path,
+                "slot" => snapshot.current_slot,
+                "head_block_root" => ?snapshot.head_block_root,
+            ),
+            Err(e) => error!(
+                self.log,
+                "Failed to store fork choice snapshot";
+                "error" => ?e,
+                "path" => ?path,
+            ),
+        }
+    }
+}
@@ -11,6 +11,7 @@ mod beacon_snapshot;
 pub mod bellatrix_readiness;
 pub mod blob_verification;
 pub mod block_reward;
+pub mod block_simulation;
 mod block_times_cache;
 mod block_verification;
 pub mod block_verification_types;
@@ -28,6 +29,7 @@ mod eth1_finalization_cache;
 pub mod events;
 pub mod execution_payload;
 pub mod fork_choice_signal;
+pub mod fork_choice_snapshot;
 pub mod fork_revert;
 pub mod graffiti_calculator;
 mod head_tracker;
@@ -272,6 +272,19 @@ lazy_static! {
         "beacon_attestation_processing_batch_unagg_signature_times",
         "Time spent on the signature verification of batch unaggregate attestation processing"
     );
+    pub static ref ATTESTATION_PROCESSING_BATCH_UNAGG_DISTINCT_DATA: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_attestation_processing_batch_unagg_distinct_data",
+        "Number of distinct AttestationData in each batch of unaggregated attestations",
+        Ok(vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0])
+    );
+    pub static ref ATTESTATION_PROCESSING_DATA_CACHE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_data_cache_hits_total",
+        "Count of unaggregated attestations in a batch which re-used the fork choice and committee lookups of another with identical AttestationData"
+    );
+    pub static ref ATTESTATION_PROCESSING_DATA_CACHE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_data_cache_misses_total",
+        "Count of unaggregated attestations in a batch which required fork choice and committee lookups"
+    );
 
     /*
      * Shuffling cache
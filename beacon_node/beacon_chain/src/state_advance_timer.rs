@@ -437,6 +437,17 @@ fn advance_head<T: BeaconChainTypes>(
         .maybe_cache_state(&state, head_block_root, &beacon_chain.spec)
         .map_err(BeaconChainError::from)?;
 
+    // Prime the op pool's reward cache so that it doesn't need to be built whilst producing a
+    // block in the next slot.
+    if let Err(e) = beacon_chain.op_pool.update_reward_cache(&state) {
+        warn!(
+            log,
+            "Unable to prime reward cache";
+            "error" => ?e,
+            "head_block_root" => ?head_block_root,
+        );
+    }
+
     let final_slot = state.slot();
 
     // If we have moved into the next slot whilst processing the state then this function is going
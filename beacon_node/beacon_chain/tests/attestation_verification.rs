@@ -1362,6 +1362,61 @@ async fn verify_attestation_for_gossip_doppelganger_detection() {
         .expect("should check if gossip aggregator was observed"));
 }
 
+/// Attestations in a batch which share their `AttestationData` must still be subject to the
+/// checks which are specific to each attestation.
+#[tokio::test]
+async fn batch_unaggregated_verification_with_shared_attestation_data() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Advance into a slot where there have not been any attestations produced.
+    harness.advance_slot();
+
+    let (state, state_root) = harness.get_current_state_and_root();
+    let committee_attestations = harness
+        .get_unaggregated_attestations(
+            &AttestationStrategy::AllValidators,
+            &state,
+            state_root,
+            harness.head_block_root(),
+            harness.get_current_slot(),
+        )
+        .into_iter()
+        .next()
+        .expect("should have at least one committee");
+    assert!(committee_attestations.len() > 2);
+
+    let mut batch = committee_attestations
+        .iter()
+        .map(|(attestation, subnet_id)| (attestation, Some(*subnet_id)))
+        .collect::<Vec<_>>();
+    // Received on the wrong subnet.
+    let (_, subnet_id) = committee_attestations[1];
+    batch[1].1 = Some(SubnetId::new(*subnet_id + 1));
+    // A duplicate of the first attestation.
+    batch.push(batch[0]);
+
+    let results = batch_verify_unaggregated_attestations(batch.into_iter(), &harness.chain)
+        .expect("should return attestation results");
+    let last = results.len() - 1;
+    for (i, result) in results.iter().enumerate() {
+        if i == 1 {
+            assert!(matches!(result, Err(Error::InvalidSubnetId { .. })));
+        } else if i == last {
+            assert!(matches!(result, Err(Error::PriorAttestationKnown { .. })));
+        } else {
+            assert!(result.is_ok(), "attestation {} should be valid", i);
+        }
+    }
+}
+
 #[tokio::test]
 async fn attestation_verification_use_head_state_fork() {
     let (harness, spec) = get_harness_capella_spec(VALIDATOR_COUNT);
@@ -1,6 +1,6 @@
 use beacon_chain::blob_verification::GossipVerifiedBlob;
-use beacon_chain::test_utils::BeaconChainHarness;
-use eth2::types::{EventKind, SseBlobSidecar};
+use beacon_chain::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+use eth2::types::{EventKind, EventTopic, SseBlobSidecar};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use std::sync::Arc;
@@ -39,7 +39,7 @@ async fn blob_sidecar_event_on_process_gossip_blob() {
         .await
         .unwrap();
 
-    let sidecar_event = blob_event_receiver.try_recv().unwrap();
+    let sidecar_event = blob_event_receiver.try_recv().unwrap().kind;
     assert_eq!(sidecar_event, EventKind::BlobSidecar(expected_sse_blobs));
 }
 
@@ -83,7 +83,7 @@ async fn blob_sidecar_event_on_process_rpc_blobs() {
 
     let mut sse_blobs: Vec<SseBlobSidecar> = vec![];
     while let Ok(sidecar_event) = blob_event_receiver.try_recv() {
-        if let EventKind::BlobSidecar(sse_blob_sidecar) = sidecar_event {
+        if let EventKind::BlobSidecar(sse_blob_sidecar) = sidecar_event.kind {
             sse_blobs.push(sse_blob_sidecar);
         } else {
             panic!("`BlobSidecar` event kind expected.");
@@ -91,3 +91,84 @@ async fn blob_sidecar_event_on_process_rpc_blobs() {
     }
     assert_eq!(sse_blobs, expected_sse_blobs);
 }
+
+/// Verifies that head and block events produced whilst a client is disconnected are replayed
+/// when it resubscribes with the ID of the last event it received.
+#[tokio::test]
+async fn missed_events_replayed_after_last_event_id() {
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(8)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    let event_handler = harness.chain.event_handler.as_ref().unwrap();
+
+    let mut head_receiver = event_handler.subscribe(EventTopic::Head);
+    let mut block_receiver = event_handler.subscribe(EventTopic::Block);
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let last_head_event_id = head_receiver.try_recv().unwrap().id;
+    let last_block_event_id = block_receiver.try_recv().unwrap().id;
+
+    // The client disconnects, leaving no live receivers.
+    drop(head_receiver);
+    drop(block_receiver);
+
+    let mut missed_roots = vec![];
+    for _ in 0..2 {
+        harness.advance_slot();
+        missed_roots.push(
+            harness
+                .extend_chain(
+                    1,
+                    BlockStrategy::OnCanonicalHead,
+                    AttestationStrategy::AllValidators,
+                )
+                .await,
+        );
+    }
+
+    let (missed_heads, _) = event_handler.subscribe_after(EventTopic::Head, last_head_event_id);
+    let missed_head_roots = missed_heads
+        .iter()
+        .map(|event| match &event.kind {
+            EventKind::Head(head) => head.block,
+            kind => panic!("unexpected event: {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(missed_head_roots, missed_roots);
+
+    let (missed_blocks, mut block_receiver) =
+        event_handler.subscribe_after(EventTopic::Block, last_block_event_id);
+    let missed_block_roots = missed_blocks
+        .iter()
+        .map(|event| match &event.kind {
+            EventKind::Block(block) => block.block,
+            kind => panic!("unexpected event: {kind:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(missed_block_roots, missed_roots);
+    assert!(missed_blocks[0].id > last_block_event_id);
+
+    // New events follow on the live receiver.
+    harness.advance_slot();
+    let new_root = harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let live = block_receiver.try_recv().unwrap();
+    assert!(matches!(live.kind, EventKind::Block(block) if block.block == new_root));
+    assert!(live.id > missed_blocks[1].id);
+}
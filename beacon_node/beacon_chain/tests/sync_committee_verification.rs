@@ -1,9 +1,12 @@
 #![cfg(not(debug_assertions))]
 
 use beacon_chain::sync_committee_verification::{Error as SyncCommitteeError, SyncCommitteeData};
-use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType, RelativeSyncCommittee};
+use beacon_chain::test_utils::{
+    BeaconChainHarness, EphemeralHarnessType, RelativeSyncCommittee, OP_POOL_DB_KEY,
+};
 use int_to_bytes::int_to_bytes32;
 use lazy_static::lazy_static;
+use operation_pool::PersistedOperationPool;
 use safe_arith::SafeArith;
 use state_processing::{
     per_block_processing::{altair::sync_committee::process_sync_aggregate, VerifySignatures},
@@ -813,3 +816,68 @@ async fn unaggregated_gossip_verification() {
         if received == subnet_id && !expected.contains(&subnet_id)
     );
 }
+
+#[tokio::test]
+async fn persisted_operation_pool_includes_naive_sync_aggregation_pool() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let state = harness.get_current_state();
+
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            Hash256::zero(),
+            &[Slot::new(1), Slot::new(2)],
+            (0..VALIDATOR_COUNT).collect::<Vec<_>>().as_slice(),
+        )
+        .await;
+
+    let current_slot = harness.chain.slot().expect("should get slot");
+    let head_state = harness.chain.head_beacon_state_cloned();
+    let head_block_root = harness.chain.head_snapshot().beacon_block_root;
+    let sync_messages = harness.make_sync_committee_messages(
+        &head_state,
+        head_block_root,
+        current_slot,
+        RelativeSyncCommittee::Current,
+    );
+
+    // Only add the messages to the naive sync aggregation pool, not the op pool.
+    for (subnet, messages) in sync_messages.iter().enumerate() {
+        let subnet_id = SyncSubnetId::new(subnet as u64);
+        for (message, _) in messages {
+            // A validator holding several positions in the subcommittee is only verified once.
+            if let Ok(verified) = harness
+                .chain
+                .verify_sync_committee_message_for_gossip(message.clone(), subnet_id)
+            {
+                harness
+                    .chain
+                    .add_to_naive_sync_aggregation_pool(verified)
+                    .expect("should add message to naive sync aggregation pool");
+            }
+        }
+    }
+
+    let num_contributions_before_persist = harness.chain.op_pool.num_sync_contributions();
+
+    harness
+        .chain
+        .persist_op_pool()
+        .expect("should persist op pool");
+
+    let restored_op_pool = harness
+        .chain
+        .store
+        .get_item::<PersistedOperationPool<E>>(&OP_POOL_DB_KEY)
+        .expect("should read db")
+        .expect("should find op pool")
+        .into_operation_pool()
+        .unwrap();
+
+    // One contribution should have been imported for each subcommittee at the head slot.
+    assert_eq!(
+        restored_op_pool.num_sync_contributions(),
+        num_contributions_before_persist + SYNC_COMMITTEE_SUBNET_COUNT as usize
+    );
+    assert_eq!(harness.chain.op_pool, restored_op_pool);
+}
@@ -479,6 +479,71 @@ async fn roundtrip_operation_pool() {
     assert_eq!(harness.chain.op_pool, restored_op_pool);
 }
 
+#[tokio::test]
+async fn persisted_operation_pool_includes_naive_aggregation_pool() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            MinimalEthSpec::slots_per_epoch() as usize * 2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            // Don't produce any attestations (we'll add them to the naive aggregation pool below).
+            AttestationStrategy::SomeValidators(vec![]),
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    let attestations = harness.get_unaggregated_attestations(
+        &AttestationStrategy::AllValidators,
+        &head.beacon_state,
+        head.beacon_state_root(),
+        head.beacon_block_root,
+        head.beacon_block.slot(),
+    );
+
+    // Only add the attestations to the naive aggregation pool, not the op pool.
+    for (attestation, subnet_id) in attestations.iter().flatten() {
+        let verified = harness
+            .chain
+            .verify_unaggregated_attestation_for_gossip(attestation, Some(*subnet_id))
+            .expect("should verify attestation");
+        harness
+            .chain
+            .add_to_naive_aggregation_pool(&verified)
+            .expect("should add attestation to naive aggregation pool");
+    }
+
+    let num_attestations_before_persist = harness.chain.op_pool.num_attestations();
+
+    harness
+        .chain
+        .persist_op_pool()
+        .expect("should persist op pool");
+
+    let restored_op_pool = harness
+        .chain
+        .store
+        .get_item::<PersistedOperationPool<MinimalEthSpec>>(&OP_POOL_DB_KEY)
+        .expect("should read db")
+        .expect("should find op pool")
+        .into_operation_pool()
+        .unwrap();
+
+    // One aggregate should have been imported for each committee at the head slot.
+    assert_eq!(
+        restored_op_pool.num_attestations(),
+        num_attestations_before_persist + attestations.len()
+    );
+    assert_eq!(harness.chain.op_pool, restored_op_pool);
+}
+
 #[tokio::test]
 async fn unaggregated_attestations_added_to_fork_choice_some_none() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() / 2;
@@ -38,6 +38,7 @@
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
 
+use crate::scheduler::{BatchCostEstimates, WorkQueues};
 use crate::work_reprocessing_queue::{
     QueuedBackfillBatch, QueuedGossipBlock, ReprocessQueueMessage,
 };
@@ -47,7 +48,7 @@ use lighthouse_network::{MessageId, NetworkGlobals, PeerId};
 use logging::TimeLatch;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use slog::{crit, debug, error, trace, warn, Logger};
+use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp;
 use std::collections::{HashSet, VecDeque};
@@ -56,7 +57,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
@@ -71,8 +72,11 @@ use work_reprocessing_queue::{
 };
 
 mod metrics;
+pub mod scheduler;
 pub mod work_reprocessing_queue;
 
+pub use scheduler::{BeaconProcessorScheduler, SchedulerConfig, WorkQueueId};
+
 /// The maximum size of the channel for work events to the `BeaconProcessor`.
 ///
 /// Setting this too low will cause consensus messages to be dropped.
@@ -178,6 +182,42 @@ impl BeaconProcessorQueueLengths {
             api_request_p1_queue: 1024,
         })
     }
+
+    /// Returns the default maximum length of the queue identified by `id`.
+    pub fn for_queue(&self, id: WorkQueueId) -> usize {
+        match id {
+            WorkQueueId::ChainSegment => self.chain_segment_queue,
+            WorkQueueId::RpcBlock => self.rpc_block_queue,
+            WorkQueueId::RpcBlob => self.rpc_blob_queue,
+            WorkQueueId::DelayedBlock => self.delayed_block_queue,
+            WorkQueueId::GossipBlock => self.gossip_block_queue,
+            WorkQueueId::GossipBlob => self.gossip_blob_queue,
+            WorkQueueId::ApiRequestP0 => self.api_request_p0_queue,
+            WorkQueueId::Aggregate => self.aggregate_queue,
+            WorkQueueId::Attestation => self.attestation_queue,
+            WorkQueueId::SyncContribution => self.sync_contribution_queue,
+            WorkQueueId::SyncMessage => self.sync_message_queue,
+            WorkQueueId::UnknownBlockAggregate => self.unknown_block_aggregate_queue,
+            WorkQueueId::UnknownBlockAttestation => self.unknown_block_attestation_queue,
+            WorkQueueId::Status => self.status_queue,
+            WorkQueueId::BlocksByRange => self.bbrange_queue,
+            WorkQueueId::BlocksByRoots => self.bbroots_queue,
+            WorkQueueId::BlobsByRange => self.blbrange_queue,
+            WorkQueueId::BlobsByRoots => self.blbroots_queue,
+            WorkQueueId::GossipAttesterSlashing => self.gossip_attester_slashing_queue,
+            WorkQueueId::GossipProposerSlashing => self.gossip_proposer_slashing_queue,
+            WorkQueueId::GossipVoluntaryExit => self.gossip_voluntary_exit_queue,
+            WorkQueueId::GossipBlsToExecutionChange => self.gossip_bls_to_execution_change_queue,
+            WorkQueueId::ApiRequestP1 => self.api_request_p1_queue,
+            WorkQueueId::BackfillChainSegment => self.backfill_chain_segment,
+            WorkQueueId::LightClientBootstrap => self.lc_bootstrap_queue,
+            WorkQueueId::LightClientOptimisticUpdateRequest => self.lc_optimistic_update_queue,
+            WorkQueueId::LightClientFinalityUpdateRequest => self.lc_finality_update_queue,
+            WorkQueueId::GossipLightClientFinalityUpdate => self.finality_update_queue,
+            WorkQueueId::GossipLightClientOptimisticUpdate => self.optimistic_update_queue,
+            WorkQueueId::UnknownLightClientUpdate => self.unknown_light_client_update_queue,
+        }
+    }
 }
 
 /// The name of the manager tokio task.
@@ -244,7 +284,15 @@ pub struct BeaconProcessorConfig {
     pub max_scheduled_work_queue_len: usize,
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
+    /// If set, gossip attestation and aggregate batches are sized adaptively from the queue depth
+    /// and this target for the time taken to verify a batch.
+    ///
+    /// Disabled by default, since smaller batches forgo the savings of batch signature
+    /// verification.
+    pub gossip_batch_latency_target: Option<Duration>,
     pub enable_backfill_rate_limiting: bool,
+    /// The initial queue priorities, lengths and batch sizes.
+    pub scheduler: SchedulerConfig,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -255,7 +303,9 @@ impl Default for BeaconProcessorConfig {
             max_scheduled_work_queue_len: DEFAULT_MAX_SCHEDULED_WORK_QUEUE_LEN,
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
+            gossip_batch_latency_target: None,
             enable_backfill_rate_limiting: true,
+            scheduler: SchedulerConfig::default(),
         }
     }
 }
@@ -266,6 +316,7 @@ pub struct BeaconProcessorChannels<E: EthSpec> {
     pub beacon_processor_rx: mpsc::Receiver<WorkEvent<E>>,
     pub work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
     pub work_reprocessing_rx: mpsc::Receiver<ReprocessQueueMessage>,
+    pub scheduler: BeaconProcessorScheduler,
 }
 
 impl<E: EthSpec> BeaconProcessorChannels<E> {
@@ -280,6 +331,7 @@ impl<E: EthSpec> BeaconProcessorChannels<E> {
             beacon_processor_rx,
             work_reprocessing_rx,
             work_reprocessing_tx,
+            scheduler: BeaconProcessorScheduler::new(config.scheduler.clone()),
         }
     }
 }
@@ -309,7 +361,7 @@ impl<T> FifoQueue<T> {
     ///
     /// Drops `item` if the queue is full.
     pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
-        if self.queue.len() == self.max_length {
+        if self.queue.len() >= self.max_length {
             error!(
                 log,
                 "Work queue is full";
@@ -352,7 +404,7 @@ impl<T> LifoQueue<T> {
     ///
     /// If the queue is full, the item at the back of the queue is dropped.
     pub fn push(&mut self, item: T) {
-        if self.queue.len() == self.max_length {
+        if self.queue.len() >= self.max_length {
             self.queue.pop_back();
         }
         self.queue.push_front(item);
@@ -363,11 +415,6 @@ impl<T> LifoQueue<T> {
         self.queue.pop_front()
     }
 
-    /// Returns `true` if the queue is full.
-    pub fn is_full(&self) -> bool {
-        self.queue.len() >= self.max_length
-    }
-
     /// Returns the current length of the queue.
     pub fn len(&self) -> usize {
         self.queue.len()
@@ -749,6 +796,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
     ///
     /// The optional `work_journal_tx` allows for an outside process to receive a log of all work
     /// events processed by `self`. This should only be used during testing.
+    ///
+    /// The order in which queues are drained and their lengths are controlled by `scheduler`,
+    /// which may be updated whilst the manager is running.
     #[allow(clippy::too_many_arguments)]
     pub fn spawn_manager<S: SlotClock + 'static>(
         mut self,
@@ -759,68 +809,15 @@ impl<E: EthSpec> BeaconProcessor<E> {
         slot_clock: S,
         maximum_gossip_clock_disparity: Duration,
         queue_lengths: BeaconProcessorQueueLengths,
+        scheduler: BeaconProcessorScheduler,
     ) -> Result<(), String> {
         // Used by workers to communicate that they are finished a task.
         let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
 
-        // Using LIFO queues for attestations since validator profits rely upon getting fresh
-        // attestations into blocks. Additionally, later attestations contain more information than
-        // earlier ones, so we consider them more valuable.
-        let mut aggregate_queue = LifoQueue::new(queue_lengths.aggregate_queue);
+        let mut queues = WorkQueues::new(queue_lengths, &self.config, scheduler);
+        let batch_costs = queues.batch_costs();
         let mut aggregate_debounce = TimeLatch::default();
-        let mut attestation_queue = LifoQueue::new(queue_lengths.attestation_queue);
         let mut attestation_debounce = TimeLatch::default();
-        let mut unknown_block_aggregate_queue =
-            LifoQueue::new(queue_lengths.unknown_block_aggregate_queue);
-        let mut unknown_block_attestation_queue =
-            LifoQueue::new(queue_lengths.unknown_block_attestation_queue);
-
-        let mut sync_message_queue = LifoQueue::new(queue_lengths.sync_message_queue);
-        let mut sync_contribution_queue = LifoQueue::new(queue_lengths.sync_contribution_queue);
-
-        // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't have
-        // a strong feeling about queue type for exits.
-        let mut gossip_voluntary_exit_queue =
-            FifoQueue::new(queue_lengths.gossip_voluntary_exit_queue);
-
-        // Using a FIFO queue for slashing to prevent people from flushing their slashings from the
-        // queues with lots of junk messages.
-        let mut gossip_proposer_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_proposer_slashing_queue);
-        let mut gossip_attester_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_attester_slashing_queue);
-
-        // Using a FIFO queue for light client updates to maintain sequence order.
-        let mut finality_update_queue = FifoQueue::new(queue_lengths.finality_update_queue);
-        let mut optimistic_update_queue = FifoQueue::new(queue_lengths.optimistic_update_queue);
-        let mut unknown_light_client_update_queue =
-            FifoQueue::new(queue_lengths.unknown_light_client_update_queue);
-
-        // Using a FIFO queue since blocks need to be imported sequentially.
-        let mut rpc_block_queue = FifoQueue::new(queue_lengths.rpc_block_queue);
-        let mut rpc_blob_queue = FifoQueue::new(queue_lengths.rpc_blob_queue);
-        let mut chain_segment_queue = FifoQueue::new(queue_lengths.chain_segment_queue);
-        let mut backfill_chain_segment = FifoQueue::new(queue_lengths.backfill_chain_segment);
-        let mut gossip_block_queue = FifoQueue::new(queue_lengths.gossip_block_queue);
-        let mut gossip_blob_queue = FifoQueue::new(queue_lengths.gossip_blob_queue);
-        let mut delayed_block_queue = FifoQueue::new(queue_lengths.delayed_block_queue);
-
-        let mut status_queue = FifoQueue::new(queue_lengths.status_queue);
-        let mut bbrange_queue = FifoQueue::new(queue_lengths.bbrange_queue);
-        let mut bbroots_queue = FifoQueue::new(queue_lengths.bbroots_queue);
-        let mut blbroots_queue = FifoQueue::new(queue_lengths.blbroots_queue);
-        let mut blbrange_queue = FifoQueue::new(queue_lengths.blbrange_queue);
-
-        let mut gossip_bls_to_execution_change_queue =
-            FifoQueue::new(queue_lengths.gossip_bls_to_execution_change_queue);
-
-        let mut lc_bootstrap_queue = FifoQueue::new(queue_lengths.lc_bootstrap_queue);
-        let mut lc_optimistic_update_queue =
-            FifoQueue::new(queue_lengths.lc_optimistic_update_queue);
-        let mut lc_finality_update_queue = FifoQueue::new(queue_lengths.lc_finality_update_queue);
-
-        let mut api_request_p0_queue = FifoQueue::new(queue_lengths.api_request_p0_queue);
-        let mut api_request_p1_queue = FifoQueue::new(queue_lengths.api_request_p1_queue);
 
         // Channels for sending work to the re-process scheduler (`work_reprocessing_tx`) and to
         // receive them back once they are ready (`ready_work_rx`).
@@ -909,6 +906,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
 
                 let _event_timer =
                     metrics::start_timer(&metrics::BEACON_PROCESSOR_EVENT_HANDLING_SECONDS);
+                if queues.apply_scheduler_update(&self.log) {
+                    info!(self.log, "Updated beacon processor scheduler");
+                }
                 if let Some(event) = &work_event {
                     metrics::inc_counter_vec(
                         &metrics::BEACON_PROCESSOR_WORK_EVENTS_RX_COUNT,
@@ -941,207 +941,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
-                        // Check for chain segments first, they're the most efficient way to get
-                        // blocks into the system.
-                        if let Some(item) = chain_segment_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check sync blocks before gossip blocks, since we've already explicitly
-                        // requested these blocks.
-                        } else if let Some(item) = rpc_block_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = rpc_blob_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check delayed blocks before gossip blocks, the gossip blocks might rely
-                        // on the delayed ones.
-                        } else if let Some(item) = delayed_block_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check gossip blocks before gossip attestations, since a block might be
-                        // required to verify some attestations.
-                        } else if let Some(item) = gossip_block_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = gossip_blob_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check the priority 0 API requests after blocks and blobs, but before attestations.
-                        } else if let Some(item) = api_request_p0_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check the aggregates, *then* the unaggregates since we assume that
-                        // aggregates are more valuable to local validators and effectively give us
-                        // more information with less signature verification time.
-                        } else if aggregate_queue.len() > 0 {
-                            let batch_size = cmp::min(
-                                aggregate_queue.len(),
-                                self.config.max_gossip_aggregate_batch_size,
-                            );
-
-                            if batch_size < 2 {
-                                // One single aggregate is in the queue, process it individually.
-                                if let Some(item) = aggregate_queue.pop() {
-                                    self.spawn_worker(item, idle_tx);
-                                }
-                            } else {
-                                // Collect two or more aggregates into a batch, so they can take
-                                // advantage of batch signature verification.
-                                //
-                                // Note: this will convert the `Work::GossipAggregate` item into a
-                                // `Work::GossipAggregateBatch` item.
-                                let mut aggregates = Vec::with_capacity(batch_size);
-                                let mut process_batch_opt = None;
-                                for _ in 0..batch_size {
-                                    if let Some(item) = aggregate_queue.pop() {
-                                        match item {
-                                            Work::GossipAggregate {
-                                                aggregate,
-                                                process_individual: _,
-                                                process_batch,
-                                            } => {
-                                                aggregates.push(*aggregate);
-                                                if process_batch_opt.is_none() {
-                                                    process_batch_opt = Some(process_batch);
-                                                }
-                                            }
-                                            _ => {
-                                                error!(self.log, "Invalid item in aggregate queue");
-                                            }
-                                        }
-                                    }
-                                }
-
-                                if let Some(process_batch) = process_batch_opt {
-                                    // Process all aggregates with a single worker.
-                                    self.spawn_worker(
-                                        Work::GossipAggregateBatch {
-                                            aggregates,
-                                            process_batch,
-                                        },
-                                        idle_tx,
-                                    )
-                                } else {
-                                    // There is no good reason for this to
-                                    // happen, it is a serious logic error.
-                                    // Since we only form batches when multiple
-                                    // work items exist, we should always have a
-                                    // work closure at this point.
-                                    crit!(self.log, "Missing aggregate work");
-                                }
-                            }
-                        // Check the unaggregated attestation queue.
-                        //
-                        // Potentially use batching.
-                        } else if attestation_queue.len() > 0 {
-                            let batch_size = cmp::min(
-                                attestation_queue.len(),
-                                self.config.max_gossip_attestation_batch_size,
-                            );
-
-                            if batch_size < 2 {
-                                // One single attestation is in the queue, process it individually.
-                                if let Some(item) = attestation_queue.pop() {
-                                    self.spawn_worker(item, idle_tx);
-                                }
-                            } else {
-                                // Collect two or more attestations into a batch, so they can take
-                                // advantage of batch signature verification.
-                                //
-                                // Note: this will convert the `Work::GossipAttestation` item into a
-                                // `Work::GossipAttestationBatch` item.
-                                let mut attestations = Vec::with_capacity(batch_size);
-                                let mut process_batch_opt = None;
-                                for _ in 0..batch_size {
-                                    if let Some(item) = attestation_queue.pop() {
-                                        match item {
-                                            Work::GossipAttestation {
-                                                attestation,
-                                                process_individual: _,
-                                                process_batch,
-                                            } => {
-                                                attestations.push(*attestation);
-                                                if process_batch_opt.is_none() {
-                                                    process_batch_opt = Some(process_batch);
-                                                }
-                                            }
-                                            _ => error!(
-                                                self.log,
-                                                "Invalid item in attestation queue"
-                                            ),
-                                        }
-                                    }
-                                }
-
-                                if let Some(process_batch) = process_batch_opt {
-                                    // Process all attestations with a single worker.
-                                    self.spawn_worker(
-                                        Work::GossipAttestationBatch {
-                                            attestations,
-                                            process_batch,
-                                        },
-                                        idle_tx,
-                                    )
-                                } else {
-                                    // There is no good reason for this to
-                                    // happen, it is a serious logic error.
-                                    // Since we only form batches when multiple
-                                    // work items exist, we should always have a
-                                    // work closure at this point.
-                                    crit!(self.log, "Missing attestations work");
-                                }
-                            }
-                        // Check sync committee messages after attestations as their rewards are lesser
-                        // and they don't influence fork choice.
-                        } else if let Some(item) = sync_contribution_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = sync_message_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Aggregates and unaggregates queued for re-processing are older and we
-                        // care about fresher ones, so check those first.
-                        } else if let Some(item) = unknown_block_aggregate_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = unknown_block_attestation_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check RPC methods next. Status messages are needed for sync so
-                        // prioritize them over syncing requests from other peers (BlocksByRange
-                        // and BlocksByRoot)
-                        } else if let Some(item) = status_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = bbrange_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = bbroots_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = blbrange_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = blbroots_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check slashings after all other consensus messages so we prioritize
-                        // following head.
-                        //
-                        // Check attester slashings before proposer slashings since they have the
-                        // potential to slash multiple validators at once.
-                        } else if let Some(item) = gossip_attester_slashing_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = gossip_proposer_slashing_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check exits and address changes late since our validators don't get
-                        // rewards from them.
-                        } else if let Some(item) = gossip_voluntary_exit_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = gossip_bls_to_execution_change_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Check the priority 1 API requests after we've
-                        // processed all the interesting things from the network
-                        // and things required for us to stay in good repute
-                        // with our P2P peers.
-                        } else if let Some(item) = api_request_p1_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Handle backfill sync chain segments.
-                        } else if let Some(item) = backfill_chain_segment.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        // Handle light client requests.
-                        } else if let Some(item) = lc_bootstrap_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = lc_optimistic_update_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                        } else if let Some(item) = lc_finality_update_queue.pop() {
-                            self.spawn_worker(item, idle_tx);
-                            // This statement should always be the final else statement.
+                        let idle_workers = self.config.max_workers - self.current_workers;
+                        if let Some(item) = queues.pop_next(idle_workers, &self.log) {
+                            self.spawn_worker(item, idle_tx, &batch_costs);
                         } else {
                             // Let the journal know that a worker is freed and there's nothing else
                             // for it to do.
@@ -1182,104 +984,18 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // There is a new work event and the chain is not syncing. Process it or queue
                     // it.
                     Some(WorkEvent { work, .. }) => {
-                        let work_id = work.str_id();
-
-                        match work {
-                            _ if can_spawn => self.spawn_worker(work, idle_tx),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
-                            // Attestation batches are formed internally within the
-                            // `BeaconProcessor`, they are not sent from external services.
-                            Work::GossipAttestationBatch { .. } => crit!(
-                                    self.log,
-                                    "Unsupported inbound event";
-                                    "type" => "GossipAttestationBatch"
-                            ),
-                            Work::GossipAggregate { .. } => aggregate_queue.push(work),
-                            // Aggregate batches are formed internally within the `BeaconProcessor`,
-                            // they are not sent from external services.
-                            Work::GossipAggregateBatch { .. } => crit!(
-                                    self.log,
-                                    "Unsupported inbound event";
-                                    "type" => "GossipAggregateBatch"
-                            ),
-                            Work::GossipBlock { .. } => {
-                                gossip_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipBlobSidecar { .. } => {
-                                gossip_blob_queue.push(work, work_id, &self.log)
-                            }
-                            Work::DelayedImportBlock { .. } => {
-                                delayed_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipVoluntaryExit { .. } => {
-                                gossip_voluntary_exit_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipProposerSlashing { .. } => {
-                                gossip_proposer_slashing_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipAttesterSlashing { .. } => {
-                                gossip_attester_slashing_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipSyncSignature { .. } => sync_message_queue.push(work),
-                            Work::GossipSyncContribution { .. } => {
-                                sync_contribution_queue.push(work)
-                            }
-                            Work::GossipLightClientFinalityUpdate { .. } => {
-                                finality_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::GossipLightClientOptimisticUpdate { .. } => {
-                                optimistic_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::RpcBlock { .. } | Work::IgnoredRpcBlock { .. } => {
-                                rpc_block_queue.push(work, work_id, &self.log)
-                            }
-                            Work::RpcBlobs { .. } => rpc_blob_queue.push(work, work_id, &self.log),
-                            Work::ChainSegment { .. } => {
-                                chain_segment_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ChainSegmentBackfill { .. } => {
-                                backfill_chain_segment.push(work, work_id, &self.log)
-                            }
-                            Work::Status { .. } => status_queue.push(work, work_id, &self.log),
-                            Work::BlocksByRangeRequest { .. } => {
-                                bbrange_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlocksByRootsRequest { .. } => {
-                                bbroots_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlobsByRangeRequest { .. } => {
-                                blbrange_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientBootstrapRequest { .. } => {
-                                lc_bootstrap_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientOptimisticUpdateRequest { .. } => {
-                                lc_optimistic_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::LightClientFinalityUpdateRequest { .. } => {
-                                lc_finality_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::UnknownBlockAttestation { .. } => {
-                                unknown_block_attestation_queue.push(work)
-                            }
-                            Work::UnknownBlockAggregate { .. } => {
-                                unknown_block_aggregate_queue.push(work)
-                            }
-                            Work::GossipBlsToExecutionChange { .. } => {
-                                gossip_bls_to_execution_change_queue.push(work, work_id, &self.log)
-                            }
-                            Work::BlobsByRootsRequest { .. } => {
-                                blbroots_queue.push(work, work_id, &self.log)
-                            }
-                            Work::UnknownLightClientOptimisticUpdate { .. } => {
-                                unknown_light_client_update_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ApiRequestP0 { .. } => {
-                                api_request_p0_queue.push(work, work_id, &self.log)
-                            }
-                            Work::ApiRequestP1 { .. } => {
-                                api_request_p1_queue.push(work, work_id, &self.log)
-                            }
+                        if can_spawn {
+                            self.spawn_worker(work, idle_tx, &batch_costs);
+                        } else if let Some(queue_id) = WorkQueueId::for_work(&work) {
+                            queues.push(queue_id, work, &self.log);
+                        } else {
+                            // Batches are formed internally within the `BeaconProcessor`, they
+                            // are not sent from external services.
+                            crit!(
+                                self.log,
+                                "Unsupported inbound event";
+                                "type" => work.str_id()
+                            );
                         }
                     }
                 }
@@ -1290,84 +1006,84 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_QUEUE_TOTAL,
-                    attestation_queue.len() as i64,
+                    queues.len(WorkQueueId::Attestation) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_QUEUE_TOTAL,
-                    aggregate_queue.len() as i64,
+                    queues.len(WorkQueueId::Aggregate) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_SYNC_MESSAGE_QUEUE_TOTAL,
-                    sync_message_queue.len() as i64,
+                    queues.len(WorkQueueId::SyncMessage) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_SYNC_CONTRIBUTION_QUEUE_TOTAL,
-                    sync_contribution_queue.len() as i64,
+                    queues.len(WorkQueueId::SyncContribution) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_QUEUE_TOTAL,
-                    gossip_block_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipBlock) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_GOSSIP_BLOB_QUEUE_TOTAL,
-                    gossip_blob_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipBlob) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_RPC_BLOCK_QUEUE_TOTAL,
-                    rpc_block_queue.len() as i64,
+                    queues.len(WorkQueueId::RpcBlock) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_RPC_BLOB_QUEUE_TOTAL,
-                    rpc_blob_queue.len() as i64,
+                    queues.len(WorkQueueId::RpcBlob) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_CHAIN_SEGMENT_QUEUE_TOTAL,
-                    chain_segment_queue.len() as i64,
+                    queues.len(WorkQueueId::ChainSegment) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_BACKFILL_CHAIN_SEGMENT_QUEUE_TOTAL,
-                    backfill_chain_segment.len() as i64,
+                    queues.len(WorkQueueId::BackfillChainSegment) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_EXIT_QUEUE_TOTAL,
-                    gossip_voluntary_exit_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipVoluntaryExit) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_PROPOSER_SLASHING_QUEUE_TOTAL,
-                    gossip_proposer_slashing_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipProposerSlashing) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_ATTESTER_SLASHING_QUEUE_TOTAL,
-                    gossip_attester_slashing_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipAttesterSlashing) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_BLS_TO_EXECUTION_CHANGE_QUEUE_TOTAL,
-                    gossip_bls_to_execution_change_queue.len() as i64,
+                    queues.len(WorkQueueId::GossipBlsToExecutionChange) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_API_REQUEST_P0_QUEUE_TOTAL,
-                    api_request_p0_queue.len() as i64,
+                    queues.len(WorkQueueId::ApiRequestP0) as i64,
                 );
                 metrics::set_gauge(
                     &metrics::BEACON_PROCESSOR_API_REQUEST_P1_QUEUE_TOTAL,
-                    api_request_p1_queue.len() as i64,
+                    queues.len(WorkQueueId::ApiRequestP1) as i64,
                 );
 
-                if aggregate_queue.is_full() && aggregate_debounce.elapsed() {
+                if queues.is_full(WorkQueueId::Aggregate) && aggregate_debounce.elapsed() {
                     error!(
                         self.log,
                         "Aggregate attestation queue full";
                         "msg" => "the system has insufficient resources for load",
-                        "queue_len" => aggregate_queue.max_length,
+                        "queue_len" => queues.max_length(WorkQueueId::Aggregate),
                     )
                 }
 
-                if attestation_queue.is_full() && attestation_debounce.elapsed() {
+                if queues.is_full(WorkQueueId::Attestation) && attestation_debounce.elapsed() {
                     error!(
                         self.log,
                         "Attestation queue full";
                         "msg" => "the system has insufficient resources for load",
-                        "queue_len" => attestation_queue.max_length,
+                        "queue_len" => queues.max_length(WorkQueueId::Attestation),
                     )
                 }
             }
@@ -1381,7 +1097,14 @@ impl<E: EthSpec> BeaconProcessor<E> {
     /// Spawns a blocking worker thread to process some `Work`.
     ///
     /// Sends an message on `idle_tx` when the work is complete and the task is stopping.
-    fn spawn_worker(&mut self, work: Work<E>, idle_tx: mpsc::Sender<()>) {
+    ///
+    /// The time taken to process batches is recorded in `batch_costs`.
+    fn spawn_worker(
+        &mut self,
+        work: Work<E>,
+        idle_tx: mpsc::Sender<()>,
+        batch_costs: &BatchCostEstimates,
+    ) {
         let work_id = work.str_id();
         let worker_timer =
             metrics::start_timer_vec(&metrics::BEACON_PROCESSOR_WORKER_TIME, &[work_id]);
@@ -1429,9 +1152,15 @@ impl<E: EthSpec> BeaconProcessor<E> {
             Work::GossipAttestationBatch {
                 attestations,
                 process_batch,
-            } => task_spawner.spawn_blocking(move || {
-                process_batch(attestations);
-            }),
+            } => {
+                let batch_costs = batch_costs.clone();
+                task_spawner.spawn_blocking(move || {
+                    let batch_len = attestations.len();
+                    let started = Instant::now();
+                    process_batch(attestations);
+                    batch_costs.record(WorkQueueId::Attestation, batch_len, started.elapsed());
+                })
+            }
             Work::GossipAggregate {
                 aggregate,
                 process_individual,
@@ -1442,9 +1171,15 @@ impl<E: EthSpec> BeaconProcessor<E> {
             Work::GossipAggregateBatch {
                 aggregates,
                 process_batch,
-            } => task_spawner.spawn_blocking(move || {
-                process_batch(aggregates);
-            }),
+            } => {
+                let batch_costs = batch_costs.clone();
+                task_spawner.spawn_blocking(move || {
+                    let batch_len = aggregates.len();
+                    let started = Instant::now();
+                    process_batch(aggregates);
+                    batch_costs.record(WorkQueueId::Aggregate, batch_len, started.elapsed());
+                })
+            }
             Work::ChainSegment(process_fn) => task_spawner.spawn_async(async move {
                 process_fn.await;
             }),
@@ -41,6 +41,22 @@ lazy_static::lazy_static! {
         "beacon_processor_event_handling_seconds",
         "Time spent handling a new message and allocating it to a queue or worker."
     );
+    pub static ref BEACON_PROCESSOR_QUEUE_WAIT_SECONDS: Result<HistogramVec> = try_create_histogram_vec(
+        "beacon_processor_queue_wait_seconds",
+        "Time that work spent in a queue before being started by a worker.",
+        &["queue"]
+    );
+    pub static ref BEACON_PROCESSOR_BATCH_SIZE: Result<HistogramVec> = try_create_histogram_vec_with_buckets(
+        "beacon_processor_batch_size",
+        "Number of items in each batch formed from a batchable queue.",
+        Ok(vec![2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0]),
+        &["queue"]
+    );
+    pub static ref BEACON_PROCESSOR_BATCH_ITEM_COST_SECONDS: Result<GaugeVec> = try_create_float_gauge_vec(
+        "beacon_processor_batch_item_cost_seconds",
+        "Estimated time taken to verify a single item within a batch, used for adaptive batch sizing.",
+        &["queue"]
+    );
     // Gossip blocks.
     pub static ref BEACON_PROCESSOR_GOSSIP_BLOCK_QUEUE_TOTAL: Result<IntGauge> = try_create_int_gauge(
         "beacon_processor_gossip_block_queue_total",
@@ -0,0 +1,1097 @@
+//! Provides the runtime-configurable scheduling policy of the `BeaconProcessor`.
+//!
+//! The `BeaconProcessor` keeps one queue per type of work. When a worker becomes free the manager
+//! walks the queues in priority order and spawns a worker for the first queue which has some work
+//! available. The priority order, the maximum length of each queue and the batch size of the
+//! batchable queues are described by a `SchedulerConfig`.
+//!
+//! A `SchedulerConfig` is provided at startup via the `BeaconProcessorConfig` and may be replaced
+//! whilst the node is running using a `BeaconProcessorScheduler` handle. The HTTP API exchanges
+//! the `eth2::lighthouse` equivalents of these types, which are converted here.
+//!
+//! Each queue may be given a batch budget. Batchable queues verify up to that many items together,
+//! whilst other queues may start that many items in a row before a lower priority queue which has
+//! work is given a turn.
+//!
+//! If `BeaconProcessorConfig::gossip_batch_latency_target` is set, the size of each batch is
+//! chosen adaptively. The batch size limit from the `SchedulerConfig` (or the
+//! `BeaconProcessorConfig`) becomes an upper bound, and the batch is further limited by:
+//!
+//! - The queue depth, so that work is spread evenly across the idle workers rather than one
+//!   worker verifying a large batch whilst the others have nothing to do.
+//! - The latency target, using an estimate of the time taken to verify each item which is learned
+//!   from previous batches.
+use crate::{
+    metrics, BeaconProcessorConfig, BeaconProcessorQueueLengths, FifoQueue, LifoQueue, Work,
+};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use slog::{crit, error, warn, Logger};
+use std::cmp;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+use types::EthSpec;
+
+/// Identifies one of the work queues maintained by the `BeaconProcessor`.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, IntoStaticStr, EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WorkQueueId {
+    ChainSegment,
+    RpcBlock,
+    RpcBlob,
+    DelayedBlock,
+    GossipBlock,
+    GossipBlob,
+    ApiRequestP0,
+    Aggregate,
+    Attestation,
+    SyncContribution,
+    SyncMessage,
+    UnknownBlockAggregate,
+    UnknownBlockAttestation,
+    Status,
+    BlocksByRange,
+    BlocksByRoots,
+    BlobsByRange,
+    BlobsByRoots,
+    GossipAttesterSlashing,
+    GossipProposerSlashing,
+    GossipVoluntaryExit,
+    GossipBlsToExecutionChange,
+    ApiRequestP1,
+    BackfillChainSegment,
+    LightClientBootstrap,
+    LightClientOptimisticUpdateRequest,
+    LightClientFinalityUpdateRequest,
+    GossipLightClientFinalityUpdate,
+    GossipLightClientOptimisticUpdate,
+    UnknownLightClientUpdate,
+}
+
+impl WorkQueueId {
+    /// Returns the queue which `work` should be placed upon, or `None` if `work` should never be
+    /// sent to the `BeaconProcessor` by external services.
+    pub fn for_work<E: EthSpec>(work: &Work<E>) -> Option<Self> {
+        let id = match work {
+            Work::GossipAttestation { .. } => WorkQueueId::Attestation,
+            Work::GossipAggregate { .. } => WorkQueueId::Aggregate,
+            // Batches are formed internally within the `BeaconProcessor`.
+            Work::GossipAttestationBatch { .. } | Work::GossipAggregateBatch { .. } => return None,
+            Work::UnknownBlockAttestation { .. } => WorkQueueId::UnknownBlockAttestation,
+            Work::UnknownBlockAggregate { .. } => WorkQueueId::UnknownBlockAggregate,
+            Work::UnknownLightClientOptimisticUpdate { .. } => {
+                WorkQueueId::UnknownLightClientUpdate
+            }
+            Work::GossipBlock(_) => WorkQueueId::GossipBlock,
+            Work::GossipBlobSidecar(_) => WorkQueueId::GossipBlob,
+            Work::DelayedImportBlock { .. } => WorkQueueId::DelayedBlock,
+            Work::GossipVoluntaryExit(_) => WorkQueueId::GossipVoluntaryExit,
+            Work::GossipProposerSlashing(_) => WorkQueueId::GossipProposerSlashing,
+            Work::GossipAttesterSlashing(_) => WorkQueueId::GossipAttesterSlashing,
+            Work::GossipSyncSignature(_) => WorkQueueId::SyncMessage,
+            Work::GossipSyncContribution(_) => WorkQueueId::SyncContribution,
+            Work::GossipLightClientFinalityUpdate(_) => {
+                WorkQueueId::GossipLightClientFinalityUpdate
+            }
+            Work::GossipLightClientOptimisticUpdate(_) => {
+                WorkQueueId::GossipLightClientOptimisticUpdate
+            }
+            Work::RpcBlock { .. } | Work::IgnoredRpcBlock { .. } => WorkQueueId::RpcBlock,
+            Work::RpcBlobs { .. } => WorkQueueId::RpcBlob,
+            Work::ChainSegment(_) => WorkQueueId::ChainSegment,
+            Work::ChainSegmentBackfill(_) => WorkQueueId::BackfillChainSegment,
+            Work::Status(_) => WorkQueueId::Status,
+            Work::BlocksByRangeRequest(_) => WorkQueueId::BlocksByRange,
+            Work::BlocksByRootsRequest(_) => WorkQueueId::BlocksByRoots,
+            Work::BlobsByRangeRequest(_) => WorkQueueId::BlobsByRange,
+            Work::BlobsByRootsRequest(_) => WorkQueueId::BlobsByRoots,
+            Work::GossipBlsToExecutionChange(_) => WorkQueueId::GossipBlsToExecutionChange,
+            Work::LightClientBootstrapRequest(_) => WorkQueueId::LightClientBootstrap,
+            Work::LightClientOptimisticUpdateRequest(_) => {
+                WorkQueueId::LightClientOptimisticUpdateRequest
+            }
+            Work::LightClientFinalityUpdateRequest(_) => {
+                WorkQueueId::LightClientFinalityUpdateRequest
+            }
+            Work::ApiRequestP0(_) => WorkQueueId::ApiRequestP0,
+            Work::ApiRequestP1(_) => WorkQueueId::ApiRequestP1,
+        };
+        Some(id)
+    }
+
+    /// Returns `true` if the queue discards its oldest items when full, rather than new ones.
+    ///
+    /// Using LIFO queues for attestations since validator profits rely upon getting fresh
+    /// attestations into blocks. Additionally, later attestations contain more information than
+    /// earlier ones, so we consider them more valuable.
+    ///
+    /// All other queues are FIFO. Blocks need to be imported sequentially, and FIFO queues prevent
+    /// people from flushing slashings and exits from the queues with lots of junk messages.
+    pub fn is_lifo(self) -> bool {
+        matches!(
+            self,
+            WorkQueueId::Aggregate
+                | WorkQueueId::Attestation
+                | WorkQueueId::UnknownBlockAggregate
+                | WorkQueueId::UnknownBlockAttestation
+                | WorkQueueId::SyncMessage
+                | WorkQueueId::SyncContribution
+        )
+    }
+
+    /// Returns `true` if items from the queue can be verified together in a single batch.
+    pub fn is_batchable(self) -> bool {
+        matches!(self, WorkQueueId::Aggregate | WorkQueueId::Attestation)
+    }
+
+    /// Returns `false` if the queue is never drained, and so may not be given a priority.
+    ///
+    /// The `BeaconProcessor` has never processed gossip light client updates; they are held until
+    /// their queues are full and then dropped. The queues are kept so that their lengths remain
+    /// configurable.
+    pub fn is_drained(self) -> bool {
+        !matches!(
+            self,
+            WorkQueueId::GossipLightClientFinalityUpdate
+                | WorkQueueId::GossipLightClientOptimisticUpdate
+                | WorkQueueId::UnknownLightClientUpdate
+        )
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Implements the conversions between `WorkQueueId` and its `eth2::lighthouse` equivalent.
+macro_rules! impl_work_queue_id_conversions {
+    ($($variant:ident),* $(,)?) => {
+        impl From<WorkQueueId> for eth2::lighthouse::WorkQueueId {
+            fn from(id: WorkQueueId) -> Self {
+                match id {
+                    $(WorkQueueId::$variant => Self::$variant,)*
+                }
+            }
+        }
+
+        impl From<eth2::lighthouse::WorkQueueId> for WorkQueueId {
+            fn from(id: eth2::lighthouse::WorkQueueId) -> Self {
+                match id {
+                    $(eth2::lighthouse::WorkQueueId::$variant => Self::$variant,)*
+                }
+            }
+        }
+    };
+}
+
+impl_work_queue_id_conversions!(
+    ChainSegment,
+    RpcBlock,
+    RpcBlob,
+    DelayedBlock,
+    GossipBlock,
+    GossipBlob,
+    ApiRequestP0,
+    Aggregate,
+    Attestation,
+    SyncContribution,
+    SyncMessage,
+    UnknownBlockAggregate,
+    UnknownBlockAttestation,
+    Status,
+    BlocksByRange,
+    BlocksByRoots,
+    BlobsByRange,
+    BlobsByRoots,
+    GossipAttesterSlashing,
+    GossipProposerSlashing,
+    GossipVoluntaryExit,
+    GossipBlsToExecutionChange,
+    ApiRequestP1,
+    BackfillChainSegment,
+    LightClientBootstrap,
+    LightClientOptimisticUpdateRequest,
+    LightClientFinalityUpdateRequest,
+    GossipLightClientFinalityUpdate,
+    GossipLightClientOptimisticUpdate,
+    UnknownLightClientUpdate,
+);
+
+/// The order in which queues are drained, highest priority first.
+///
+/// Queues for which `WorkQueueId::is_drained` is `false` are omitted.
+pub const DEFAULT_PRIORITIES: &[WorkQueueId] = &[
+    // Check chain segments first, they're the most efficient way to get blocks into the system.
+    WorkQueueId::ChainSegment,
+    // Check sync blocks before gossip blocks, since we've already explicitly requested these
+    // blocks.
+    WorkQueueId::RpcBlock,
+    WorkQueueId::RpcBlob,
+    // Check delayed blocks before gossip blocks, the gossip blocks might rely on the delayed ones.
+    WorkQueueId::DelayedBlock,
+    // Check gossip blocks before gossip attestations, since a block might be required to verify
+    // some attestations.
+    WorkQueueId::GossipBlock,
+    WorkQueueId::GossipBlob,
+    // Check the priority 0 API requests after blocks and blobs, but before attestations.
+    WorkQueueId::ApiRequestP0,
+    // Check the aggregates, *then* the unaggregates since we assume that aggregates are more
+    // valuable to local validators and effectively give us more information with less signature
+    // verification time.
+    WorkQueueId::Aggregate,
+    WorkQueueId::Attestation,
+    // Check sync committee messages after attestations as their rewards are lesser and they
+    // don't influence fork choice.
+    WorkQueueId::SyncContribution,
+    WorkQueueId::SyncMessage,
+    // Aggregates and unaggregates queued for re-processing are older and we care about fresher
+    // ones, so check those first.
+    WorkQueueId::UnknownBlockAggregate,
+    WorkQueueId::UnknownBlockAttestation,
+    // Check RPC methods next. Status messages are needed for sync so prioritize them over syncing
+    // requests from other peers (BlocksByRange and BlocksByRoot).
+    WorkQueueId::Status,
+    WorkQueueId::BlocksByRange,
+    WorkQueueId::BlocksByRoots,
+    WorkQueueId::BlobsByRange,
+    WorkQueueId::BlobsByRoots,
+    // Check slashings after all other consensus messages so we prioritize following head.
+    //
+    // Check attester slashings before proposer slashings since they have the potential to slash
+    // multiple validators at once.
+    WorkQueueId::GossipAttesterSlashing,
+    WorkQueueId::GossipProposerSlashing,
+    // Check exits and address changes late since our validators don't get rewards from them.
+    WorkQueueId::GossipVoluntaryExit,
+    WorkQueueId::GossipBlsToExecutionChange,
+    // Check the priority 1 API requests after we've processed all the interesting things from
+    // the network and things required for us to stay in good repute with our P2P peers.
+    WorkQueueId::ApiRequestP1,
+    WorkQueueId::BackfillChainSegment,
+    WorkQueueId::LightClientBootstrap,
+    WorkQueueId::LightClientOptimisticUpdateRequest,
+    WorkQueueId::LightClientFinalityUpdateRequest,
+];
+
+/// Describes how the `BeaconProcessor` prioritises and bounds its work queues.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Every drained queue, in the order that they are drained when a worker becomes free.
+    pub priorities: Vec<WorkQueueId>,
+    /// Overrides for the maximum number of items held by each queue.
+    ///
+    /// Queues which are not present use a default derived from the size of the validator set.
+    pub queue_lengths: HashMap<WorkQueueId, usize>,
+    /// Overrides for the batch budget of each queue.
+    ///
+    /// For batchable queues this is the maximum number of items verified by a single worker, which
+    /// otherwise defaults to the batch size from the `BeaconProcessorConfig`. For all other queues
+    /// it is the number of items started in a row before a lower priority queue which has work is
+    /// given a turn, which is otherwise unlimited.
+    pub batch_sizes: HashMap<WorkQueueId, usize>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            priorities: DEFAULT_PRIORITIES.to_vec(),
+            queue_lengths: HashMap::new(),
+            batch_sizes: HashMap::new(),
+        }
+    }
+}
+
+impl From<SchedulerConfig> for eth2::lighthouse::SchedulerConfig {
+    fn from(config: SchedulerConfig) -> Self {
+        Self {
+            priorities: config.priorities.into_iter().map(Into::into).collect(),
+            queue_lengths: config
+                .queue_lengths
+                .into_iter()
+                .map(|(id, len)| (id.into(), len))
+                .collect(),
+            batch_sizes: config
+                .batch_sizes
+                .into_iter()
+                .map(|(id, size)| (id.into(), size))
+                .collect(),
+        }
+    }
+}
+
+impl From<eth2::lighthouse::SchedulerConfig> for SchedulerConfig {
+    fn from(config: eth2::lighthouse::SchedulerConfig) -> Self {
+        Self {
+            priorities: config.priorities.into_iter().map(Into::into).collect(),
+            queue_lengths: config
+                .queue_lengths
+                .into_iter()
+                .map(|(id, len)| (id.into(), len))
+                .collect(),
+            batch_sizes: config
+                .batch_sizes
+                .into_iter()
+                .map(|(id, size)| (id.into(), size))
+                .collect(),
+        }
+    }
+}
+
+impl SchedulerConfig {
+    /// Returns an error if the config would leave a queue undrained or unable to hold work.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        for id in &self.priorities {
+            if !id.is_drained() {
+                return Err(format!("{:?} is never drained and has no priority", id));
+            }
+            if !seen.insert(*id) {
+                return Err(format!("{:?} appears more than once in priorities", id));
+            }
+        }
+        if let Some(missing) = WorkQueueId::iter().find(|id| id.is_drained() && !seen.contains(id))
+        {
+            return Err(format!("{:?} is missing from priorities", missing));
+        }
+
+        if let Some((id, _)) = self.queue_lengths.iter().find(|(_, len)| **len == 0) {
+            return Err(format!("queue length for {:?} must be non-zero", id));
+        }
+
+        if let Some((id, _)) = self.batch_sizes.iter().find(|(_, size)| **size == 0) {
+            return Err(format!("batch size for {:?} must be non-zero", id));
+        }
+
+        Ok(())
+    }
+}
+
+/// A handle to the `SchedulerConfig` used by a running `BeaconProcessor`.
+///
+/// Updates are applied by the manager task before it next handles an event.
+#[derive(Clone)]
+pub struct BeaconProcessorScheduler {
+    config: Arc<RwLock<SchedulerConfig>>,
+    updated: Arc<AtomicBool>,
+}
+
+impl BeaconProcessorScheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(config)),
+            updated: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Returns the config which is currently in use, or will be once the manager wakes.
+    pub fn config(&self) -> SchedulerConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace the scheduler config, returning an error if `config` is invalid.
+    pub fn update(&self, config: SchedulerConfig) -> Result<(), String> {
+        config.validate()?;
+        *self.config.write() = config;
+        self.updated.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the config if it has been updated since the last call.
+    fn take_update(&self) -> Option<SchedulerConfig> {
+        if self.updated.swap(false, Ordering::AcqRel) {
+            Some(self.config())
+        } else {
+            None
+        }
+    }
+}
+
+/// The smallest batch which will be formed. Queues holding fewer items have each item processed
+/// individually.
+const MIN_BATCH_SIZE: usize = 2;
+
+/// The weight of the existing estimate when a new observation of the per-item cost is recorded,
+/// out of `BATCH_COST_WEIGHT_DENOMINATOR`.
+const BATCH_COST_WEIGHT_NUMERATOR: u64 = 4;
+const BATCH_COST_WEIGHT_DENOMINATOR: u64 = 5;
+
+/// Estimates of the time taken to verify a single item from each batchable queue.
+///
+/// The estimates are an exponentially weighted moving average of the batches verified by workers,
+/// which is shared between the manager task and the workers.
+#[derive(Clone, Default)]
+pub(crate) struct BatchCostEstimates {
+    attestation_nanos: Arc<AtomicU64>,
+    aggregate_nanos: Arc<AtomicU64>,
+}
+
+impl BatchCostEstimates {
+    fn nanos(&self, id: WorkQueueId) -> Option<&AtomicU64> {
+        match id {
+            WorkQueueId::Attestation => Some(&self.attestation_nanos),
+            WorkQueueId::Aggregate => Some(&self.aggregate_nanos),
+            _ => None,
+        }
+    }
+
+    /// Returns the estimated time to verify a single item from the queue, if there is one.
+    pub fn per_item(&self, id: WorkQueueId) -> Option<Duration> {
+        self.nanos(id)
+            .map(|nanos| nanos.load(Ordering::Relaxed))
+            .filter(|nanos| *nanos > 0)
+            .map(Duration::from_nanos)
+    }
+
+    /// Update the estimate for the queue with a batch of `batch_len` items which took `elapsed` to
+    /// verify.
+    pub fn record(&self, id: WorkQueueId, batch_len: usize, elapsed: Duration) {
+        let (Some(nanos), Ok(batch_len)) = (self.nanos(id), u64::try_from(batch_len)) else {
+            return;
+        };
+        if batch_len == 0 {
+            return;
+        }
+
+        let sample = u64::try_from(elapsed.as_nanos())
+            .unwrap_or(u64::MAX)
+            .checked_div(batch_len)
+            .unwrap_or(0)
+            .max(1);
+        let _ = nanos.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |previous| {
+            if previous == 0 {
+                Some(sample)
+            } else {
+                Some(
+                    previous
+                        .saturating_mul(BATCH_COST_WEIGHT_NUMERATOR)
+                        .saturating_add(sample.saturating_mul(
+                            BATCH_COST_WEIGHT_DENOMINATOR - BATCH_COST_WEIGHT_NUMERATOR,
+                        ))
+                        / BATCH_COST_WEIGHT_DENOMINATOR,
+                )
+            }
+        });
+
+        if let Some(per_item) = self.per_item(id) {
+            metrics::set_float_gauge_vec(
+                &metrics::BEACON_PROCESSOR_BATCH_ITEM_COST_SECONDS,
+                &[id.into()],
+                per_item.as_secs_f64(),
+            );
+        }
+    }
+}
+
+/// Returns the number of items to take from a queue of `queue_len` items to form a batch.
+///
+/// The batch is no larger than `max_batch_size`, nor larger than necessary to spread the queue
+/// across `idle_workers`. If `per_item_cost` is known, the batch is also no larger than the number
+/// of items which can be verified within `latency_target`.
+///
+/// A result of less than `MIN_BATCH_SIZE` indicates that a single item should be processed
+/// individually.
+fn adaptive_batch_size(
+    queue_len: usize,
+    max_batch_size: usize,
+    idle_workers: usize,
+    per_item_cost: Option<Duration>,
+    latency_target: Duration,
+) -> usize {
+    let spread = queue_len.div_ceil(cmp::max(idle_workers, 1));
+    let latency_bound = per_item_cost
+        .and_then(|cost| latency_target.as_nanos().checked_div(cost.as_nanos()))
+        .map_or(max_batch_size, |items| {
+            usize::try_from(items).unwrap_or(usize::MAX)
+        });
+    let upper_bound = cmp::max(cmp::min(max_batch_size, latency_bound), MIN_BATCH_SIZE);
+
+    cmp::min(cmp::max(spread, MIN_BATCH_SIZE), upper_bound).min(queue_len)
+}
+
+/// An item of work along with the time it was placed on a queue.
+struct QueuedWork<E: EthSpec> {
+    work: Work<E>,
+    queued_at: Instant,
+}
+
+enum WorkQueue<E: EthSpec> {
+    Fifo(FifoQueue<QueuedWork<E>>),
+    Lifo(LifoQueue<QueuedWork<E>>),
+}
+
+impl<E: EthSpec> WorkQueue<E> {
+    fn new(id: WorkQueueId, max_length: usize) -> Self {
+        if id.is_lifo() {
+            WorkQueue::Lifo(LifoQueue::new(max_length))
+        } else {
+            WorkQueue::Fifo(FifoQueue::new(max_length))
+        }
+    }
+
+    fn pop(&mut self) -> Option<QueuedWork<E>> {
+        match self {
+            WorkQueue::Fifo(queue) => queue.pop(),
+            WorkQueue::Lifo(queue) => queue.pop(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            WorkQueue::Fifo(queue) => queue.len(),
+            WorkQueue::Lifo(queue) => queue.len(),
+        }
+    }
+
+    fn max_length(&self) -> usize {
+        match self {
+            WorkQueue::Fifo(queue) => queue.max_length,
+            WorkQueue::Lifo(queue) => queue.max_length,
+        }
+    }
+
+    /// Set the maximum length of the queue, returning any items beyond it.
+    ///
+    /// As when pushing to a full queue, FIFO queues drop their newest items and LIFO queues drop
+    /// their oldest.
+    fn set_max_length(&mut self, max_length: usize) -> VecDeque<QueuedWork<E>> {
+        let queue = match self {
+            WorkQueue::Fifo(queue) => {
+                queue.max_length = max_length;
+                &mut queue.queue
+            }
+            WorkQueue::Lifo(queue) => {
+                queue.max_length = max_length;
+                &mut queue.queue
+            }
+        };
+        if queue.len() > max_length {
+            queue.split_off(max_length)
+        } else {
+            VecDeque::new()
+        }
+    }
+}
+
+/// The set of work queues owned by the `BeaconProcessor` manager task.
+pub(crate) struct WorkQueues<E: EthSpec> {
+    /// Indexed by `WorkQueueId::index`.
+    queues: Vec<WorkQueue<E>>,
+    priorities: Vec<WorkQueueId>,
+    batch_sizes: HashMap<WorkQueueId, usize>,
+    /// The queue which work was last taken from, and the number of times in a row it was chosen.
+    dispatch_streak: Option<(WorkQueueId, usize)>,
+    default_lengths: BeaconProcessorQueueLengths,
+    default_attestation_batch_size: usize,
+    default_aggregate_batch_size: usize,
+    batch_latency_target: Option<Duration>,
+    batch_costs: BatchCostEstimates,
+    scheduler: BeaconProcessorScheduler,
+}
+
+impl<E: EthSpec> WorkQueues<E> {
+    pub fn new(
+        default_lengths: BeaconProcessorQueueLengths,
+        processor_config: &BeaconProcessorConfig,
+        scheduler: BeaconProcessorScheduler,
+    ) -> Self {
+        let config = scheduler.config();
+        let queues = WorkQueueId::iter()
+            .map(|id| {
+                let max_length = config
+                    .queue_lengths
+                    .get(&id)
+                    .copied()
+                    .unwrap_or_else(|| default_lengths.for_queue(id));
+                WorkQueue::new(id, max_length)
+            })
+            .collect();
+
+        Self {
+            queues,
+            priorities: config.priorities,
+            batch_sizes: config.batch_sizes,
+            dispatch_streak: None,
+            default_lengths,
+            default_attestation_batch_size: processor_config.max_gossip_attestation_batch_size,
+            default_aggregate_batch_size: processor_config.max_gossip_aggregate_batch_size,
+            batch_latency_target: processor_config.gossip_batch_latency_target,
+            batch_costs: BatchCostEstimates::default(),
+            scheduler,
+        }
+    }
+
+    /// Applies any pending update to the scheduler config, returning `true` if one was applied.
+    ///
+    /// Queues which are shortened drop the items which no longer fit.
+    pub fn apply_scheduler_update(&mut self, log: &Logger) -> bool {
+        let Some(config) = self.scheduler.take_update() else {
+            return false;
+        };
+
+        for id in WorkQueueId::iter() {
+            let max_length = config
+                .queue_lengths
+                .get(&id)
+                .copied()
+                .unwrap_or_else(|| self.default_lengths.for_queue(id));
+            let dropped = self.queues[id.index()].set_max_length(max_length);
+            if !dropped.is_empty() {
+                for item in &dropped {
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_WORK_EVENTS_IGNORED_COUNT,
+                        &[item.work.str_id()],
+                    );
+                }
+                warn!(
+                    log,
+                    "Dropped work whilst shortening queue";
+                    "msg" => "the scheduler update reduced the queue length below its contents",
+                    "queue" => <&'static str>::from(id),
+                    "dropped" => dropped.len(),
+                    "queue_len" => max_length,
+                );
+            }
+        }
+        self.priorities = config.priorities;
+        self.batch_sizes = config.batch_sizes;
+        true
+    }
+
+    /// Add `work` to the queue identified by `id`.
+    pub fn push(&mut self, id: WorkQueueId, work: Work<E>, log: &Logger) {
+        let work_id = work.str_id();
+        let item = QueuedWork {
+            work,
+            queued_at: Instant::now(),
+        };
+        match &mut self.queues[id.index()] {
+            WorkQueue::Fifo(queue) => queue.push(item, work_id, log),
+            WorkQueue::Lifo(queue) => queue.push(item),
+        }
+    }
+
+    /// Returns a handle to the per-item cost estimates, to be updated by workers.
+    pub fn batch_costs(&self) -> BatchCostEstimates {
+        self.batch_costs.clone()
+    }
+
+    /// Remove the next item of work from the highest priority queue which is not empty.
+    ///
+    /// Items from batchable queues are collected into a batch where possible. `idle_workers` is
+    /// the number of workers (including the one which will receive this work) that could be
+    /// spawned.
+    ///
+    /// A queue which has spent its batch budget is skipped in favour of lower priority queues, but
+    /// is still drained if they have no work.
+    pub fn pop_next(&mut self, idle_workers: usize, log: &Logger) -> Option<Work<E>> {
+        let mut budget_spent = None;
+        for i in 0..self.priorities.len() {
+            let id = self.priorities[i];
+            if self.queues[id.index()].len() == 0 {
+                continue;
+            }
+
+            if self.is_budget_spent(id) {
+                budget_spent.get_or_insert(id);
+            } else if let Some(work) = self.pop_work(id, idle_workers, log) {
+                return Some(work);
+            }
+        }
+        budget_spent.and_then(|id| self.pop_work(id, idle_workers, log))
+    }
+
+    /// Returns `true` if a non-batchable queue has been chosen as many times in a row as its
+    /// batch budget allows.
+    fn is_budget_spent(&self, id: WorkQueueId) -> bool {
+        if id.is_batchable() {
+            return false;
+        }
+        match (self.dispatch_streak, self.batch_sizes.get(&id)) {
+            (Some((streak_id, count)), Some(budget)) => streak_id == id && count >= *budget,
+            _ => false,
+        }
+    }
+
+    fn pop_work(&mut self, id: WorkQueueId, idle_workers: usize, log: &Logger) -> Option<Work<E>> {
+        let work = if id.is_batchable() {
+            self.pop_batch(id, idle_workers, log)
+        } else {
+            self.pop(id)
+        }?;
+
+        self.dispatch_streak = match self.dispatch_streak {
+            Some((streak_id, count)) if streak_id == id => Some((id, count.saturating_add(1))),
+            _ => Some((id, 1)),
+        };
+        Some(work)
+    }
+
+    pub fn len(&self, id: WorkQueueId) -> usize {
+        self.queues[id.index()].len()
+    }
+
+    pub fn max_length(&self, id: WorkQueueId) -> usize {
+        self.queues[id.index()].max_length()
+    }
+
+    pub fn is_full(&self, id: WorkQueueId) -> bool {
+        self.len(id) >= self.max_length(id)
+    }
+
+    fn max_batch_size(&self, id: WorkQueueId) -> usize {
+        self.batch_sizes.get(&id).copied().unwrap_or(match id {
+            WorkQueueId::Attestation => self.default_attestation_batch_size,
+            _ => self.default_aggregate_batch_size,
+        })
+    }
+
+    fn batch_size(&self, id: WorkQueueId, idle_workers: usize) -> usize {
+        let queue_len = self.len(id);
+        let max_batch_size = self.max_batch_size(id);
+        match self.batch_latency_target {
+            Some(latency_target) => adaptive_batch_size(
+                queue_len,
+                max_batch_size,
+                idle_workers,
+                self.batch_costs.per_item(id),
+                latency_target,
+            ),
+            None => cmp::min(queue_len, max_batch_size),
+        }
+    }
+
+    /// Pop a single item from the queue, recording the time it spent waiting.
+    fn pop(&mut self, id: WorkQueueId) -> Option<Work<E>> {
+        let QueuedWork { work, queued_at } = self.queues[id.index()].pop()?;
+        metrics::observe_timer_vec(
+            &metrics::BEACON_PROCESSOR_QUEUE_WAIT_SECONDS,
+            &[id.into()],
+            queued_at.elapsed(),
+        );
+        Some(work)
+    }
+
+    fn pop_batch(&mut self, id: WorkQueueId, idle_workers: usize, log: &Logger) -> Option<Work<E>> {
+        let batch_size = self.batch_size(id, idle_workers);
+
+        if batch_size < MIN_BATCH_SIZE {
+            // One single item is in the queue, process it individually.
+            return self.pop(id);
+        }
+
+        if let Some(histogram) =
+            metrics::get_histogram(&metrics::BEACON_PROCESSOR_BATCH_SIZE, &[id.into()])
+        {
+            histogram.observe(batch_size as f64);
+        }
+
+        // Collect two or more items into a batch, so they can take advantage of batch signature
+        // verification.
+        //
+        // Note: this will convert the `Work::GossipAttestation` and `Work::GossipAggregate` items
+        // into `Work::GossipAttestationBatch` and `Work::GossipAggregateBatch` items.
+        let work = match id {
+            WorkQueueId::Attestation => {
+                let mut attestations = Vec::with_capacity(batch_size);
+                let mut process_batch_opt = None;
+                for _ in 0..batch_size {
+                    match self.pop(id) {
+                        Some(Work::GossipAttestation {
+                            attestation,
+                            process_individual: _,
+                            process_batch,
+                        }) => {
+                            attestations.push(*attestation);
+                            if process_batch_opt.is_none() {
+                                process_batch_opt = Some(process_batch);
+                            }
+                        }
+                        Some(_) => error!(log, "Invalid item in attestation queue"),
+                        None => break,
+                    }
+                }
+
+                process_batch_opt.map(|process_batch| Work::GossipAttestationBatch {
+                    attestations,
+                    process_batch,
+                })
+            }
+            WorkQueueId::Aggregate => {
+                let mut aggregates = Vec::with_capacity(batch_size);
+                let mut process_batch_opt = None;
+                for _ in 0..batch_size {
+                    match self.pop(id) {
+                        Some(Work::GossipAggregate {
+                            aggregate,
+                            process_individual: _,
+                            process_batch,
+                        }) => {
+                            aggregates.push(*aggregate);
+                            if process_batch_opt.is_none() {
+                                process_batch_opt = Some(process_batch);
+                            }
+                        }
+                        Some(_) => error!(log, "Invalid item in aggregate queue"),
+                        None => break,
+                    }
+                }
+
+                process_batch_opt.map(|process_batch| Work::GossipAggregateBatch {
+                    aggregates,
+                    process_batch,
+                })
+            }
+            _ => return self.pop(id),
+        };
+
+        if work.is_none() {
+            // There is no good reason for this to happen, it is a serious logic error. Since we
+            // only form batches when multiple work items exist, we should always have a work
+            // closure at this point.
+            crit!(log, "Missing batch work"; "queue" => <&'static str>::from(id));
+        }
+        work
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use types::{BeaconState, Eth1Data, MainnetEthSpec};
+
+    fn work_queues(config: SchedulerConfig) -> WorkQueues<MainnetEthSpec> {
+        let spec = MainnetEthSpec::default_spec();
+        let state = BeaconState::<MainnetEthSpec>::new(0, Eth1Data::default(), &spec);
+        WorkQueues::new(
+            BeaconProcessorQueueLengths::from_state(&state, &spec).unwrap(),
+            &BeaconProcessorConfig::default(),
+            BeaconProcessorScheduler::new(config),
+        )
+    }
+
+    fn null_logger() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    #[test]
+    fn default_config_is_valid() {
+        SchedulerConfig::default().validate().unwrap();
+    }
+
+    #[test]
+    fn default_priorities_cover_all_drained_queues() {
+        assert_eq!(
+            DEFAULT_PRIORITIES.len(),
+            WorkQueueId::iter().filter(|id| id.is_drained()).count()
+        );
+    }
+
+    #[test]
+    fn rejects_undrained_queue() {
+        let mut config = SchedulerConfig::default();
+        config
+            .priorities
+            .push(WorkQueueId::GossipLightClientFinalityUpdate);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_missing_queue() {
+        let mut config = SchedulerConfig::default();
+        config
+            .priorities
+            .retain(|id| *id != WorkQueueId::BackfillChainSegment);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_queue() {
+        let mut config = SchedulerConfig::default();
+        config.priorities.push(WorkQueueId::GossipBlock);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_zero_queue_length() {
+        let mut config = SchedulerConfig::default();
+        config.queue_lengths.insert(WorkQueueId::Status, 0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_zero_batch_size() {
+        let mut config = SchedulerConfig::default();
+        config.batch_sizes.insert(WorkQueueId::Attestation, 32);
+        config.batch_sizes.insert(WorkQueueId::GossipBlock, 4);
+        config.validate().unwrap();
+
+        config.batch_sizes.insert(WorkQueueId::BlocksByRange, 0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut config = SchedulerConfig::default();
+        config.priorities.swap(0, 1);
+        config.queue_lengths.insert(WorkQueueId::RpcBlock, 2048);
+        config.batch_sizes.insert(WorkQueueId::Aggregate, 16);
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<SchedulerConfig>(&json).unwrap(),
+            config
+        );
+
+        // The `eth2` types share the same representation.
+        let api_config = eth2::lighthouse::SchedulerConfig::from(config.clone());
+        assert_eq!(serde_json::to_string(&api_config).unwrap(), json);
+        assert_eq!(SchedulerConfig::from(api_config), config);
+    }
+
+    #[test]
+    fn work_queue_id_conversion_round_trip() {
+        for id in WorkQueueId::iter() {
+            let api_id = eth2::lighthouse::WorkQueueId::from(id);
+            assert_eq!(
+                serde_json::to_string(&api_id).unwrap(),
+                serde_json::to_string(&id).unwrap()
+            );
+            assert_eq!(WorkQueueId::from(api_id), id);
+        }
+    }
+
+    #[test]
+    fn adaptive_batch_size_spreads_queue_across_idle_workers() {
+        let target = Duration::from_millis(100);
+
+        // A single item is processed individually.
+        assert_eq!(adaptive_batch_size(1, 64, 4, None, target), 1);
+        // A shallow queue is split between the idle workers.
+        assert_eq!(adaptive_batch_size(16, 64, 4, None, target), 4);
+        assert_eq!(adaptive_batch_size(17, 64, 4, None, target), 5);
+        // Batches are never smaller than the minimum whilst enough items are queued.
+        assert_eq!(adaptive_batch_size(3, 64, 4, None, target), 2);
+        // A deep queue is limited by the maximum batch size.
+        assert_eq!(adaptive_batch_size(1_000, 64, 1, None, target), 64);
+    }
+
+    #[test]
+    fn adaptive_batch_size_respects_latency_target() {
+        let target = Duration::from_millis(100);
+
+        // 10 items fit within the target.
+        let cost = Some(Duration::from_millis(10));
+        assert_eq!(adaptive_batch_size(1_000, 64, 1, cost, target), 10);
+        // The maximum batch size still applies for cheap items.
+        let cost = Some(Duration::from_micros(1));
+        assert_eq!(adaptive_batch_size(1_000, 64, 1, cost, target), 64);
+        // Very expensive items still form minimum sized batches.
+        let cost = Some(Duration::from_secs(1));
+        assert_eq!(
+            adaptive_batch_size(1_000, 64, 1, cost, target),
+            MIN_BATCH_SIZE
+        );
+    }
+
+    #[test]
+    fn batch_cost_estimates_are_smoothed() {
+        let costs = BatchCostEstimates::default();
+        assert_eq!(costs.per_item(WorkQueueId::Attestation), None);
+
+        costs.record(WorkQueueId::Attestation, 10, Duration::from_millis(10));
+        assert_eq!(
+            costs.per_item(WorkQueueId::Attestation),
+            Some(Duration::from_millis(1))
+        );
+
+        // The new observation is given a weight of 1/5.
+        costs.record(WorkQueueId::Attestation, 10, Duration::from_millis(60));
+        assert_eq!(
+            costs.per_item(WorkQueueId::Attestation),
+            Some(Duration::from_millis(2))
+        );
+
+        // Other queues are unaffected, and unbatchable queues are ignored.
+        assert_eq!(costs.per_item(WorkQueueId::Aggregate), None);
+        costs.record(WorkQueueId::GossipBlock, 10, Duration::from_millis(10));
+        assert_eq!(costs.per_item(WorkQueueId::GossipBlock), None);
+    }
+
+    #[test]
+    fn update_applies_only_valid_configs() {
+        let scheduler = BeaconProcessorScheduler::new(SchedulerConfig::default());
+        assert!(scheduler.take_update().is_none());
+
+        let mut invalid = SchedulerConfig::default();
+        invalid.priorities.clear();
+        assert!(scheduler.update(invalid).is_err());
+        assert!(scheduler.take_update().is_none());
+
+        let mut valid = SchedulerConfig::default();
+        valid.priorities.reverse();
+        scheduler.update(valid.clone()).unwrap();
+        assert_eq!(scheduler.take_update(), Some(valid));
+        assert!(scheduler.take_update().is_none());
+    }
+
+    #[test]
+    fn batch_budget_gives_lower_priority_queues_a_turn() {
+        let log = null_logger();
+        let mut config = SchedulerConfig::default();
+        config.batch_sizes.insert(WorkQueueId::Status, 2);
+        let mut queues = work_queues(config);
+
+        for _ in 0..3 {
+            queues.push(WorkQueueId::Status, Work::Status(Box::new(|| {})), &log);
+        }
+        queues.push(
+            WorkQueueId::BlobsByRange,
+            Work::BlobsByRangeRequest(Box::new(|| {})),
+            &log,
+        );
+
+        let popped = std::iter::from_fn(|| queues.pop_next(1, &log))
+            .map(|work| matches!(work, Work::Status(_)))
+            .collect::<Vec<_>>();
+        assert_eq!(popped, vec![true, true, false, true]);
+    }
+
+    #[test]
+    fn shrinking_a_queue_drops_excess_items() {
+        let log = null_logger();
+        let mut queues = work_queues(SchedulerConfig::default());
+
+        let processed = Arc::new(Mutex::new(vec![]));
+        for i in 0..4 {
+            let processed = processed.clone();
+            queues.push(
+                WorkQueueId::SyncMessage,
+                Work::GossipSyncSignature(Box::new(move || processed.lock().push(i))),
+                &log,
+            );
+        }
+
+        let mut config = SchedulerConfig::default();
+        config.queue_lengths.insert(WorkQueueId::SyncMessage, 2);
+        queues.scheduler.update(config).unwrap();
+        assert!(queues.apply_scheduler_update(&log));
+        assert_eq!(queues.len(WorkQueueId::SyncMessage), 2);
+
+        // The LIFO queue keeps its newest items.
+        while let Some(work) = queues.pop_next(1, &log) {
+            match work {
+                Work::GossipSyncSignature(process_fn) => process_fn(),
+                _ => panic!("unexpected work"),
+            }
+        }
+        assert_eq!(*processed.lock(), vec![3, 2]);
+    }
+}
@@ -32,7 +32,7 @@ use execution_layer::ExecutionLayer;
 use futures::channel::mpsc::Receiver;
 use genesis::{interop_genesis_state, Eth1GenesisService, DEFAULT_ETH1_BLOCK_HASH};
 use lighthouse_network::{prometheus_client::registry::Registry, NetworkGlobals};
-use monitoring_api::{MonitoringHttpClient, ProcessType};
+use monitoring_api::{MonitoringClient, ProcessType};
 use network::{NetworkConfig, NetworkSenders, NetworkService};
 use slasher::Slasher;
 use slasher_service::SlasherService;
@@ -563,6 +563,7 @@ where
                         network_globals: None,
                         beacon_processor_send: None,
                         beacon_processor_reprocess_send: None,
+                        beacon_processor_scheduler: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
                         log: context.log().clone(),
                         sse_logging_components: runtime_context.sse_logging_components.clone(),
@@ -732,14 +733,14 @@ where
     }
 
     /// Start the explorer client which periodically sends beacon
-    /// and system metrics to the configured endpoint.
+    /// and system metrics to the configured destinations.
     pub fn monitoring_client(self, config: &monitoring_api::Config) -> Result<Self, String> {
         let context = self
             .runtime_context
             .as_ref()
             .ok_or("monitoring_client requires a runtime_context")?
             .service_context("monitoring_client".into());
-        let monitoring_client = MonitoringHttpClient::new(config, context.log().clone())?;
+        let monitoring_client = MonitoringClient::new(config, context.log().clone())?;
         monitoring_client.auto_update(
             context.executor,
             vec![ProcessType::BeaconNode, ProcessType::System],
@@ -812,6 +813,7 @@ where
                 beacon_processor_reprocess_send: Some(
                     beacon_processor_channels.work_reprocessing_tx.clone(),
                 ),
+                beacon_processor_scheduler: Some(beacon_processor_channels.scheduler.clone()),
                 sse_logging_components: runtime_context.sse_logging_components.clone(),
                 log: log.clone(),
             });
@@ -892,6 +894,7 @@ where
                             .beacon_state,
                         &beacon_chain.spec,
                     )?,
+                    beacon_processor_channels.scheduler.clone(),
                 )?;
             }
 
@@ -65,6 +65,8 @@ mod payload_status;
 pub mod test_utils;
 mod versioned_hashes;
 
+pub use versioned_hashes::{extract_blob_transactions, BlobTransaction};
+
 /// Indicates the default jwt authenticated execution endpoint.
 pub const DEFAULT_EXECUTION_ENDPOINT: &str = "http://localhost:8551/";
 
@@ -264,6 +264,11 @@ pub async fn handle_rpc<E: EthSpec>(
                 get_param(params, 0).map_err(|s| (s, BAD_PARAMS_ERROR_CODE))?;
             let id = request.into();
 
+            let delay = *ctx.get_payload_delay.lock();
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+
             let response = ctx
                 .execution_block_generator
                 .write()
@@ -21,6 +21,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::{runtime, sync::oneshot};
 use types::{EthSpec, ExecutionBlockHash, Uint256};
 use warp::{http::StatusCode, Filter, Rejection};
@@ -161,6 +162,7 @@ impl<E: EthSpec> MockServer<E> {
             new_payload_statuses: <_>::default(),
             fcu_payload_statuses: <_>::default(),
             syncing_response: Arc::new(Mutex::new(Ok(false))),
+            get_payload_delay: <_>::default(),
             engine_capabilities: Arc::new(RwLock::new(DEFAULT_ENGINE_CAPABILITIES)),
             _phantom: PhantomData,
         });
@@ -259,6 +261,11 @@ impl<E: EthSpec> MockServer<E> {
         *self.ctx.static_forkchoice_updated_response.lock() = Some(status);
     }
 
+    /// Delay each `engine_getPayload` response by `delay`, or remove the delay if `None`.
+    pub fn set_get_payload_delay(&self, delay: Option<Duration>) {
+        *self.ctx.get_payload_delay.lock() = delay;
+    }
+
     fn valid_status() -> PayloadStatusV1 {
         PayloadStatusV1 {
             status: PayloadStatusV1Status::Valid,
@@ -536,6 +543,9 @@ pub struct Context<E: EthSpec> {
     pub fcu_payload_statuses:
         Arc<Mutex<HashMap<ExecutionBlockHash, Result<PayloadStatusV1, String>>>>,
     pub syncing_response: Arc<Mutex<Result<bool, String>>>,
+    /// An artificial delay applied to each `engine_getPayload` response, used to simulate a slow
+    /// block proposer.
+    pub get_payload_delay: Arc<Mutex<Option<Duration>>>,
 
     pub engine_capabilities: Arc<RwLock<EngineCapabilities>>,
     pub _phantom: PhantomData<E>,
@@ -1,6 +1,7 @@
-use alloy_consensus::TxEnvelope;
+use alloy_consensus::{Signed, TxEip4844, TxEnvelope};
 use alloy_rlp::Decodable;
-use types::{EthSpec, ExecutionPayloadRef, Hash256, Unsigned, VersionedHash};
+use ethers_core::types::{RecoveryMessage, Signature, H256, U256};
+use types::{Address, EthSpec, ExecutionPayloadRef, Hash256, Unsigned, VersionedHash};
 
 #[derive(Debug)]
 pub enum Error {
@@ -64,6 +65,57 @@ pub fn extract_versioned_hashes_from_transactions<E: EthSpec>(
     Ok(versioned_hashes)
 }
 
+/// The blob versioned hashes committed to by a single type 3 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobTransaction {
+    /// The sender of the transaction, or `None` if it could not be recovered from the signature.
+    pub sender: Option<Address>,
+    pub versioned_hashes: Vec<VersionedHash>,
+}
+
+pub fn extract_blob_transactions<E: EthSpec>(
+    transactions: &types::Transactions<E>,
+) -> Result<Vec<BlobTransaction>, Error> {
+    let mut blob_transactions = Vec::new();
+
+    for tx in transactions {
+        match beacon_tx_to_tx_envelope(tx)? {
+            TxEnvelope::Eip4844(signed_tx_eip4844) => {
+                blob_transactions.push(BlobTransaction {
+                    sender: recover_sender(&signed_tx_eip4844),
+                    versioned_hashes: signed_tx_eip4844
+                        .tx()
+                        .blob_versioned_hashes
+                        .iter()
+                        .map(|fb| Hash256::from(fb.0))
+                        .collect(),
+                });
+            }
+            TxEnvelope::Legacy(_)
+            | TxEnvelope::TaggedLegacy(_)
+            | TxEnvelope::Eip2930(_)
+            | TxEnvelope::Eip1559(_) => {}
+        }
+    }
+
+    Ok(blob_transactions)
+}
+
+/// Recovers the address which signed `tx`.
+fn recover_sender(tx: &Signed<TxEip4844>) -> Option<Address> {
+    let signature = tx.signature();
+    let signature = Signature {
+        r: U256::from_big_endian(&signature.r().to_be_bytes::<32>()),
+        s: U256::from_big_endian(&signature.s().to_be_bytes::<32>()),
+        v: 27 + u64::from(signature.v().y_parity()),
+    };
+    let message = RecoveryMessage::Hash(H256::from(tx.signature_hash().0));
+    signature
+        .recover(message)
+        .ok()
+        .map(|sender| Address::from_slice(sender.as_bytes()))
+}
+
 pub fn beacon_tx_to_tx_envelope<N: Unsigned>(
     tx: &types::Transaction<N>,
 ) -> Result<TxEnvelope, Error> {
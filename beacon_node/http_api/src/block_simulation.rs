@@ -0,0 +1,47 @@
+use crate::{
+    build_block_contents,
+    version::{add_consensus_version_header, inconsistent_fork_rejection},
+};
+use beacon_chain::{block_simulation::BlockSimulation, BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::BlockSimulationQuery;
+use std::sync::Arc;
+use types::Slot;
+use warp::hyper::{Body, Response};
+
+/// Simulate the production of a block at `slot` and return it alongside its rewards and op pool
+/// packing summary.
+pub async fn get_block_simulation<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    slot: Slot,
+    query: BlockSimulationQuery,
+) -> Result<Response<Body>, warp::Rejection> {
+    let BlockSimulation {
+        block_response,
+        reward,
+        packing,
+    } = chain
+        .simulate_block_production(slot, query)
+        .await
+        .map_err(warp_utils::reject::block_production_error)?;
+
+    let fork_name = block_response
+        .fork_name(&chain.spec)
+        .map_err(inconsistent_fork_rejection)?;
+    let execution_payload_blinded = block_response.is_blinded();
+    let execution_payload_value = block_response.execution_payload_value();
+
+    let block = build_block_contents::build_block_contents(fork_name, block_response)?;
+
+    let simulation = eth2::lighthouse::BlockSimulation {
+        block,
+        execution_payload_blinded,
+        execution_payload_value,
+        rewards: reward,
+        packing,
+    };
+
+    Ok(add_consensus_version_header(
+        warp::reply::json(&simulation),
+        fork_name,
+    ))
+}
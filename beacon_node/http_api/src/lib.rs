@@ -10,6 +10,7 @@ mod attester_duties;
 mod block_id;
 mod block_packing_efficiency;
 mod block_rewards;
+mod block_simulation;
 mod build_block_contents;
 mod builder_states;
 mod database;
@@ -20,6 +21,7 @@ mod publish_attestations;
 mod publish_blocks;
 mod standard_block_rewards;
 mod state_id;
+mod state_proof;
 mod sync_committee_rewards;
 mod sync_committees;
 mod task_spawner;
@@ -32,11 +34,14 @@ mod version;
 
 use crate::produce_block::{produce_blinded_block_v2, produce_block_v2, produce_block_v3};
 use beacon_chain::{
-    attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
-    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, WhenSlotSkipped,
+    attestation_verification::VerifiedAttestation, events::SseEvent,
+    observed_operations::ObservationOutcome, validator_monitor::timestamp_now,
+    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
+    WhenSlotSkipped,
+};
+use beacon_processor::{
+    work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorScheduler, BeaconProcessorSend,
 };
-use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
 pub use block_id::BlockId;
 use builder_states::get_next_withdrawals;
 use bytes::Bytes;
@@ -46,7 +51,9 @@ use eth2::types::{
     PublishBlockRequest, ValidatorBalancesRequestBody, ValidatorId, ValidatorStatus,
     ValidatorsRequestBody,
 };
-use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
+use eth2::{
+    CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, LAST_EVENT_ID_HEADER, SSZ_CONTENT_TYPE_HEADER,
+};
 use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
@@ -130,6 +137,7 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub beacon_processor_send: Option<BeaconProcessorSend<T::EthSpec>>,
     pub beacon_processor_reprocess_send: Option<Sender<ReprocessQueueMessage>>,
+    pub beacon_processor_scheduler: Option<BeaconProcessorScheduler>,
     pub eth1_service: Option<eth1::Service>,
     pub sse_logging_components: Option<SSELoggingComponents>,
     pub log: Logger,
@@ -534,6 +542,19 @@ pub fn serve<T: BeaconChainTypes>(
         .filter(|_| config.enable_beacon_processor);
     let reprocess_send_filter = warp::any().map(move || beacon_processor_reprocess_send.clone());
 
+    // Create a `warp` filter that provides access to the beacon processor's scheduler.
+    let inner_beacon_processor_scheduler = ctx.beacon_processor_scheduler.clone();
+    let beacon_processor_scheduler_filter = warp::any()
+        .map(move || inner_beacon_processor_scheduler.clone())
+        .and_then(|scheduler| async move {
+            match scheduler {
+                Some(scheduler) => Ok(scheduler),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "beacon processor is not initialized.".to_string(),
+                )),
+            }
+        });
+
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
 
     /*
@@ -4051,6 +4072,30 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_choice/snapshot
+    let get_lighthouse_fork_choice_snapshot = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path("snapshot"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    Response::builder()
+                        .status(200)
+                        .body(chain.fork_choice_snapshot().as_ssz_bytes().into())
+                        .map(|res: Response<Body>| add_ssz_content_type_header(res))
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "failed to create response: {}",
+                                e
+                            ))
+                        })
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4206,7 +4251,7 @@ pub fn serve<T: BeaconChainTypes>(
     let post_lighthouse_database_reconstruct = database_path
         .and(warp::path("reconstruct"))
         .and(warp::path::end())
-        .and(not_while_syncing_filter)
+        .and(not_while_syncing_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -4221,6 +4266,49 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/beacon_processor/scheduler
+    let get_lighthouse_beacon_processor_scheduler = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path("scheduler"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(beacon_processor_scheduler_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, scheduler: BeaconProcessorScheduler| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    Ok(eth2::lighthouse::SchedulerConfig::from(scheduler.config()))
+                })
+            },
+        );
+
+    // POST lighthouse/beacon_processor/scheduler
+    let post_lighthouse_beacon_processor_scheduler = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path("scheduler"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(beacon_processor_scheduler_filter)
+        .and(log_filter.clone())
+        .then(
+            |scheduler_config: eth2::lighthouse::SchedulerConfig,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             scheduler: BeaconProcessorScheduler,
+             log: Logger| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    scheduler
+                        .update(scheduler_config.clone().into())
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    info!(
+                        log,
+                        "Beacon processor scheduler updated";
+                        "priorities" => ?scheduler_config.priorities,
+                    );
+                    Ok(scheduler_config)
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4286,6 +4374,60 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/block_simulation/{slot}
+    let get_lighthouse_block_simulation = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("block_simulation"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::BlockSimulationQuery>())
+        .and(not_while_syncing_filter)
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot,
+             query,
+             not_synced_filter: Result<(), Rejection>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    not_synced_filter?;
+                    block_simulation::get_block_simulation(chain, slot, query).await
+                })
+            },
+        );
+
+    // GET lighthouse/proof/states/{state_id}
+    let get_lighthouse_proof_states = warp::path("lighthouse")
+        .and(warp::path("proof"))
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid state ID".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(multi_key_query::<eth2::lighthouse::StateProofQuery>())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |state_id: StateId,
+             query_res: Result<eth2::lighthouse::StateProofQuery, warp::Rejection>,
+             accept_header: Option<api_types::Accept>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let query = query_res?;
+                    state_proof::get_state_proof(state_id, query, accept_header, &chain)
+                })
+            },
+        );
+
     // GET lighthouse/merge_readiness
     let get_lighthouse_merge_readiness = warp::path("lighthouse")
         .and(warp::path("merge_readiness"))
@@ -4309,77 +4451,58 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("events"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::EventQuery>())
+        .and(warp::header::optional::<String>(LAST_EVENT_ID_HEADER))
         .and(task_spawner_filter.clone())
         .and(chain_filter)
         .then(
             |topics_res: Result<api_types::EventQuery, warp::Rejection>,
+             last_event_id: Option<String>,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_response_task(Priority::P0, move || {
                     let topics = topics_res?;
+                    let last_event_id = last_event_id
+                        .map(|id| {
+                            id.parse::<u64>().map_err(|e| {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "invalid {LAST_EVENT_ID_HEADER}: {e:?}"
+                                ))
+                            })
+                        })
+                        .transpose()?;
+
+                    let sse_event = |event: SseEvent<T::EthSpec>| {
+                        Event::default()
+                            .id(event.id.to_string())
+                            .event(event.kind.topic_name())
+                            .json_data(event.kind)
+                            .unwrap_or_else(|e| {
+                                Event::default().comment(format!("error - bad json: {e:?}"))
+                            })
+                    };
+
                     // for each topic subscribed spawn a new subscription
                     let mut receivers = Vec::with_capacity(topics.topics.len());
+                    // events missed by a reconnecting client, which are sent before any others
+                    let mut missed_events = vec![];
 
                     if let Some(event_handler) = chain.event_handler.as_ref() {
                         for topic in topics.topics {
-                            let receiver = match topic {
-                                api_types::EventTopic::Head => event_handler.subscribe_head(),
-                                api_types::EventTopic::Block => event_handler.subscribe_block(),
-                                api_types::EventTopic::BlobSidecar => {
-                                    event_handler.subscribe_blob_sidecar()
-                                }
-                                api_types::EventTopic::Attestation => {
-                                    event_handler.subscribe_attestation()
-                                }
-                                api_types::EventTopic::VoluntaryExit => {
-                                    event_handler.subscribe_exit()
-                                }
-                                api_types::EventTopic::FinalizedCheckpoint => {
-                                    event_handler.subscribe_finalized()
-                                }
-                                api_types::EventTopic::ChainReorg => {
-                                    event_handler.subscribe_reorgs()
-                                }
-                                api_types::EventTopic::ContributionAndProof => {
-                                    event_handler.subscribe_contributions()
-                                }
-                                api_types::EventTopic::PayloadAttributes => {
-                                    event_handler.subscribe_payload_attributes()
-                                }
-                                api_types::EventTopic::LateHead => {
-                                    event_handler.subscribe_late_head()
-                                }
-                                api_types::EventTopic::LightClientFinalityUpdate => {
-                                    event_handler.subscribe_light_client_finality_update()
-                                }
-                                api_types::EventTopic::LightClientOptimisticUpdate => {
-                                    event_handler.subscribe_light_client_optimistic_update()
-                                }
-                                api_types::EventTopic::BlockReward => {
-                                    event_handler.subscribe_block_reward()
-                                }
-                                api_types::EventTopic::AttesterSlashing => {
-                                    event_handler.subscribe_attester_slashing()
-                                }
-                                api_types::EventTopic::ProposerSlashing => {
-                                    event_handler.subscribe_proposer_slashing()
-                                }
-                                api_types::EventTopic::BlsToExecutionChange => {
-                                    event_handler.subscribe_bls_to_execution_change()
+                            let receiver = match last_event_id {
+                                Some(last_event_id) => {
+                                    let (missed, receiver) =
+                                        event_handler.subscribe_after(topic, last_event_id);
+                                    missed_events.extend(missed);
+                                    receiver
                                 }
+                                None => event_handler.subscribe(topic),
                             };
 
                             receivers.push(
                                 BroadcastStream::new(receiver)
-                                    .map(|msg| {
+                                    .map(move |msg| {
                                         match msg {
-                                            Ok(data) => Event::default()
-                                                .event(data.topic_name())
-                                                .json_data(data)
-                                                .unwrap_or_else(|e| {
-                                                    Event::default()
-                                                        .comment(format!("error - bad json: {e:?}"))
-                                                }),
+                                            Ok(event) => sse_event(event),
                                             // Do not terminate the stream if the channel fills
                                             // up. Just drop some messages and send a comment to
                                             // the client.
@@ -4399,7 +4522,14 @@ pub fn serve<T: BeaconChainTypes>(
                         ));
                     }
 
-                    let s = futures::stream::select_all(receivers);
+                    missed_events.sort_unstable_by_key(|event| event.id);
+                    let missed = futures::stream::iter(
+                        missed_events
+                            .into_iter()
+                            .map(sse_event)
+                            .map(Ok::<_, std::convert::Infallible>),
+                    );
+                    let s = missed.chain(futures::stream::select_all(receivers));
 
                     Ok(warp::sse::reply(warp::sse::keep_alive().stream(s)))
                 })
@@ -4507,6 +4637,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_fork_choice_snapshot)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
                 .uor(get_lighthouse_eth1_syncing)
@@ -4514,6 +4645,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_beacon_processor_scheduler)
                 .uor(get_lighthouse_block_rewards)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(
@@ -4529,6 +4661,8 @@ pub fn serve<T: BeaconChainTypes>(
                         .and(get_beacon_light_client_bootstrap),
                 )
                 .uor(get_lighthouse_block_packing_efficiency)
+                .uor(get_lighthouse_block_simulation)
+                .uor(get_lighthouse_proof_states)
                 .uor(get_lighthouse_merge_readiness)
                 .uor(get_events)
                 .uor(get_expected_withdrawals)
@@ -4571,6 +4705,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_beacon_processor_scheduler)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
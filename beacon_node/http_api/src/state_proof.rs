@@ -0,0 +1,79 @@
+use crate::{state_id::StateId, version::add_ssz_content_type_header};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{MerkleProof, StateProof, StateProofQuery};
+use eth2::types::Accept;
+use ssz::Encode;
+use types::BeaconStateError;
+use warp::{
+    hyper::{Body, Response},
+    Reply,
+};
+
+/// Compute Merkle proofs against the root of the state identified by `state_id` for each of the
+/// generalized indices and field paths in `query`.
+pub fn get_state_proof<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: StateProofQuery,
+    accept_header: Option<Accept>,
+    chain: &BeaconChain<T>,
+) -> Result<Response<Body>, warp::Rejection> {
+    let StateProofQuery { gindex, path } = query;
+    if gindex.is_none() && path.is_none() {
+        return Err(warp_utils::reject::custom_bad_request(
+            "at least one gindex or path must be provided".to_string(),
+        ));
+    }
+
+    let (mut state, _execution_optimistic, _finalized) = state_id.state(chain)?;
+    let state_root = state
+        .update_tree_hash_cache()
+        .map_err(warp_utils::reject::beacon_state_error)?;
+
+    let mut gindices = gindex.unwrap_or_default();
+    for path in path.unwrap_or_default() {
+        gindices.push(
+            state
+                .generalized_index_for_path(&path)
+                .map_err(proof_error)?,
+        );
+    }
+
+    let proofs = gindices
+        .into_iter()
+        .map(|gindex| {
+            let (leaf, branch) = state
+                .compute_generalized_merkle_proof(gindex, &chain.state_proof_cache)
+                .map_err(proof_error)?;
+            Ok(MerkleProof {
+                gindex,
+                leaf,
+                branch,
+            })
+        })
+        .collect::<Result<Vec<_>, warp::Rejection>>()?;
+
+    let proof = StateProof { state_root, proofs };
+
+    match accept_header {
+        Some(Accept::Ssz) => Response::builder()
+            .status(200)
+            .body(proof.as_ssz_bytes().into())
+            .map(|res: Response<Body>| add_ssz_content_type_header(res))
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+            }),
+        _ => Ok(warp::reply::json(&proof).into_response()),
+    }
+}
+
+fn proof_error(e: BeaconStateError) -> warp::Rejection {
+    match e {
+        BeaconStateError::InvalidGeneralizedIndex => {
+            warp_utils::reject::custom_bad_request("invalid generalized index".to_string())
+        }
+        BeaconStateError::InvalidProofPath(e) => {
+            warp_utils::reject::custom_bad_request(format!("invalid path: {e}"))
+        }
+        e => warp_utils::reject::beacon_state_error(e),
+    }
+}
@@ -190,6 +190,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         beacon_processor_rx,
         work_reprocessing_tx,
         work_reprocessing_rx,
+        scheduler,
     } = BeaconProcessorChannels::new(&beacon_processor_config);
 
     let beacon_processor_send = beacon_processor_tx;
@@ -213,6 +214,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
             &chain.spec,
         )
         .unwrap(),
+        scheduler.clone(),
     )
     .unwrap();
 
@@ -229,6 +231,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         network_globals: Some(network_globals),
         beacon_processor_send: Some(beacon_processor_send),
         beacon_processor_reprocess_send: Some(reprocess_send),
+        beacon_processor_scheduler: Some(scheduler),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
         log,
@@ -1,16 +1,18 @@
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
+    fork_choice_snapshot::ForkChoiceSnapshot,
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
 use environment::null_logger;
 use eth2::{
+    lighthouse::{BlockSimulationQuery, StateProofQuery, WorkQueueId},
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{
         BlockId as CoreBlockId, ForkChoiceNode, ProduceBlockV3Response, StateId as CoreStateId, *,
     },
-    BeaconNodeHttpClient, Error, StatusCode, Timeouts,
+    BeaconNodeHttpClient, Error, StatusCode, Timeouts, LAST_EVENT_ID_HEADER,
 };
 use execution_layer::test_utils::{
     MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
@@ -26,6 +28,7 @@ use network::NetworkReceivers;
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
+use ssz::Decode;
 use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::per_slot_processing;
 use state_processing::state_advance::partial_state_advance;
@@ -5072,6 +5075,26 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_fork_choice_snapshot(self) -> Self {
+        let bytes = self
+            .client
+            .get_lighthouse_fork_choice_snapshot_ssz()
+            .await
+            .unwrap()
+            .unwrap();
+        let snapshot = ForkChoiceSnapshot::from_ssz_bytes(&bytes).unwrap();
+        let fc_store = snapshot.persisted_fork_choice.fork_choice_store;
+        let cached_head = self.chain.canonical_head.cached_head();
+
+        assert_eq!(snapshot.head_block_root, cached_head.head_block_root());
+        assert_eq!(
+            fc_store.justified_checkpoint,
+            cached_head.justified_checkpoint()
+        );
+
+        self
+    }
+
     pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
         self.client
@@ -5139,6 +5162,109 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_analysis_block_simulation(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let head_root = self.chain.head_beacon_block_root();
+
+        let simulation = self
+            .client
+            .get_lighthouse_analysis_block_simulation::<E>(slot, &BlockSimulationQuery::default())
+            .await
+            .unwrap();
+        let packing = simulation.packing;
+        assert_eq!(
+            packing.attestations.included,
+            packing.packed_attestations.len()
+        );
+        assert_eq!(packing.attestations.selected, packing.attestations.included);
+
+        let excluded = self
+            .client
+            .get_lighthouse_analysis_block_simulation::<E>(
+                slot,
+                &BlockSimulationQuery {
+                    exclude_attestations: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(excluded.packing.attestations.included, 0);
+        assert!(excluded.packing.packed_attestations.is_empty());
+        assert_eq!(excluded.rewards.attestations, 0);
+
+        // Simulating a block must not import it.
+        assert_eq!(self.chain.head_beacon_block_root(), head_root);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proof_states(self) -> Self {
+        let mut state = self.chain.head_beacon_state_cloned();
+        let state_root = state.update_tree_hash_cache().unwrap();
+        let slot_gindex = state.generalized_index_for_path("slot").unwrap();
+
+        let query = StateProofQuery {
+            gindex: Some(vec![slot_gindex]),
+            path: Some(vec![
+                "validators.3.effective_balance".to_string(),
+                "balances.5".to_string(),
+                "finalized_checkpoint.root".to_string(),
+            ]),
+        };
+        let proof = self
+            .client
+            .get_lighthouse_proof_states(CoreStateId::Root(state_root), &query)
+            .await
+            .unwrap();
+
+        assert_eq!(proof.state_root, state_root);
+        assert_eq!(proof.proofs.len(), 4);
+        assert_eq!(proof.proofs[0].gindex, slot_gindex);
+        assert_eq!(proof.proofs[0].leaf, state.slot().tree_hash_root());
+        assert_eq!(
+            proof.proofs[1].leaf,
+            state
+                .get_validator(3)
+                .unwrap()
+                .effective_balance
+                .tree_hash_root()
+        );
+        for merkle_proof in &proof.proofs {
+            let depth = 63 - merkle_proof.gindex.leading_zeros() as usize;
+            let index = merkle_proof.gindex as usize - (1 << depth);
+            assert!(merkle_proof::verify_merkle_proof(
+                merkle_proof.leaf,
+                &merkle_proof.branch,
+                depth,
+                index,
+                state_root
+            ));
+        }
+
+        let invalid_queries = [
+            StateProofQuery::default(),
+            StateProofQuery {
+                gindex: Some(vec![0]),
+                path: None,
+            },
+            StateProofQuery {
+                gindex: None,
+                path: Some(vec!["validators.1000000".to_string()]),
+            },
+        ];
+        for query in invalid_queries {
+            let err = self
+                .client
+                .get_lighthouse_proof_states(CoreStateId::Head, &query)
+                .await
+                .unwrap_err();
+            assert_eq!(err.status().unwrap(), 400);
+        }
+
+        self
+    }
+
     pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
         let response = self
             .client
@@ -5149,6 +5275,62 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_lighthouse_beacon_processor_scheduler(self) -> Self {
+        let config = self
+            .client
+            .get_lighthouse_beacon_processor_scheduler()
+            .await
+            .unwrap();
+        assert_eq!(config, beacon_processor::SchedulerConfig::default().into());
+
+        // Prioritise block import over everything else, shrink the backfill queue and limit how
+        // many blocks by range requests are served in a row.
+        let mut new_config = config.clone();
+        new_config
+            .priorities
+            .retain(|id| *id != WorkQueueId::GossipBlock);
+        new_config.priorities.insert(0, WorkQueueId::GossipBlock);
+        new_config
+            .queue_lengths
+            .insert(WorkQueueId::BackfillChainSegment, 8);
+        new_config.batch_sizes.insert(WorkQueueId::Aggregate, 16);
+        new_config.batch_sizes.insert(WorkQueueId::BlocksByRange, 4);
+
+        let response = self
+            .client
+            .post_lighthouse_beacon_processor_scheduler(&new_config)
+            .await
+            .unwrap();
+        assert_eq!(response, new_config);
+        assert_eq!(
+            self.client
+                .get_lighthouse_beacon_processor_scheduler()
+                .await
+                .unwrap(),
+            new_config
+        );
+
+        // Gossip light client updates are never drained, so cannot be given a priority.
+        let mut invalid_config = new_config.clone();
+        invalid_config
+            .priorities
+            .push(WorkQueueId::GossipLightClientFinalityUpdate);
+        let err = self
+            .client
+            .post_lighthouse_beacon_processor_scheduler(&invalid_config)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        // Restore the default config for subsequent tests.
+        self.client
+            .post_lighthouse_beacon_processor_scheduler(&config)
+            .await
+            .unwrap();
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -5213,6 +5395,62 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_events_resume(self) -> Self {
+        let topics = [EventTopic::VoluntaryExit, EventTopic::BlsToExecutionChange];
+        let event_handler = self.chain.event_handler.as_ref().unwrap();
+        let mut receivers = topics.map(|topic| event_handler.subscribe(topic));
+
+        // Produce two events whilst subscribed, then disconnect.
+        self.client
+            .post_beacon_pool_voluntary_exits(&self.voluntary_exit)
+            .await
+            .unwrap();
+        let exit_event = receivers[0].recv().await.unwrap();
+        self.client
+            .post_beacon_pool_bls_to_execution_changes(&[self.bls_to_execution_change.clone()])
+            .await
+            .unwrap();
+        let bls_event = receivers[1].recv().await.unwrap();
+        assert!(bls_event.id > exit_event.id);
+        drop(receivers);
+
+        let mut url = eth2::Url::parse(self.client.as_ref()).unwrap();
+        url.set_path("eth/v1/events");
+        url.set_query(Some("topics=voluntary_exit,bls_to_execution_change"));
+
+        // Reconnecting after the exit event replays only the BLS to execution change.
+        let response = self
+            .client
+            .get_response(url.clone(), |b| {
+                b.header(LAST_EVENT_ID_HEADER, exit_event.id.to_string())
+            })
+            .await
+            .unwrap();
+        let mut stream = response.bytes_stream();
+        let replayed = tokio::time::timeout(Duration::from_secs(10), stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            EventKind::<E>::from_sse_bytes(replayed.as_ref()).unwrap(),
+            EventKind::BlsToExecutionChange(Box::new(self.bls_to_execution_change.clone()))
+        );
+        assert!(std::str::from_utf8(&replayed)
+            .unwrap()
+            .contains(&format!("id:{}", bls_event.id)));
+
+        // An invalid ID is rejected.
+        let err = self
+            .client
+            .get_response(url, |b| b.header(LAST_EVENT_ID_HEADER, "not-a-number"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_events(self) -> Self {
         // Subscribe to all events
         let topics = vec![
@@ -5617,6 +5855,11 @@ async fn get_events() {
     ApiTester::new().await.test_get_events().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_events_resume() {
+    ApiTester::new().await.test_get_events_resume().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_altair() {
     let mut config = ApiTesterConfig::default();
@@ -6457,6 +6700,8 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_fork_choice_snapshot()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -6471,8 +6716,14 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_database_info()
         .await
+        .test_get_lighthouse_analysis_block_simulation()
+        .await
+        .test_get_lighthouse_proof_states()
+        .await
         .test_post_lighthouse_database_reconstruct()
         .await
+        .test_lighthouse_beacon_processor_scheduler()
+        .await
         .test_post_lighthouse_liveness()
         .await;
 }
@@ -1,5 +1,6 @@
 use crate::listen_addr::{ListenAddr, ListenAddress};
 use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use crate::service::link_conditioner::NodeLinks;
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -97,6 +98,10 @@ pub struct Config {
     /// Disables quic support.
     pub disable_quic_support: bool,
 
+    /// Conditions applied to the links with other nodes in a local test network.
+    #[serde(skip)]
+    pub link_conditioner: Option<NodeLinks>,
+
     /// Attempt to construct external port mappings with UPnP.
     pub upnp_enabled: bool,
 
@@ -335,6 +340,7 @@ impl Default for Config {
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             disable_quic_support: false,
+            link_conditioner: None,
             upnp_enabled: true,
             network_load: 4,
             private: false,
@@ -124,5 +124,6 @@ pub use peer_manager::{
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Request, Response};
+pub use service::link_conditioner::{LinkConditioner, LinkConditions};
 pub use service::utils::*;
 pub use service::{Gossipsub, NetworkEvent};
@@ -0,0 +1,512 @@
+//! Fault injection for the TCP links between nodes in a local test network.
+//!
+//! A `LinkConditioner` is shared by all the nodes of a network which run in the same process.
+//! Each node identifies itself and its peers by their TCP listening port, so the conditioner is
+//! only suitable for networks where each node listens on a distinct port of the same host (e.g.,
+//! the simulator).
+//!
+//! Conditions are applied to the connections that a node dials, since only the dialer knows the
+//! listening port of the remote node. Every connection has a dialer, so every link is conditioned
+//! provided all nodes share the conditioner. Latency and loss are applied in both directions by
+//! delaying both writes and reads of the dialed stream.
+//!
+//! TCP is a reliable transport, so a lost packet manifests as a retransmission delay rather than
+//! missing data. Loss is modelled by delaying a write by `RETRANSMISSION_DELAY` with the
+//! configured probability. Delays never reorder data within a connection.
+//!
+//! A partition refuses new connections between the partitioned nodes and closes existing ones the
+//! next time they are read from or written to.
+
+use futures::io::{AsyncRead, AsyncWrite};
+use libp2p::core::ConnectedPoint;
+use libp2p::multiaddr::Protocol;
+use libp2p::Multiaddr;
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant, Sleep};
+
+/// The delay applied to a write which is "lost", approximating the minimum TCP retransmission
+/// timeout on Linux.
+pub const RETRANSMISSION_DELAY: Duration = Duration::from_millis(200);
+
+/// The maximum number of bytes that may be buffered in each direction of a conditioned stream
+/// before back-pressure is applied.
+const MAX_BUFFERED_BYTES: usize = 1 << 20;
+
+/// The size of the buffer used to read from the underlying stream.
+const READ_CHUNK_SIZE: usize = 16 * 1024;
+
+/// The conditions of the link between two nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkConditions {
+    /// The delay added to data sent in each direction.
+    pub latency: Duration,
+    /// The probability, between `0.0` and `1.0`, that a write in either direction is lost and
+    /// must be retransmitted.
+    pub packet_loss: f64,
+    /// Whether the nodes are unable to communicate at all.
+    pub partitioned: bool,
+}
+
+/// A controller for the conditions of the links between the nodes of a local network.
+///
+/// Conditions may be changed at any time and apply to existing connections.
+#[derive(Clone, Default)]
+pub struct LinkConditioner {
+    links: Arc<RwLock<HashMap<(u16, u16), LinkConditions>>>,
+}
+
+impl fmt::Debug for LinkConditioner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LinkConditioner")
+            .field("links", &*self.links.read())
+            .finish()
+    }
+}
+
+/// Links are symmetric, so are keyed by the ordered pair of ports.
+fn link_key(a: u16, b: u16) -> (u16, u16) {
+    (a.min(b), a.max(b))
+}
+
+impl LinkConditioner {
+    /// Returns a handle for the node listening on `tcp_port`, to be passed to its transport.
+    pub fn for_node(&self, tcp_port: u16) -> NodeLinks {
+        NodeLinks {
+            conditioner: self.clone(),
+            local_port: tcp_port,
+        }
+    }
+
+    /// Returns the conditions of the link between the nodes listening on `a` and `b`.
+    pub fn link_conditions(&self, a: u16, b: u16) -> LinkConditions {
+        self.links
+            .read()
+            .get(&link_key(a, b))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets the conditions of the link between the nodes listening on `a` and `b`.
+    pub fn set_link_conditions(&self, a: u16, b: u16, conditions: LinkConditions) {
+        let mut links = self.links.write();
+        if conditions == LinkConditions::default() {
+            links.remove(&link_key(a, b));
+        } else {
+            links.insert(link_key(a, b), conditions);
+        }
+    }
+
+    /// Partitions each group of nodes from the nodes in every other group. Nodes which are not in
+    /// any group are unaffected.
+    pub fn partition(&self, groups: &[Vec<u16>]) {
+        for (i, group) in groups.iter().enumerate() {
+            for other_group in &groups[i + 1..] {
+                for &a in group {
+                    for &b in other_group {
+                        let conditions = LinkConditions {
+                            partitioned: true,
+                            ..self.link_conditions(a, b)
+                        };
+                        self.set_link_conditions(a, b, conditions);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes all partitions, leaving the latency and loss of each link unchanged.
+    pub fn heal_partitions(&self) {
+        let mut links = self.links.write();
+        for conditions in links.values_mut() {
+            conditions.partitioned = false;
+        }
+        links.retain(|_, conditions| *conditions != LinkConditions::default());
+    }
+
+    /// Restores every link to perfect conditions.
+    pub fn clear(&self) {
+        self.links.write().clear();
+    }
+}
+
+/// The links of a single node, identified by its TCP listening port.
+#[derive(Debug, Clone)]
+pub struct NodeLinks {
+    conditioner: LinkConditioner,
+    local_port: u16,
+}
+
+impl NodeLinks {
+    /// Conditions a newly established connection, or refuses it if the nodes are partitioned.
+    ///
+    /// Only dialed connections are conditioned, see the module documentation.
+    pub(crate) fn condition<S>(
+        &self,
+        stream: S,
+        endpoint: &ConnectedPoint,
+    ) -> io::Result<ConditionedStream<S>> {
+        let link = match endpoint {
+            ConnectedPoint::Dialer { address, .. } => tcp_port(address).map(|remote_port| Link {
+                conditioner: self.conditioner.clone(),
+                local_port: self.local_port,
+                remote_port,
+            }),
+            ConnectedPoint::Listener { .. } => None,
+        };
+
+        if let Some(link) = &link {
+            if link.conditions().partitioned {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionRefused,
+                    "link is partitioned",
+                ));
+            }
+        }
+
+        Ok(ConditionedStream::new(stream, link))
+    }
+}
+
+fn tcp_port(address: &Multiaddr) -> Option<u16> {
+    address.iter().find_map(|protocol| match protocol {
+        Protocol::Tcp(port) => Some(port),
+        _ => None,
+    })
+}
+
+struct Link {
+    conditioner: LinkConditioner,
+    local_port: u16,
+    remote_port: u16,
+}
+
+impl Link {
+    fn conditions(&self) -> LinkConditions {
+        self.conditioner
+            .link_conditions(self.local_port, self.remote_port)
+    }
+}
+
+/// Data which may not be delivered before its deadline.
+struct DelayedQueue {
+    chunks: VecDeque<(Instant, Vec<u8>)>,
+    /// The number of bytes of the front chunk which have already been delivered.
+    offset: usize,
+    buffered_bytes: usize,
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl DelayedQueue {
+    fn new() -> Self {
+        Self {
+            chunks: VecDeque::new(),
+            offset: 0,
+            buffered_bytes: 0,
+            timer: None,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.buffered_bytes >= MAX_BUFFERED_BYTES
+    }
+
+    /// Queues `data` for delivery after the delay dictated by `conditions`, without reordering it
+    /// ahead of any data already queued.
+    fn push(&mut self, data: Vec<u8>, conditions: &LinkConditions) {
+        let mut delay = conditions.latency;
+        if conditions.packet_loss > 0.0 && rand::random::<f64>() < conditions.packet_loss {
+            delay += RETRANSMISSION_DELAY;
+        }
+        let mut deadline = Instant::now() + delay;
+        if let Some((last_deadline, _)) = self.chunks.back() {
+            deadline = deadline.max(*last_deadline);
+        }
+        self.buffered_bytes += data.len();
+        self.chunks.push_back((deadline, data));
+    }
+
+    /// Returns the undelivered bytes of the front chunk if its deadline has passed, otherwise
+    /// registers the waker to be woken at the deadline.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Option<&[u8]>> {
+        let Some((deadline, _)) = self.chunks.front() else {
+            return Poll::Ready(None);
+        };
+        let deadline = *deadline;
+        if deadline > Instant::now() {
+            let timer = self
+                .timer
+                .get_or_insert_with(|| Box::pin(sleep_until(deadline)));
+            if timer.deadline() != deadline {
+                timer.as_mut().reset(deadline);
+            }
+            if timer.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+        Poll::Ready(self.chunks.front().map(|(_, data)| &data[self.offset..]))
+    }
+
+    /// Marks `n` bytes of the front chunk as delivered.
+    fn consume(&mut self, n: usize) {
+        self.offset += n;
+        self.buffered_bytes -= n;
+        if self
+            .chunks
+            .front()
+            .is_some_and(|(_, data)| self.offset >= data.len())
+        {
+            self.chunks.pop_front();
+            self.offset = 0;
+        }
+    }
+}
+
+/// A stream which applies the conditions of its link to all data written and read.
+///
+/// Streams without a link (i.e., inbound connections) are passed through unmodified.
+pub struct ConditionedStream<S> {
+    inner: S,
+    link: Option<Link>,
+    writes: DelayedQueue,
+    reads: DelayedQueue,
+    read_eof: bool,
+}
+
+impl<S> ConditionedStream<S> {
+    /// Returns a stream which passes all data through unmodified.
+    pub(crate) fn unconditioned(inner: S) -> Self {
+        Self::new(inner, None)
+    }
+
+    fn new(inner: S, link: Option<Link>) -> Self {
+        Self {
+            inner,
+            link,
+            writes: DelayedQueue::new(),
+            reads: DelayedQueue::new(),
+            read_eof: false,
+        }
+    }
+
+    /// Returns the current conditions of the link, or an error if it has been partitioned.
+    fn conditions(&self) -> io::Result<Option<LinkConditions>> {
+        match self.link.as_ref().map(Link::conditions) {
+            Some(conditions) if conditions.partitioned => Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "link is partitioned",
+            )),
+            conditions => Ok(conditions),
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> ConditionedStream<S> {
+    /// Writes all queued data whose deadline has passed to the underlying stream.
+    fn poll_write_queued(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            let data = match self.writes.poll_ready(cx) {
+                Poll::Ready(Some(data)) => data,
+                Poll::Ready(None) => return Poll::Ready(Ok(())),
+                Poll::Pending => return Poll::Pending,
+            };
+            let written = match Pin::new(&mut self.inner).poll_write(cx, data) {
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Poll::Ready(Ok(written)) => written,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            self.writes.consume(written);
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ConditionedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let Some(conditions) = this.conditions()? else {
+            return Pin::new(&mut this.inner).poll_write(cx, buf);
+        };
+
+        if this.writes.is_full() {
+            // Apply back-pressure until some of the queued data has been written.
+            if let Poll::Ready(Err(e)) = this.poll_write_queued(cx) {
+                return Poll::Ready(Err(e));
+            }
+            if this.writes.is_full() {
+                return Poll::Pending;
+            }
+        }
+
+        this.writes.push(buf.to_vec(), &conditions);
+        // Make progress on the queue, which also registers a wake-up for the next deadline.
+        if let Poll::Ready(Err(e)) = this.poll_write_queued(cx) {
+            return Poll::Ready(Err(e));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.conditions()?;
+        match this.poll_write_queued(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.inner).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if this.conditions().is_ok() {
+            match this.poll_write_queued(cx) {
+                Poll::Ready(Ok(())) => {}
+                other => return other,
+            }
+        }
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ConditionedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let Some(conditions) = this.conditions()? else {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        };
+
+        // Read everything available from the underlying stream, so that its arrival time is
+        // recorded as accurately as possible.
+        while !this.read_eof && !this.reads.is_full() {
+            let mut chunk = vec![0; READ_CHUNK_SIZE];
+            match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => this.read_eof = true,
+                Poll::Ready(Ok(n)) => {
+                    chunk.truncate(n);
+                    this.reads.push(chunk, &conditions);
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => break,
+            }
+        }
+
+        match this.reads.poll_ready(cx) {
+            Poll::Ready(Some(data)) => {
+                let n = data.len().min(buf.len());
+                buf[..n].copy_from_slice(&data[..n]);
+                this.reads.consume(n);
+                Poll::Ready(Ok(n))
+            }
+            // Only signal the end of the stream once all of the data before it is delivered.
+            Poll::Ready(None) if this.read_eof => Poll::Ready(Ok(0)),
+            Poll::Ready(None) | Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::{AsyncReadExt, AsyncWriteExt, Cursor};
+
+    fn dialer(port: u16) -> ConnectedPoint {
+        ConnectedPoint::Dialer {
+            address: format!("/ip4/127.0.0.1/tcp/{port}").parse().unwrap(),
+            role_override: libp2p::core::Endpoint::Dialer,
+        }
+    }
+
+    #[test]
+    fn partition_groups() {
+        let conditioner = LinkConditioner::default();
+        conditioner.set_link_conditions(
+            1,
+            3,
+            LinkConditions {
+                latency: Duration::from_millis(10),
+                ..<_>::default()
+            },
+        );
+        conditioner.partition(&[vec![1, 2], vec![3]]);
+
+        assert!(!conditioner.link_conditions(1, 2).partitioned);
+        assert!(conditioner.link_conditions(1, 3).partitioned);
+        assert!(conditioner.link_conditions(3, 2).partitioned);
+        // Nodes which are not in a group are unaffected.
+        assert!(!conditioner.link_conditions(4, 1).partitioned);
+
+        conditioner.heal_partitions();
+        assert_eq!(
+            conditioner.link_conditions(3, 1),
+            LinkConditions {
+                latency: Duration::from_millis(10),
+                ..<_>::default()
+            }
+        );
+        assert_eq!(conditioner.link_conditions(2, 3), LinkConditions::default());
+    }
+
+    #[tokio::test]
+    async fn partitioned_links_refuse_and_close_connections() {
+        let conditioner = LinkConditioner::default();
+        let links = conditioner.for_node(1);
+
+        let mut stream = links.condition(Cursor::new(vec![]), &dialer(2)).unwrap();
+        stream.write_all(b"hello").await.unwrap();
+
+        conditioner.partition(&[vec![1], vec![2]]);
+        assert!(links.condition(Cursor::new(vec![]), &dialer(2)).is_err());
+        assert!(stream.write_all(b"world").await.is_err());
+        // Connections to other nodes are unaffected.
+        assert!(links.condition(Cursor::new(vec![]), &dialer(3)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn latency_delays_writes_and_reads() {
+        let latency = Duration::from_millis(50);
+        let conditioner = LinkConditioner::default();
+        conditioner.set_link_conditions(
+            1,
+            2,
+            LinkConditions {
+                latency,
+                ..<_>::default()
+            },
+        );
+        let links = conditioner.for_node(1);
+
+        let start = Instant::now();
+        let mut stream = links.condition(Cursor::new(vec![]), &dialer(2)).unwrap();
+        stream.write_all(b"hello").await.unwrap();
+        stream.flush().await.unwrap();
+        assert!(start.elapsed() >= latency);
+        assert_eq!(stream.inner.get_ref(), b"hello");
+
+        let start = Instant::now();
+        let mut stream = links
+            .condition(Cursor::new(b"world".to_vec()), &dialer(2))
+            .unwrap();
+        let mut received = vec![];
+        stream.read_to_end(&mut received).await.unwrap();
+        assert!(start.elapsed() >= latency);
+        assert_eq!(received, b"world");
+    }
+}
@@ -49,6 +49,7 @@ pub mod api_types;
 mod behaviour;
 mod gossip_cache;
 pub mod gossipsub_scoring_parameters;
+pub mod link_conditioner;
 pub mod utils;
 /// The number of peers we target per subnet for discovery queries.
 pub const TARGET_SUBNET_PEERS: usize = 3;
@@ -400,8 +401,12 @@ impl<AppReqId: ReqId, E: EthSpec> Network<AppReqId, E> {
         };
 
         // Set up the transport - tcp/quic with noise and mplex
-        let transport = build_transport(local_keypair.clone(), !config.disable_quic_support)
-            .map_err(|e| format!("Failed to build transport: {:?}", e))?;
+        let transport = build_transport(
+            local_keypair.clone(),
+            !config.disable_quic_support,
+            config.link_conditioner.clone(),
+        )
+        .map_err(|e| format!("Failed to build transport: {:?}", e))?;
 
         // use the executor for libp2p
         struct Executor(task_executor::TaskExecutor);
@@ -1,9 +1,11 @@
 use crate::multiaddr::Protocol;
 use crate::rpc::{MetaData, MetaDataV1, MetaDataV2};
+use crate::service::link_conditioner::{ConditionedStream, NodeLinks};
 use crate::types::{
     error, EnrAttestationBitfield, EnrSyncCommitteeBitfield, GossipEncoding, GossipKind,
 };
 use crate::{GossipTopic, NetworkConfig};
+use futures::future;
 use futures::future::Either;
 use libp2p::core::{multiaddr::Multiaddr, muxing::StreamMuxerBox, transport::Boxed};
 use libp2p::identity::{secp256k1, Keypair};
@@ -38,9 +40,13 @@ type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
 
 /// The implementation supports TCP/IP, QUIC (experimental) over UDP, noise as the encryption layer, and
 /// mplex/yamux as the multiplexing layer (when using TCP).
+///
+/// If `link_conditioner` is set, TCP connections are subject to its conditions and QUIC is
+/// disabled, as QUIC connections cannot be conditioned.
 pub fn build_transport(
     local_private_key: Keypair,
     quic_support: bool,
+    link_conditioner: Option<NodeLinks>,
 ) -> std::io::Result<BoxedTransport> {
     // mplex config
     let mut mplex_config = libp2p_mplex::MplexConfig::new();
@@ -50,7 +56,14 @@ pub fn build_transport(
     // yamux config
     let yamux_config = yamux::Config::default();
     // Creates the TCP transport layer
+    let quic_support = quic_support && link_conditioner.is_none();
     let tcp = libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default().nodelay(true))
+        .and_then(move |stream, endpoint| {
+            future::ready(match &link_conditioner {
+                Some(links) => links.condition(stream, &endpoint),
+                None => Ok(ConditionedStream::unconditioned(stream)),
+            })
+        })
         .upgrade(core::upgrade::Version::V1)
         .authenticate(generate_noise_config(&local_private_key))
         .multiplex(core::upgrade::SelectUpgrade::new(
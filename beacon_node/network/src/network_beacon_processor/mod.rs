@@ -13,6 +13,7 @@ use lighthouse_network::{
     rpc::{BlocksByRangeRequest, BlocksByRootRequest, LightClientBootstrapRequest, StatusMessage},
     Client, MessageId, NetworkGlobals, PeerId, PeerRequestId,
 };
+use logging::{set_block_trace_root, BLOCK_TRACE_TARGET};
 use slog::{debug, Logger};
 use slot_clock::ManualSlotClock;
 use std::path::PathBuf;
@@ -21,6 +22,7 @@ use std::time::Duration;
 use store::MemoryStore;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{info_span, Instrument};
 use types::*;
 
 pub use sync_methods::ChainSegmentProcessId;
@@ -168,6 +170,11 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         seen_timestamp: Duration,
     ) -> Result<(), Error<T::EthSpec>> {
+        let span = info_span!(target: BLOCK_TRACE_TARGET, "gossip_block", slot = %block.slot());
+        if !span.is_disabled() {
+            set_block_trace_root(&span, block.canonical_root().as_fixed_bytes());
+        }
+
         let processor = self.clone();
         let process_fn = async move {
             let reprocess_tx = processor.reprocess_tx.clone();
@@ -185,7 +192,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     seen_timestamp,
                 )
                 .await
-        };
+        }
+        .instrument(span);
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
@@ -203,6 +211,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         blob_sidecar: Arc<BlobSidecar<T::EthSpec>>,
         seen_timestamp: Duration,
     ) -> Result<(), Error<T::EthSpec>> {
+        let span = info_span!(
+            target: BLOCK_TRACE_TARGET,
+            "gossip_blob",
+            slot = %blob_sidecar.slot(),
+            index = blob_index
+        );
+        if !span.is_disabled() {
+            set_block_trace_root(&span, blob_sidecar.block_root().as_fixed_bytes());
+        }
+
         let processor = self.clone();
         let process_fn = async move {
             processor
@@ -215,7 +233,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     seen_timestamp,
                 )
                 .await
-        };
+        }
+        .instrument(span);
 
         self.try_send(BeaconWorkEvent {
             drop_during_sync: false,
@@ -660,6 +679,7 @@ impl<E: EthSpec> NetworkBeaconProcessor<TestBeaconChainType<E>> {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx: _work_reprocessing_rx,
+            scheduler: _,
         } = <_>::default();
 
         let (network_tx, _network_rx) = mpsc::unbounded_channel();
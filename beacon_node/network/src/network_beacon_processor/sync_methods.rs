@@ -17,11 +17,13 @@ use beacon_processor::{
     AsyncFn, BlockingFn, DuplicateCache,
 };
 use lighthouse_network::PeerAction;
+use logging::{set_block_trace_root, BLOCK_TRACE_TARGET};
 use slog::{debug, error, info, warn};
 use std::sync::Arc;
 use std::time::Duration;
 use store::KzgCommitment;
 use tokio::sync::mpsc;
+use tracing::{info_span, Instrument};
 use types::beacon_block_body::format_kzg_commitments;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::BlockImportSource;
@@ -56,6 +58,11 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         seen_timestamp: Duration,
         process_type: BlockProcessType,
     ) -> AsyncFn {
+        let span = info_span!(target: BLOCK_TRACE_TARGET, "rpc_block", slot = %block.slot());
+        if !span.is_disabled() {
+            set_block_trace_root(&span, block_root.as_fixed_bytes());
+        }
+
         let process_fn = async move {
             let reprocess_tx = self.reprocess_tx.clone();
             let duplicate_cache = self.duplicate_cache.clone();
@@ -68,7 +75,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 duplicate_cache,
             )
             .await;
-        };
+        }
+        .instrument(span);
         Box::pin(process_fn)
     }
 
@@ -207,11 +215,17 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         seen_timestamp: Duration,
         process_type: BlockProcessType,
     ) -> AsyncFn {
+        let span = info_span!(target: BLOCK_TRACE_TARGET, "rpc_blobs");
+        if !span.is_disabled() {
+            set_block_trace_root(&span, block_root.as_fixed_bytes());
+        }
+
         let process_fn = async move {
             self.clone()
                 .process_rpc_blobs(block_root, blobs, seen_timestamp, process_type)
                 .await;
-        };
+        }
+        .instrument(span);
         Box::pin(process_fn)
     }
 
@@ -192,6 +192,7 @@ impl TestRig {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx,
+            scheduler,
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
         let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
@@ -244,6 +245,7 @@ impl TestRig {
                 &chain.spec,
             )
             .unwrap(),
+            scheduler,
         );
 
         assert!(beacon_processor.is_ok());
@@ -82,6 +82,7 @@ mod tests {
                 beacon_processor_rx: _beacon_processor_rx,
                 work_reprocessing_tx,
                 work_reprocessing_rx: _work_reprocessing_rx,
+                scheduler: _,
             } = <_>::default();
 
             let _network_service = NetworkService::start(
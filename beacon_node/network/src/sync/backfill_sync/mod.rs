@@ -964,7 +964,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         if let Some(batch) = self.batches.get_mut(&batch_id) {
             let (request, is_blob_batch) = batch.to_blocks_by_range_request();
             match network.blocks_and_blobs_by_range_request(
-                peer,
+                &[(peer, peer)],
                 is_blob_batch,
                 request,
                 RangeRequestId::BackfillSync { batch_id },
@@ -1,6 +1,7 @@
 use crate::network_beacon_processor::NetworkBeaconProcessor;
 
 use crate::service::RequestId;
+use crate::status::ToStatusMessage;
 use crate::sync::manager::{
     BlockProcessType, RequestId as SyncRequestId, SingleLookupReqId, SyncManager,
 };
@@ -25,7 +26,7 @@ use beacon_chain::{
 use beacon_processor::WorkEvent;
 use lighthouse_network::rpc::{RPCError, RPCResponseErrorCode};
 use lighthouse_network::types::SyncState;
-use lighthouse_network::{NetworkGlobals, Request};
+use lighthouse_network::{NetworkGlobals, Request, SyncInfo};
 use slog::info;
 use slot_clock::{ManualSlotClock, SlotClock, TestingSlotClock};
 use store::MemoryStore;
@@ -33,7 +34,7 @@ use tokio::sync::mpsc;
 use types::test_utils::TestRandom;
 use types::{
     test_utils::{SeedableRng, XorShiftRng},
-    BlobSidecar, ForkName, MinimalEthSpec as E, SignedBeaconBlock, Slot,
+    BlobSidecar, EthSpec, ForkName, MinimalEthSpec as E, SignedBeaconBlock, Slot,
 };
 use types::{BeaconState, BeaconStateBase};
 
@@ -799,6 +800,49 @@ impl TestRig {
         self.expect_no_penalty_for(peer_id);
     }
 
+    /// Adds a peer whose head is ten epochs ahead of ours, at `head_root`, triggering range sync.
+    fn add_head_peer(&mut self, head_root: Hash256) -> PeerId {
+        let peer_id = self.new_connected_peer();
+        let local = self.harness.chain.status_message();
+        self.send_sync_message(SyncMessage::AddPeer(
+            peer_id,
+            SyncInfo {
+                head_slot: local.head_slot + 10 * E::slots_per_epoch(),
+                head_root,
+                finalized_epoch: local.finalized_epoch,
+                finalized_root: local.finalized_root,
+            },
+        ));
+        peer_id
+    }
+
+    /// Pops the requests of a range sync batch, returning their id and the peers the blocks and
+    /// the blobs were requested from.
+    #[track_caller]
+    fn expect_range_batch_request(&mut self) -> (Id, PeerId, PeerId) {
+        let (id, blocks_peer) = self
+            .pop_received_network_event(|ev| match ev {
+                NetworkMessage::SendRequest {
+                    peer_id,
+                    request: Request::BlocksByRange(_),
+                    request_id: RequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }),
+                } => Some((*id, *peer_id)),
+                _ => None,
+            })
+            .unwrap_or_else(|e| panic!("Expected blocks by range request: {e}"));
+        let blobs_peer = self
+            .pop_received_network_event(|ev| match ev {
+                NetworkMessage::SendRequest {
+                    peer_id,
+                    request: Request::BlobsByRange(_),
+                    request_id: RequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id: blobs_id }),
+                } if *blobs_id == id => Some(*peer_id),
+                _ => None,
+            })
+            .unwrap_or_else(|e| panic!("Expected blobs by range request for {id}: {e}"));
+        (id, blocks_peer, blobs_peer)
+    }
+
     pub fn block_with_parent_and_blobs(
         &mut self,
         parent_root: Hash256,
@@ -1561,6 +1605,43 @@ fn blobs_in_da_checker_skip_download() {
     r.expect_no_active_lookups();
 }
 
+#[test]
+fn range_sync_retries_batch_without_faulty_blobs_peer() {
+    let Some(mut r) = TestRig::test_setup_after_deneb() else {
+        return;
+    };
+    let head_root = Hash256::random();
+
+    // With a single peer, the first batch is downloaded entirely from it.
+    let peer_a = r.add_head_peer(head_root);
+    let (_, blocks_peer, blobs_peer) = r.expect_range_batch_request();
+    assert_eq!((blocks_peer, blobs_peer), (peer_a, peer_a));
+
+    // The blobs of the second batch are downloaded from the other peer.
+    let peer_b = r.add_head_peer(head_root);
+    let (id, blocks_peer, blobs_peer) = r.expect_range_batch_request();
+    assert_eq!((blocks_peer, blobs_peer), (peer_b, peer_a));
+
+    // Blobs requests count towards the load of a peer, so the third batch's blobs are downloaded
+    // from the peer serving fewer requests.
+    let peer_c = r.add_head_peer(head_root);
+    let (_, blocks_peer, blobs_peer) = r.expect_range_batch_request();
+    assert_eq!((blocks_peer, blobs_peer), (peer_c, peer_b));
+
+    // The blobs request of the second batch fails.
+    r.send_sync_message(SyncMessage::RpcError {
+        peer_id: peer_a,
+        request_id: SyncRequestId::RangeBlockAndBlobs { id },
+        error: RPCError::ErrorResponse(RPCResponseErrorCode::ServerError, "blobs".into()),
+    });
+
+    // The batch is retried, and the faulty blobs peer is avoided for both blocks and blobs.
+    let (_, blocks_peer, blobs_peer) = r.expect_range_batch_request();
+    assert_ne!(blocks_peer, peer_a);
+    assert_ne!(blobs_peer, peer_a);
+    assert_ne!(blocks_peer, blobs_peer);
+}
+
 mod deneb_only {
     use super::*;
     use beacon_chain::{
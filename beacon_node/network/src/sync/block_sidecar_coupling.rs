@@ -8,6 +8,15 @@ use super::range_sync::ByRangeRequestType;
 
 #[derive(Debug)]
 pub struct BlocksAndBlobsRequestInfo<E: EthSpec> {
+    /// The parts the request was split into, in slot order.
+    parts: Vec<RequestPart<E>>,
+    /// Used to determine if this accumulator should wait for a sidecars stream termination
+    request_type: ByRangeRequestType,
+}
+
+/// A contiguous slot range of a request, downloaded from its own pair of peers.
+#[derive(Debug)]
+struct RequestPart<E: EthSpec> {
     /// Blocks we have received awaiting for their corresponding sidecar.
     accumulated_blocks: VecDeque<Arc<SignedBeaconBlock<E>>>,
     /// Sidecars we have received awaiting for their corresponding block.
@@ -16,48 +25,130 @@ pub struct BlocksAndBlobsRequestInfo<E: EthSpec> {
     is_blocks_stream_terminated: bool,
     /// Whether the individual RPC request for sidecars is finished or not.
     is_sidecars_stream_terminated: bool,
-    /// Used to determine if this accumulator should wait for a sidecars stream termination
-    request_type: ByRangeRequestType,
-    /// The peer the request was made to.
-    pub(crate) peer_id: PeerId,
+    /// The peer the blocks were requested from.
+    blocks_peer_id: PeerId,
+    /// The peer the sidecars were requested from. This may be the same as `blocks_peer_id`.
+    blobs_peer_id: PeerId,
+}
+
+/// A failure to couple the blocks and sidecars of a request.
+#[derive(Debug)]
+pub struct CouplingError {
+    pub msg: String,
+    /// The peers which sent the invalid data.
+    ///
+    /// The blocks response is taken as authoritative, so any inconsistency in the sidecars is
+    /// attributed to the peer which sent them. Sidecars for slots without a block may be the
+    /// result of either peer misbehaving, so both are held responsible.
+    pub faulty_peers: Vec<PeerId>,
 }
 
 impl<E: EthSpec> BlocksAndBlobsRequestInfo<E> {
-    pub fn new(request_type: ByRangeRequestType, peer_id: PeerId) -> Self {
+    /// Creates the accumulator of a request split into consecutive parts, one for each pair of
+    /// blocks and blobs peers in `peers`.
+    ///
+    /// The blocks peers must be distinct, as must the blobs peers, so that each response can be
+    /// attributed to its part.
+    pub fn new(request_type: ByRangeRequestType, peers: &[(PeerId, PeerId)]) -> Self {
+        let parts = peers
+            .iter()
+            .map(|&(blocks_peer_id, blobs_peer_id)| RequestPart {
+                accumulated_blocks: <_>::default(),
+                accumulated_sidecars: <_>::default(),
+                is_blocks_stream_terminated: <_>::default(),
+                is_sidecars_stream_terminated: <_>::default(),
+                blocks_peer_id,
+                blobs_peer_id,
+            })
+            .collect();
         Self {
-            accumulated_blocks: <_>::default(),
-            accumulated_sidecars: <_>::default(),
-            is_blocks_stream_terminated: <_>::default(),
-            is_sidecars_stream_terminated: <_>::default(),
+            parts,
             request_type,
-            peer_id,
         }
     }
 
-    pub fn get_request_type(&self) -> ByRangeRequestType {
-        self.request_type
+    /// The peer the blocks of the first part were requested from, which the batch is assigned to.
+    pub fn blocks_peer_id(&self) -> Option<PeerId> {
+        self.parts.first().map(|part| part.blocks_peer_id)
     }
 
-    pub fn add_block_response(&mut self, block_opt: Option<Arc<SignedBeaconBlock<E>>>) {
+    pub fn add_block_response(
+        &mut self,
+        peer_id: &PeerId,
+        block_opt: Option<Arc<SignedBeaconBlock<E>>>,
+    ) {
+        let Some(part) = self
+            .parts
+            .iter_mut()
+            .find(|part| part.blocks_peer_id == *peer_id)
+        else {
+            return;
+        };
         match block_opt {
-            Some(block) => self.accumulated_blocks.push_back(block),
-            None => self.is_blocks_stream_terminated = true,
+            Some(block) => part.accumulated_blocks.push_back(block),
+            None => part.is_blocks_stream_terminated = true,
         }
     }
 
-    pub fn add_sidecar_response(&mut self, sidecar_opt: Option<Arc<BlobSidecar<E>>>) {
+    pub fn add_sidecar_response(
+        &mut self,
+        peer_id: &PeerId,
+        sidecar_opt: Option<Arc<BlobSidecar<E>>>,
+    ) {
+        let Some(part) = self
+            .parts
+            .iter_mut()
+            .find(|part| part.blobs_peer_id == *peer_id)
+        else {
+            return;
+        };
         match sidecar_opt {
-            Some(sidecar) => self.accumulated_sidecars.push_back(sidecar),
-            None => self.is_sidecars_stream_terminated = true,
+            Some(sidecar) => part.accumulated_sidecars.push_back(sidecar),
+            None => part.is_sidecars_stream_terminated = true,
         }
     }
 
-    pub fn into_responses(self) -> Result<Vec<RpcBlock<E>>, String> {
-        let BlocksAndBlobsRequestInfo {
+    /// Returns `true` if any part of the request was made to `peer_id`, for either blocks or
+    /// sidecars.
+    pub fn is_requested_from(&self, peer_id: &PeerId) -> bool {
+        self.parts
+            .iter()
+            .any(|part| part.blocks_peer_id == *peer_id || part.blobs_peer_id == *peer_id)
+    }
+
+    pub fn into_responses(self) -> Result<Vec<RpcBlock<E>>, CouplingError> {
+        let mut responses = Vec::new();
+        for part in self.parts {
+            responses.extend(part.into_responses()?);
+        }
+        Ok(responses)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let blobs_requested = match self.request_type {
+            ByRangeRequestType::Blocks => false,
+            ByRangeRequestType::BlocksAndBlobs => true,
+        };
+        self.parts.iter().all(|part| {
+            part.is_blocks_stream_terminated
+                && (!blobs_requested || part.is_sidecars_stream_terminated)
+        })
+    }
+}
+
+impl<E: EthSpec> RequestPart<E> {
+    fn into_responses(self) -> Result<Vec<RpcBlock<E>>, CouplingError> {
+        let RequestPart {
             accumulated_blocks,
             accumulated_sidecars,
+            blocks_peer_id,
+            blobs_peer_id,
             ..
         } = self;
+        let blobs_fault = |msg: String| CouplingError {
+            msg,
+            faulty_peers: vec![blobs_peer_id],
+        };
 
         // There can't be more more blobs than blocks. i.e. sending any blob (empty
         // included) for a skipped slot is not permitted.
@@ -72,40 +163,46 @@ impl<E: EthSpec> BlocksAndBlobsRequestInfo<E> {
                     .unwrap_or(false);
                 pair_next_blob
             } {
-                blob_list.push(blob_iter.next().ok_or("Missing next blob".to_string())?);
+                blob_list.push(
+                    blob_iter
+                        .next()
+                        .ok_or_else(|| blobs_fault("Missing next blob".to_string()))?,
+                );
             }
 
             let mut blobs_buffer = vec![None; E::max_blobs_per_block()];
             for blob in blob_list {
                 let blob_index = blob.index as usize;
                 let Some(blob_opt) = blobs_buffer.get_mut(blob_index) else {
-                    return Err("Invalid blob index".to_string());
+                    return Err(blobs_fault("Invalid blob index".to_string()));
                 };
                 if blob_opt.is_some() {
-                    return Err("Repeat blob index".to_string());
+                    return Err(blobs_fault("Repeat blob index".to_string()));
                 } else {
                     *blob_opt = Some(blob);
                 }
             }
             let blobs = VariableList::from(blobs_buffer.into_iter().flatten().collect::<Vec<_>>());
-            responses.push(RpcBlock::new(None, block, Some(blobs)).map_err(|e| format!("{e:?}"))?)
+            responses.push(
+                RpcBlock::new(None, block, Some(blobs))
+                    .map_err(|e| blobs_fault(format!("{e:?}")))?,
+            )
         }
 
         // if accumulated sidecars is not empty, throw an error.
         if blob_iter.next().is_some() {
-            return Err("Received sidecars that don't pair well".to_string());
+            let mut faulty_peers = vec![blobs_peer_id];
+            if blocks_peer_id != blobs_peer_id {
+                faulty_peers.push(blocks_peer_id);
+            }
+            return Err(CouplingError {
+                msg: "Received sidecars that don't pair well".to_string(),
+                faulty_peers,
+            });
         }
 
         Ok(responses)
     }
-
-    pub fn is_finished(&self) -> bool {
-        let blobs_requested = match self.request_type {
-            ByRangeRequestType::Blocks => false,
-            ByRangeRequestType::BlocksAndBlobs => true,
-        };
-        self.is_blocks_stream_terminated && (!blobs_requested || self.is_sidecars_stream_terminated)
-    }
 }
 
 #[cfg(test)]
@@ -120,7 +217,8 @@ mod tests {
     #[test]
     fn no_blobs_into_responses() {
         let peer_id = PeerId::random();
-        let mut info = BlocksAndBlobsRequestInfo::<E>::new(ByRangeRequestType::Blocks, peer_id);
+        let mut info =
+            BlocksAndBlobsRequestInfo::<E>::new(ByRangeRequestType::Blocks, &[(peer_id, peer_id)]);
         let mut rng = XorShiftRng::from_seed([42; 16]);
         let blocks = (0..4)
             .map(|_| generate_rand_block_and_blobs::<E>(ForkName::Base, NumBlobs::None, &mut rng).0)
@@ -128,9 +226,9 @@ mod tests {
 
         // Send blocks and complete terminate response
         for block in blocks {
-            info.add_block_response(Some(block.into()));
+            info.add_block_response(&peer_id, Some(block.into()));
         }
-        info.add_block_response(None);
+        info.add_block_response(&peer_id, None);
 
         // Assert response is finished and RpcBlocks can be constructed
         assert!(info.is_finished());
@@ -140,8 +238,10 @@ mod tests {
     #[test]
     fn empty_blobs_into_responses() {
         let peer_id = PeerId::random();
-        let mut info =
-            BlocksAndBlobsRequestInfo::<E>::new(ByRangeRequestType::BlocksAndBlobs, peer_id);
+        let mut info = BlocksAndBlobsRequestInfo::<E>::new(
+            ByRangeRequestType::BlocksAndBlobs,
+            &[(peer_id, peer_id)],
+        );
         let mut rng = XorShiftRng::from_seed([42; 16]);
         let blocks = (0..4)
             .map(|_| {
@@ -152,11 +252,11 @@ mod tests {
 
         // Send blocks and complete terminate response
         for block in blocks {
-            info.add_block_response(Some(block.into()));
+            info.add_block_response(&peer_id, Some(block.into()));
         }
-        info.add_block_response(None);
+        info.add_block_response(&peer_id, None);
         // Expect no blobs returned
-        info.add_sidecar_response(None);
+        info.add_sidecar_response(&peer_id, None);
 
         // Assert response is finished and RpcBlocks can be constructed, even if blobs weren't returned.
         // This makes sure we don't expect blobs here when they have expired. Checking this logic should
@@ -164,4 +264,81 @@ mod tests {
         assert!(info.is_finished());
         info.into_responses().unwrap();
     }
+
+    #[test]
+    fn invalid_blobs_attributed_to_blobs_peer() {
+        let blocks_peer_id = PeerId::random();
+        let blobs_peer_id = PeerId::random();
+        let mut info = BlocksAndBlobsRequestInfo::<E>::new(
+            ByRangeRequestType::BlocksAndBlobs,
+            &[(blocks_peer_id, blobs_peer_id)],
+        );
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let (block, blobs) =
+            generate_rand_block_and_blobs::<E>(ForkName::Deneb, NumBlobs::Number(2), &mut rng);
+
+        // Send the block, but only one of its two blobs.
+        info.add_block_response(&blocks_peer_id, Some(block.into()));
+        info.add_block_response(&blocks_peer_id, None);
+        info.add_sidecar_response(&blobs_peer_id, Some(blobs[0].clone().into()));
+        info.add_sidecar_response(&blobs_peer_id, None);
+
+        assert!(info.is_finished());
+        let error = info.into_responses().unwrap_err();
+        assert_eq!(error.faulty_peers, vec![blobs_peer_id]);
+    }
+
+    #[test]
+    fn unpaired_blobs_attributed_to_both_peers() {
+        let blocks_peer_id = PeerId::random();
+        let blobs_peer_id = PeerId::random();
+        let mut info = BlocksAndBlobsRequestInfo::<E>::new(
+            ByRangeRequestType::BlocksAndBlobs,
+            &[(blocks_peer_id, blobs_peer_id)],
+        );
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let (_block, blobs) =
+            generate_rand_block_and_blobs::<E>(ForkName::Deneb, NumBlobs::Number(1), &mut rng);
+
+        // Send a blob without its block.
+        info.add_block_response(&blocks_peer_id, None);
+        info.add_sidecar_response(&blobs_peer_id, Some(blobs[0].clone().into()));
+        info.add_sidecar_response(&blobs_peer_id, None);
+
+        assert!(info.is_finished());
+        let error = info.into_responses().unwrap_err();
+        assert_eq!(error.faulty_peers, vec![blobs_peer_id, blocks_peer_id]);
+    }
+
+    #[test]
+    fn split_request_attributes_faults_to_its_part() {
+        let peers = (0..2)
+            .map(|_| (PeerId::random(), PeerId::random()))
+            .collect::<Vec<_>>();
+        let mut info =
+            BlocksAndBlobsRequestInfo::<E>::new(ByRangeRequestType::BlocksAndBlobs, &peers);
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        let (first_block, first_blobs) =
+            generate_rand_block_and_blobs::<E>(ForkName::Deneb, NumBlobs::Number(1), &mut rng);
+        let (second_block, second_blobs) =
+            generate_rand_block_and_blobs::<E>(ForkName::Deneb, NumBlobs::Number(2), &mut rng);
+
+        // The first part is complete.
+        info.add_block_response(&peers[0].0, Some(first_block.into()));
+        info.add_block_response(&peers[0].0, None);
+        info.add_sidecar_response(&peers[0].1, Some(first_blobs[0].clone().into()));
+        info.add_sidecar_response(&peers[0].1, None);
+        assert!(!info.is_finished());
+
+        // The second part is missing one of its blobs.
+        info.add_block_response(&peers[1].0, Some(second_block.into()));
+        info.add_block_response(&peers[1].0, None);
+        info.add_sidecar_response(&peers[1].1, Some(second_blobs[0].clone().into()));
+        info.add_sidecar_response(&peers[1].1, None);
+
+        assert!(info.is_finished());
+        assert_eq!(info.blocks_peer_id(), Some(peers[0].0));
+        let error = info.into_responses().unwrap_err();
+        assert_eq!(error.faulty_peers, vec![peers[1].1]);
+    }
 }
@@ -44,7 +44,6 @@ use crate::status::ToStatusMessage;
 use crate::sync::block_lookups::{
     BlobRequestState, BlockComponent, BlockRequestState, DownloadResult,
 };
-use crate::sync::block_sidecar_coupling::BlocksAndBlobsRequestInfo;
 use beacon_chain::block_verification_types::AsBlock;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::validator_monitor::timestamp_now;
@@ -352,26 +351,10 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 self.on_single_blob_response(id, peer_id, RpcEvent::RPCError(error))
             }
             RequestId::RangeBlockAndBlobs { id } => {
-                if let Some(sender_id) = self.network.range_request_failed(id) {
-                    match sender_id {
-                        RangeRequestId::RangeSync { chain_id, batch_id } => {
-                            self.range_sync.inject_error(
-                                &mut self.network,
-                                peer_id,
-                                batch_id,
-                                chain_id,
-                                id,
-                            );
-                            self.update_sync_state();
-                        }
-                        RangeRequestId::BackfillSync { batch_id } => match self
-                            .backfill_sync
-                            .inject_error(&mut self.network, batch_id, &peer_id, id)
-                        {
-                            Ok(_) => {}
-                            Err(_) => self.update_sync_state(),
-                        },
-                    }
+                if let Some((sender_id, blocks_peer_id)) = self.network.range_request_failed(id) {
+                    // The request failed because of the peer that returned the error, which may
+                    // not be the peer the batch is assigned to.
+                    self.range_request_failed(id, sender_id, blocks_peer_id, &[peer_id]);
                 } else {
                     debug!(
                         self.log,
@@ -385,6 +368,39 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         }
     }
 
+    /// Fails the range request `id` of `sender_id`, whose batch is assigned to `blocks_peer_id`,
+    /// holding `faulty_peers` responsible.
+    fn range_request_failed(
+        &mut self,
+        id: Id,
+        sender_id: RangeRequestId,
+        blocks_peer_id: PeerId,
+        faulty_peers: &[PeerId],
+    ) {
+        match sender_id {
+            RangeRequestId::RangeSync { chain_id, batch_id } => {
+                self.range_sync.inject_error(
+                    &mut self.network,
+                    blocks_peer_id,
+                    batch_id,
+                    chain_id,
+                    id,
+                    faulty_peers,
+                );
+                self.update_sync_state();
+            }
+            RangeRequestId::BackfillSync { batch_id } => match self.backfill_sync.inject_error(
+                &mut self.network,
+                batch_id,
+                &blocks_peer_id,
+                id,
+            ) {
+                Ok(_) => {}
+                Err(_) => self.update_sync_state(),
+            },
+        }
+    }
+
     /// Handles a peer disconnect.
     ///
     /// It is important that a peer disconnect retries all the batches/lookups as
@@ -927,8 +943,11 @@ impl<T: BeaconChainTypes> SyncManager<T> {
     ) {
         if let Some(resp) = self
             .network
-            .range_block_and_blob_response(id, block_or_blob)
+            .range_block_and_blob_response(id, &peer_id, block_or_blob)
         {
+            // The batch is assigned to the peer serving the blocks of its first part, regardless of
+            // which peer sent the final response.
+            let blocks_peer_id = resp.blocks_peer_id;
             match resp.responses {
                 Ok(blocks) => {
                     for block in blocks
@@ -941,7 +960,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                             RangeRequestId::RangeSync { chain_id, batch_id } => {
                                 self.range_sync.blocks_by_range_response(
                                     &mut self.network,
-                                    peer_id,
+                                    blocks_peer_id,
                                     chain_id,
                                     batch_id,
                                     id,
@@ -953,7 +972,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                                 match self.backfill_sync.on_block_response(
                                     &mut self.network,
                                     batch_id,
-                                    &peer_id,
+                                    &blocks_peer_id,
                                     id,
                                     block,
                                 ) {
@@ -970,28 +989,25 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     }
                 }
                 Err(e) => {
-                    // Re-insert the request so we can retry
-                    self.network.insert_range_blocks_and_blobs_request(
-                        id,
-                        resp.sender_id,
-                        BlocksAndBlobsRequestInfo::new(resp.request_type, peer_id),
-                    );
                     // inform range that the request needs to be treated as failed
                     // With time we will want to downgrade this log
                     warn!(
                         self.log,
                         "Blocks and blobs request for range received invalid data";
-                        "peer_id" => %peer_id,
+                        "blocks_peer" => %blocks_peer_id,
+                        "faulty_peers" => ?e.faulty_peers,
                         "sender_id" => ?resp.sender_id,
-                        "error" => e.clone()
+                        "error" => e.msg
                     );
-                    let id = RequestId::RangeBlockAndBlobs { id };
-                    self.network.report_peer(
-                        peer_id,
-                        PeerAction::MidToleranceError,
-                        "block_blob_faulty_batch",
-                    );
-                    self.inject_error(peer_id, id, RPCError::InvalidData(e))
+                    // Only penalise the peers which sent the invalid data.
+                    for faulty_peer in &e.faulty_peers {
+                        self.network.report_peer(
+                            *faulty_peer,
+                            PeerAction::MidToleranceError,
+                            "block_blob_faulty_batch",
+                        );
+                    }
+                    self.range_request_failed(id, resp.sender_id, blocks_peer_id, &e.faulty_peers)
                 }
             }
         }
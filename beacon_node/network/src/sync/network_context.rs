@@ -3,7 +3,7 @@
 
 use self::requests::{ActiveBlobsByRootRequest, ActiveBlocksByRootRequest};
 pub use self::requests::{BlobsByRootSingleBlockRequest, BlocksByRootSingleRequest};
-use super::block_sidecar_coupling::BlocksAndBlobsRequestInfo;
+use super::block_sidecar_coupling::{BlocksAndBlobsRequestInfo, CouplingError};
 use super::manager::{Id, RequestId as SyncRequestId};
 use super::range_sync::{BatchId, ByRangeRequestType, ChainId};
 use crate::network_beacon_processor::NetworkBeaconProcessor;
@@ -30,8 +30,8 @@ mod requests;
 
 pub struct BlocksAndBlobsByRangeResponse<E: EthSpec> {
     pub sender_id: RangeRequestId,
-    pub responses: Result<Vec<RpcBlock<E>>, String>,
-    pub request_type: ByRangeRequestType,
+    pub responses: Result<Vec<RpcBlock<E>>, CouplingError>,
+    pub blocks_peer_id: PeerId,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -183,7 +183,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             self.range_blocks_and_blobs_requests
                 .iter()
                 .filter_map(|(id, request)| {
-                    if request.1.peer_id == *peer_id {
+                    if request.1.is_requested_from(peer_id) {
                         Some(SyncRequestId::RangeBlockAndBlobs { id: *id })
                     } else {
                         None
@@ -256,48 +256,58 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
     }
 
     /// A blocks by range request for the range sync algorithm.
+    ///
+    /// The request is split into consecutive parts, one for each pair of blocks and blobs peers
+    /// in `peers`. If the batch requires blobs, each part's blobs are requested in parallel from
+    /// its blobs peer, which may be the same as its blocks peer.
     pub fn blocks_by_range_request(
         &mut self,
-        peer_id: PeerId,
+        peers: &[(PeerId, PeerId)],
         batch_type: ByRangeRequestType,
         request: BlocksByRangeRequest,
     ) -> Result<Id, RpcRequestSendError> {
         let id = self.next_id();
-        trace!(
-            self.log,
-            "Sending BlocksByRange request";
-            "method" => "BlocksByRange",
-            "count" => request.count(),
-            "peer" => %peer_id,
-        );
-        self.network_send
-            .send(NetworkMessage::SendRequest {
-                peer_id,
-                request: Request::BlocksByRange(request.clone()),
-                request_id: RequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }),
-            })
-            .map_err(|_| RpcRequestSendError::NetworkSendError)?;
-
-        if matches!(batch_type, ByRangeRequestType::BlocksAndBlobs) {
-            debug!(
+        for (&(peer_id, blobs_peer_id), request) in
+            peers.iter().zip(split_range_request(&request, peers.len()))
+        {
+            trace!(
                 self.log,
-                "Sending BlobsByRange requests";
-                "method" => "BlobsByRange",
+                "Sending BlocksByRange request";
+                "method" => "BlocksByRange",
+                "start_slot" => request.start_slot(),
                 "count" => request.count(),
                 "peer" => %peer_id,
             );
-
-            // Create the blob request based on the blocks request.
             self.network_send
                 .send(NetworkMessage::SendRequest {
                     peer_id,
-                    request: Request::BlobsByRange(BlobsByRangeRequest {
-                        start_slot: *request.start_slot(),
-                        count: *request.count(),
-                    }),
+                    request: Request::BlocksByRange(request.clone()),
                     request_id: RequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }),
                 })
                 .map_err(|_| RpcRequestSendError::NetworkSendError)?;
+
+            if matches!(batch_type, ByRangeRequestType::BlocksAndBlobs) {
+                debug!(
+                    self.log,
+                    "Sending BlobsByRange requests";
+                    "method" => "BlobsByRange",
+                    "start_slot" => request.start_slot(),
+                    "count" => request.count(),
+                    "peer" => %blobs_peer_id,
+                );
+
+                // Create the blob request based on the blocks request.
+                self.network_send
+                    .send(NetworkMessage::SendRequest {
+                        peer_id: blobs_peer_id,
+                        request: Request::BlobsByRange(BlobsByRangeRequest {
+                            start_slot: *request.start_slot(),
+                            count: *request.count(),
+                        }),
+                        request_id: RequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }),
+                    })
+                    .map_err(|_| RpcRequestSendError::NetworkSendError)?;
+            }
         }
 
         Ok(id)
@@ -306,35 +316,43 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
     /// A blocks by range request sent by the range sync algorithm
     pub fn blocks_and_blobs_by_range_request(
         &mut self,
-        peer_id: PeerId,
+        peers: &[(PeerId, PeerId)],
         batch_type: ByRangeRequestType,
         request: BlocksByRangeRequest,
         sender_id: RangeRequestId,
     ) -> Result<Id, RpcRequestSendError> {
-        let id = self.blocks_by_range_request(peer_id, batch_type, request)?;
+        // The request is never split into more parts than it has slots. Drop any peers which would
+        // not be sent a part, otherwise the request would wait on their responses forever.
+        let parts = usize::try_from(*request.count()).map_or(usize::MAX, |count| count.max(1));
+        let peers = &peers[..peers.len().min(parts)];
+
+        let id = self.blocks_by_range_request(peers, batch_type, request)?;
         self.range_blocks_and_blobs_requests.insert(
             id,
-            (
-                sender_id,
-                BlocksAndBlobsRequestInfo::new(batch_type, peer_id),
-            ),
+            (sender_id, BlocksAndBlobsRequestInfo::new(batch_type, peers)),
         );
         Ok(id)
     }
 
-    pub fn range_request_failed(&mut self, request_id: Id) -> Option<RangeRequestId> {
-        let sender_id = self
+    /// Removes a failed range request, returning its sender and the peer its blocks were requested
+    /// from.
+    ///
+    /// The batch is always assigned to the peer serving the blocks of its first part, so the
+    /// sender is expected to attribute the failure to the peer that caused it.
+    pub fn range_request_failed(&mut self, request_id: Id) -> Option<(RangeRequestId, PeerId)> {
+        let request = self
             .range_blocks_and_blobs_requests
             .remove(&request_id)
-            .map(|(sender_id, _info)| sender_id);
-        if let Some(sender_id) = sender_id {
+            .and_then(|(sender_id, info)| Some((sender_id, info.blocks_peer_id()?)));
+        if let Some((sender_id, blocks_peer_id)) = request {
             debug!(
                 self.log,
                 "Sync range request failed";
                 "request_id" => request_id,
-                "sender_id" => ?sender_id
+                "sender_id" => ?sender_id,
+                "blocks_peer" => %blocks_peer_id,
             );
-            Some(sender_id)
+            Some((sender_id, blocks_peer_id))
         } else {
             debug!(self.log, "Sync range request failed"; "request_id" => request_id);
             None
@@ -346,22 +364,27 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
     pub fn range_block_and_blob_response(
         &mut self,
         request_id: Id,
+        peer_id: &PeerId,
         block_or_blob: BlockOrBlob<T::EthSpec>,
     ) -> Option<BlocksAndBlobsByRangeResponse<T::EthSpec>> {
         match self.range_blocks_and_blobs_requests.entry(request_id) {
             Entry::Occupied(mut entry) => {
                 let (_, info) = entry.get_mut();
                 match block_or_blob {
-                    BlockOrBlob::Block(maybe_block) => info.add_block_response(maybe_block),
-                    BlockOrBlob::Blob(maybe_sidecar) => info.add_sidecar_response(maybe_sidecar),
+                    BlockOrBlob::Block(maybe_block) => {
+                        info.add_block_response(peer_id, maybe_block)
+                    }
+                    BlockOrBlob::Blob(maybe_sidecar) => {
+                        info.add_sidecar_response(peer_id, maybe_sidecar)
+                    }
                 }
                 if info.is_finished() {
                     // If the request is finished, dequeue everything
                     let (sender_id, info) = entry.remove();
-                    let request_type = info.get_request_type();
+                    let blocks_peer_id = info.blocks_peer_id()?;
                     Some(BlocksAndBlobsByRangeResponse {
                         sender_id,
-                        request_type,
+                        blocks_peer_id,
                         responses: info.into_responses(),
                     })
                 } else {
@@ -612,16 +635,6 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         }
     }
 
-    pub fn insert_range_blocks_and_blobs_request(
-        &mut self,
-        id: Id,
-        sender_id: RangeRequestId,
-        info: BlocksAndBlobsRequestInfo<T::EthSpec>,
-    ) {
-        self.range_blocks_and_blobs_requests
-            .insert(id, (sender_id, info));
-    }
-
     // Request handlers
 
     pub fn on_single_block_response(
@@ -773,6 +786,22 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
     }
 }
 
+/// Splits `request` into `parts` consecutive requests covering the same slots, with their counts
+/// differing by at most one.
+fn split_range_request(request: &BlocksByRangeRequest, parts: usize) -> Vec<BlocksByRangeRequest> {
+    let parts = (parts as u64).clamp(1, (*request.count()).max(1));
+    let (count, remainder) = (*request.count() / parts, *request.count() % parts);
+    let mut start_slot = *request.start_slot();
+    (0..parts)
+        .map(|part| {
+            let part_count = count + u64::from(part < remainder);
+            let part_request = BlocksByRangeRequest::new(start_slot, part_count);
+            start_slot += part_count;
+            part_request
+        })
+        .collect()
+}
+
 fn to_fixed_blob_sidecar_list<E: EthSpec>(
     blobs: Vec<Arc<BlobSidecar<E>>>,
 ) -> Result<FixedBlobSidecarList<E>, LookupVerifyError> {
@@ -101,8 +101,9 @@ pub struct BatchInfo<E: EthSpec, B: BatchConfig = RangeSyncBatchConfig> {
     failed_processing_attempts: Vec<Attempt>,
     /// Number of processing attempts that have failed but we do not count.
     non_faulty_processing_attempts: u8,
-    /// The number of download retries this batch has undergone due to a failed request.
-    failed_download_attempts: Vec<PeerId>,
+    /// The download retries this batch has undergone due to a failed request, with the peers
+    /// responsible for each failure.
+    failed_download_attempts: Vec<Vec<PeerId>>,
     /// State of the batch.
     state: BatchState<E>,
     /// Whether this batch contains all blocks or all blocks and blobs.
@@ -182,7 +183,7 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
         }
 
         for download in &self.failed_download_attempts {
-            peers.insert(*download);
+            peers.extend(download.iter().copied());
         }
 
         peers
@@ -296,7 +297,7 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
                     if let Some((expected, received)) = failed_range {
                         // this is a failed download, register the attempt and check if the batch
                         // can be tried again
-                        self.failed_download_attempts.push(peer);
+                        self.failed_download_attempts.push(vec![peer]);
                         self.state = if self.failed_download_attempts.len()
                             >= B::max_batch_download_attempts() as usize
                         {
@@ -334,12 +335,29 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
     pub fn download_failed(
         &mut self,
         mark_failed: bool,
+    ) -> Result<BatchOperationOutcome, WrongState> {
+        self.register_download_failure(|peer| mark_failed.then(|| vec![peer]))
+    }
+
+    /// Mark the download as failed because of `faulty_peers`, which need not include the peer the
+    /// batch is being downloaded from (e.g. when only the peer serving its blobs misbehaved).
+    /// This counts as a single failed attempt.
+    pub fn download_failed_by(
+        &mut self,
+        faulty_peers: &[PeerId],
+    ) -> Result<BatchOperationOutcome, WrongState> {
+        self.register_download_failure(|_| Some(faulty_peers.to_vec()))
+    }
+
+    fn register_download_failure(
+        &mut self,
+        faulty_peers: impl FnOnce(PeerId) -> Option<Vec<PeerId>>,
     ) -> Result<BatchOperationOutcome, WrongState> {
         match self.state.poison() {
             BatchState::Downloading(peer, _, _request_id) => {
                 // register the attempt and check if the batch can be tried again
-                if mark_failed {
-                    self.failed_download_attempts.push(peer);
+                if let Some(faulty_peers) = faulty_peers(peer) {
+                    self.failed_download_attempts.push(faulty_peers);
                 }
                 self.state = if self.failed_download_attempts.len()
                     >= B::max_batch_download_attempts() as usize
@@ -1,4 +1,4 @@
-use super::batch::{BatchInfo, BatchProcessingResult, BatchState};
+use super::batch::{BatchInfo, BatchProcessingResult, BatchState, ByRangeRequestType};
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::network_context::RangeRequestId;
 use crate::sync::{
@@ -25,6 +25,10 @@ pub const EPOCHS_PER_BATCH: u64 = 1;
 /// The maximum number of batches to queue before requesting more.
 const BATCH_BUFFER_SIZE: u8 = 5;
 
+/// The maximum number of peers a single batch is split across. Each of them serves the blocks of
+/// a part of the batch, and the blobs of another part.
+const MAX_PEERS_PER_BATCH: usize = 4;
+
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
 /// required.
@@ -75,7 +79,7 @@ pub struct SyncingChain<T: BeaconChainTypes> {
 
     /// The peers that agree on the `target_head_slot` and `target_head_root` as a canonical chain
     /// and thus available to download this chain from, as well as the batches we are currently
-    /// requesting.
+    /// requesting blocks or blobs from each of them.
     peers: FnvHashMap<PeerId, HashSet<BatchId>>,
 
     /// Starting epoch of the next batch that needs to be downloaded.
@@ -182,9 +186,13 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         if let Some(batch_ids) = self.peers.remove(peer_id) {
             // fail the batches.
             for id in batch_ids {
+                // the batch may also be downloading from other peers
+                for active_requests in self.peers.values_mut() {
+                    active_requests.remove(&id);
+                }
                 if let Some(batch) = self.batches.get_mut(&id) {
                     if let BatchOperationOutcome::Failed { blacklist } =
-                        batch.download_failed(true)?
+                        batch.download_failed_by(&[*peer_id])?
                     {
                         return Err(RemoveChain::ChainFailed {
                             blacklist,
@@ -249,10 +257,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             Ok(KeepChain)
         } else {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
-            // Remove the request from the peer's active batches
-            self.peers
-                .get_mut(peer_id)
-                .map(|active_requests| active_requests.remove(&batch_id));
+            // Remove the request from the active batches of the peers it was downloaded from
+            for active_requests in self.peers.values_mut() {
+                active_requests.remove(&batch_id);
+            }
 
             match batch.download_completed() {
                 Ok(received) => {
@@ -653,9 +661,9 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                         }
                     }
                 }
-                BatchState::Downloading(peer, ..) => {
-                    // remove this batch from the peer's active requests
-                    if let Some(active_batches) = self.peers.get_mut(peer) {
+                BatchState::Downloading(..) => {
+                    // remove this batch from the active requests of the peers downloading it
+                    for active_batches in self.peers.values_mut() {
                         active_batches.remove(&id);
                     }
                 }
@@ -809,15 +817,16 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 
-    /// An RPC error has occurred.
+    /// An RPC error has occurred, or the response was invalid, because of `faulty_peers`.
     ///
-    /// If the batch exists it is re-requested.
+    /// If the batch exists it is re-requested, avoiding the faulty peers if possible.
     pub fn inject_error(
         &mut self,
         network: &mut SyncNetworkContext<T>,
         batch_id: BatchId,
         peer_id: &PeerId,
         request_id: Id,
+        faulty_peers: &[PeerId],
     ) -> ProcessingResult {
         if let Some(batch) = self.batches.get_mut(&batch_id) {
             // A batch could be retried without the peer failing the request (disconnecting/
@@ -840,12 +849,15 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 "batch_epoch" => batch_id,
                 "batch_state" => ?batch.state(),
                 "peer_id" => %peer_id,
+                "faulty_peers" => ?faulty_peers,
                 "request_id" => %request_id
             );
-            if let Some(active_requests) = self.peers.get_mut(peer_id) {
+            for active_requests in self.peers.values_mut() {
                 active_requests.remove(&batch_id);
             }
-            if let BatchOperationOutcome::Failed { blacklist } = batch.download_failed(true)? {
+            if let BatchOperationOutcome::Failed { blacklist } =
+                batch.download_failed_by(faulty_peers)?
+            {
                 return Err(RemoveChain::ChainFailed {
                     blacklist,
                     failing_batch: batch_id,
@@ -897,6 +909,63 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 
+    /// Selects the peers to download a batch from, as the pairs of peers serving the blocks and
+    /// the blobs of each consecutive part of the batch. The batch is assigned to `peer`, which
+    /// serves the blocks of the first part.
+    ///
+    /// The batch is split across `peer` and other idle peers which have not failed it, up to
+    /// `max_peers`, so that a slow peer only delays its share of the batch. The blobs of
+    /// each part are downloaded from the peer serving the blocks of the next one. If no other
+    /// peer is idle, the blobs are downloaded from the least busy peer which has not failed the
+    /// batch, falling back to `peer`.
+    fn batch_peers(
+        &self,
+        batch_id: BatchId,
+        peer: PeerId,
+        batch_type: ByRangeRequestType,
+        max_peers: usize,
+    ) -> Vec<(PeerId, PeerId)> {
+        let failed_peers = self
+            .batches
+            .get(&batch_id)
+            .map(|batch| batch.failed_peers())
+            .unwrap_or_default();
+
+        let mut blocks_peers = vec![peer];
+        blocks_peers.extend(
+            self.peers
+                .iter()
+                .filter(|(other, requests)| {
+                    **other != peer && requests.is_empty() && !failed_peers.contains(*other)
+                })
+                .map(|(other, _)| *other)
+                .take(max_peers.saturating_sub(1)),
+        );
+
+        match batch_type {
+            ByRangeRequestType::Blocks => blocks_peers
+                .iter()
+                .map(|&blocks_peer| (blocks_peer, blocks_peer))
+                .collect(),
+            ByRangeRequestType::BlocksAndBlobs if blocks_peers.len() > 1 => blocks_peers
+                .iter()
+                .zip(blocks_peers.iter().cycle().skip(1))
+                .map(|(&blocks_peer, &blobs_peer)| (blocks_peer, blobs_peer))
+                .collect(),
+            ByRangeRequestType::BlocksAndBlobs => {
+                let blobs_peer = self
+                    .peers
+                    .iter()
+                    .filter(|(other, _)| **other != peer)
+                    .map(|(other, requests)| (failed_peers.contains(other), requests.len(), *other))
+                    .min()
+                    .map(|(_, _, other)| other)
+                    .unwrap_or(peer);
+                vec![(peer, blobs_peer)]
+            }
+        }
+    }
+
     /// Requests the batch assigned to the given id from a given peer.
     pub fn send_batch(
         &mut self,
@@ -904,10 +973,20 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         batch_id: BatchId,
         peer: PeerId,
     ) -> ProcessingResult {
+        let Some((request, batch_type)) = self
+            .batches
+            .get(&batch_id)
+            .map(|batch| batch.to_blocks_by_range_request())
+        else {
+            return Ok(KeepChain);
+        };
+        // each part of the batch requests at least one slot
+        let max_peers = MAX_PEERS_PER_BATCH.min(*request.count() as usize);
+        let batch_peers = self.batch_peers(batch_id, peer, batch_type, max_peers);
+
         if let Some(batch) = self.batches.get_mut(&batch_id) {
-            let (request, batch_type) = batch.to_blocks_by_range_request();
             match network.blocks_and_blobs_by_range_request(
-                peer,
+                &batch_peers,
                 batch_type,
                 request,
                 RangeRequestId::RangeSync {
@@ -927,20 +1006,19 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                     } else {
                         debug!(self.log, "Requesting batch"; "epoch" => batch_id, &batch);
                     }
-                    // register the batch for this peer
-                    return self
-                        .peers
-                        .get_mut(&peer)
-                        .map(|requests| {
+                    // register the batch for the peers serving its blocks and blobs
+                    for (blocks_peer, blobs_peer) in batch_peers {
+                        for peer in [blocks_peer, blobs_peer] {
+                            let Some(requests) = self.peers.get_mut(&peer) else {
+                                return Err(RemoveChain::WrongChainState(format!(
+                                    "Sending batch to a peer that is not in the chain: {}",
+                                    peer
+                                )));
+                            };
                             requests.insert(batch_id);
-                            Ok(KeepChain)
-                        })
-                        .unwrap_or_else(|| {
-                            Err(RemoveChain::WrongChainState(format!(
-                                "Sending batch to a peer that is not in the chain: {}",
-                                peer
-                            )))
-                        });
+                        }
+                    }
+                    return Ok(KeepChain);
                 }
                 Err(e) => {
                     // NOTE: under normal conditions this shouldn't happen but we handle it anyway
@@ -1028,6 +1106,14 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
 
         while let Some(peer) = idle_peers.pop() {
+            // the peer may have been assigned part of a batch split across idle peers
+            if self
+                .peers
+                .get(&peer)
+                .map_or(true, |requests| !requests.is_empty())
+            {
+                continue;
+            }
             if let Some(batch_id) = self.include_next_batch(network) {
                 // send the batch
                 self.send_batch(network, batch_id, peer)?;
@@ -292,7 +292,7 @@ where
         }
     }
 
-    /// An RPC error has occurred.
+    /// An RPC error has occurred, or the response was invalid, because of `faulty_peers`.
     ///
     /// Check to see if the request corresponds to a pending batch. If so, re-request it if possible, if there have
     /// been too many failed attempts for the batch, remove the chain.
@@ -303,10 +303,11 @@ where
         batch_id: BatchId,
         chain_id: ChainId,
         request_id: Id,
+        faulty_peers: &[PeerId],
     ) {
         // check that this request is pending
         match self.chains.call_by_id(chain_id, |chain| {
-            chain.inject_error(network, batch_id, &peer_id, request_id)
+            chain.inject_error(network, batch_id, &peer_id, request_id, faulty_peers)
         }) {
             Ok((removed_chain, sync_type)) => {
                 if let Some((removed_chain, remove_reason)) = removed_chain {
@@ -550,18 +551,21 @@ mod tests {
 
         fn complete_range_block_and_blobs_response(
             &mut self,
+            peer_id: &PeerId,
             block_req: RequestId,
             blob_req_opt: Option<RequestId>,
         ) -> (ChainId, BatchId, Id) {
             if blob_req_opt.is_some() {
                 match block_req {
                     RequestId::Sync(crate::sync::manager::RequestId::RangeBlockAndBlobs { id }) => {
-                        let _ = self
-                            .cx
-                            .range_block_and_blob_response(id, BlockOrBlob::Block(None));
+                        let _ = self.cx.range_block_and_blob_response(
+                            id,
+                            peer_id,
+                            BlockOrBlob::Block(None),
+                        );
                         let response = self
                             .cx
-                            .range_block_and_blob_response(id, BlockOrBlob::Blob(None))
+                            .range_block_and_blob_response(id, peer_id, BlockOrBlob::Blob(None))
                             .unwrap();
                         let (chain_id, batch_id) =
                             TestRig::unwrap_range_request_id(response.sender_id);
@@ -574,7 +578,7 @@ mod tests {
                     RequestId::Sync(crate::sync::manager::RequestId::RangeBlockAndBlobs { id }) => {
                         let response = self
                             .cx
-                            .range_block_and_blob_response(id, BlockOrBlob::Block(None))
+                            .range_block_and_blob_response(id, peer_id, BlockOrBlob::Block(None))
                             .unwrap();
                         let (chain_id, batch_id) =
                             TestRig::unwrap_range_request_id(response.sender_id);
@@ -790,7 +794,7 @@ mod tests {
         let (block_req, blob_req_opt) = rig.grab_request(&peer1, fork);
 
         let (chain1, batch1, id1) =
-            rig.complete_range_block_and_blobs_response(block_req, blob_req_opt);
+            rig.complete_range_block_and_blobs_response(&peer1, block_req, blob_req_opt);
 
         // make the ee offline
         rig.cx.update_execution_engine_state(EngineState::Offline);
@@ -807,7 +811,7 @@ mod tests {
         let (block_req, blob_req_opt) = rig.grab_request(&peer2, fork);
 
         let (chain2, batch2, id2) =
-            rig.complete_range_block_and_blobs_response(block_req, blob_req_opt);
+            rig.complete_range_block_and_blobs_response(&peer2, block_req, blob_req_opt);
 
         // send the response to the request
         range.blocks_by_range_response(&mut rig.cx, peer2, chain2, batch2, id2, None);
@@ -243,6 +243,16 @@ impl<E: EthSpec> OperationPool<E> {
             })
     }
 
+    /// Update the reward cache for `state` ahead of block production.
+    ///
+    /// The reward cache is otherwise built lazily by `get_attestations`, which places it on the
+    /// critical path of the first proposal of each slot (including the first proposal after a
+    /// restart).
+    pub fn update_reward_cache(&self, state: &BeaconState<E>) -> Result<(), OpPoolError> {
+        let _timer = metrics::start_timer(&metrics::BUILD_REWARD_CACHE_TIME);
+        self.reward_cache.write().update(state)
+    }
+
     /// Get a list of attestations for inclusion in a block.
     ///
     /// The `validity_filter` is a closure that provides extra filtering of the attestations
@@ -272,7 +282,8 @@ impl<E: EthSpec> OperationPool<E> {
             .get_total_active_balance()
             .map_err(OpPoolError::GetAttestationsTotalBalanceError)?;
 
-        // Update the reward cache.
+        // Update the reward cache. This is a no-op if it was already primed for `state` via
+        // `update_reward_cache`.
         let reward_timer = metrics::start_timer(&metrics::BUILD_REWARD_CACHE_TIME);
         let mut reward_cache = self.reward_cache.write();
         reward_cache.update(state)?;
@@ -649,6 +660,11 @@ impl<E: EthSpec> OperationPool<E> {
         self.voluntary_exits.read().len()
     }
 
+    /// Total number of BLS to execution changes in the pool.
+    pub fn num_bls_to_execution_changes(&self) -> usize {
+        self.bls_to_execution_changes.read().iter_fifo().count()
+    }
+
     /// Returns all known `Attestation` objects.
     ///
     /// This method may return objects that are invalid for block inclusion.
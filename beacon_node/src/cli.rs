@@ -650,6 +650,47 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("monitoring-file")
+                .long("monitoring-file")
+                .value_name("PATH")
+                .help("Enables the monitoring service for appending the metrics that would be sent to \
+                the monitoring-endpoint to a local file, one JSON object per line. The file is \
+                rotated once it reaches 10MB, keeping the 5 most recent rotated files.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-file-period")
+                .long("monitoring-file-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each write to \
+                       the monitoring-file. Default: 60s")
+                .requires("monitoring-file")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-remote-write")
+                .long("monitoring-remote-write")
+                .value_name("ADDRESS")
+                .help("Enables the monitoring service for pushing the metrics that would be sent to \
+                the monitoring-endpoint to a Prometheus remote-write endpoint, e.g. \
+                http://localhost:9090/api/v1/write. Metrics which cannot be sent are retried \
+                once the endpoint is available again.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-remote-write-period")
+                .long("monitoring-remote-write-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each message sent to \
+                       the monitoring-remote-write endpoint. Default: 60s")
+                .requires("monitoring-remote-write")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
 
         /*
          * Standard staking flags
@@ -1486,6 +1527,17 @@ pub fn cli_app() -> Command {
                     filling up their disks.")
             .display_order(0)
         )
+        .arg(
+            Arg::new("fork-choice-snapshot-dir")
+            .action(ArgAction::Set)
+            .long("fork-choice-snapshot-dir")
+            .value_name("DIR")
+            .help("Whenever a re-org occurs, store a snapshot of fork choice as an SSZ file in \
+                    this directory. Snapshots can be replayed with `lcli replay-fork-choice`. This \
+                    feature is only recommended for developers. This directory is not pruned, \
+                    users should be careful to avoid filling up their disks.")
+            .display_order(0)
+        )
         .arg(
             Arg::new("progressive-balances")
                 .long("progressive-balances")
@@ -1555,6 +1607,31 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-processor-batch-latency-target")
+                .long("beacon-processor-batch-latency-target")
+                .value_name("MILLISECONDS")
+                .help("Target time for verifying a batch of gossip attestations or aggregates. \
+                       Batches are sized from the depth of the queue and the observed verification \
+                       time so that they complete within this target, up to the configured batch \
+                       sizes. By default, or if set to 0, the configured batch sizes are always \
+                       used.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-scheduler-config")
+                .long("beacon-processor-scheduler-config")
+                .value_name("FILE")
+                .help("Path to a JSON file specifying the order in which beacon processor queues \
+                       are drained, along with overrides for queue lengths and batch sizes. \
+                       The configuration can be changed at runtime via the \
+                       /lighthouse/beacon_processor/scheduler HTTP API endpoint.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-duplicate-warn-logs")
                 .long("disable-duplicate-warn-logs")
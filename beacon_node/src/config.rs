@@ -213,7 +213,30 @@ pub fn get_config<E: EthSpec>(
     /*
      * Explorer metrics
      */
-    if let Some(monitoring_endpoint) = cli_args.get_one::<String>("monitoring-endpoint") {
+    let monitoring_endpoint = clap_utils::parse_optional(cli_args, "monitoring-endpoint")?;
+    let monitoring_file = clap_utils::parse_optional(cli_args, "monitoring-file")?
+        .map(|path| {
+            Ok::<_, String>(monitoring_api::FileConfig {
+                path,
+                update_period_secs: clap_utils::parse_optional(cli_args, "monitoring-file-period")?,
+            })
+        })
+        .transpose()?;
+    let monitoring_remote_write = clap_utils::parse_optional(cli_args, "monitoring-remote-write")?
+        .map(|endpoint| {
+            Ok::<_, String>(monitoring_api::RemoteWriteConfig {
+                endpoint,
+                update_period_secs: clap_utils::parse_optional(
+                    cli_args,
+                    "monitoring-remote-write-period",
+                )?,
+            })
+        })
+        .transpose()?;
+    if monitoring_endpoint.is_some()
+        || monitoring_file.is_some()
+        || monitoring_remote_write.is_some()
+    {
         let update_period_secs =
             clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
 
@@ -221,7 +244,9 @@ pub fn get_config<E: EthSpec>(
             db_path: None,
             freezer_db_path: None,
             update_period_secs,
-            monitoring_endpoint: monitoring_endpoint.to_string(),
+            monitoring_endpoint,
+            file: monitoring_file,
+            remote_write: monitoring_remote_write,
         });
     }
 
@@ -859,6 +884,10 @@ pub fn get_config<E: EthSpec>(
         client_config.network.invalid_block_storage = Some(path);
     }
 
+    if let Some(path) = clap_utils::parse_optional(cli_args, "fork-choice-snapshot-dir")? {
+        client_config.chain.fork_choice_snapshot_dir = Some(path);
+    }
+
     if cli_args.get_one::<String>("progressive-balances").is_some() {
         warn!(
             log,
@@ -888,6 +917,25 @@ pub fn get_config<E: EthSpec>(
         .beacon_processor
         .max_gossip_aggregate_batch_size =
         clap_utils::parse_required(cli_args, "beacon-processor-aggregate-batch-size")?;
+    let batch_latency_target_ms: Option<u64> =
+        clap_utils::parse_optional(cli_args, "beacon-processor-batch-latency-target")?;
+    client_config.beacon_processor.gossip_batch_latency_target = batch_latency_target_ms
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis);
+
+    if let Some(scheduler_config_path) =
+        cli_args.get_one::<String>("beacon-processor-scheduler-config")
+    {
+        let file = fs::File::open(scheduler_config_path)
+            .map_err(|e| format!("Failed to open beacon processor scheduler config: {}", e))?;
+        client_config.beacon_processor.scheduler = serde_json::from_reader(file)
+            .map_err(|e| format!("Unable to parse beacon processor scheduler config: {}", e))?;
+        client_config
+            .beacon_processor
+            .scheduler
+            .validate()
+            .map_err(|e| format!("Invalid beacon processor scheduler config: {}", e))?;
+    }
 
     Ok(client_config)
 }
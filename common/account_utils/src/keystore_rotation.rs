@@ -0,0 +1,297 @@
+//! Re-encrypts existing voting keystores with a new password and/or key derivation function
+//! (KDF), without changing the underlying keypair.
+//!
+//! This allows an operator to rotate keystore passwords or to upgrade the KDF parameters of
+//! keystores which were created with weaker settings.
+
+use crate::ZeroizeString;
+use eth2_keystore::json_keystore::{Kdf, Pbkdf2, Prf, Scrypt};
+use eth2_keystore::{Keystore, KeystoreBuilder, DKLEN, SALT_SIZE};
+use filesystem::{create_with_600_perms, Error as FsError};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use types::Keypair;
+
+/// The suffix appended to the path of a keystore or password file to create its backup.
+pub const BACKUP_SUFFIX: &str = ".backup";
+/// The suffix appended to the path of a keystore or password file whilst it is being written.
+const TEMP_SUFFIX: &str = ".tmp";
+
+/// The default scrypt cost parameter, matching `eth2_keystore::default_kdf`.
+pub const DEFAULT_SCRYPT_N: u32 = 262_144;
+/// The default PBKDF2 iteration count.
+pub const DEFAULT_PBKDF2_C: u32 = 262_144;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The keystore could not be encrypted with the new password and KDF.
+    UnableToEncryptKeystore(eth2_keystore::Error),
+    /// The keystore could not be serialized to JSON.
+    UnableToEncodeKeystore(eth2_keystore::Error),
+    /// The existing keystore or password file could not be backed up.
+    UnableToBackup(PathBuf, io::Error),
+    /// The new keystore or password file could not be written.
+    UnableToWriteFile(PathBuf, FsError),
+}
+
+/// The key derivation function used to encrypt a keystore, along with its cost parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "function", rename_all = "lowercase")]
+pub enum KeystoreKdf {
+    /// Scrypt with cost parameter `n`, where `r = 8` and `p = 1`.
+    Scrypt { n: u32 },
+    /// PBKDF2 with HMAC-SHA256 and iteration count `c`.
+    Pbkdf2 { c: u32 },
+}
+
+impl Default for KeystoreKdf {
+    fn default() -> Self {
+        KeystoreKdf::Scrypt {
+            n: DEFAULT_SCRYPT_N,
+        }
+    }
+}
+
+impl KeystoreKdf {
+    /// Returns the KDF for a new keystore, using a random salt.
+    pub fn to_kdf(self) -> Kdf {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+        match self {
+            KeystoreKdf::Scrypt { n } => Kdf::Scrypt(Scrypt {
+                dklen: DKLEN,
+                n,
+                r: 8,
+                p: 1,
+                salt: salt.into(),
+            }),
+            KeystoreKdf::Pbkdf2 { c } => Kdf::Pbkdf2(Pbkdf2 {
+                c,
+                dklen: DKLEN,
+                prf: Prf::HmacSha256,
+                salt: salt.into(),
+            }),
+        }
+    }
+}
+
+/// Returns `path` with `suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = OsString::from(path);
+    path.push(suffix);
+    path.into()
+}
+
+/// Returns the path where the first backup of the file at `path` is stored.
+pub fn backup_path(path: &Path) -> PathBuf {
+    nth_backup_path(path, 0)
+}
+
+/// Returns the path of the `n`-th backup of the file at `path`.
+///
+/// The first backup has no number, later backups are numbered from 1 so that a rotation never
+/// overwrites the backup of a previous rotation.
+pub fn nth_backup_path(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        with_suffix(path, BACKUP_SUFFIX)
+    } else {
+        with_suffix(path, &format!("{}.{}", BACKUP_SUFFIX, n))
+    }
+}
+
+/// Encrypts `keypair` with `password` and `kdf`, retaining the derivation path and description of
+/// `keystore`.
+///
+/// This is computationally expensive and performs no I/O, so callers should avoid holding any
+/// locks whilst it runs.
+pub fn encrypt_keystore(
+    keypair: &Keypair,
+    keystore: &Keystore,
+    password: &ZeroizeString,
+    kdf: KeystoreKdf,
+) -> Result<Keystore, Error> {
+    KeystoreBuilder::new(
+        keypair,
+        password.as_str().as_bytes(),
+        keystore.path().unwrap_or_default(),
+    )
+    .and_then(|builder| {
+        builder
+            .description(keystore.description().unwrap_or_default().to_string())
+            .kdf(kdf.to_kdf())
+            .build()
+    })
+    .map_err(Error::UnableToEncryptKeystore)
+}
+
+/// Replaces the keystore at `keystore_path` with `keystore` and, if provided, the password file at
+/// `password_path` with `password`.
+///
+/// Before any changes are made, the existing keystore and password file are copied to the first
+/// free `nth_backup_path`. Both new files are then written to temporary files, and only once both
+/// have been written are they renamed over the originals, starting with the password file.
+pub fn replace_keystore(
+    keystore: &Keystore,
+    keystore_path: &Path,
+    password: &ZeroizeString,
+    password_path: Option<&Path>,
+) -> Result<(), Error> {
+    let keystore_json = keystore
+        .to_json_string()
+        .map_err(Error::UnableToEncodeKeystore)?;
+
+    let mut files = vec![];
+    if let Some(password_path) = password_path {
+        files.push((password_path, password.as_str().as_bytes()));
+    }
+    files.push((keystore_path, keystore_json.as_bytes()));
+
+    let existing = files
+        .iter()
+        .map(|(path, _)| *path)
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    let backup_index = (0..)
+        .find(|&n| {
+            existing
+                .iter()
+                .all(|path| !nth_backup_path(path, n).exists())
+        })
+        .unwrap_or_default();
+    for path in existing {
+        let backup_path = nth_backup_path(path, backup_index);
+        fs::copy(path, &backup_path).map_err(|e| Error::UnableToBackup(backup_path, e))?;
+    }
+
+    for (path, bytes) in &files {
+        write_temporary_file(path, &with_suffix(path, TEMP_SUFFIX), bytes)
+            .map_err(|e| Error::UnableToWriteFile(path.to_path_buf(), e))?;
+    }
+    for (path, _) in &files {
+        fs::rename(with_suffix(path, TEMP_SUFFIX), path).map_err(|e| {
+            Error::UnableToWriteFile(path.to_path_buf(), FsError::UnableToRenameFile(e))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Writes `bytes` to `temp_path`, preserving the permissions of `file_path` if it exists.
+fn write_temporary_file(file_path: &Path, temp_path: &Path, bytes: &[u8]) -> Result<(), FsError> {
+    if file_path.exists() {
+        fs::copy(file_path, temp_path).map_err(FsError::UnableToCopyFile)?;
+        fs::write(temp_path, bytes).map_err(FsError::UnableToWriteFile)
+    } else {
+        create_with_600_perms(temp_path, bytes)
+    }
+}
+
+/// Encrypts `keypair` with `password` and `kdf`, replacing the keystore at `keystore_path` and,
+/// if provided, the password file at `password_path`.
+///
+/// The new keystore retains the derivation path and description of `keystore`, which should be
+/// the keystore currently at `keystore_path`. See `replace_keystore` for how the files are backed
+/// up and replaced.
+pub fn reencrypt_keystore(
+    keypair: &Keypair,
+    keystore: &Keystore,
+    keystore_path: &Path,
+    password: &ZeroizeString,
+    password_path: Option<&Path>,
+    kdf: KeystoreKdf,
+) -> Result<Keystore, Error> {
+    let new_keystore = encrypt_keystore(keypair, keystore, password, kdf)?;
+    replace_keystore(&new_keystore, keystore_path, password, password_path)?;
+    Ok(new_keystore)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{random_password_string, read_password_string};
+    use tempfile::tempdir;
+
+    #[test]
+    fn reencrypt_with_new_password_and_kdf() {
+        let dir = tempdir().unwrap();
+        let keystore_path = dir.path().join("voting-keystore.json");
+        let password_path = dir.path().join("password");
+
+        let keypair = Keypair::random();
+        let old_password = random_password_string();
+        let old_keystore = KeystoreBuilder::new(
+            &keypair,
+            old_password.as_str().as_bytes(),
+            "m/12381/3600/0/0/0".into(),
+        )
+        .unwrap()
+        .kdf(KeystoreKdf::Scrypt { n: 2 }.to_kdf())
+        .build()
+        .unwrap();
+        fs::write(&keystore_path, old_keystore.to_json_string().unwrap()).unwrap();
+        fs::write(&password_path, old_password.as_str()).unwrap();
+
+        let new_password = random_password_string();
+        let new_keystore = reencrypt_keystore(
+            &keypair,
+            &old_keystore,
+            &keystore_path,
+            &new_password,
+            Some(password_path.as_path()),
+            KeystoreKdf::Pbkdf2 { c: 2 },
+        )
+        .unwrap();
+
+        // The new files replace the old ones.
+        let keystore = Keystore::from_json_file(&keystore_path).unwrap();
+        assert_eq!(keystore, new_keystore);
+        assert!(matches!(keystore.kdf(), Kdf::Pbkdf2(Pbkdf2 { c: 2, .. })));
+        assert_eq!(keystore.path(), old_keystore.path());
+        assert_eq!(
+            keystore
+                .decrypt_keypair(new_password.as_str().as_bytes())
+                .unwrap()
+                .pk,
+            keypair.pk
+        );
+        assert!(keystore
+            .decrypt_keypair(old_password.as_str().as_bytes())
+            .is_err());
+        assert!(read_password_string(&password_path).unwrap() == new_password);
+
+        // The old files are backed up.
+        assert_eq!(
+            Keystore::from_json_file(backup_path(&keystore_path)).unwrap(),
+            old_keystore
+        );
+        assert!(read_password_string(backup_path(&password_path)).unwrap() == old_password);
+
+        // A second rotation keeps the first backup.
+        let newest_password = random_password_string();
+        reencrypt_keystore(
+            &keypair,
+            &new_keystore,
+            &keystore_path,
+            &newest_password,
+            Some(password_path.as_path()),
+            KeystoreKdf::Pbkdf2 { c: 2 },
+        )
+        .unwrap();
+        assert_eq!(
+            Keystore::from_json_file(backup_path(&keystore_path)).unwrap(),
+            old_keystore
+        );
+        assert!(read_password_string(backup_path(&password_path)).unwrap() == old_password);
+        assert_eq!(
+            Keystore::from_json_file(nth_backup_path(&keystore_path, 1)).unwrap(),
+            new_keystore
+        );
+        assert!(read_password_string(nth_backup_path(&password_path, 1)).unwrap() == new_password);
+        assert!(read_password_string(&password_path).unwrap() == newest_password);
+        assert!(!with_suffix(&keystore_path, TEMP_SUFFIX).exists());
+        assert!(!with_suffix(&password_path, TEMP_SUFFIX).exists());
+    }
+}
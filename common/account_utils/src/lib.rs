@@ -18,6 +18,7 @@ use std::thread::sleep;
 use std::time::Duration;
 use zeroize::Zeroize;
 
+pub mod keystore_rotation;
 pub mod validator_definitions;
 
 pub use eth2_keystore;
@@ -0,0 +1,345 @@
+//! Support for loading the arguments of a subcommand from a TOML file via `--config-file`.
+//!
+//! Each key in the file is the name of an argument of the subcommand, or of a global argument,
+//! and each value is the value of that argument:
+//!
+//! ```toml
+//! network = "mainnet"
+//! execution-endpoint = "http://localhost:8551"
+//! http = true
+//! target-peers = 100
+//! libp2p-addresses = ["/ip4/1.2.3.4/tcp/9000", "/ip4/5.6.7.8/tcp/9000"]
+//! ```
+//!
+//! Flags take a boolean, where `false` is equivalent to omitting the flag. Lists are passed as a
+//! comma-separated value, or as repeated arguments if the argument may be repeated. Arguments
+//! provided on the command line take precedence over the file.
+
+use crate::flags::CONFIG_FILE_FLAG;
+use crate::parse_optional;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Arguments which only apply to a single invocation and so cannot appear in a config file.
+const EXCLUDED_ARGS: &[&str] = &[
+    CONFIG_FILE_FLAG,
+    "dump-config",
+    "dump-chain-config",
+    "immediate-shutdown",
+    "help",
+    "version",
+];
+
+/// Parses the process arguments with `cli`, adding the arguments from the file provided by
+/// `--config-file`, if any.
+///
+/// Exits the process on a parse error, like `Command::get_matches`.
+pub fn get_matches_with_config_file(cli: Command) -> Result<ArgMatches, String> {
+    let matches = cli.clone().get_matches();
+    let Some(path) = matches.get_one::<String>(CONFIG_FILE_FLAG) else {
+        return Ok(matches);
+    };
+
+    let args = args_with_config_file(&cli, &matches, Path::new(path), std::env::args_os())?;
+    Ok(cli.get_matches_from(args))
+}
+
+/// Returns `args` with the arguments from the config file at `path` appended, excluding those
+/// which were already provided on the command line.
+pub fn args_with_config_file(
+    cli: &Command,
+    matches: &ArgMatches,
+    path: &Path,
+    args: impl IntoIterator<Item = OsString>,
+) -> Result<Vec<OsString>, String> {
+    let (subcommand, sub_matches) = matches
+        .subcommand()
+        .ok_or_else(|| format!("--{} requires a subcommand", CONFIG_FILE_FLAG))?;
+    // The arguments are appended to the end of the command line, which is only correct when the
+    // last subcommand is the one they belong to.
+    if sub_matches.subcommand().is_some() {
+        return Err(format!(
+            "--{} is not supported by nested subcommands",
+            CONFIG_FILE_FLAG
+        ));
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read config file {:?}: {}", path, e))?;
+    let table: Table = toml::from_str(&contents)
+        .map_err(|e| format!("Unable to parse config file {:?}: {}", path, e))?;
+
+    let mut args = args.into_iter().collect::<Vec<_>>();
+    for (key, value) in table {
+        let arg = find_arg(cli, subcommand, &key)
+            .ok_or_else(|| format!("Unknown key `{}` in config file {:?}", key, path))?;
+        if EXCLUDED_ARGS.contains(&key.as_str()) {
+            return Err(format!("`{}` cannot be set in a config file", key));
+        }
+        if sub_matches.value_source(key.as_str()) == Some(ValueSource::CommandLine) {
+            continue;
+        }
+        args.extend(
+            arg_to_cli(arg, &value)
+                .map_err(|e| format!("Invalid value for `{}` in config file: {}", key, e))?,
+        );
+    }
+    Ok(args)
+}
+
+/// Returns the config file equivalent of the arguments provided on the command line for
+/// `subcommand`.
+pub fn config_file_from_matches(
+    cli: &Command,
+    subcommand: &str,
+    matches: &ArgMatches,
+) -> Result<String, String> {
+    let sub_cmd = cli
+        .find_subcommand(subcommand)
+        .ok_or_else(|| format!("Unknown subcommand {}", subcommand))?;
+
+    let mut table = Table::new();
+    for arg in sub_cmd.get_arguments().chain(global_args(cli)) {
+        let id = arg.get_id().as_str();
+        if EXCLUDED_ARGS.contains(&id) || matches.value_source(id) != Some(ValueSource::CommandLine)
+        {
+            continue;
+        }
+
+        let value = if arg.get_action().takes_values() {
+            let mut values = matches
+                .get_raw(id)
+                .into_iter()
+                .flatten()
+                .map(|value| {
+                    value
+                        .to_str()
+                        .map(|value| Value::String(value.to_string()))
+                        .ok_or_else(|| format!("Value of {} is not valid UTF-8", id))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.len() == 1 && !matches!(arg.get_action(), ArgAction::Append) {
+                values.remove(0)
+            } else {
+                Value::Array(values)
+            }
+        } else {
+            Value::Boolean(true)
+        };
+        table.insert(id.to_string(), value);
+    }
+
+    toml::to_string(&table).map_err(|e| format!("Unable to serialize config file: {}", e))
+}
+
+/// Writes the config file equivalent of the command line arguments of `subcommand` if the
+/// `dump-config` flag is set to a path ending in `.toml`.
+pub fn check_dump_config_file(
+    cli: &Command,
+    subcommand: &str,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    if let Some(dump_path) =
+        parse_optional::<PathBuf>(matches, "dump-config")?.filter(|path| is_config_file_path(path))
+    {
+        let contents = config_file_from_matches(cli, subcommand, matches)?;
+        fs::write(&dump_path, contents)
+            .map_err(|e| format!("Failed to write config file {:?}: {:?}", dump_path, e))?;
+    }
+    Ok(())
+}
+
+/// Returns `true` if `path` is a config file rather than a JSON dump of the config.
+pub fn is_config_file_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "toml")
+}
+
+fn global_args(cli: &Command) -> impl Iterator<Item = &Arg> {
+    cli.get_arguments().filter(|arg| arg.is_global_set())
+}
+
+fn find_arg<'a>(cli: &'a Command, subcommand: &str, key: &str) -> Option<&'a Arg> {
+    cli.find_subcommand(subcommand)?
+        .get_arguments()
+        .chain(global_args(cli))
+        .find(|arg| arg.get_id() == key)
+}
+
+/// Converts a config file value into the equivalent command line arguments.
+fn arg_to_cli(arg: &Arg, value: &Value) -> Result<Vec<OsString>, String> {
+    let long = arg
+        .get_long()
+        .ok_or_else(|| "argument has no long name".to_string())?;
+
+    if !arg.get_action().takes_values() {
+        return match value {
+            Value::Boolean(true) => Ok(vec![format!("--{}", long).into()]),
+            Value::Boolean(false) => Ok(vec![]),
+            _ => Err("expected a boolean".into()),
+        };
+    }
+
+    let values = match value {
+        Value::Array(values) => values
+            .iter()
+            .map(value_to_string)
+            .collect::<Result<Vec<_>, _>>()?,
+        value => vec![value_to_string(value)?],
+    };
+    let values = if matches!(arg.get_action(), ArgAction::Append) {
+        values
+    } else {
+        vec![values.join(",")]
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|value| format!("--{}={}", long, value).into())
+        .collect())
+}
+
+fn value_to_string(value: &Value) -> Result<String, String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Integer(value) => Ok(value.to_string()),
+        Value::Float(value) => Ok(value.to_string()),
+        Value::Boolean(value) => Ok(value.to_string()),
+        Value::Array(_) | Value::Table(_) | Value::Datetime(_) => {
+            Err("expected a string, number, boolean or list of those".into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn cli() -> Command {
+        Command::new("lighthouse")
+            .arg(
+                Arg::new("network")
+                    .long("network")
+                    .action(ArgAction::Set)
+                    .global(true),
+            )
+            .arg(
+                Arg::new(CONFIG_FILE_FLAG)
+                    .long(CONFIG_FILE_FLAG)
+                    .action(ArgAction::Set)
+                    .global(true),
+            )
+            .subcommand(
+                Command::new("beacon_node")
+                    .arg(Arg::new("http").long("http").action(ArgAction::SetTrue))
+                    .arg(Arg::new("port").long("port").action(ArgAction::Set))
+                    .arg(
+                        Arg::new("boot-nodes")
+                            .long("boot-nodes")
+                            .action(ArgAction::Set),
+                    )
+                    .arg(
+                        Arg::new("trusted-peer")
+                            .long("trusted-peer")
+                            .action(ArgAction::Append),
+                    ),
+            )
+    }
+
+    fn matches_with_config_file(args: &[&str], contents: &str) -> Result<ArgMatches, String> {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let args = ["lighthouse", "beacon_node", "--config-file", path]
+            .iter()
+            .chain(args)
+            .map(OsString::from)
+            .collect::<Vec<_>>();
+        let matches = cli().get_matches_from(args.clone());
+        let args = args_with_config_file(&cli(), &matches, file.path(), args)?;
+        cli().try_get_matches_from(args).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn config_file_values() {
+        let matches = matches_with_config_file(
+            &["--port", "9001"],
+            r#"
+            network = "holesky"
+            http = true
+            port = 9000
+            boot-nodes = ["a", "b"]
+            trusted-peer = ["c", "d"]
+            "#,
+        )
+        .unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+
+        assert_eq!(matches.get_one::<String>("network").unwrap(), "holesky");
+        assert!(matches.get_flag("http"));
+        // The command line takes precedence.
+        assert_eq!(matches.get_one::<String>("port").unwrap(), "9001");
+        assert_eq!(matches.get_one::<String>("boot-nodes").unwrap(), "a,b");
+        assert_eq!(
+            matches
+                .get_many::<String>("trusted-peer")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            ["c", "d"]
+        );
+    }
+
+    #[test]
+    fn invalid_config_files() {
+        for contents in [
+            "unknown = 1",
+            "config-file = \"config.toml\"",
+            "http = \"yes\"",
+            "port = { value = 1 }",
+            "not toml",
+        ] {
+            assert!(
+                matches_with_config_file(&[], contents).is_err(),
+                "{contents}"
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let args = [
+            "lighthouse",
+            "beacon_node",
+            "--network",
+            "holesky",
+            "--http",
+            "--port",
+            "9000",
+            "--trusted-peer",
+            "c",
+        ];
+        let matches = cli().get_matches_from(args);
+        let (_, sub_matches) = matches.subcommand().unwrap();
+        let contents = config_file_from_matches(&cli(), "beacon_node", sub_matches).unwrap();
+
+        let matches = matches_with_config_file(&[], &contents).unwrap();
+        let (_, matches) = matches.subcommand().unwrap();
+        assert_eq!(matches.get_one::<String>("network").unwrap(), "holesky");
+        assert!(matches.get_flag("http"));
+        assert_eq!(matches.get_one::<String>("port").unwrap(), "9000");
+        assert_eq!(
+            matches
+                .get_many::<String>("trusted-peer")
+                .unwrap()
+                .collect::<Vec<_>>(),
+            ["c"]
+        );
+    }
+}
@@ -9,6 +9,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use types::{ChainSpec, Config, EthSpec};
 
+pub mod config_file;
 pub mod flags;
 
 pub const BAD_TESTNET_DIR_MESSAGE: &str = "The hard-coded testnet directory was invalid. \
@@ -170,6 +171,8 @@ pub fn parse_ssz_optional<T: Decode>(
 }
 
 /// Writes configs to file if `dump-config` or `dump-chain-config` flags are set
+///
+/// A `dump-config` path ending in `.toml` is handled by `config_file::check_dump_config_file`.
 pub fn check_dump_configs<S, E>(
     matches: &ArgMatches,
     config: S,
@@ -179,7 +182,9 @@ where
     S: serde::Serialize,
     E: EthSpec,
 {
-    if let Some(dump_path) = parse_optional::<PathBuf>(matches, "dump-config")? {
+    if let Some(dump_path) = parse_optional::<PathBuf>(matches, "dump-config")?
+        .filter(|path| !config_file::is_config_file_path(path))
+    {
         let mut file = std::fs::File::create(dump_path)
             .map_err(|e| format!("Failed to open file for writing config: {:?}", e))?;
         serde_json::to_writer(&mut file, &config)
@@ -16,7 +16,8 @@ pub mod types;
 
 use self::mixin::{RequestAccept, ResponseOptional};
 use self::types::{Error as ResponseError, *};
-use futures::Stream;
+use bytes::Bytes;
+use futures::{stream::BoxStream, Stream};
 use futures_util::StreamExt;
 use lighthouse_network::PeerId;
 use pretty_reqwest_error::PrettyReqwestError;
@@ -47,6 +48,14 @@ pub const CONSENSUS_BLOCK_VALUE_HEADER: &str = "Eth-Consensus-Block-Value";
 pub const CONTENT_TYPE_HEADER: &str = "Content-Type";
 pub const SSZ_CONTENT_TYPE_HEADER: &str = "application/octet-stream";
 
+pub const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Delay before the first attempt to re-establish a dropped event stream, doubled on each
+/// subsequent attempt.
+const EVENT_STREAM_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Upper bound on the delay between attempts to re-establish a dropped event stream.
+const EVENT_STREAM_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(32);
+
 #[derive(Debug)]
 pub enum Error {
     /// The `reqwest` client raised an error.
@@ -1404,22 +1413,28 @@ impl BeaconNodeHttpClient {
             .map(|opt| opt.map(|r| r.data))
     }
 
-    /// `POST beacon/rewards/sync_committee`
+    /// `POST beacon/rewards/sync_committee/{block_id}`
+    ///
+    /// Returns the rewards of all sync committee members if `validators` is empty, or `None` if the
+    /// block predates Altair.
     pub async fn post_beacon_rewards_sync_committee(
         &self,
-        rewards: &[Option<Vec<lighthouse::SyncCommitteeReward>>],
-    ) -> Result<(), Error> {
+        block_id: BlockId,
+        validators: &[ValidatorId],
+    ) -> Result<
+        ExecutionOptimisticFinalizedResponse<Option<Vec<lighthouse::SyncCommitteeReward>>>,
+        Error,
+    > {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("sync_committee");
-
-        self.post(path, &rewards).await?;
+            .push("sync_committee")
+            .push(&block_id.to_string());
 
-        Ok(())
+        self.post_with_response(path, &validators).await
     }
 
     /// `GET beacon/rewards/blocks`
@@ -2355,6 +2370,82 @@ impl BeaconNodeHttpClient {
         &self,
         topic: &[EventTopic],
     ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
+        let path = self.events_path(topic)?;
+        Ok(self
+            .client
+            .get(path)
+            .send()
+            .await?
+            .bytes_stream()
+            .map(|next| match next {
+                Ok(bytes) => EventKind::from_sse_bytes(bytes.as_ref()),
+                Err(e) => Err(Error::HttpClient(e.into())),
+            }))
+    }
+
+    /// `GET events?topics`
+    ///
+    /// If the connection drops, the stream reconnects and resumes from the last event received,
+    /// backing off exponentially between attempts. The stream ends after `max_reconnects`
+    /// consecutive reconnections fail to deliver an event.
+    pub async fn get_events_resumable<E: EthSpec>(
+        &self,
+        topic: &[EventTopic],
+        max_reconnects: u32,
+    ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error> {
+        let path = self.events_path(topic)?;
+        let state = EventStreamState {
+            client: self.clone(),
+            stream: Some(self.get_event_stream(path.clone(), None).await?),
+            path,
+            last_event_id: None,
+            reconnect_attempts: 0,
+            max_reconnects,
+        };
+
+        Ok(futures::stream::unfold(state, |mut state| async move {
+            loop {
+                let Some(stream) = state.stream.as_mut() else {
+                    if state.reconnect_attempts >= state.max_reconnects {
+                        return None;
+                    }
+                    let delay = EVENT_STREAM_RECONNECT_DELAY
+                        .saturating_mul(2u32.saturating_pow(state.reconnect_attempts))
+                        .min(EVENT_STREAM_MAX_RECONNECT_DELAY);
+                    tokio::time::sleep(delay).await;
+                    state.reconnect_attempts += 1;
+                    match state
+                        .client
+                        .get_event_stream(state.path.clone(), state.last_event_id.as_deref())
+                        .await
+                    {
+                        Ok(stream) => state.stream = Some(stream),
+                        Err(e) => return Some((Err(e), state)),
+                    }
+                    continue;
+                };
+
+                match stream.next().await {
+                    Some(Ok(bytes)) => {
+                        if let Some(id) = sse_event_id(bytes.as_ref()) {
+                            state.last_event_id = Some(id);
+                        }
+                        state.reconnect_attempts = 0;
+                        return Some((EventKind::from_sse_bytes(bytes.as_ref()), state));
+                    }
+                    Some(Err(e)) => {
+                        state.stream = None;
+                        return Some((Err(Error::HttpClient(e.into())), state));
+                    }
+                    // The server closed the connection.
+                    None => state.stream = None,
+                }
+            }
+        }))
+    }
+
+    /// Returns the URL of the events endpoint subscribed to `topic`.
+    fn events_path(&self, topic: &[EventTopic]) -> Result<Url, Error> {
         let mut path = self.eth_path(V1)?;
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
@@ -2366,17 +2457,23 @@ impl BeaconNodeHttpClient {
             .collect::<Vec<_>>()
             .join(",");
         path.query_pairs_mut().append_pair("topics", &topic_string);
+        Ok(path)
+    }
 
-        Ok(self
-            .client
-            .get(path)
-            .send()
-            .await?
-            .bytes_stream()
-            .map(|next| match next {
-                Ok(bytes) => EventKind::from_sse_bytes(bytes.as_ref()),
-                Err(e) => Err(Error::HttpClient(e.into())),
-            }))
+    /// Open a connection to the events endpoint at `path`, resuming after `last_event_id` if
+    /// provided.
+    async fn get_event_stream(
+        &self,
+        path: Url,
+        last_event_id: Option<&str>,
+    ) -> Result<BoxStream<'static, reqwest::Result<Bytes>>, Error> {
+        let response = self
+            .get_response(path, |builder| match last_event_id {
+                Some(id) => builder.header(LAST_EVENT_ID_HEADER, id),
+                None => builder,
+            })
+            .await?;
+        Ok(response.bytes_stream().boxed())
     }
 
     /// `POST validator/duties/sync/{epoch}`
@@ -2403,6 +2500,30 @@ impl BeaconNodeHttpClient {
     }
 }
 
+/// State for the self-reconnecting stream returned by
+/// `BeaconNodeHttpClient::get_events_resumable`.
+struct EventStreamState {
+    client: BeaconNodeHttpClient,
+    path: Url,
+    /// The ID of the last event received, sent when reconnecting so that missed events are
+    /// replayed.
+    last_event_id: Option<String>,
+    /// The current connection, or `None` if it has dropped.
+    stream: Option<BoxStream<'static, reqwest::Result<Bytes>>>,
+    /// Number of reconnections made since an event was last received.
+    reconnect_attempts: u32,
+    max_reconnects: u32,
+}
+
+/// Returns the value of the `id` field of a server-sent event, if any.
+fn sse_event_id(message: &[u8]) -> Option<String> {
+    std::str::from_utf8(message)
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("id:"))
+        .map(|id| id.trim().to_string())
+}
+
 /// Returns `Ok(response)` if the response is a `200 OK` response. Otherwise, creates an
 /// appropriate error message.
 pub async fn ok_or_error(response: Response) -> Result<Response, Error> {
@@ -2,14 +2,18 @@
 
 mod attestation_performance;
 pub mod attestation_rewards;
+mod beacon_processor_scheduler;
 mod block_packing_efficiency;
 mod block_rewards;
+mod block_simulation;
 mod standard_block_rewards;
+mod state_proof;
 mod sync_committee_rewards;
 
 use crate::{
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        Accept, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse,
+        StateId, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
@@ -23,12 +27,18 @@ pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
 pub use attestation_rewards::StandardAttestationRewards;
+pub use beacon_processor_scheduler::{SchedulerConfig, WorkQueueId};
 pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
+pub use block_simulation::{
+    BlockSimulation, BlockSimulationPacking, BlockSimulationQuery, OperationPacking,
+    PackedAttestation, SyncAggregatePacking,
+};
 pub use lighthouse_network::{types::SyncState, PeerInfo};
 pub use standard_block_rewards::StandardBlockReward;
+pub use state_proof::{MerkleProof, StateProof, StateProofQuery};
 pub use sync_committee_rewards::SyncCommitteeReward;
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
@@ -410,6 +420,22 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/fork_choice/snapshot`
+    ///
+    /// Returns the SSZ-encoded `ForkChoiceSnapshot` of the beacon node.
+    pub async fn get_lighthouse_fork_choice_snapshot_ssz(&self) -> Result<Option<Vec<u8>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("snapshot");
+
+        self.get_bytes_opt_accept_header(path, Accept::Ssz, self.timeouts.get_debug_beacon_states)
+            .await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -515,6 +541,37 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/beacon_processor/scheduler`
+    pub async fn get_lighthouse_beacon_processor_scheduler(
+        &self,
+    ) -> Result<SchedulerConfig, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor")
+            .push("scheduler");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/beacon_processor/scheduler`
+    pub async fn post_lighthouse_beacon_processor_scheduler(
+        &self,
+        config: &SchedulerConfig,
+    ) -> Result<SchedulerConfig, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor")
+            .push("scheduler");
+
+        self.post_with_response(path, config).await
+    }
+
     /// `POST lighthouse/database/reconstruct`
     pub async fn post_lighthouse_database_reconstruct(&self) -> Result<String, Error> {
         let mut path = self.server.full.clone();
@@ -596,4 +653,85 @@ impl BeaconNodeHttpClient {
 
         self.get(path).await
     }
+
+    /// `GET` lighthouse/analysis/block_simulation/{slot}
+    pub async fn get_lighthouse_analysis_block_simulation<E: EthSpec>(
+        &self,
+        slot: Slot,
+        query: &BlockSimulationQuery,
+    ) -> Result<BlockSimulation<E>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_simulation")
+            .push(&slot.to_string());
+
+        if let Some(graffiti) = query.graffiti {
+            path.query_pairs_mut()
+                .append_pair("graffiti", &graffiti.to_string());
+        }
+
+        if let Some(builder_boost_factor) = query.builder_boost_factor {
+            path.query_pairs_mut()
+                .append_pair("builder_boost_factor", &builder_boost_factor.to_string());
+        }
+
+        for (key, exclude) in [
+            ("exclude_attestations", query.exclude_attestations),
+            (
+                "exclude_proposer_slashings",
+                query.exclude_proposer_slashings,
+            ),
+            (
+                "exclude_attester_slashings",
+                query.exclude_attester_slashings,
+            ),
+            ("exclude_voluntary_exits", query.exclude_voluntary_exits),
+            (
+                "exclude_bls_to_execution_changes",
+                query.exclude_bls_to_execution_changes,
+            ),
+            ("exclude_sync_aggregate", query.exclude_sync_aggregate),
+        ] {
+            if exclude {
+                path.query_pairs_mut().append_pair(key, "true");
+            }
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/proof/states/{state_id}`
+    pub async fn get_lighthouse_proof_states(
+        &self,
+        state_id: StateId,
+        query: &StateProofQuery,
+    ) -> Result<StateProof, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proof")
+            .push("states")
+            .push(&state_id.to_string());
+
+        if let Some(gindices) = &query.gindex {
+            let gindices = gindices
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut().append_pair("gindex", &gindices);
+        }
+
+        if let Some(paths) = &query.path {
+            path.query_pairs_mut().append_pair("path", &paths.join(","));
+        }
+
+        self.get(path).await
+    }
 }
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Identifies one of the work queues maintained by the beacon processor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkQueueId {
+    ChainSegment,
+    RpcBlock,
+    RpcBlob,
+    DelayedBlock,
+    GossipBlock,
+    GossipBlob,
+    ApiRequestP0,
+    Aggregate,
+    Attestation,
+    SyncContribution,
+    SyncMessage,
+    UnknownBlockAggregate,
+    UnknownBlockAttestation,
+    Status,
+    BlocksByRange,
+    BlocksByRoots,
+    BlobsByRange,
+    BlobsByRoots,
+    GossipAttesterSlashing,
+    GossipProposerSlashing,
+    GossipVoluntaryExit,
+    GossipBlsToExecutionChange,
+    ApiRequestP1,
+    BackfillChainSegment,
+    LightClientBootstrap,
+    LightClientOptimisticUpdateRequest,
+    LightClientFinalityUpdateRequest,
+    GossipLightClientFinalityUpdate,
+    GossipLightClientOptimisticUpdate,
+    UnknownLightClientUpdate,
+}
+
+/// The scheduling policy of the beacon processor, as used by
+/// `lighthouse/beacon_processor/scheduler`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// The queues in the order that they are drained when a worker becomes free.
+    pub priorities: Vec<WorkQueueId>,
+    /// Overrides for the maximum number of items held by each queue.
+    #[serde(default)]
+    pub queue_lengths: HashMap<WorkQueueId, usize>,
+    /// Overrides for the batch budget of each queue.
+    #[serde(default)]
+    pub batch_sizes: HashMap<WorkQueueId, usize>,
+}
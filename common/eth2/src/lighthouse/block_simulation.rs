@@ -0,0 +1,87 @@
+use super::StandardBlockReward;
+use crate::types::ProduceBlockV3Response;
+use serde::{Deserialize, Serialize};
+use types::{AttestationData, EthSpec, Graffiti, Uint256};
+
+/// Query parameters for the `/lighthouse/analysis/block_simulation/{slot}` endpoint.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockSimulationQuery {
+    /// Graffiti to include in the block, as if it were supplied by a validator client.
+    pub graffiti: Option<Graffiti>,
+    /// Builder boost factor, with the same meaning as for `produceBlockV3`.
+    ///
+    /// A value of `0` forces a locally built payload to be used.
+    pub builder_boost_factor: Option<u64>,
+    #[serde(default)]
+    pub exclude_attestations: bool,
+    #[serde(default)]
+    pub exclude_proposer_slashings: bool,
+    #[serde(default)]
+    pub exclude_attester_slashings: bool,
+    #[serde(default)]
+    pub exclude_voluntary_exits: bool,
+    #[serde(default)]
+    pub exclude_bls_to_execution_changes: bool,
+    /// Replace the sync aggregate with an empty one.
+    #[serde(default)]
+    pub exclude_sync_aggregate: bool,
+}
+
+/// The block which the beacon node would produce for a slot, along with its value.
+///
+/// The block is unsigned and carries an empty RANDAO reveal, so it cannot be published.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct BlockSimulation<E: EthSpec> {
+    pub block: ProduceBlockV3Response<E>,
+    pub execution_payload_blinded: bool,
+    /// Value of the execution payload in Wei.
+    #[serde(with = "serde_utils::u256_dec")]
+    pub execution_payload_value: Uint256,
+    /// Breakdown of the consensus rewards paid to the proposer, in GWei.
+    pub rewards: StandardBlockReward,
+    /// Summary of the operations selected from the op pool.
+    pub packing: BlockSimulationPacking,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockSimulationPacking {
+    pub attestations: OperationPacking,
+    pub proposer_slashings: OperationPacking,
+    pub attester_slashings: OperationPacking,
+    pub voluntary_exits: OperationPacking,
+    pub bls_to_execution_changes: OperationPacking,
+    /// Sync aggregate participation, or `None` prior to Altair.
+    pub sync_aggregate: Option<SyncAggregatePacking>,
+    /// The attestations included in the block, in the order they were packed.
+    pub packed_attestations: Vec<PackedAttestation>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct OperationPacking {
+    /// Number of operations of this type held in the op pool.
+    ///
+    /// This may include operations which are no longer valid for inclusion.
+    pub available: usize,
+    /// Number of operations selected by the op pool for the block.
+    pub selected: usize,
+    /// Number of operations included in the block, after exclusions were applied.
+    pub included: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct SyncAggregatePacking {
+    pub selected_participants: usize,
+    pub included_participants: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PackedAttestation {
+    pub data: AttestationData,
+    /// Number of validators aggregated into the attestation.
+    pub num_attesters: usize,
+    /// Number of attesters not already covered by the state or an earlier attestation.
+    pub num_fresh_attesters: usize,
+    /// Proposer reward for including the fresh attesters, in GWei.
+    pub reward: u64,
+}
@@ -0,0 +1,36 @@
+use crate::types::option_query_vec;
+use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use types::Hash256;
+
+/// Query parameters for the `/lighthouse/proof/states/{state_id}` endpoint.
+///
+/// Each parameter accepts a comma-separated list, and at least one generalized index or path must
+/// be supplied.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct StateProofQuery {
+    /// Generalized indices relative to the root of the state.
+    #[serde(default, deserialize_with = "option_query_vec")]
+    pub gindex: Option<Vec<u64>>,
+    /// Dot-separated field paths, e.g. `validators.7.effective_balance`.
+    #[serde(default, deserialize_with = "option_query_vec")]
+    pub path: Option<Vec<String>>,
+}
+
+/// Merkle proofs for a set of nodes in the tree of a single state.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct StateProof {
+    pub state_root: Hash256,
+    /// One proof per requested generalized index, followed by one per requested path.
+    pub proofs: Vec<MerkleProof>,
+}
+
+/// A proof that `leaf` is at `gindex` in the tree with root `StateProof::state_root`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct MerkleProof {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gindex: u64,
+    pub leaf: Hash256,
+    /// Sibling nodes from the bottom of the tree to the top.
+    pub branch: Vec<Hash256>,
+}
@@ -482,6 +482,55 @@ impl ValidatorClientHttpClient {
         self.post(path, &request).await
     }
 
+    /// `POST lighthouse/validators/{validator_pubkey}/reencrypt`
+    pub async fn post_lighthouse_validators_reencrypt(
+        &self,
+        voting_pubkey: &PublicKeyBytes,
+        request: &KeystoreReencryptRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&voting_pubkey.to_string())
+            .push("reencrypt");
+
+        self.post(path, &request).await
+    }
+
+    /// `GET lighthouse/validators/exits`
+    pub async fn get_lighthouse_validators_exits(
+        &self,
+    ) -> Result<GenericResponse<Vec<VoluntaryExitData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("exits");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validators/exits`
+    pub async fn post_lighthouse_validators_exits(
+        &self,
+        request: &VoluntaryExitsRequest,
+    ) -> Result<GenericResponse<Vec<VoluntaryExitData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("exits");
+
+        self.post(path, &request).await
+    }
+
     /// `PATCH lighthouse/validators/{validator_pubkey}`
     #[allow(clippy::too_many_arguments)]
     pub async fn patch_lighthouse_validators(
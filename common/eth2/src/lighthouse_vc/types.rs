@@ -1,4 +1,4 @@
-use account_utils::ZeroizeString;
+use account_utils::{keystore_rotation::KeystoreKdf, ZeroizeString};
 use eth2_keystore::Keystore;
 use graffiti::GraffitiString;
 use serde::{Deserialize, Serialize};
@@ -100,6 +100,47 @@ pub struct ValidatorPatchRequest {
     pub prefer_builder_proposals: Option<bool>,
 }
 
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystoreReencryptRequest {
+    /// The new keystore password. If omitted, a random password is generated and stored in the
+    /// same manner as the existing password.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<ZeroizeString>,
+    #[serde(default)]
+    pub kdf: KeystoreKdf,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoluntaryExitsRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+    /// The epoch at which the exits become valid. If omitted, the current epoch is used and the
+    /// exits are published immediately.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoluntaryExitStatus {
+    /// The exit has been published to the beacon node.
+    Published,
+    /// The exit is held by the validator client until its epoch arrives.
+    Scheduled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VoluntaryExitData {
+    pub pubkey: PublicKeyBytes,
+    pub status: VoluntaryExitStatus,
+    pub signed_voluntary_exit: SignedVoluntaryExit,
+    /// The exit epoch projected from the exit queue of the head state, if known.
+    pub projected_exit_epoch: Option<Epoch>,
+    /// The withdrawable epoch projected from the exit queue of the head state, if known.
+    pub projected_withdrawable_epoch: Option<Epoch>,
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct KeystoreValidatorsPostRequest {
     pub password: ZeroizeString,
@@ -625,7 +625,7 @@ where
     Ok(Vec::from(QueryVec::from(vec)))
 }
 
-fn option_query_vec<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+pub(crate) fn option_query_vec<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
 where
     D: serde::Deserializer<'de>,
     T: FromStr,
@@ -18,9 +18,13 @@ pub mod async_record;
 mod sse_logging_components;
 mod tracing_logging_layer;
 mod tracing_metrics_layer;
+mod tracing_otlp_layer;
 
 pub use sse_logging_components::SSELoggingComponents;
 pub use tracing_metrics_layer::MetricsLayer;
+pub use tracing_otlp_layer::{
+    set_block_trace_root, shutdown_otlp_exporter, OtlpConfig, BLOCK_TRACE_TARGET,
+};
 
 /// The minimum interval between log messages indicating that a queue is full.
 const LOG_DEBOUNCE_INTERVAL: Duration = Duration::from_secs(30);
@@ -221,10 +225,20 @@ impl TimeLatch {
     }
 }
 
-pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
+pub fn create_tracing_layer(base_tracing_log_path: PathBuf, otlp_config: Option<OtlpConfig>) {
     let filter_layer = match tracing_subscriber::EnvFilter::try_from_default_env()
         .or_else(|_| tracing_subscriber::EnvFilter::try_new("warn"))
     {
+        // Block tracing spans must pass the global filter to reach the OTLP layer.
+        Ok(filter) if otlp_config.is_some() => {
+            match format!("{BLOCK_TRACE_TARGET}=trace").parse() {
+                Ok(directive) => filter.add_directive(directive),
+                Err(e) => {
+                    eprintln!("Failed to initialize dependency logging {e}");
+                    return;
+                }
+            }
+        }
         Ok(filter) => filter,
         Err(e) => {
             eprintln!("Failed to initialize dependency logging {e}");
@@ -264,12 +278,25 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         _discv5_guard,
     };
 
+    let otlp_layer = match otlp_config
+        .as_ref()
+        .map(tracing_otlp_layer::otlp_layer)
+        .transpose()
+    {
+        Ok(layer) => layer,
+        Err(e) => {
+            eprintln!("Failed to initialize OpenTelemetry tracing {e}");
+            None
+        }
+    };
+
     if let Err(e) = tracing_subscriber::fmt()
         .with_env_filter(filter_layer)
         .with_writer(std::io::sink)
         .finish()
         .with(MetricsLayer)
         .with(custom_layer)
+        .with(otlp_layer)
         .try_init()
     {
         eprintln!("Failed to initialize dependency logging {e}");
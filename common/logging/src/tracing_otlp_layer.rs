@@ -0,0 +1,127 @@
+//! Exports block and blob processing spans to an OpenTelemetry collector over OTLP.
+//!
+//! Only spans emitted with the `BLOCK_TRACE_TARGET` target are exported. The trace ID of each
+//! exported trace is derived from the root of the block being processed, so that the spans
+//! produced on gossip receipt, verification, data availability checking, `newPayload` and fork
+//! choice for a single block are grouped together in the collector (and can be correlated across
+//! nodes).
+use opentelemetry::trace::{
+    SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState, TracerProvider as _,
+};
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{runtime, trace::Config, Resource};
+use tracing::{Level, Subscriber};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{filter::Targets, registry::LookupSpan, Layer};
+
+/// The `tracing` target used for all spans that should be exported to the collector.
+pub const BLOCK_TRACE_TARGET: &str = "block_trace";
+
+/// The name of the tracer registered with the global tracer provider.
+const TRACER_NAME: &str = "lighthouse";
+
+/// Configuration for the OTLP exporter.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// gRPC endpoint of the collector, e.g. `http://localhost:4317`.
+    pub endpoint: String,
+    /// Value of the `service.name` resource attribute attached to all spans.
+    pub service_name: String,
+}
+
+/// Build a layer which exports `BLOCK_TRACE_TARGET` spans to the collector described by `config`.
+///
+/// Must be called from within a Tokio runtime, since the batch exporter spawns a task onto it.
+pub fn otlp_layer<S>(config: &OtlpConfig) -> Result<impl Layer<S>, String>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.endpoint.clone()),
+        )
+        .with_trace_config(
+            Config::default().with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                config.service_name.clone(),
+            )])),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|e| format!("Unable to start OTLP exporter: {e}"))?;
+
+    let tracer = provider.tracer(TRACER_NAME);
+    global::set_tracer_provider(provider);
+
+    Ok(tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(Targets::new().with_target(BLOCK_TRACE_TARGET, Level::TRACE)))
+}
+
+/// Flush any pending spans and shut down the exporter.
+pub fn shutdown_otlp_exporter() {
+    global::shutdown_tracer_provider();
+}
+
+/// Make `span` a child of a synthetic remote span whose trace ID is derived from `block_root`.
+///
+/// This gives every span describing the processing of a given block the same trace ID,
+/// regardless of which task or thread it was created on.
+pub fn set_block_trace_root(span: &tracing::Span, block_root: &[u8; 32]) {
+    let mut trace_id = [0; 16];
+    trace_id.copy_from_slice(&block_root[0..16]);
+    let mut span_id = [0; 8];
+    span_id.copy_from_slice(&block_root[16..24]);
+
+    let parent = SpanContext::new(
+        TraceId::from_bytes(trace_id),
+        SpanId::from_bytes(span_id),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    span.set_parent(Context::new().with_remote_span_context(parent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Returns the trace ID of a span whose trace root was set from `block_root`.
+    fn trace_id_for_block_root(block_root: [u8; 32]) -> TraceId {
+        let tracer = TracerProvider::builder().build().tracer(TRACER_NAME);
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(target: BLOCK_TRACE_TARGET, "process_block");
+            set_block_trace_root(&span, &block_root);
+            span.context().span().span_context().trace_id()
+        })
+    }
+
+    #[test]
+    fn same_block_root_gives_same_trace_id() {
+        let block_root = [42; 32];
+        assert_eq!(
+            trace_id_for_block_root(block_root),
+            trace_id_for_block_root(block_root)
+        );
+    }
+
+    #[test]
+    fn different_block_roots_give_different_trace_ids() {
+        let mut other_block_root = [42; 32];
+        other_block_root[0] = 0;
+        assert_ne!(
+            trace_id_for_block_root([42; 32]),
+            trace_id_for_block_root(other_block_root)
+        );
+    }
+}
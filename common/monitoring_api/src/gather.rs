@@ -178,6 +178,15 @@ pub fn gather_metrics(metrics_map: &HashMap<String, JsonMetric>) -> Option<serde
     Some(serde_json::Value::Object(res))
 }
 
+/// Returns `true` if either discovery or libp2p has found the node to be reachable by peers.
+pub fn gather_nat_open() -> bool {
+    lighthouse_metrics::gather()
+        .iter()
+        .filter(|mf| mf.get_name() == "nat_open")
+        .flat_map(|mf| mf.get_metric())
+        .any(|metric| metric.get_gauge().get_value() > 0.0)
+}
+
 /// Gathers and returns the lighthouse beacon metrics.
 pub fn gather_beacon_metrics(
     db_path: &Path,
@@ -1,24 +1,34 @@
 mod gather;
+mod remote_write;
+mod sink;
 mod types;
+use std::collections::VecDeque;
+use std::sync::Arc;
 use std::{path::PathBuf, time::Duration};
 
 use eth2::lighthouse::SystemHealth;
-use gather::{gather_beacon_metrics, gather_validator_metrics};
-use reqwest::{IntoUrl, Response};
+use gather::{gather_beacon_metrics, gather_nat_open, gather_validator_metrics};
+use reqwest::Response;
 pub use reqwest::{StatusCode, Url};
-use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
-use slog::{debug, error, info};
+use sink::{FileSink, HttpSink, RemoteWriteSink, Sink};
+use slog::{debug, error, info, warn};
 use task_executor::TaskExecutor;
 use tokio::time::{interval_at, Instant};
 use types::*;
 
+pub use sink::{MAX_FILE_SIZE_BYTES, MAX_ROTATED_FILES};
 pub use types::ProcessType;
 
 /// Duration after which we collect and send metrics to remote endpoint.
 pub const DEFAULT_UPDATE_DURATION: u64 = 60;
 /// Timeout for HTTP requests.
 pub const TIMEOUT_DURATION: u64 = 5;
+/// The maximum number of batches of metrics retained for each sink while it is unavailable.
+pub const MAX_QUEUED_BATCHES: usize = 120;
+/// The maximum number of batches sent to a sink in each update period, so that the batches queued
+/// while a sink was unavailable are sent gradually once it is available again.
+pub const MAX_BATCHES_PER_UPDATE: usize = 2;
 
 #[derive(Debug)]
 pub enum Error {
@@ -33,6 +43,31 @@ pub enum Error {
     ServerMessage(ErrorMessage),
     /// The server returned an error message where the body was unable to be parsed.
     StatusCode(StatusCode),
+    /// The metrics could not be serialized.
+    Serialize(String),
+    /// The metrics could not be written to a file.
+    Io(std::io::Error),
+}
+
+impl Error {
+    /// Returns `false` if the destination rejected the metrics, in which case sending the same
+    /// metrics again will fail in the same way.
+    ///
+    /// Client errors are not retried, except for timeouts and rate limiting.
+    pub fn is_retryable(&self) -> bool {
+        let status = match self {
+            Error::StatusCode(status) => *status,
+            Error::ServerMessage(message) => match StatusCode::from_u16(message.code) {
+                Ok(status) => status,
+                Err(_) => return true,
+            },
+            Error::Serialize(_) => return false,
+            _ => return true,
+        };
+        !status.is_client_error()
+            || status == StatusCode::REQUEST_TIMEOUT
+            || status == StatusCode::TOO_MANY_REQUESTS
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -47,90 +82,163 @@ impl std::fmt::Display for Error {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
-    /// Endpoint
-    pub monitoring_endpoint: String,
+    /// Endpoint of a beaconcha.in-compatible HTTP receiver.
+    pub monitoring_endpoint: Option<String>,
     /// Path for the hot database required for fetching beacon db size metrics.
     /// Note: not relevant for validator and system metrics.
     pub db_path: Option<PathBuf>,
     /// Path for the cold database required for fetching beacon db size metrics.
     /// Note: not relevant for validator and system metrics.
     pub freezer_db_path: Option<PathBuf>,
+    /// User-defined update period in seconds for the `monitoring_endpoint`.
+    pub update_period_secs: Option<u64>,
+    /// Local file to which metrics are appended as JSON lines.
+    pub file: Option<FileConfig>,
+    /// Prometheus remote-write endpoint.
+    pub remote_write: Option<RemoteWriteConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileConfig {
+    pub path: PathBuf,
+    /// User-defined update period in seconds.
+    pub update_period_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteWriteConfig {
+    pub endpoint: String,
     /// User-defined update period in seconds.
     pub update_period_secs: Option<u64>,
 }
 
-#[derive(Clone)]
-pub struct MonitoringHttpClient {
-    client: reqwest::Client,
+fn update_period(update_period_secs: Option<u64>) -> Duration {
+    Duration::from_secs(update_period_secs.unwrap_or(DEFAULT_UPDATE_DURATION))
+}
+
+pub struct MonitoringClient {
     /// Path to the hot database. Required for getting db size metrics
     db_path: Option<PathBuf>,
     /// Path to the freezer database.
     freezer_db_path: Option<PathBuf>,
-    update_period: Duration,
-    monitoring_endpoint: SensitiveUrl,
+    /// Each sink with its update period.
+    sinks: Vec<(Sink, Duration)>,
     log: slog::Logger,
 }
 
-impl MonitoringHttpClient {
+impl MonitoringClient {
     pub fn new(config: &Config, log: slog::Logger) -> Result<Self, String> {
+        let mut sinks = vec![];
+        if let Some(endpoint) = &config.monitoring_endpoint {
+            sinks.push((
+                Sink::Http(HttpSink::new(endpoint)?),
+                update_period(config.update_period_secs),
+            ));
+        }
+        if let Some(file) = &config.file {
+            sinks.push((
+                Sink::File(FileSink::new(file.path.clone())),
+                update_period(file.update_period_secs),
+            ));
+        }
+        if let Some(remote_write) = &config.remote_write {
+            sinks.push((
+                Sink::RemoteWrite(RemoteWriteSink::new(&remote_write.endpoint)?),
+                update_period(remote_write.update_period_secs),
+            ));
+        }
+        if sinks.is_empty() {
+            return Err("The monitoring service requires at least one destination".to_string());
+        }
+
         Ok(Self {
-            client: reqwest::Client::new(),
             db_path: config.db_path.clone(),
             freezer_db_path: config.freezer_db_path.clone(),
-            update_period: Duration::from_secs(
-                config.update_period_secs.unwrap_or(DEFAULT_UPDATE_DURATION),
-            ),
-            monitoring_endpoint: SensitiveUrl::parse(&config.monitoring_endpoint)
-                .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?,
+            sinks,
             log,
         })
     }
 
-    /// Perform a HTTP POST request.
-    async fn post<T: Serialize, U: IntoUrl>(&self, url: U, body: &T) -> Result<(), Error> {
-        let response = self
-            .client
-            .post(url)
-            .json(body)
-            .timeout(Duration::from_secs(TIMEOUT_DURATION))
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-        ok_or_error(response).await?;
-        Ok(())
-    }
+    /// Creates a task for each sink which periodically sends the metrics of the provided
+    /// processes to the sink.
+    ///
+    /// Metrics which cannot be sent are queued and sent once the sink is available again, at most
+    /// `MAX_BATCHES_PER_UPDATE` batches per update period. Metrics which are rejected by the sink
+    /// are dropped.
+    pub fn auto_update(mut self, executor: TaskExecutor, processes: Vec<ProcessType>) {
+        let sinks = std::mem::take(&mut self.sinks);
+        let client = Arc::new(self);
+        for (sink, update_period) in sinks {
+            let mut interval = interval_at(
+                // Have some initial delay for the metrics to get initialized
+                Instant::now() + Duration::from_secs(25),
+                update_period,
+            );
 
-    /// Creates a task which periodically sends the provided process metrics
-    /// to the configured remote endpoint.
-    pub fn auto_update(self, executor: TaskExecutor, processes: Vec<ProcessType>) {
-        let mut interval = interval_at(
-            // Have some initial delay for the metrics to get initialized
-            Instant::now() + Duration::from_secs(25),
-            self.update_period,
-        );
-
-        info!(
-            self.log,
-            "Starting monitoring API";
-            "endpoint" => %self.monitoring_endpoint,
-            "update_period" => format!("{}s", self.update_period.as_secs()),
-        );
-
-        let update_future = async move {
-            loop {
-                interval.tick().await;
-                match self.send_metrics(&processes).await {
-                    Ok(()) => {
-                        debug!(self.log, "Metrics sent to remote server"; "endpoint" => %self.monitoring_endpoint);
-                    }
-                    Err(e) => {
-                        error!(self.log, "Failed to send metrics to remote endpoint"; "error" => %e)
+            info!(
+                client.log,
+                "Starting monitoring API";
+                "destination" => %sink,
+                "update_period" => format!("{}s", update_period.as_secs()),
+            );
+
+            let client = client.clone();
+            let processes = processes.clone();
+            let update_future = async move {
+                let mut queue = VecDeque::new();
+                loop {
+                    interval.tick().await;
+                    if queue.len() == MAX_QUEUED_BATCHES {
+                        warn!(
+                            client.log,
+                            "Dropping unsent monitoring metrics";
+                            "destination" => %sink,
+                        );
+                        queue.pop_front();
                     }
+                    queue.push_back(client.gather_metrics(&processes).await);
+                    client.send_queued_metrics(&sink, &mut queue).await;
                 }
-            }
-        };
+            };
 
-        executor.spawn(update_future, "monitoring_api");
+            executor.spawn(update_future, "monitoring_api");
+        }
+    }
+
+    /// Sends up to `MAX_BATCHES_PER_UPDATE` batches in `queue` to `sink` in order, stopping at
+    /// the first failure which may succeed if retried.
+    async fn send_queued_metrics(&self, sink: &Sink, queue: &mut VecDeque<Vec<MonitoringMetrics>>) {
+        for _ in 0..MAX_BATCHES_PER_UPDATE {
+            let Some(metrics) = queue.front() else {
+                break;
+            };
+            match sink.send(metrics).await {
+                Ok(()) => {
+                    debug!(self.log, "Metrics sent to monitoring destination"; "destination" => %sink);
+                    queue.pop_front();
+                }
+                Err(e) if !e.is_retryable() => {
+                    error!(
+                        self.log,
+                        "Monitoring destination rejected metrics";
+                        "msg" => "the metrics will not be retried",
+                        "destination" => %sink,
+                        "error" => %e,
+                    );
+                    queue.pop_front();
+                }
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Failed to send metrics to monitoring destination";
+                        "destination" => %sink,
+                        "error" => %e,
+                        "queued_batches" => queue.len(),
+                    );
+                    break;
+                }
+            }
+        }
     }
 
     /// Gets beacon metrics and updates the metrics struct
@@ -180,8 +288,8 @@ impl MonitoringHttpClient {
         }
     }
 
-    /// Send metrics to the remote endpoint
-    pub async fn send_metrics(&self, processes: &[ProcessType]) -> Result<(), Error> {
+    /// Gets the metrics of each process, logging any which cannot be gathered.
+    pub async fn gather_metrics(&self, processes: &[ProcessType]) -> Vec<MonitoringMetrics> {
         let mut metrics = Vec::new();
         for process in processes {
             match self.get_metrics(process).await {
@@ -194,13 +302,17 @@ impl MonitoringHttpClient {
                 Ok(metric) => metrics.push(metric),
             }
         }
-        info!(
-            self.log,
-            "Sending metrics to remote endpoint";
-            "endpoint" => %self.monitoring_endpoint
-        );
-        self.post(self.monitoring_endpoint.full.clone(), &metrics)
-            .await
+
+        // Only the beacon node has a network, and so a NAT status.
+        if processes.contains(&ProcessType::BeaconNode) {
+            let nat_open = gather_nat_open();
+            for metric in &mut metrics {
+                if let Process::System(system_metrics) = &mut metric.process_metrics {
+                    system_metrics.network_node_nat_open = Some(nat_open);
+                }
+            }
+        }
+        metrics
     }
 }
 
@@ -217,3 +329,30 @@ async fn ok_or_error(response: Response) -> Result<Response, Error> {
         Err(Error::StatusCode(status))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retryable_errors() {
+        let server_message = |code| {
+            Error::ServerMessage(ErrorMessage {
+                code,
+                message: String::new(),
+                stacktraces: vec![],
+            })
+        };
+
+        assert!(Error::StatusCode(StatusCode::INTERNAL_SERVER_ERROR).is_retryable());
+        assert!(Error::StatusCode(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+        assert!(Error::StatusCode(StatusCode::REQUEST_TIMEOUT).is_retryable());
+        assert!(server_message(503).is_retryable());
+        assert!(Error::Io(std::io::ErrorKind::Other.into()).is_retryable());
+
+        assert!(!Error::StatusCode(StatusCode::BAD_REQUEST).is_retryable());
+        assert!(!Error::StatusCode(StatusCode::UNAUTHORIZED).is_retryable());
+        assert!(!server_message(400).is_retryable());
+        assert!(!Error::Serialize(String::new()).is_retryable());
+    }
+}
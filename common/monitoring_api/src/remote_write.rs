@@ -0,0 +1,190 @@
+//! Encoding of monitoring metrics as a Prometheus remote-write request.
+//!
+//! A request is a snappy-compressed `WriteRequest` protobuf message:
+//!
+//! ```protobuf
+//! message WriteRequest { repeated TimeSeries timeseries = 1; }
+//! message TimeSeries { repeated Label labels = 1; repeated Sample samples = 2; }
+//! message Label { string name = 1; string value = 2; }
+//! message Sample { double value = 1; int64 timestamp = 2; }
+//! ```
+//!
+//! The messages are simple enough to be encoded by hand rather than pulling in a protobuf
+//! compiler.
+
+use crate::types::MonitoringMetrics;
+use crate::Error;
+use serde_json::Value;
+
+/// The prefix of the name of each time series.
+pub const METRIC_NAME_PREFIX: &str = "lighthouse_monitoring_";
+
+/// Metadata keys which are not exported as time series.
+const EXCLUDED_KEYS: &[&str] = &["version", "timestamp", "process"];
+
+const WIRE_TYPE_VARINT: u8 = 0;
+const WIRE_TYPE_FIXED64: u8 = 1;
+const WIRE_TYPE_LENGTH_DELIMITED: u8 = 2;
+
+#[derive(Debug, PartialEq)]
+struct TimeSeries {
+    /// Labels, sorted by name.
+    labels: Vec<(String, String)>,
+    value: f64,
+    timestamp_ms: i64,
+}
+
+/// Converts each numeric or boolean value of `metrics` into a time series labelled by process.
+fn time_series(metrics: &[MonitoringMetrics]) -> Result<Vec<TimeSeries>, Error> {
+    let mut series = vec![];
+    for metric in metrics {
+        let Value::Object(fields) =
+            serde_json::to_value(metric).map_err(|e| Error::Serialize(e.to_string()))?
+        else {
+            return Err(Error::Serialize("metrics are not a JSON object".into()));
+        };
+        let timestamp_ms = fields
+            .get("timestamp")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| Error::Serialize("metrics have no timestamp".into()))?;
+        let process = fields
+            .get("process")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        for (key, value) in &fields {
+            if EXCLUDED_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+            let value = match value {
+                Value::Number(number) => number.as_f64(),
+                Value::Bool(value) => Some(if *value { 1.0 } else { 0.0 }),
+                _ => None,
+            };
+            if let Some(value) = value {
+                series.push(TimeSeries {
+                    labels: vec![
+                        ("__name__".into(), format!("{}{}", METRIC_NAME_PREFIX, key)),
+                        ("process".into(), process.clone()),
+                    ],
+                    value,
+                    timestamp_ms,
+                });
+            }
+        }
+    }
+    Ok(series)
+}
+
+fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn encode_key(field: u64, wire_type: u8, buf: &mut Vec<u8>) {
+    encode_varint((field << 3) | wire_type as u64, buf);
+}
+
+fn encode_bytes(field: u64, bytes: &[u8], buf: &mut Vec<u8>) {
+    encode_key(field, WIRE_TYPE_LENGTH_DELIMITED, buf);
+    encode_varint(bytes.len() as u64, buf);
+    buf.extend_from_slice(bytes);
+}
+
+fn encode_time_series(series: &TimeSeries) -> Vec<u8> {
+    let mut buf = vec![];
+    for (name, value) in &series.labels {
+        let mut label = vec![];
+        encode_bytes(1, name.as_bytes(), &mut label);
+        encode_bytes(2, value.as_bytes(), &mut label);
+        encode_bytes(1, &label, &mut buf);
+    }
+
+    let mut sample = vec![];
+    encode_key(1, WIRE_TYPE_FIXED64, &mut sample);
+    sample.extend_from_slice(&series.value.to_le_bytes());
+    encode_key(2, WIRE_TYPE_VARINT, &mut sample);
+    encode_varint(series.timestamp_ms as u64, &mut sample);
+    encode_bytes(2, &sample, &mut buf);
+
+    buf
+}
+
+fn encode_write_request(series: &[TimeSeries]) -> Vec<u8> {
+    let mut buf = vec![];
+    for series in series {
+        encode_bytes(1, &encode_time_series(series), &mut buf);
+    }
+    buf
+}
+
+/// Returns the body of a remote-write request containing `metrics`.
+pub fn write_request_body(metrics: &[MonitoringMetrics]) -> Result<Vec<u8>, Error> {
+    let request = encode_write_request(&time_series(metrics)?);
+    snap::raw::Encoder::new()
+        .compress_vec(&request)
+        .map_err(|e| Error::Serialize(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Metadata, Process, ProcessType, ValidatorProcessMetrics};
+    use serde_json::json;
+
+    #[test]
+    fn encode_single_series() {
+        let series = TimeSeries {
+            labels: vec![("a".into(), "b".into())],
+            value: 1.0,
+            timestamp_ms: 300,
+        };
+        let label = [0x0a, 0x01, b'a', 0x12, 0x01, b'b'];
+        let sample = [
+            0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f, 0x10, 0xac, 0x02,
+        ];
+        let mut expected_series = vec![0x0a, label.len() as u8];
+        expected_series.extend_from_slice(&label);
+        expected_series.extend_from_slice(&[0x12, sample.len() as u8]);
+        expected_series.extend_from_slice(&sample);
+        let mut expected = vec![0x0a, expected_series.len() as u8];
+        expected.extend_from_slice(&expected_series);
+
+        assert_eq!(encode_write_request(&[series]), expected);
+    }
+
+    #[test]
+    fn metrics_to_time_series() {
+        let metrics = MonitoringMetrics {
+            metadata: Metadata::new(ProcessType::Validator),
+            process_metrics: Process::Validator(ValidatorProcessMetrics {
+                common: Default::default(),
+                validator: json!({ "validator_active": 3, "sync_eth2_fallback_connected": true }),
+            }),
+        };
+        let series = time_series(&[metrics]).unwrap();
+
+        let find = |name: &str| {
+            series
+                .iter()
+                .find(|series| series.labels[0].1 == format!("{}{}", METRIC_NAME_PREFIX, name))
+                .unwrap()
+        };
+        assert_eq!(find("validator_active").value, 3.0);
+        assert_eq!(find("sync_eth2_fallback_connected").value, 1.0);
+        assert_eq!(find("memory_process_bytes").value, 0.0);
+        assert_eq!(
+            find("validator_active").labels[1],
+            ("process".to_string(), "validator".to_string())
+        );
+        // Strings and metadata are not exported.
+        assert!(series
+            .iter()
+            .all(|series| !series.labels[0].1.ends_with("client_name")
+                && !series.labels[0].1.ends_with("version")));
+    }
+}
@@ -0,0 +1,213 @@
+//! Destinations for monitoring metrics.
+
+use crate::remote_write::write_request_body;
+use crate::types::MonitoringMetrics;
+use crate::{ok_or_error, Error, TIMEOUT_DURATION};
+use reqwest::header::{CONTENT_ENCODING, CONTENT_TYPE};
+use sensitive_url::SensitiveUrl;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The size at which a metrics file is rotated.
+pub const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+/// The number of rotated metrics files to keep, in addition to the current file.
+pub const MAX_ROTATED_FILES: usize = 5;
+
+/// A destination to which each batch of gathered metrics is sent.
+pub enum Sink {
+    /// A beaconcha.in-compatible HTTP endpoint.
+    Http(HttpSink),
+    /// A local file with one JSON object per line.
+    File(FileSink),
+    /// A Prometheus remote-write endpoint.
+    RemoteWrite(RemoteWriteSink),
+}
+
+impl Sink {
+    pub async fn send(&self, metrics: &[MonitoringMetrics]) -> Result<(), Error> {
+        match self {
+            Sink::Http(sink) => sink.send(metrics).await,
+            Sink::File(sink) => sink.append(metrics),
+            Sink::RemoteWrite(sink) => sink.send(metrics).await,
+        }
+    }
+}
+
+impl fmt::Display for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Sink::Http(sink) => write!(f, "endpoint {}", sink.endpoint),
+            Sink::File(sink) => write!(f, "file {}", sink.path.display()),
+            Sink::RemoteWrite(sink) => write!(f, "remote-write endpoint {}", sink.endpoint),
+        }
+    }
+}
+
+pub struct HttpSink {
+    client: reqwest::Client,
+    endpoint: SensitiveUrl,
+}
+
+impl HttpSink {
+    pub fn new(endpoint: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint: SensitiveUrl::parse(endpoint)
+                .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?,
+        })
+    }
+
+    /// Perform a HTTP POST request.
+    async fn send(&self, metrics: &[MonitoringMetrics]) -> Result<(), Error> {
+        let response = self
+            .client
+            .post(self.endpoint.full.clone())
+            .json(metrics)
+            .timeout(Duration::from_secs(TIMEOUT_DURATION))
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response).await?;
+        Ok(())
+    }
+}
+
+pub struct FileSink {
+    path: PathBuf,
+    max_file_size: u64,
+}
+
+impl FileSink {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            max_file_size: MAX_FILE_SIZE_BYTES,
+        }
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    /// Moves the current file to `<path>.1`, shifting the existing rotated files along and
+    /// deleting the oldest.
+    fn rotate(&self) -> std::io::Result<()> {
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let rotated_path = self.rotated_path(index);
+            if rotated_path.exists() {
+                fs::rename(rotated_path, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    /// Appends each of `metrics` as a line of JSON, first rotating the file if it would exceed
+    /// the maximum size.
+    fn append(&self, metrics: &[MonitoringMetrics]) -> Result<(), Error> {
+        let mut lines = vec![];
+        for metric in metrics {
+            serde_json::to_writer(&mut lines, metric)
+                .map_err(|e| Error::Serialize(e.to_string()))?;
+            lines.push(b'\n');
+        }
+
+        let size = file_size(&self.path)?;
+        if size > 0 && size + lines.len() as u64 > self.max_file_size {
+            self.rotate().map_err(Error::Io)?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&lines))
+            .map_err(Error::Io)
+    }
+}
+
+fn file_size(path: &Path) -> Result<u64, Error> {
+    match fs::metadata(path) {
+        Ok(metadata) => Ok(metadata.len()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(Error::Io(e)),
+    }
+}
+
+pub struct RemoteWriteSink {
+    client: reqwest::Client,
+    endpoint: SensitiveUrl,
+}
+
+impl RemoteWriteSink {
+    pub fn new(endpoint: &str) -> Result<Self, String> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint: SensitiveUrl::parse(endpoint)
+                .map_err(|e| format!("Invalid remote-write endpoint: {:?}", e))?,
+        })
+    }
+
+    async fn send(&self, metrics: &[MonitoringMetrics]) -> Result<(), Error> {
+        let response = self
+            .client
+            .post(self.endpoint.full.clone())
+            .header(CONTENT_ENCODING, "snappy")
+            .header(CONTENT_TYPE, "application/x-protobuf")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(write_request_body(metrics)?)
+            .timeout(Duration::from_secs(TIMEOUT_DURATION))
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        // Remote-write receivers respond with `204 No Content`.
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(Error::StatusCode(status))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Metadata, Process, ProcessType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn file_rotation() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("metrics.jsonl");
+        let sink = FileSink {
+            path: path.clone(),
+            max_file_size: 1,
+        };
+        let metrics = MonitoringMetrics {
+            metadata: Metadata::new(ProcessType::System),
+            process_metrics: Process::System(Default::default()),
+        };
+
+        // Each write after the first rotates the file.
+        for _ in 0..MAX_ROTATED_FILES + 2 {
+            sink.append(&[metrics.clone(), metrics.clone()]).unwrap();
+        }
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        for line in contents.lines() {
+            let decoded: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(decoded, serde_json::to_value(&metrics).unwrap());
+        }
+        for index in 1..=MAX_ROTATED_FILES {
+            assert!(sink.rotated_path(index).exists());
+        }
+        assert!(!sink.rotated_path(MAX_ROTATED_FILES + 1).exists());
+    }
+}
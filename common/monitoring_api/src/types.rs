@@ -109,6 +109,10 @@ pub struct SystemMetrics {
 
     misc_node_boot_ts_seconds: u64,
     misc_os: String,
+
+    /// Whether the node is reachable by peers, for beacon nodes only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_node_nat_open: Option<bool>,
 }
 
 impl From<SystemHealth> for SystemMetrics {
@@ -141,6 +145,7 @@ impl From<SystemHealth> for SystemMetrics {
 
             misc_node_boot_ts_seconds: health.misc_node_boot_ts_seconds,
             misc_os,
+            network_node_nat_open: None,
         }
     }
 }
@@ -312,13 +312,6 @@ where
     E: EthSpec,
     F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
 {
-    let mut pubkeys = Vec::with_capacity(indexed_attestation.attesting_indices.len());
-    for &validator_idx in &indexed_attestation.attesting_indices {
-        pubkeys.push(
-            get_pubkey(validator_idx as usize).ok_or(Error::ValidatorUnknown(validator_idx))?,
-        );
-    }
-
     let domain = spec.get_domain(
         indexed_attestation.data.target.epoch,
         Domain::BeaconAttester,
@@ -328,6 +321,33 @@ where
 
     let message = indexed_attestation.data.signing_root(domain);
 
+    indexed_attestation_signature_set_from_pubkeys_and_message(
+        get_pubkey,
+        signature,
+        indexed_attestation,
+        message,
+    )
+}
+
+/// Returns the signature set for the given `indexed_attestation` where pubkeys are supplied
+/// directly and the signing root of its `AttestationData` has already been computed.
+pub fn indexed_attestation_signature_set_from_pubkeys_and_message<'a, 'b, E, F>(
+    get_pubkey: F,
+    signature: &'a AggregateSignature,
+    indexed_attestation: &'b IndexedAttestation<E>,
+    message: Hash256,
+) -> Result<SignatureSet<'a>>
+where
+    E: EthSpec,
+    F: Fn(usize) -> Option<Cow<'a, PublicKey>>,
+{
+    let mut pubkeys = Vec::with_capacity(indexed_attestation.attesting_indices.len());
+    for &validator_idx in &indexed_attestation.attesting_indices {
+        pubkeys.push(
+            get_pubkey(validator_idx as usize).ok_or(Error::ValidatorUnknown(validator_idx))?,
+        );
+    }
+
     Ok(SignatureSet::multiple_pubkeys(signature, pubkeys, message))
 }
 
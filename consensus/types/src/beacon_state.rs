@@ -28,6 +28,7 @@ pub use self::committee_cache::{
 pub use crate::beacon_state::balance::Balance;
 pub use crate::beacon_state::exit_cache::ExitCache;
 pub use crate::beacon_state::progressive_balances_cache::*;
+pub use crate::beacon_state::proof::{StateProofCache, DEFAULT_STATE_PROOF_CACHE_SIZE};
 pub use crate::beacon_state::slashings_cache::SlashingsCache;
 pub use eth_spec::*;
 pub use iter::BlockRootsIter;
@@ -39,6 +40,7 @@ mod balance;
 mod exit_cache;
 mod iter;
 mod progressive_balances_cache;
+mod proof;
 mod pubkey_cache;
 mod slashings_cache;
 mod tests;
@@ -157,6 +159,8 @@ pub enum Error {
     TotalActiveBalanceDiffUninitialized,
     MissingImmutableValidator(usize),
     IndexNotSupported(usize),
+    InvalidGeneralizedIndex,
+    InvalidProofPath(String),
     InvalidFlagIndex(usize),
     MerkleTreeError(merkle_proof::MerkleTreeError),
 }
@@ -2446,6 +2450,45 @@ impl<E: EthSpec> BeaconState<E> {
         Ok(())
     }
 
+    /// Returns the name and tree hash root of each field of the state, in order.
+    fn field_roots(&self) -> Vec<(&'static str, Hash256)> {
+        let mut roots = vec![];
+        #[allow(clippy::arithmetic_side_effects)]
+        match self {
+            BeaconState::Base(state) => {
+                map_beacon_state_base_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+            BeaconState::Altair(state) => {
+                map_beacon_state_altair_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+            BeaconState::Bellatrix(state) => {
+                map_beacon_state_bellatrix_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+            BeaconState::Capella(state) => {
+                map_beacon_state_capella_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+            BeaconState::Deneb(state) => {
+                map_beacon_state_deneb_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+            BeaconState::Electra(state) => {
+                map_beacon_state_electra_fields!(state, |name, field| {
+                    roots.push((name, field.tree_hash_root()));
+                });
+            }
+        };
+        roots
+    }
+
     pub fn compute_merkle_proof(&self, generalized_index: usize) -> Result<Vec<Hash256>, Error> {
         // 1. Convert generalized index to field index.
         let field_index = match generalized_index {
@@ -2473,40 +2516,11 @@ impl<E: EthSpec> BeaconState<E> {
         };
 
         // 2. Get all `BeaconState` leaves.
-        let mut leaves = vec![];
-        #[allow(clippy::arithmetic_side_effects)]
-        match self {
-            BeaconState::Base(state) => {
-                map_beacon_state_base_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-            BeaconState::Altair(state) => {
-                map_beacon_state_altair_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-            BeaconState::Bellatrix(state) => {
-                map_beacon_state_bellatrix_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-            BeaconState::Capella(state) => {
-                map_beacon_state_capella_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-            BeaconState::Deneb(state) => {
-                map_beacon_state_deneb_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-            BeaconState::Electra(state) => {
-                map_beacon_state_electra_fields!(state, |_, field| {
-                    leaves.push(field.tree_hash_root());
-                });
-            }
-        };
+        let leaves = self
+            .field_roots()
+            .into_iter()
+            .map(|(_, root)| root)
+            .collect::<Vec<_>>();
 
         // 3. Make deposit tree.
         // Use the depth of the `BeaconState` fields (i.e. `log2(32) = 5`).
@@ -0,0 +1,609 @@
+//! Merkle proofs for arbitrary fields of the `BeaconState`.
+//!
+//! Fields are addressed either by generalized index or by a dot-separated field path such as
+//! `validators.7.effective_balance` or `historical_summaries.3`. The roots of the top-level fields
+//! are read from the state's tree hash cache, so only the field being descended into needs to be
+//! hashed when computing a proof.
+//!
+//! Hashing a large list such as the validator registry is still expensive, so the upper layers of
+//! the trees of vectors and lists are kept in a `StateProofCache`, keyed by the cached root of the
+//! sequence. Subsequent proofs into an unchanged sequence only hash the small subtree containing
+//! the proven chunk.
+#![allow(clippy::arithmetic_side_effects)]
+
+use crate::Unsigned;
+use crate::{BeaconState, Checkpoint, Eth1Data, EthSpec, Hash256, HistoricalSummary, Validator};
+use ethereum_hashing::{hash32_concat, ZERO_HASHES};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tree_hash::{mix_in_length, TreeHash, TreeHashType};
+
+use super::Error;
+
+/// The number of sequences for which layers are cached by default.
+pub const DEFAULT_STATE_PROOF_CACHE_SIZE: usize = 8;
+
+/// Nodes of a sequence's tree below this height are recomputed for each proof rather than cached,
+/// which limits each cache entry to roughly 1/8th of the size of the sequence's chunks.
+const UNCACHED_HEIGHT: usize = 4;
+
+/// The layers of part of a Merkle tree, starting with the lowest.
+type Layers = Vec<Vec<Hash256>>;
+
+/// A cache of the upper layers of the trees of `BeaconState` vectors and lists.
+pub struct StateProofCache {
+    entries: Mutex<VecDeque<((Hash256, usize), Arc<Layers>)>>,
+    capacity: usize,
+    misses: AtomicU64,
+}
+
+impl Default for StateProofCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_STATE_PROOF_CACHE_SIZE)
+    }
+}
+
+impl StateProofCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The number of sequences whose layers are cached.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of times that a sequence had to be hashed in full.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the layers for the tree with `root` and `depth`, computing them with `compute` if
+    /// they are not cached.
+    fn get_or_insert(
+        &self,
+        root: Hash256,
+        depth: usize,
+        compute: impl FnOnce() -> Layers,
+    ) -> Arc<Layers> {
+        let key = (root, depth);
+        {
+            let mut entries = self.entries.lock();
+            let position = entries.iter().position(|(k, _)| *k == key);
+            if let Some(entry) = position.and_then(|position| entries.remove(position)) {
+                // Move the entry to the back so that it is the last to be evicted.
+                let layers = entry.1.clone();
+                entries.push_back(entry);
+                return layers;
+            }
+        }
+
+        // Hash without holding the lock, since this may take some time for a large list.
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let layers = Arc::new(compute());
+        if self.capacity > 0 {
+            let mut entries = self.entries.lock();
+            if !entries.iter().any(|(k, _)| *k == key) {
+                if entries.len() >= self.capacity {
+                    entries.pop_front();
+                }
+                entries.push_back((key, layers.clone()));
+            }
+        }
+        layers
+    }
+}
+
+/// The chunks at the bottom of the data tree of a `ProofNode`.
+enum Chunks<'a> {
+    /// The chunks of a container or leaf, which are few enough to hash for each proof.
+    Small(Vec<Hash256>),
+    /// The chunks of a vector or list, whose upper layers are cached under `root`.
+    Sequence {
+        root: Hash256,
+        len: usize,
+        chunk: Box<dyn Fn(usize) -> Hash256 + 'a>,
+    },
+}
+
+/// A node in the Merkle tree of the `BeaconState` which a proof can pass through.
+///
+/// Each node is the root of an SSZ value: a container, vector or list (whose data tree has
+/// `chunks` at its leaves), or a leaf which cannot be descended into.
+struct ProofNode<'a> {
+    chunks: Chunks<'a>,
+    /// Depth of the data tree.
+    depth: usize,
+    /// The number of elements (or fields, for a container).
+    len: usize,
+    /// `true` if the length is mixed into the root, i.e. this is an SSZ list.
+    is_list: bool,
+    /// The number of elements packed into each chunk.
+    packing_factor: usize,
+    /// The names of the fields of a container.
+    field_names: Vec<&'static str>,
+    /// Returns the node of the composite element at a chunk index, if it can be descended into.
+    #[allow(clippy::type_complexity)]
+    child: Option<Box<dyn Fn(usize) -> Option<ProofNode<'a>> + 'a>>,
+}
+
+macro_rules! container_node {
+    ($value:expr, $($field:ident),+) => {{
+        let value = $value;
+        ProofNode::container(
+            vec![$(stringify!($field)),+],
+            vec![$(value.$field.tree_hash_root()),+],
+        )
+    }};
+}
+
+/// Returns a `ProofNode` for a `milhouse` (or `ssz_types`) vector or list field of the state.
+macro_rules! sequence_node {
+    ($sequence:expr, $limit:ty, $is_list:expr) => {{
+        let sequence = $sequence;
+        ProofNode::sequence(
+            sequence.tree_hash_root(),
+            move |i| sequence.get(i),
+            sequence.len(),
+            <$limit>::to_usize(),
+            $is_list,
+        )
+    }};
+}
+
+impl<'a> ProofNode<'a> {
+    fn container(field_names: Vec<&'static str>, field_roots: Vec<Hash256>) -> Self {
+        Self {
+            depth: tree_depth(field_roots.len()),
+            len: field_roots.len(),
+            chunks: Chunks::Small(field_roots),
+            is_list: false,
+            packing_factor: 1,
+            field_names,
+            child: None,
+        }
+    }
+
+    /// A vector or list with tree hash root `root`, containing `len` elements which are returned
+    /// by `get`, with capacity for `limit` elements.
+    ///
+    /// Elements are only hashed if the layers for `root` are not already cached.
+    fn sequence<T: TreeHash + 'a>(
+        root: Hash256,
+        get: impl Fn(usize) -> Option<&'a T> + 'a,
+        len: usize,
+        limit: usize,
+        is_list: bool,
+    ) -> Self {
+        let (packing_factor, chunk): (usize, Box<dyn Fn(usize) -> Hash256 + 'a>) =
+            match T::tree_hash_type() {
+                TreeHashType::Basic => {
+                    let packing_factor = T::tree_hash_packing_factor();
+                    let chunk = move |chunk_index: usize| {
+                        let mut bytes = (chunk_index * packing_factor..)
+                            .take(packing_factor)
+                            .map_while(&get)
+                            .flat_map(|element| element.tree_hash_packed_encoding())
+                            .collect::<Vec<u8>>();
+                        bytes.resize(32, 0);
+                        Hash256::from_slice(&bytes)
+                    };
+                    (packing_factor, Box::new(chunk))
+                }
+                TreeHashType::Container | TreeHashType::List | TreeHashType::Vector => {
+                    let chunk = move |index: usize| {
+                        get(index).map(TreeHash::tree_hash_root).unwrap_or_default()
+                    };
+                    (1, Box::new(chunk))
+                }
+            };
+        Self {
+            chunks: Chunks::Sequence {
+                root,
+                len: len.div_ceil(packing_factor),
+                chunk,
+            },
+            depth: tree_depth(limit.div_ceil(packing_factor)),
+            len,
+            is_list,
+            packing_factor,
+            field_names: vec![],
+            child: None,
+        }
+    }
+
+    fn with_children(mut self, child: impl Fn(usize) -> Option<ProofNode<'a>> + 'a) -> Self {
+        self.child = Some(Box::new(child));
+        self
+    }
+
+    fn child(&self, chunk_index: usize) -> Option<ProofNode<'a>> {
+        self.child.as_ref().and_then(|child| child(chunk_index))
+    }
+
+    fn num_chunks(&self) -> usize {
+        match &self.chunks {
+            Chunks::Small(chunks) => chunks.len(),
+            Chunks::Sequence { len, .. } => *len,
+        }
+    }
+
+    /// Returns the chunk index for the path segment `segment`.
+    fn chunk_index(&self, segment: &str) -> Result<usize, Error> {
+        let index = if self.field_names.is_empty() {
+            segment
+                .parse::<usize>()
+                .map_err(|_| Error::InvalidProofPath(format!("{segment} is not an index")))?
+        } else {
+            self.field_names
+                .iter()
+                .position(|name| *name == segment)
+                .ok_or_else(|| Error::InvalidProofPath(format!("unknown field {segment}")))?
+        };
+        if index >= self.len {
+            return Err(Error::InvalidProofPath(format!(
+                "index {segment} out of bounds for length {}",
+                self.len
+            )));
+        }
+        Ok(index / self.packing_factor)
+    }
+
+    /// The layers of the subtree of height `height` whose root has index `subtree_index`.
+    fn subtree_layers(
+        chunk: &dyn Fn(usize) -> Hash256,
+        num_chunks: usize,
+        height: usize,
+        subtree_index: usize,
+    ) -> Layers {
+        let first = subtree_index << height;
+        let leaves = (first..num_chunks)
+            .take(1 << height)
+            .map(chunk)
+            .collect::<Vec<_>>();
+        hash_layers(leaves, 0, height)
+    }
+
+    /// Returns the node at `height` and `index` within the data tree, along with the branch from
+    /// that node to the root of the data tree in bottom-up order.
+    fn data_branch(
+        &self,
+        height: usize,
+        index: usize,
+        cache: &StateProofCache,
+    ) -> (Hash256, Vec<Hash256>) {
+        let (root, num_chunks, chunk) = match &self.chunks {
+            Chunks::Small(chunks) => {
+                return layers_branch(
+                    &hash_layers(chunks.clone(), 0, self.depth),
+                    0,
+                    height,
+                    index,
+                )
+            }
+            Chunks::Sequence { root, len, chunk } => (*root, *len, chunk),
+        };
+
+        let base_height = std::cmp::min(UNCACHED_HEIGHT, self.depth);
+        let upper_layers = cache.get_or_insert(root, self.depth, || {
+            let subtree_roots = (0..num_chunks.div_ceil(1 << base_height))
+                .map(|subtree_index| {
+                    let layers =
+                        Self::subtree_layers(chunk, num_chunks, base_height, subtree_index);
+                    layer_node(&layers[base_height], 0, base_height)
+                })
+                .collect();
+            hash_layers(subtree_roots, base_height, self.depth)
+        });
+
+        if height >= base_height {
+            return layers_branch(&upper_layers, base_height, height, index);
+        }
+
+        // Hash the subtree below the cached layers which contains the node.
+        let subtree_index = index >> (base_height - height);
+        let subtree_layers = Self::subtree_layers(chunk, num_chunks, base_height, subtree_index);
+        let index_in_subtree = index - (subtree_index << (base_height - height));
+        let (node, mut branch) = layers_branch(&subtree_layers, 0, height, index_in_subtree);
+        let (_, upper_branch) =
+            layers_branch(&upper_layers, base_height, base_height, subtree_index);
+        branch.extend(upper_branch);
+        (node, branch)
+    }
+
+    /// Compute the leaf and branch (in bottom-up order) for the node at the path given by `bits`,
+    /// where `bits` are the bits of a generalized index relative to this node, excluding the
+    /// leading `1`.
+    fn prove(
+        &self,
+        bits: &[bool],
+        cache: &StateProofCache,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        let (bits, length_branch) = if self.is_list {
+            let length_root = (self.len as u64).tree_hash_root();
+            match bits.split_first() {
+                None => {
+                    let (data_root, _) = self.data_branch(self.depth, 0, cache);
+                    return Ok((mix_in_length(&data_root, self.len), vec![]));
+                }
+                // The length mix-in is a leaf.
+                Some((true, [])) => {
+                    let (data_root, _) = self.data_branch(self.depth, 0, cache);
+                    return Ok((length_root, vec![data_root]));
+                }
+                Some((true, _)) => return Err(Error::InvalidGeneralizedIndex),
+                Some((false, rest)) => (rest, Some(length_root)),
+            }
+        } else {
+            (bits, None)
+        };
+
+        let steps = std::cmp::min(bits.len(), self.depth);
+        let (tree_bits, rest) = bits.split_at(steps);
+        let index = tree_bits
+            .iter()
+            .fold(0, |index, bit| (index << 1) | usize::from(*bit));
+
+        let (leaf, mut branch) = self.data_branch(self.depth - steps, index, cache);
+        branch.extend(length_branch);
+
+        if rest.is_empty() {
+            return Ok((leaf, branch));
+        }
+
+        // Descend into a composite element, which must be within the data tree's leaves.
+        let child = if steps == self.depth && index < self.num_chunks() {
+            self.child(index)
+        } else {
+            None
+        }
+        .ok_or(Error::InvalidGeneralizedIndex)?;
+        let (leaf, mut child_branch) = child.prove(rest, cache)?;
+        child_branch.extend(branch);
+        Ok((leaf, child_branch))
+    }
+}
+
+/// Append the `num_bits` low bits of `value` to `generalized_index`.
+fn push_bits(generalized_index: u64, num_bits: usize, value: u64) -> Result<u64, Error> {
+    if (generalized_index.leading_zeros() as usize) < num_bits {
+        return Err(Error::InvalidGeneralizedIndex);
+    }
+    Ok((generalized_index << num_bits) | value)
+}
+
+/// The depth of a tree with at least `num_leaves` leaves.
+fn tree_depth(num_leaves: usize) -> usize {
+    num_leaves.next_power_of_two().trailing_zeros() as usize
+}
+
+fn zero_hash(height: usize) -> Hash256 {
+    ZERO_HASHES
+        .get(height)
+        .map(|hash| Hash256::from_slice(hash))
+        .unwrap_or_default()
+}
+
+/// Returns the node at `index` of a layer at `height`, which is a zero hash if it is beyond the
+/// end of the layer.
+fn layer_node(layer: &[Hash256], index: usize, height: usize) -> Hash256 {
+    layer
+        .get(index)
+        .copied()
+        .unwrap_or_else(|| zero_hash(height))
+}
+
+/// Hash `nodes` at `height` up to `root_height`, returning every layer from `nodes` to the root.
+fn hash_layers(nodes: Vec<Hash256>, height: usize, root_height: usize) -> Layers {
+    let mut layers = Vec::with_capacity(root_height - height + 1);
+    layers.push(nodes);
+    for current_height in height..root_height {
+        let next = layers[layers.len() - 1]
+            .chunks(2)
+            .map(|pair| {
+                let left = pair[0];
+                let right = pair
+                    .get(1)
+                    .copied()
+                    .unwrap_or_else(|| zero_hash(current_height));
+                Hash256::from_slice(&hash32_concat(left.as_bytes(), right.as_bytes()))
+            })
+            .collect();
+        layers.push(next);
+    }
+    layers
+}
+
+/// Returns the node at `height` and `index` within `layers` (whose lowest layer is at
+/// `base_height`), along with the branch from that node to the top of `layers` in bottom-up order.
+fn layers_branch(
+    layers: &[Vec<Hash256>],
+    base_height: usize,
+    height: usize,
+    mut index: usize,
+) -> (Hash256, Vec<Hash256>) {
+    let node = layer_node(&layers[height - base_height], index, height);
+    let root_height = base_height + layers.len() - 1;
+    let branch = (height..root_height)
+        .map(|current_height| {
+            let sibling = layer_node(
+                &layers[current_height - base_height],
+                index ^ 1,
+                current_height,
+            );
+            index /= 2;
+            sibling
+        })
+        .collect();
+    (node, branch)
+}
+
+fn validator_node(validator: &Validator) -> ProofNode<'_> {
+    container_node!(
+        validator,
+        pubkey,
+        withdrawal_credentials,
+        effective_balance,
+        slashed,
+        activation_eligibility_epoch,
+        activation_epoch,
+        exit_epoch,
+        withdrawable_epoch
+    )
+}
+
+fn checkpoint_node<'a>(checkpoint: Checkpoint) -> ProofNode<'a> {
+    container_node!(checkpoint, epoch, root)
+}
+
+fn eth1_data_node(eth1_data: &Eth1Data) -> ProofNode<'_> {
+    container_node!(eth1_data, deposit_root, deposit_count, block_hash)
+}
+
+fn historical_summary_node(summary: &HistoricalSummary) -> ProofNode<'_> {
+    ProofNode::container(
+        vec!["block_summary_root", "state_summary_root"],
+        vec![summary.block_summary_root(), summary.state_summary_root()],
+    )
+}
+
+impl<E: EthSpec> BeaconState<E> {
+    /// Compute the leaf and branch (in bottom-up order) at `generalized_index` relative to the
+    /// root of the state.
+    ///
+    /// The tree hash cache must be up to date, see `Self::update_tree_hash_cache`. The layers of
+    /// any vector or list which the proof descends into are read from or added to `cache`.
+    pub fn compute_generalized_merkle_proof(
+        &self,
+        generalized_index: u64,
+        cache: &StateProofCache,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        if generalized_index == 0 {
+            return Err(Error::InvalidGeneralizedIndex);
+        }
+        // The bits of the generalized index following the leading `1`, most significant first.
+        let num_bits = 63 - generalized_index.leading_zeros() as usize;
+        let bits = (0..num_bits)
+            .rev()
+            .map(|i| (generalized_index >> i) & 1 == 1)
+            .collect::<Vec<_>>();
+        self.proof_node().prove(&bits, cache)
+    }
+
+    /// Convert a dot-separated field path (e.g. `validators.7.effective_balance`) into a
+    /// generalized index relative to the root of the state.
+    ///
+    /// Elements of lists of basic values (e.g. `balances.7`) resolve to the chunk which contains
+    /// them.
+    pub fn generalized_index_for_path(&self, path: &str) -> Result<u64, Error> {
+        let mut generalized_index = 1u64;
+        let mut node = Some(self.proof_node());
+        let mut segments = path.split('.').peekable();
+
+        while let Some(segment) = segments.next() {
+            let current = node
+                .take()
+                .ok_or_else(|| Error::InvalidProofPath(format!("cannot descend into {segment}")))?;
+            let chunk_index = current.chunk_index(segment)?;
+
+            if current.is_list {
+                generalized_index = push_bits(generalized_index, 1, 0)?;
+            }
+            generalized_index = push_bits(generalized_index, current.depth, chunk_index as u64)?;
+
+            if segments.peek().is_some() {
+                node = current.child(chunk_index);
+            }
+        }
+
+        Ok(generalized_index)
+    }
+
+    fn proof_node(&self) -> ProofNode<'_> {
+        let (field_names, field_roots) = self.field_roots().into_iter().unzip();
+        let node = ProofNode::container(field_names, field_roots);
+        let field_names = node.field_names.clone();
+        node.with_children(move |index| self.field_proof_node(field_names.get(index)?))
+    }
+
+    /// Returns the node for a top-level field which can be descended into.
+    fn field_proof_node(&self, name: &str) -> Option<ProofNode<'_>> {
+        let node = match name {
+            "fork" => container_node!(self.fork(), previous_version, current_version, epoch),
+            "latest_block_header" => container_node!(
+                self.latest_block_header(),
+                slot,
+                proposer_index,
+                parent_root,
+                state_root,
+                body_root
+            ),
+            "block_roots" => sequence_node!(self.block_roots(), E::SlotsPerHistoricalRoot, false),
+            "state_roots" => sequence_node!(self.state_roots(), E::SlotsPerHistoricalRoot, false),
+            "historical_roots" => {
+                sequence_node!(self.historical_roots(), E::HistoricalRootsLimit, true)
+            }
+            "eth1_data" => eth1_data_node(self.eth1_data()),
+            "eth1_data_votes" => {
+                let votes = self.eth1_data_votes();
+                sequence_node!(votes, E::SlotsPerEth1VotingPeriod, true)
+                    .with_children(move |i| votes.get(i).map(eth1_data_node))
+            }
+            "validators" => {
+                let validators = self.validators();
+                sequence_node!(validators, E::ValidatorRegistryLimit, true)
+                    .with_children(move |i| validators.get(i).map(validator_node))
+            }
+            "balances" => sequence_node!(self.balances(), E::ValidatorRegistryLimit, true),
+            "randao_mixes" => {
+                sequence_node!(self.randao_mixes(), E::EpochsPerHistoricalVector, false)
+            }
+            "slashings" => sequence_node!(self.slashings(), E::EpochsPerSlashingsVector, false),
+            "previous_epoch_participation" => {
+                let participation = self.previous_epoch_participation().ok()?;
+                sequence_node!(participation, E::ValidatorRegistryLimit, true)
+            }
+            "current_epoch_participation" => {
+                let participation = self.current_epoch_participation().ok()?;
+                sequence_node!(participation, E::ValidatorRegistryLimit, true)
+            }
+            "previous_justified_checkpoint" => {
+                checkpoint_node(self.previous_justified_checkpoint())
+            }
+            "current_justified_checkpoint" => checkpoint_node(self.current_justified_checkpoint()),
+            "finalized_checkpoint" => checkpoint_node(self.finalized_checkpoint()),
+            "inactivity_scores" => {
+                let scores = self.inactivity_scores().ok()?;
+                sequence_node!(scores, E::ValidatorRegistryLimit, true)
+            }
+            "current_sync_committee" | "next_sync_committee" => {
+                let committee = if name == "current_sync_committee" {
+                    self.current_sync_committee().ok()?
+                } else {
+                    self.next_sync_committee().ok()?
+                };
+                container_node!(committee, pubkeys, aggregate_pubkey).with_children(move |i| {
+                    (i == 0)
+                        .then(|| sequence_node!(&committee.pubkeys, E::SyncCommitteeSize, false))
+                })
+            }
+            "historical_summaries" => {
+                let summaries = self.historical_summaries().ok()?;
+                sequence_node!(summaries, E::HistoricalRootsLimit, true)
+                    .with_children(move |i| summaries.get(i).map(historical_summary_node))
+            }
+            _ => return None,
+        };
+        Some(node)
+    }
+}
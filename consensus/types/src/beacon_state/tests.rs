@@ -4,12 +4,13 @@ use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
 use beacon_chain::types::{
     test_utils::TestRandom, BeaconState, BeaconStateAltair, BeaconStateBase, BeaconStateError,
     ChainSpec, Domain, Epoch, EthSpec, Hash256, Keypair, MainnetEthSpec, MinimalEthSpec,
-    RelativeEpoch, Slot, Vector,
+    RelativeEpoch, Slot, StateProofCache, Vector,
 };
 use lazy_static::lazy_static;
 use ssz::Encode;
 use std::ops::Mul;
 use swap_or_not_shuffle::compute_shuffled_index;
+use tree_hash::TreeHash;
 
 pub const MAX_VALIDATOR_COUNT: usize = 129;
 pub const SLOT_OFFSET: Slot = Slot::new(1);
@@ -404,3 +405,74 @@ fn decode_base_and_altair() {
             .expect_err("bad altair state cannot be decoded");
     }
 }
+
+#[tokio::test]
+async fn generalized_merkle_proofs() {
+    let mut state = build_state::<MinimalEthSpec>(16).await;
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let cache = StateProofCache::default();
+    let uncached = StateProofCache::new(0);
+
+    let verify_proof = |path: &str, cache: &StateProofCache| {
+        let generalized_index = state.generalized_index_for_path(path).unwrap();
+        let (leaf, branch) = state
+            .compute_generalized_merkle_proof(generalized_index, cache)
+            .unwrap();
+        let depth = 63 - generalized_index.leading_zeros() as usize;
+        let index = generalized_index as usize - (1 << depth);
+        assert!(
+            merkle_proof::verify_merkle_proof(leaf, &branch, depth, index, state_root),
+            "invalid proof for {path}"
+        );
+        (leaf, branch)
+    };
+
+    for path in [
+        "slot",
+        "fork.current_version",
+        "latest_block_header.parent_root",
+        "block_roots.3",
+        "block_roots.40",
+        "eth1_data.deposit_count",
+        "validators",
+        "validators.7",
+        "validators.7.effective_balance",
+        "balances.9",
+        "randao_mixes.1",
+        "finalized_checkpoint.epoch",
+    ] {
+        assert_eq!(verify_proof(path, &cache), verify_proof(path, &uncached));
+    }
+    assert!(uncached.is_empty());
+
+    // Further proofs into the same vectors and lists are served from the cache, without hashing
+    // every element again.
+    let misses = cache.misses();
+    for path in [
+        "block_roots.63",
+        "validators.3",
+        "validators.11.slashed",
+        "balances.2",
+    ] {
+        verify_proof(path, &cache);
+    }
+    assert_eq!(cache.misses(), misses);
+
+    // Validator list length mix-in.
+    let generalized_index = state.generalized_index_for_path("validators").unwrap() * 2 + 1;
+    let (leaf, _) = state
+        .compute_generalized_merkle_proof(generalized_index, &cache)
+        .unwrap();
+    assert_eq!(leaf, 16u64.tree_hash_root());
+
+    for path in ["nonexistent", "validators.16", "slot.0", "balances.x"] {
+        assert!(matches!(
+            state.generalized_index_for_path(path),
+            Err(BeaconStateError::InvalidProofPath(_))
+        ));
+    }
+    assert_eq!(
+        state.compute_generalized_merkle_proof(0, &cache),
+        Err(BeaconStateError::InvalidGeneralizedIndex)
+    );
+}
@@ -37,6 +37,14 @@ pub struct HistoricalSummary {
 }
 
 impl HistoricalSummary {
+    pub fn block_summary_root(&self) -> Hash256 {
+        self.block_summary_root
+    }
+
+    pub fn state_summary_root(&self) -> Hash256 {
+        self.state_summary_root
+    }
+
     pub fn new<E: EthSpec>(state: &BeaconState<E>) -> Self {
         Self {
             block_summary_root: state.block_roots().tree_hash_root(),
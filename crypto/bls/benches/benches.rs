@@ -0,0 +1,88 @@
+//! Compares the performance of the `blst` and `zkcrypto` backends.
+//!
+//! Run with `cargo bench -p bls --features zkcrypto`.
+
+use bls::Hash256;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::borrow::Cow;
+
+const AGGREGATE_SIZES: &[usize] = &[1, 16, 128];
+const SIGNATURE_SET_COUNTS: &[usize] = &[1, 16, 64];
+
+macro_rules! bench_backend {
+    ($fn_name: ident, $backend: literal, $impls: ident) => {
+        fn $fn_name(c: &mut Criterion) {
+            use bls::$impls::*;
+
+            let msg = Hash256::from_low_u64_be(42);
+            let keypairs = (0..*AGGREGATE_SIZES.iter().max().unwrap())
+                .map(|_| Keypair::random())
+                .collect::<Vec<_>>();
+
+            let sk = &keypairs[0].sk;
+            let pk = &keypairs[0].pk;
+            let sig = sk.sign(msg);
+
+            c.bench_function(&format!("{}/sign", $backend), |b| {
+                b.iter(|| black_box(sk.sign(black_box(msg))))
+            });
+
+            c.bench_function(&format!("{}/verify", $backend), |b| {
+                b.iter(|| black_box(sig.verify(pk, msg)))
+            });
+
+            c.bench_function(&format!("{}/deserialize_public_key", $backend), |b| {
+                let bytes = pk.serialize();
+                b.iter(|| black_box(PublicKey::deserialize(&bytes).unwrap()))
+            });
+
+            c.bench_function(&format!("{}/deserialize_signature", $backend), |b| {
+                let bytes = sig.serialize();
+                b.iter(|| black_box(Signature::deserialize(&bytes).unwrap()))
+            });
+
+            let mut group = c.benchmark_group(format!("{}/fast_aggregate_verify", $backend));
+            for &size in AGGREGATE_SIZES {
+                let mut agg_sig = AggregateSignature::infinity();
+                for keypair in &keypairs[..size] {
+                    agg_sig.add_assign(&keypair.sk.sign(msg));
+                }
+                let pubkeys = keypairs[..size].iter().map(|kp| &kp.pk).collect::<Vec<_>>();
+
+                group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+                    b.iter(|| black_box(agg_sig.fast_aggregate_verify(msg, &pubkeys)))
+                });
+            }
+            group.finish();
+
+            let mut group = c.benchmark_group(format!("{}/verify_signature_sets", $backend));
+            for &count in SIGNATURE_SET_COUNTS {
+                let signatures = (0..count)
+                    .map(|i| keypairs[i].sk.sign(Hash256::from_low_u64_be(i as u64)))
+                    .collect::<Vec<_>>();
+                let sets = signatures
+                    .iter()
+                    .enumerate()
+                    .map(|(i, sig)| {
+                        SignatureSet::single_pubkey(
+                            sig,
+                            Cow::Borrowed(&keypairs[i].pk),
+                            Hash256::from_low_u64_be(i as u64),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+
+                group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+                    b.iter(|| black_box(verify_signature_sets(sets.iter())))
+                });
+            }
+            group.finish();
+        }
+    };
+}
+
+bench_backend!(blst, "blst", blst_implementations);
+bench_backend!(zkcrypto, "zkcrypto", zkcrypto_implementations);
+
+criterion_group!(benches, blst, zkcrypto);
+criterion_main!(benches);
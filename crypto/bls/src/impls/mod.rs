@@ -1,3 +1,5 @@
 #[cfg(feature = "supranational")]
 pub mod blst;
 pub mod fake_crypto;
+#[cfg(feature = "zkcrypto")]
+pub mod zkcrypto;
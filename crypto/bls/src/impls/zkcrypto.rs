@@ -0,0 +1,284 @@
+use crate::{
+    generic_aggregate_public_key::TAggregatePublicKey,
+    generic_aggregate_signature::TAggregateSignature,
+    generic_public_key::{GenericPublicKey, TPublicKey, PUBLIC_KEY_BYTES_LEN},
+    generic_secret_key::{TSecretKey, SECRET_KEY_BYTES_LEN},
+    generic_signature::{TSignature, SIGNATURE_BYTES_LEN},
+    Error, Hash256, ZeroizeHash,
+};
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use rand::Rng;
+
+pub const DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Provides the externally-facing, core BLS types.
+pub mod types {
+    pub use super::verify_signature_sets;
+    pub use super::AggregatePublicKey;
+    pub use super::AggregateSignature;
+    pub use super::PublicKey;
+    pub use super::SecretKey;
+    pub use super::Signature;
+    pub use super::SignatureSet;
+}
+
+pub type SignatureSet<'a> = crate::generic_signature_set::GenericSignatureSet<
+    'a,
+    PublicKey,
+    AggregatePublicKey,
+    Signature,
+    AggregateSignature,
+>;
+
+/// Hashes `msg` to a point in G2, as per the `hash_to_curve` function in the BLS spec.
+fn hash_to_g2(msg: Hash256) -> G2Projective {
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(msg.as_bytes(), DST)
+}
+
+/// Returns `true` if `e(g1, signature) == e(pk_1, msg_1) * ... * e(pk_n, msg_n)`.
+///
+/// The pairings are checked as a single product, `e(-g1, signature) * e(pk_1, msg_1) * ...`,
+/// so that only one final exponentiation is required.
+fn pairing_check(signature: &G2Affine, pairs: &[(G1Affine, G2Affine)]) -> bool {
+    let neg_g1 = -G1Affine::generator();
+    let mut points = Vec::with_capacity(pairs.len() + 1);
+    points.push((neg_g1, G2Prepared::from(*signature)));
+    for (pubkey, msg) in pairs {
+        points.push((*pubkey, G2Prepared::from(*msg)));
+    }
+    let terms = points.iter().map(|(g1, g2)| (g1, g2)).collect::<Vec<_>>();
+
+    multi_miller_loop(&terms).final_exponentiation() == Gt::identity()
+}
+
+/// Verifies each of `signature_sets` using a random linear combination, such that the sets may
+/// share a single final exponentiation.
+pub fn verify_signature_sets<'a>(
+    signature_sets: impl ExactSizeIterator<Item = &'a SignatureSet<'a>>,
+) -> bool {
+    let sets = signature_sets.collect::<Vec<_>>();
+
+    if sets.is_empty() {
+        return false;
+    }
+
+    let rng = &mut rand::thread_rng();
+
+    let mut aggregate_signature = G2Projective::identity();
+    let mut pairs = Vec::with_capacity(sets.len());
+
+    for set in &sets {
+        // Generate a random, non-zero scalar.
+        let mut rand_i = 0u64;
+        while rand_i == 0 {
+            rand_i = rng.gen();
+        }
+        let rand_i = Scalar::from(rand_i);
+
+        // Any "empty" signature should cause a signature failure. Signatures have already been
+        // subgroup checked during deserialization.
+        let Some(signature) = set.signature.point() else {
+            return false;
+        };
+
+        // A signature that has no signing keys is invalid.
+        if set.signing_keys.is_empty() {
+            return false;
+        }
+
+        // Public keys have already been checked for subgroup and infinity.
+        let signing_key = set
+            .signing_keys
+            .iter()
+            .fold(G1Projective::identity(), |acc, pk| acc + pk.point().0);
+
+        aggregate_signature += signature.0 * rand_i;
+        pairs.push((
+            G1Affine::from(signing_key * rand_i),
+            G2Affine::from(hash_to_g2(set.message)),
+        ));
+    }
+
+    pairing_check(&G2Affine::from(aggregate_signature), &pairs)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(G1Affine);
+
+impl TPublicKey for PublicKey {
+    fn serialize(&self) -> [u8; PUBLIC_KEY_BYTES_LEN] {
+        self.0.to_compressed()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: &[u8; PUBLIC_KEY_BYTES_LEN] =
+            bytes.try_into().map_err(|_| Error::InvalidByteLength {
+                got: bytes.len(),
+                expected: PUBLIC_KEY_BYTES_LEN,
+            })?;
+        // Performs the subgroup check, noting the infinity check is done in
+        // `generic_public_key.rs`.
+        Option::<G1Affine>::from(G1Affine::from_compressed(bytes))
+            .map(Self)
+            .ok_or(Error::ZkcryptoError("invalid public key"))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregatePublicKey(G1Projective);
+
+impl TAggregatePublicKey<PublicKey> for AggregatePublicKey {
+    fn to_public_key(&self) -> GenericPublicKey<PublicKey> {
+        GenericPublicKey::from_point(PublicKey(G1Affine::from(self.0)))
+    }
+
+    fn aggregate(pubkeys: &[GenericPublicKey<PublicKey>]) -> Result<Self, Error> {
+        if pubkeys.is_empty() {
+            return Err(Error::ZkcryptoError("no public keys to aggregate"));
+        }
+
+        // Public keys have already been checked for subgroup and infinity
+        Ok(Self(
+            pubkeys
+                .iter()
+                .fold(G1Projective::identity(), |acc, pk| acc + pk.point().0),
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature(G2Affine);
+
+impl TSignature<PublicKey> for Signature {
+    fn serialize(&self) -> [u8; SIGNATURE_BYTES_LEN] {
+        self.0.to_compressed()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let bytes: &[u8; SIGNATURE_BYTES_LEN] =
+            bytes.try_into().map_err(|_| Error::InvalidByteLength {
+                got: bytes.len(),
+                expected: SIGNATURE_BYTES_LEN,
+            })?;
+        // Performs the subgroup check, so it is not repeated during verification.
+        Option::<G2Affine>::from(G2Affine::from_compressed(bytes))
+            .map(Self)
+            .ok_or(Error::ZkcryptoError("invalid signature"))
+    }
+
+    fn verify(&self, pubkey: &PublicKey, msg: Hash256) -> bool {
+        pairing_check(&self.0, &[(pubkey.0, G2Affine::from(hash_to_g2(msg)))])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregateSignature(G2Projective);
+
+impl TAggregateSignature<PublicKey, AggregatePublicKey, Signature> for AggregateSignature {
+    fn infinity() -> Self {
+        Self(G2Projective::identity())
+    }
+
+    fn add_assign(&mut self, other: &Signature) {
+        self.0 += G2Projective::from(other.0)
+    }
+
+    fn add_assign_aggregate(&mut self, other: &Self) {
+        self.0 += other.0
+    }
+
+    fn serialize(&self) -> [u8; SIGNATURE_BYTES_LEN] {
+        G2Affine::from(self.0).to_compressed()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        Signature::deserialize(bytes).map(|sig| Self(sig.0.into()))
+    }
+
+    fn fast_aggregate_verify(
+        &self,
+        msg: Hash256,
+        pubkeys: &[&GenericPublicKey<PublicKey>],
+    ) -> bool {
+        // Public keys are already valid due to PoP
+        let pubkey = pubkeys
+            .iter()
+            .fold(G1Projective::identity(), |acc, pk| acc + pk.point().0);
+        pairing_check(
+            &G2Affine::from(self.0),
+            &[(G1Affine::from(pubkey), G2Affine::from(hash_to_g2(msg)))],
+        )
+    }
+
+    fn aggregate_verify(&self, msgs: &[Hash256], pubkeys: &[&GenericPublicKey<PublicKey>]) -> bool {
+        // Public keys have already been checked for subgroup and infinity
+        let pairs = pubkeys
+            .iter()
+            .zip(msgs)
+            .map(|(pk, msg)| (pk.point().0, G2Affine::from(hash_to_g2(*msg))))
+            .collect::<Vec<_>>();
+        pairing_check(&G2Affine::from(self.0), &pairs)
+    }
+}
+
+#[derive(Clone)]
+pub struct SecretKey(Scalar);
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        // Use a volatile write so the compiler cannot elide the zeroing of the secret material.
+        //
+        // Safety: `self.0` is a valid, aligned and initialized `Scalar`.
+        unsafe { std::ptr::write_volatile(&mut self.0, Scalar::from(0u64)) };
+    }
+}
+
+impl TSecretKey<Signature, PublicKey> for SecretKey {
+    fn random() -> Self {
+        let rng = &mut rand::thread_rng();
+        loop {
+            let mut bytes = [0; 64];
+            rng.fill(&mut bytes[..]);
+            let scalar = Scalar::from_bytes_wide(&bytes);
+            bytes.iter_mut().for_each(|b| *b = 0);
+            if scalar != Scalar::from(0u64) {
+                return Self(scalar);
+            }
+        }
+    }
+
+    fn public_key(&self) -> PublicKey {
+        PublicKey(G1Affine::from(G1Affine::generator() * self.0))
+    }
+
+    fn sign(&self, msg: Hash256) -> Signature {
+        Signature(G2Affine::from(hash_to_g2(msg) * self.0))
+    }
+
+    fn serialize(&self) -> ZeroizeHash {
+        // Secret keys are big-endian, whilst `Scalar` is little-endian.
+        let mut bytes = self.0.to_bytes();
+        bytes.reverse();
+        bytes.into()
+    }
+
+    fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
+        let mut le_bytes: [u8; SECRET_KEY_BYTES_LEN] =
+            bytes
+                .try_into()
+                .map_err(|_| Error::InvalidSecretKeyLength {
+                    got: bytes.len(),
+                    expected: SECRET_KEY_BYTES_LEN,
+                })?;
+        le_bytes.reverse();
+        let scalar = Option::<Scalar>::from(Scalar::from_bytes(&le_bytes));
+        le_bytes.iter_mut().for_each(|b| *b = 0);
+        // Fails if the secret key is not less than the curve order.
+        scalar
+            .map(Self)
+            .ok_or(Error::ZkcryptoError("invalid secret key"))
+    }
+}
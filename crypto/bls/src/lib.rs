@@ -9,12 +9,14 @@
 //! are supported via compile-time flags. There are three backends supported via features:
 //!
 //! - `supranational`: the pure-assembly, highly optimized version from the `blst` crate.
+//! - `zkcrypto`: a pure-Rust version from the `bls12_381` crate, which is slower than `blst` but
+//!     does not require a C toolchain. Takes precedence over `supranational` when both are enabled.
 //! - `fake_crypto`: an always-returns-valid implementation that is only useful for testing
 //!     scenarios which intend to *ignore* real cryptography.
 //!
 //! This crate uses traits to reduce code-duplication between the two implementations. For example,
 //! the `GenericPublicKey` struct exported from this crate is generic across the `TPublicKey` trait
-//! (i.e., `PublicKey<TPublicKey>`). `TPublicKey` is implemented by all three backends (see the
+//! (i.e., `PublicKey<TPublicKey>`). `TPublicKey` is implemented by all backends (see the
 //! `impls.rs` module).
 
 #[macro_use]
@@ -57,6 +59,9 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// An error was raised from the zkcrypto BLS12-381 library.
+    #[cfg(feature = "zkcrypto")]
+    ZkcryptoError(&'static str),
 }
 
 #[cfg(feature = "supranational")]
@@ -126,14 +131,23 @@ macro_rules! define_mod {
 
 #[cfg(feature = "supranational")]
 define_mod!(blst_implementations, crate::impls::blst::types);
+#[cfg(feature = "zkcrypto")]
+define_mod!(zkcrypto_implementations, crate::impls::zkcrypto::types);
 #[cfg(feature = "fake_crypto")]
 define_mod!(
     fake_crypto_implementations,
     crate::impls::fake_crypto::types
 );
 
-#[cfg(all(feature = "supranational", not(feature = "fake_crypto"),))]
+#[cfg(all(
+    feature = "supranational",
+    not(feature = "zkcrypto"),
+    not(feature = "fake_crypto"),
+))]
 pub use blst_implementations::*;
 
+#[cfg(all(feature = "zkcrypto", not(feature = "fake_crypto"),))]
+pub use zkcrypto_implementations::*;
+
 #[cfg(feature = "fake_crypto")]
 pub use fake_crypto_implementations::*;
@@ -509,3 +509,8 @@ macro_rules! test_suite {
 mod blst {
     test_suite!(blst_implementations);
 }
+
+#[cfg(feature = "zkcrypto")]
+mod zkcrypto {
+    test_suite!(zkcrypto_implementations);
+}
@@ -5,6 +5,7 @@ mod indexed_attestations;
 mod mnemonic_validators;
 mod mock_el;
 mod parse_ssz;
+mod replay_fork_choice;
 mod skip_slots;
 mod state_root;
 mod transition_blocks;
@@ -230,6 +231,76 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("replay-fork-choice")
+                .about("Loads a fork choice snapshot and replays the blocks and attestations \
+                    which followed it, printing the decisions of fork choice at each slot.")
+                .arg(
+                    Arg::new("snapshot-path")
+                        .long("snapshot-path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("Path to load a fork choice snapshot from as SSZ. If not provided, \
+                            the current snapshot is downloaded from --beacon-url.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("URL to a beacon-API provider, used to download blocks and states.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("slots")
+                        .long("slots")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .default_value("32")
+                        .help("Number of slots after the snapshot to replay.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("extra-block-roots")
+                        .long("extra-block-roots")
+                        .value_name("ROOTS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated roots of non-canonical blocks to apply in addition \
+                            to the canonical chain, e.g. the blocks which were re-orged out.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("block-delay-ms")
+                        .long("block-delay-ms")
+                        .value_name("MILLISECONDS")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("Delay after the start of the slot with which each block is \
+                            applied. Blocks applied after the attestation deadline do not receive \
+                            proposer boost.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("re-org-head-threshold")
+                        .long("re-org-head-threshold")
+                        .value_name("PERCENT")
+                        .action(ArgAction::Set)
+                        .help("Percentage of head vote weight below which a proposer would \
+                            attempt a re-org. Defaults to the beacon node default.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("re-org-parent-threshold")
+                        .long("re-org-parent-threshold")
+                        .value_name("PERCENT")
+                        .action(ArgAction::Set)
+                        .help("Percentage of parent vote weight above which a proposer would \
+                            attempt a re-org. Defaults to the beacon node default.")
+                        .display_order(0)
+                )
+        )
         .subcommand(
             Command::new("pretty-ssz")
                 .about("Parses SSZ-encoded data from a file")
@@ -631,6 +702,11 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             skip_slots::run::<E>(env, network_config, matches)
                 .map_err(|e| format!("Failed to skip slots: {}", e))
         }
+        Some(("replay-fork-choice", matches)) => {
+            let network_config = get_network_config()?;
+            replay_fork_choice::run::<E>(env, network_config, matches)
+                .map_err(|e| format!("Failed to replay fork choice: {}", e))
+        }
         Some(("pretty-ssz", matches)) => {
             let network_config = get_network_config()?;
             run_parse_ssz::<E>(network_config, matches)
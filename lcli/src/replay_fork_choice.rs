@@ -0,0 +1,405 @@
+//! # Replay Fork Choice
+//!
+//! Use this tool to load a fork choice snapshot taken by a beacon node and replay the blocks and
+//! attestations which followed it, printing the decisions made by fork choice at each slot.
+//! Useful for understanding why fork choice selected a surprising head.
+//!
+//! Snapshots can be downloaded from the `lighthouse/fork_choice/snapshot` endpoint or written
+//! automatically on each re-org by running the beacon node with `--fork-choice-snapshot-dir`.
+//!
+//! Fork choice is first re-run at the slot of the snapshot, which reproduces the head decision of
+//! a snapshot taken on a re-org. Then, for each slot after the snapshot the tool:
+//!
+//! 1. Advances fork choice to the slot and prints the head, as seen by a proposer at the start
+//!    of the slot.
+//! 2. Prints the outcome of `get_proposer_head`, i.e. whether a proposer would re-org the head.
+//! 3. Applies any blocks at the slot, followed by the attestations they contain.
+//! 4. Prints the new head, the proposer boost root and the weight of each viable chain tip.
+//!
+//! Blocks and their post-states are downloaded from a beaconAPI. Only canonical blocks can be
+//! found by slot, so any orphaned blocks must be provided via `--extra-block-roots`.
+//!
+//! Justified balances are read from the balances cache contained in the snapshot, or from the
+//! states of replayed blocks. If the justified checkpoint changes to a block which is in neither,
+//! replay will fail.
+//!
+//! ## Examples
+//!
+//! ### Replay from a snapshot on disk
+//!
+//! ```ignore
+//! lcli replay-fork-choice \
+//!     --beacon-url http://localhost:5052 \
+//!     --snapshot-path /tmp/fork_choice_9216032_0x6c69...0bec.ssz \
+//!     --slots 4 \
+//!     --extra-block-roots 0x5a1d6c1a2e0a0b0b9a3ab0b7f9d4cbe1e0e5f00d7fb0c1b5de3e2b4e8dfe7c2a
+//! ```
+//!
+//! ### Replay from the current state of a beacon node
+//!
+//! ```ignore
+//! lcli replay-fork-choice --beacon-url http://localhost:5052 --slots 0
+//! ```
+use beacon_chain::{
+    chain_config::{
+        DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_RE_ORG_HEAD_THRESHOLD,
+        DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_PARENT_THRESHOLD,
+    },
+    fork_choice_snapshot::ForkChoiceSnapshot,
+    BeaconForkChoiceStore,
+};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
+use eth2_network_config::Eth2NetworkConfig;
+use fork_choice::{
+    AttestationFromBlock, ForkChoice, ForkChoiceStore, PayloadVerificationStatus,
+    ProposerHeadError, ResetPayloadStatuses,
+};
+use log::info;
+use slog::Logger;
+use ssz::Decode;
+use state_processing::common::get_indexed_attestation;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use store::{HotColdDB, MemoryStore};
+use types::{ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+type ReplayStore<E> = BeaconForkChoiceStore<E, MemoryStore<E>, MemoryStore<E>>;
+type ReplayForkChoice<E> = ForkChoice<ReplayStore<E>, E>;
+
+#[derive(Debug)]
+struct Config {
+    slots: u64,
+    block_delay: Duration,
+    re_org_head_threshold: ReOrgThreshold,
+    re_org_parent_threshold: ReOrgThreshold,
+}
+
+pub fn run<E: EthSpec>(
+    env: Environment<E>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = &network_config.chain_spec::<E>()?;
+    let executor = env.core_context().executor;
+    let log = env.core_context().log().clone();
+
+    /*
+     * Parse CLI arguments.
+     */
+
+    let beacon_url: SensitiveUrl = parse_required(matches, "beacon-url")?;
+    let snapshot_path: Option<PathBuf> = parse_optional(matches, "snapshot-path")?;
+    let extra_block_roots: Vec<Hash256> = parse_optional::<String>(matches, "extra-block-roots")?
+        .map(|roots| {
+            roots
+                .split(',')
+                .map(|root| {
+                    root.trim()
+                        .parse()
+                        .map_err(|e| format!("Invalid block root {}: {:?}", root, e))
+                })
+                .collect::<Result<_, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let config = Config {
+        slots: parse_required(matches, "slots")?,
+        block_delay: Duration::from_millis(parse_required(matches, "block-delay-ms")?),
+        re_org_head_threshold: parse_optional(matches, "re-org-head-threshold")?
+            .map(ReOrgThreshold)
+            .unwrap_or(DEFAULT_RE_ORG_HEAD_THRESHOLD),
+        re_org_parent_threshold: parse_optional(matches, "re-org-parent-threshold")?
+            .map(ReOrgThreshold)
+            .unwrap_or(DEFAULT_RE_ORG_PARENT_THRESHOLD),
+    };
+
+    info!("Using {} spec", E::spec_name());
+    info!("{:?}", &config);
+
+    let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
+
+    executor
+        .handle()
+        .ok_or("shutdown in progress")?
+        .block_on(async move {
+            /*
+             * Load the snapshot from disk or beaconAPI URL.
+             */
+
+            let snapshot_bytes = if let Some(snapshot_path) = snapshot_path {
+                info!("Snapshot path: {:?}", snapshot_path);
+                fs::read(&snapshot_path)
+                    .map_err(|e| format!("Unable to read {:?}: {:?}", snapshot_path, e))?
+            } else {
+                info!("Downloading snapshot from beacon node");
+                client
+                    .get_lighthouse_fork_choice_snapshot_ssz()
+                    .await
+                    .map_err(|e| format!("Failed to download snapshot: {:?}", e))?
+                    .ok_or("Beacon node does not support fork choice snapshots")?
+            };
+            let snapshot = ForkChoiceSnapshot::from_ssz_bytes(&snapshot_bytes)
+                .map_err(|e| format!("Invalid fork choice snapshot: {:?}", e))?;
+
+            replay::<E>(&client, snapshot, &extra_block_roots, &config, spec, log).await
+        })
+}
+
+async fn replay<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    snapshot: ForkChoiceSnapshot,
+    extra_block_roots: &[Hash256],
+    config: &Config,
+    spec: &ChainSpec,
+    log: Logger,
+) -> Result<(), String> {
+    let store = Arc::new(
+        HotColdDB::open_ephemeral(<_>::default(), spec.clone(), log.clone())
+            .map_err(|e| format!("Failed to create ephemeral store: {:?}", e))?,
+    );
+    let fc_store = BeaconForkChoiceStore::from_persisted(
+        snapshot.persisted_fork_choice.fork_choice_store,
+        store.clone(),
+    )
+    .map_err(|e| format!("Unable to load fork choice store: {:?}", e))?;
+    let mut fork_choice = ForkChoice::from_persisted(
+        snapshot.persisted_fork_choice.fork_choice,
+        ResetPayloadStatuses::OnlyWithInvalidPayload,
+        fc_store,
+        spec,
+        &log,
+    )
+    .map_err(|e| format!("Unable to load fork choice: {:?}", e))?;
+
+    println!(
+        "Loaded snapshot at slot {} with head {:?}",
+        snapshot.current_slot, snapshot.head_block_root
+    );
+    println!(
+        "Queued attestations: {}",
+        fork_choice.queued_attestations().len()
+    );
+    print_fork_choice(&fork_choice);
+
+    /*
+     * Download the blocks to be replayed, grouped by slot.
+     */
+
+    let start_slot = snapshot.current_slot + 1;
+    let end_slot = snapshot.current_slot + config.slots;
+    let mut blocks: BTreeMap<Slot, Vec<(Hash256, SignedBeaconBlock<E>)>> = BTreeMap::new();
+
+    let canonical_ids =
+        (start_slot.as_u64()..=end_slot.as_u64()).map(|slot| BlockId::Slot(Slot::new(slot)));
+    let extra_ids = extra_block_roots.iter().map(|root| BlockId::Root(*root));
+    for block_id in canonical_ids.chain(extra_ids) {
+        let Some(block) = client
+            .get_beacon_blocks::<E>(block_id)
+            .await
+            .map_err(|e| format!("Failed to download block {:?}: {:?}", block_id, e))?
+            .map(|resp| resp.data)
+        else {
+            if let BlockId::Root(root) = block_id {
+                return Err(format!("Unable to locate block {:?}", root));
+            }
+            continue;
+        };
+        let block_root = block.canonical_root();
+        let slot_blocks = blocks.entry(block.slot()).or_default();
+        if slot_blocks.iter().all(|(root, _)| *root != block_root) {
+            slot_blocks.push((block_root, block));
+        }
+    }
+
+    // Re-run fork choice at the slot of the snapshot. Snapshots taken on a re-org are captured
+    // before the head is recomputed, so this reproduces the decision which caused the re-org.
+    let head = fork_choice
+        .get_head(snapshot.current_slot, spec)
+        .map_err(|e| format!("Failed to run fork choice at snapshot slot: {:?}", e))?;
+    println!("Head at snapshot slot: {:?}", head);
+    print_fork_choice(&fork_choice);
+
+    /*
+     * Replay each slot.
+     */
+
+    for slot in (start_slot.as_u64()..=end_slot.as_u64()).map(Slot::new) {
+        println!();
+        println!("=== Slot {} ===", slot);
+
+        let head = fork_choice
+            .get_head(slot, spec)
+            .map_err(|e| format!("Failed to run fork choice at slot {}: {:?}", slot, e))?;
+        println!("Head at start of slot: {:?}", head);
+        print_proposer_head(&fork_choice, slot, head, config);
+
+        for (block_root, block) in blocks.remove(&slot).unwrap_or_default() {
+            apply_block(
+                &mut fork_choice,
+                client,
+                &store,
+                slot,
+                block_root,
+                block,
+                config,
+                spec,
+            )
+            .await?;
+        }
+
+        let head = fork_choice
+            .get_head(slot, spec)
+            .map_err(|e| format!("Failed to run fork choice at slot {}: {:?}", slot, e))?;
+        println!("Head after blocks: {:?}", head);
+        print_fork_choice(&fork_choice);
+    }
+
+    Ok(())
+}
+
+/// Apply `block` and the attestations it contains to fork choice.
+#[allow(clippy::too_many_arguments)]
+async fn apply_block<E: EthSpec>(
+    fork_choice: &mut ReplayForkChoice<E>,
+    client: &BeaconNodeHttpClient,
+    store: &HotColdDB<E, MemoryStore<E>, MemoryStore<E>>,
+    slot: Slot,
+    block_root: Hash256,
+    block: SignedBeaconBlock<E>,
+    config: &Config,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let state_id = StateId::Root(block.state_root());
+    let mut state = client
+        .get_debug_beacon_states::<E>(state_id)
+        .await
+        .map_err(|e| format!("Failed to download state: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?
+        .data;
+    state
+        .build_all_caches(spec)
+        .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+
+    // Make the block and state available in case the justified checkpoint moves to this block.
+    store
+        .put_state(&block.state_root(), &state)
+        .map_err(|e| format!("Unable to store state: {:?}", e))?;
+    store
+        .put_block(&block_root, block.clone())
+        .map_err(|e| format!("Unable to store block: {:?}", e))?;
+
+    match fork_choice.on_block(
+        slot,
+        block.message(),
+        block_root,
+        config.block_delay,
+        &state,
+        PayloadVerificationStatus::Verified,
+        spec,
+    ) {
+        Ok(()) => println!(
+            "Applied block {:?} (proposer {}, parent {:?})",
+            block_root,
+            block.message().proposer_index(),
+            block.parent_root()
+        ),
+        Err(e) => {
+            println!("Rejected block {:?}: {:?}", block_root, e);
+            return Ok(());
+        }
+    }
+
+    let mut applied = 0;
+    for attestation in block.message().body().attestations() {
+        let indexed_attestation = state
+            .get_beacon_committee(attestation.data.slot, attestation.data.index)
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|committee| {
+                get_indexed_attestation(committee.committee, attestation)
+                    .map_err(|e| format!("{:?}", e))
+            })
+            .map_err(|e| format!("Error constructing indexed attestation: {}", e))?;
+
+        if fork_choice
+            .on_attestation(slot, &indexed_attestation, AttestationFromBlock::True)
+            .is_ok()
+        {
+            applied += 1;
+        }
+    }
+    println!(
+        "Applied {}/{} attestations from block {:?}",
+        applied,
+        block.message().body().attestations().len(),
+        block_root
+    );
+
+    Ok(())
+}
+
+/// Print whether a proposer at `slot` would re-org `head`.
+fn print_proposer_head<E: EthSpec>(
+    fork_choice: &ReplayForkChoice<E>,
+    slot: Slot,
+    head: Hash256,
+    config: &Config,
+) {
+    match fork_choice.get_proposer_head(
+        slot,
+        head,
+        config.re_org_head_threshold,
+        config.re_org_parent_threshold,
+        &DisallowedReOrgOffsets::default(),
+        DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
+    ) {
+        Ok(info) => println!(
+            "Proposer would re-org head {:?} (weight {} < {}) onto parent {:?} (weight {} > {})",
+            info.head_node.root,
+            info.head_node.weight,
+            info.re_org_head_weight_threshold,
+            info.parent_node.root,
+            info.parent_node.weight,
+            info.re_org_parent_weight_threshold
+        ),
+        Err(ProposerHeadError::DoNotReOrg(reason)) => {
+            println!("Proposer would build on head: {}", reason)
+        }
+        Err(ProposerHeadError::Error(e)) => println!("Unable to compute proposer head: {:?}", e),
+    }
+}
+
+/// Print the checkpoints, proposer boost root and the weight of each chain tip in fork choice.
+fn print_fork_choice<E: EthSpec>(fork_choice: &ReplayForkChoice<E>) {
+    let fc_store = fork_choice.fc_store();
+    println!(
+        "Justified: {:?}, finalized: {:?}",
+        fc_store.justified_checkpoint(),
+        fc_store.finalized_checkpoint()
+    );
+    println!(
+        "Proposer boost root: {:?}",
+        fork_choice.proposer_boost_root()
+    );
+
+    let nodes = &fork_choice.proto_array().core_proto_array().nodes;
+    let parents: HashSet<usize> = nodes.iter().filter_map(|node| node.parent).collect();
+    for (index, node) in nodes.iter().enumerate() {
+        if !parents.contains(&index) {
+            println!(
+                "Tip {:?} at slot {}: weight {}",
+                node.root, node.slot, node.weight
+            );
+        }
+    }
+}
@@ -3,7 +3,8 @@ mod metrics;
 use beacon_node::ProductionBeaconNode;
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use clap_utils::{
-    flags::DISABLE_MALLOC_TUNING_FLAG, get_color_style, get_eth2_network_config, FLAG_HEADER,
+    flags::{CONFIG_FILE_FLAG, DISABLE_MALLOC_TUNING_FLAG},
+    get_color_style, get_eth2_network_config, FLAG_HEADER,
 };
 use directory::{parse_path_or_default, DEFAULT_BEACON_NODE_DIR, DEFAULT_VALIDATOR_DIR};
 use environment::{EnvironmentBuilder, LoggerConfig};
@@ -41,7 +42,9 @@ lazy_static! {
 }
 
 fn bls_library_name() -> &'static str {
-    if cfg!(feature = "portable") {
+    if cfg!(feature = "zkcrypto") {
+        "zkcrypto"
+    } else if cfg!(feature = "portable") {
         "blst-portable"
     } else if cfg!(feature = "modern") {
         "blst-modern"
@@ -69,14 +72,8 @@ fn build_profile_name() -> String {
         .to_string()
 }
 
-fn main() {
-    // Enable backtraces unless a RUST_BACKTRACE value has already been explicitly provided.
-    if std::env::var("RUST_BACKTRACE").is_err() {
-        std::env::set_var("RUST_BACKTRACE", "1");
-    }
-
-    // Parse the CLI parameters.
-    let matches = Command::new("Lighthouse")
+fn cli_app() -> Command {
+    Command::new("Lighthouse")
         .version(SHORT_VERSION.as_str())
         .author("Sigma Prime <contact@sigmaprime.io>")
         .styles(get_color_style())
@@ -260,11 +257,26 @@ fn main() {
                 .global(true)
                 .display_order(0)
         )
+        .arg(
+            Arg::new(CONFIG_FILE_FLAG)
+                .long(CONFIG_FILE_FLAG)
+                .value_name("FILE")
+                .help(
+                    "Path to a TOML file of values for the arguments of the subcommand, keyed \
+                    by the argument name without the leading dashes, e.g. \
+                    `execution-endpoint = \"http://localhost:8551\"`. Flags take a boolean. \
+                    Arguments provided on the command line take precedence over the file.")
+                .action(ArgAction::Set)
+                .global(true)
+                .display_order(0)
+        )
         .arg(
             Arg::new("dump-config")
                 .long("dump-config")
                 .hide(true)
-                .help("Dumps the config to a desired location. Used for testing only.")
+                .help("Dumps the config to a desired location. If the path ends in `.toml` the \
+                       command line arguments are dumped in the format of --config-file instead. \
+                       Used for testing only.")
                 .action(ArgAction::Set)
                 .global(true)
                 .display_order(0)
@@ -385,6 +397,30 @@ fn main() {
                 .global(true)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("telemetry-collector-url")
+                .long("telemetry-collector-url")
+                .value_name("URL")
+                .help(
+                    "URL of an OpenTelemetry collector to which spans describing the processing \
+                    of blocks and blobs are exported via OTLP/gRPC, e.g. http://localhost:4317. \
+                    Spans for a given block share a trace ID derived from its block root.",
+                )
+                .action(ArgAction::Set)
+                .global(true)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("telemetry-service-name")
+                .long("telemetry-service-name")
+                .value_name("NAME")
+                .help("The service name reported to the OpenTelemetry collector.")
+                .requires("telemetry-collector-url")
+                .action(ArgAction::Set)
+                .default_value("lighthouse")
+                .global(true)
+                .display_order(0)
+        )
         .arg(
             Arg::new("help")
             .long("help")
@@ -400,7 +436,22 @@ fn main() {
         .subcommand(account_manager::cli_app())
         .subcommand(database_manager::cli_app())
         .subcommand(validator_manager::cli_app())
-        .get_matches();
+}
+
+fn main() {
+    // Enable backtraces unless a RUST_BACKTRACE value has already been explicitly provided.
+    if std::env::var("RUST_BACKTRACE").is_err() {
+        std::env::set_var("RUST_BACKTRACE", "1");
+    }
+
+    // Parse the CLI parameters, including any provided with `--config-file`.
+    let matches = match clap_utils::config_file::get_matches_with_config_file(cli_app()) {
+        Ok(matches) => matches,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1)
+        }
+    };
 
     // Configure the allocator early in the process, before it has the chance to use the default values for
     // anything important.
@@ -610,7 +661,21 @@ fn run<E: EthSpec>(
 
     let path = tracing_log_path.clone().unwrap();
 
-    logging::create_tracing_layer(path);
+    let otlp_config = clap_utils::parse_optional::<String>(matches, "telemetry-collector-url")?
+        .map(|endpoint| {
+            Ok::<_, String>(logging::OtlpConfig {
+                endpoint,
+                service_name: clap_utils::parse_required(matches, "telemetry-service-name")?,
+            })
+        })
+        .transpose()?;
+    let otlp_enabled = otlp_config.is_some();
+
+    // The OTLP exporter spawns its batching task onto the current Tokio runtime.
+    {
+        let _guard = environment.runtime().enter();
+        logging::create_tracing_layer(path, otlp_config);
+    }
 
     // Allow Prometheus to export the time at which the process was started.
     metrics::expose_process_start_time(&log);
@@ -690,6 +755,7 @@ fn run<E: EthSpec>(
             config.logger_config = logger_config;
             // Dump configs if `dump-config` or `dump-chain-config` flags are set
             clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
+            clap_utils::config_file::check_dump_config_file(&cli_app(), "beacon_node", matches)?;
 
             let shutdown_flag = matches.get_flag("immediate-shutdown");
             if shutdown_flag {
@@ -719,6 +785,11 @@ fn run<E: EthSpec>(
                 .map_err(|e| format!("Unable to initialize validator config: {}", e))?;
             // Dump configs if `dump-config` or `dump-chain-config` flags are set
             clap_utils::check_dump_configs::<_, E>(matches, &config, &context.eth2_config.spec)?;
+            clap_utils::config_file::check_dump_config_file(
+                &cli_app(),
+                "validator_client",
+                matches,
+            )?;
 
             let shutdown_flag = matches.get_flag("immediate-shutdown");
             if shutdown_flag {
@@ -755,6 +826,10 @@ fn run<E: EthSpec>(
 
     environment.fire_signal();
 
+    if otlp_enabled {
+        logging::shutdown_otlp_exporter();
+    }
+
     // Shutdown the environment once all tasks have completed.
     environment.shutdown_on_idle();
 
@@ -6,7 +6,7 @@ use beacon_node::beacon_chain::chain_config::{
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
 };
 use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
-use beacon_processor::BeaconProcessorConfig;
+use beacon_processor::{BeaconProcessorConfig, SchedulerConfig, WorkQueueId};
 use eth1::Eth1Endpoint;
 use lighthouse_network::PeerId;
 use lighthouse_version;
@@ -2395,10 +2395,34 @@ fn monitoring_endpoint() {
         .run_with_zero_port()
         .with_config(|config| {
             let api_conf = config.monitoring_api.as_ref().unwrap();
-            assert_eq!(api_conf.monitoring_endpoint.as_str(), "http://example:8000");
+            assert_eq!(
+                api_conf.monitoring_endpoint.as_deref(),
+                Some("http://example:8000")
+            );
             assert_eq!(api_conf.update_period_secs, Some(30));
         });
 }
+#[test]
+fn monitoring_file_and_remote_write() {
+    CommandLineTest::new()
+        .flag("monitoring-file", Some("/tmp/metrics.jsonl"))
+        .flag("monitoring-file-period", Some("10"))
+        .flag(
+            "monitoring-remote-write",
+            Some("http://example:9090/api/v1/write"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let api_conf = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(api_conf.monitoring_endpoint, None);
+            let file = api_conf.file.as_ref().unwrap();
+            assert_eq!(file.path, PathBuf::from("/tmp/metrics.jsonl"));
+            assert_eq!(file.update_period_secs, Some(10));
+            let remote_write = api_conf.remote_write.as_ref().unwrap();
+            assert_eq!(remote_write.endpoint, "http://example:9090/api/v1/write");
+            assert_eq!(remote_write.update_period_secs, None);
+        });
+}
 
 // Tests for Logger flags.
 #[test]
@@ -2586,6 +2610,27 @@ fn invalid_gossip_verified_blocks_path_default() {
         .with_config(|config| assert_eq!(config.network.invalid_block_storage, None));
 }
 
+#[test]
+fn fork_choice_snapshot_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.fork_choice_snapshot_dir, None));
+}
+
+#[test]
+fn fork_choice_snapshot_dir() {
+    let path = "/tmp/fork-choice-snapshots";
+    CommandLineTest::new()
+        .flag("fork-choice-snapshot-dir", Some(path))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.fork_choice_snapshot_dir,
+                Some(PathBuf::from(path))
+            )
+        });
+}
+
 #[test]
 fn invalid_gossip_verified_blocks_path() {
     let path = "/home/karlm/naughty-blocks";
@@ -2620,6 +2665,7 @@ fn beacon_processor() {
         .flag("beacon-processor-reprocess-queue-len", Some("3"))
         .flag("beacon-processor-attestation-batch-size", Some("4"))
         .flag("beacon-processor-aggregate-batch-size", Some("5"))
+        .flag("beacon-processor-batch-latency-target", Some("6"))
         .flag("disable-backfill-rate-limiting", None)
         .run_with_zero_port()
         .with_config(|config| {
@@ -2631,12 +2677,69 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    gossip_batch_latency_target: Some(Duration::from_millis(6)),
+                    enable_backfill_rate_limiting: false,
+                    scheduler: SchedulerConfig::default(),
                 }
             )
         });
 }
 
+#[test]
+fn beacon_processor_batch_latency_target_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.beacon_processor.gossip_batch_latency_target, None)
+        });
+}
+
+#[test]
+fn beacon_processor_batch_latency_target_zero() {
+    CommandLineTest::new()
+        .flag("beacon-processor-batch-latency-target", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.beacon_processor.gossip_batch_latency_target, None)
+        });
+}
+
+#[test]
+fn beacon_processor_scheduler_config_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("scheduler.json");
+    let mut scheduler_config = SchedulerConfig::default();
+    scheduler_config.priorities.swap(0, 1);
+    scheduler_config
+        .queue_lengths
+        .insert(WorkQueueId::BackfillChainSegment, 16);
+    scheduler_config
+        .batch_sizes
+        .insert(WorkQueueId::Attestation, 32);
+    let file = File::create(&path).expect("Unable to create file");
+    serde_json::to_writer(file, &scheduler_config).expect("Unable to write to file");
+
+    CommandLineTest::new()
+        .flag("beacon-processor-scheduler-config", path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.beacon_processor.scheduler, scheduler_config));
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_scheduler_config_invalid() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("scheduler.json");
+    let mut scheduler_config = SchedulerConfig::default();
+    scheduler_config.priorities.pop();
+    let file = File::create(&path).expect("Unable to create file");
+    serde_json::to_writer(file, &scheduler_config).expect("Unable to write to file");
+
+    CommandLineTest::new()
+        .flag("beacon-processor-scheduler-config", path.to_str())
+        .run_with_zero_port();
+}
+
 #[test]
 #[should_panic]
 fn beacon_processor_zero_workers() {
@@ -2705,3 +2808,68 @@ fn genesis_state_url_value() {
             assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(42));
         });
 }
+
+fn write_config_file(dir: &TempDir, contents: &str) -> PathBuf {
+    let path = dir.path().join("config.toml");
+    let mut file = File::create(&path).expect("Unable to create config file");
+    file.write_all(contents.as_bytes())
+        .expect("Unable to write config file");
+    path
+}
+
+#[test]
+fn config_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = write_config_file(
+        &dir,
+        "target-peers = 55\nhttp = true\ngenesis-state-url-timeout = 42\n",
+    );
+    CommandLineTest::new()
+        .flag("config-file", path.to_str())
+        .flag("genesis-state-url-timeout", Some("43"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.target_peers, 55);
+            assert!(config.http_api.enabled);
+            // The command line takes precedence over the file.
+            assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(43));
+        });
+}
+
+#[test]
+#[should_panic]
+fn config_file_unknown_key() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = write_config_file(&dir, "target-peer = 55\n");
+    CommandLineTest::new()
+        .flag("config-file", path.to_str())
+        .run_with_zero_port();
+}
+
+#[test]
+fn dump_config_file() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("config.toml");
+    let output = base_cmd()
+        .arg("--datadir")
+        .arg(dir.path().as_os_str())
+        .arg("--allow-insecure-genesis-sync")
+        .arg("-z")
+        .arg("--target-peers")
+        .arg("55")
+        .arg("--http")
+        .arg("--dump-config")
+        .arg(path.as_os_str())
+        .arg("--immediate-shutdown")
+        .output()
+        .expect("should run command");
+    assert!(output.status.success());
+
+    CommandLineTest::new()
+        .flag("config-file", path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.target_peers, 55);
+            assert!(config.http_api.enabled);
+        });
+}
@@ -1,7 +1,13 @@
-use validator_client::{config::DEFAULT_WEB3SIGNER_KEEP_ALIVE, ApiTopic, Config};
+use validator_client::{
+    config::DEFAULT_WEB3SIGNER_KEEP_ALIVE,
+    proposer_config::{self, ProposerConfigSource},
+    webhook::{WebhookConfig, WebhookEventKind},
+    ApiTopic, Config,
+};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
+use sensitive_url::SensitiveUrl;
 use std::fs::File;
 use std::io::Write;
 use std::net::IpAddr;
@@ -110,6 +116,21 @@ fn disable_auto_discover_flag() {
         .with_config(|config| assert!(config.disable_auto_discover));
 }
 
+#[test]
+fn reload_validator_definitions_flag() {
+    CommandLineTest::new()
+        .flag("reload-validator-definitions", None)
+        .run()
+        .with_config(|config| assert!(config.reload_validator_definitions));
+}
+
+#[test]
+fn reload_validator_definitions_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.reload_validator_definitions));
+}
+
 #[test]
 fn init_slashing_protections_flag() {
     CommandLineTest::new()
@@ -423,6 +444,72 @@ fn no_doppelganger_protection_flag() {
         .with_config(|config| assert!(!config.enable_doppelganger_protection));
 }
 #[test]
+fn doppelganger_peer_vcs_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .flag(
+            "doppelganger-peer-vcs",
+            Some("http://localhost:5062,http://localhost:5063"),
+        )
+        .flag(
+            "doppelganger-peer-vc-tokens",
+            Some(
+                vec![
+                    dir.path().join("api-token-1.txt").to_str().unwrap(),
+                    dir.path().join("api-token-2.txt").to_str().unwrap(),
+                ]
+                .join(",")
+                .as_str(),
+            ),
+        )
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.doppelganger_peer_vcs.len(), 2);
+            assert_eq!(
+                config.doppelganger_peer_vcs[1].full.to_string(),
+                "http://localhost:5063/"
+            );
+            assert_eq!(
+                config.doppelganger_peer_vc_tokens,
+                vec![
+                    dir.path().join("api-token-1.txt"),
+                    dir.path().join("api-token-2.txt")
+                ]
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn doppelganger_peer_vcs_token_count_mismatch() {
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .flag(
+            "doppelganger-peer-vcs",
+            Some("http://localhost:5062,http://localhost:5063"),
+        )
+        .flag("doppelganger-peer-vc-tokens", Some("/tmp/api-token.txt"))
+        .run();
+}
+#[test]
+fn doppelganger_lock_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("enable-doppelganger-protection", None)
+        .flag("doppelganger-lock-dir", dir.path().to_str())
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.doppelganger_lock_dir, Some(dir.path().to_path_buf()))
+        });
+}
+#[test]
+fn doppelganger_remote_checks_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert!(config.doppelganger_peer_vcs.is_empty());
+        assert!(config.doppelganger_lock_dir.is_none());
+    });
+}
+#[test]
 fn produce_block_v3_flag() {
     CommandLineTest::new()
         .flag("produce-block-v3", None)
@@ -491,6 +578,123 @@ fn no_prefer_builder_proposals_flag() {
         .with_config(|config| assert!(!config.prefer_builder_proposals));
 }
 #[test]
+fn no_proposer_config_flag() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.proposer_config, None);
+        assert_eq!(
+            config.proposer_config_refresh_interval,
+            proposer_config::DEFAULT_REFRESH_INTERVAL
+        );
+    });
+}
+#[test]
+fn proposer_config_file_flag() {
+    CommandLineTest::new()
+        .flag("proposer-config-file", Some("./proposer-config.json"))
+        .flag("proposer-config-refresh-interval", Some("12"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.proposer_config,
+                Some(ProposerConfigSource::File(PathBuf::from(
+                    "./proposer-config.json"
+                )))
+            );
+            assert_eq!(
+                config.proposer_config_refresh_interval,
+                Duration::from_secs(12)
+            );
+        });
+}
+#[test]
+fn proposer_config_url_flag() {
+    CommandLineTest::new()
+        .flag("proposer-config-url", Some("http://localhost:8080/config"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.proposer_config,
+                Some(ProposerConfigSource::Url(
+                    SensitiveUrl::parse("http://localhost:8080/config").unwrap()
+                ))
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn proposer_config_file_and_url_flags() {
+    CommandLineTest::new()
+        .flag("proposer-config-file", Some("./proposer-config.json"))
+        .flag("proposer-config-url", Some("http://localhost:8080/config"))
+        .run();
+}
+#[test]
+#[should_panic]
+fn proposer_config_refresh_interval_without_source() {
+    CommandLineTest::new()
+        .flag("proposer-config-refresh-interval", Some("12"))
+        .run();
+}
+#[test]
+fn webhook_flags_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.webhook, None));
+}
+#[test]
+fn webhook_url_flag() {
+    CommandLineTest::new()
+        .flag("webhook-url", Some("http://localhost:9000/hook"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.webhook,
+                Some(WebhookConfig {
+                    url: SensitiveUrl::parse("http://localhost:9000/hook").unwrap(),
+                    events: WebhookEventKind::all(),
+                    max_retries: 3,
+                })
+            );
+        });
+}
+#[test]
+fn webhook_events_and_max_retries_flags() {
+    CommandLineTest::new()
+        .flag("webhook-url", Some("http://localhost:9000/hook"))
+        .flag(
+            "webhook-events",
+            Some("proposal-failed,doppelganger-detected"),
+        )
+        .flag("webhook-max-retries", Some("0"))
+        .run()
+        .with_config(|config| {
+            let webhook = config.webhook.as_ref().unwrap();
+            assert_eq!(
+                webhook.events,
+                vec![
+                    WebhookEventKind::ProposalFailed,
+                    WebhookEventKind::DoppelgangerDetected
+                ]
+            );
+            assert_eq!(webhook.max_retries, 0);
+        });
+}
+#[test]
+#[should_panic]
+fn webhook_events_flag_unknown_event() {
+    CommandLineTest::new()
+        .flag("webhook-url", Some("http://localhost:9000/hook"))
+        .flag("webhook-events", Some("proposal-missed"))
+        .run();
+}
+#[test]
+#[should_panic]
+fn webhook_events_flag_without_url() {
+    CommandLineTest::new()
+        .flag("webhook-events", Some("proposal-failed"))
+        .run();
+}
+#[test]
 fn no_builder_registration_timestamp_override_flag() {
     CommandLineTest::new()
         .run()
@@ -513,10 +717,34 @@ fn monitoring_endpoint() {
         .run()
         .with_config(|config| {
             let api_conf = config.monitoring_api.as_ref().unwrap();
-            assert_eq!(api_conf.monitoring_endpoint.as_str(), "http://example:8000");
+            assert_eq!(
+                api_conf.monitoring_endpoint.as_deref(),
+                Some("http://example:8000")
+            );
             assert_eq!(api_conf.update_period_secs, Some(30));
         });
 }
+#[test]
+fn monitoring_file_and_remote_write() {
+    CommandLineTest::new()
+        .flag("monitoring-file", Some("/tmp/metrics.jsonl"))
+        .flag("monitoring-file-period", Some("10"))
+        .flag(
+            "monitoring-remote-write",
+            Some("http://example:9090/api/v1/write"),
+        )
+        .run()
+        .with_config(|config| {
+            let api_conf = config.monitoring_api.as_ref().unwrap();
+            assert_eq!(api_conf.monitoring_endpoint, None);
+            let file = api_conf.file.as_ref().unwrap();
+            assert_eq!(file.path, PathBuf::from("/tmp/metrics.jsonl"));
+            assert_eq!(file.update_period_secs, Some(10));
+            let remote_write = api_conf.remote_write.as_ref().unwrap();
+            assert_eq!(remote_write.endpoint, "http://example:9090/api/v1/write");
+            assert_eq!(remote_write.update_period_secs, None);
+        });
+}
 
 #[test]
 fn disable_run_on_all_flag() {
@@ -672,3 +900,33 @@ fn validator_web3_signer_keep_alive_override() {
             );
         });
 }
+
+#[test]
+fn config_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("config.toml");
+    let mut file = File::create(&path).expect("Unable to create file");
+    let contents = r#"
+        beacon-nodes = ["http://localhost:1001", "http://localhost:1002"]
+        init-slashing-protection = true
+        web3-signer-keep-alive-timeout = 1000
+    "#;
+    file.write_all(contents.as_bytes())
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("config-file", path.to_str())
+        .flag("web3-signer-keep-alive-timeout", Some("2000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_nodes[1].full.to_string(),
+                "http://localhost:1002/"
+            );
+            assert!(config.init_slashing_protection);
+            // The command line takes precedence over the file.
+            assert_eq!(
+                config.web3_signer_keep_alive_timeout,
+                Some(Duration::from_secs(2))
+            );
+        });
+}
@@ -1,3 +1,4 @@
+use account_utils::keystore_rotation::KeystoreKdf;
 use eth2::SensitiveUrl;
 use serde::de::DeserializeOwned;
 use std::fs;
@@ -8,9 +9,12 @@ use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
+    bls_to_execution_change::BlsToExecutionChangeConfig,
     create_validators::CreateConfig,
+    exit_validators::{self, ExitConfig},
     import_validators::ImportConfig,
     move_validators::{MoveConfig, PasswordSource, Validators},
+    rotate_keystores::{self, RotateConfig, RotateTarget},
 };
 
 const EXAMPLE_ETH1_ADDRESS: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
@@ -105,6 +109,24 @@ impl CommandLineTest<MoveConfig> {
     }
 }
 
+impl CommandLineTest<ExitConfig> {
+    fn validators_exit() -> Self {
+        Self::default().flag("exit", None)
+    }
+}
+
+impl CommandLineTest<BlsToExecutionChangeConfig> {
+    fn validators_bls_to_execution_change() -> Self {
+        Self::default().flag("bls-to-execution-change", None)
+    }
+}
+
+impl CommandLineTest<RotateConfig> {
+    fn validators_rotate_keystores() -> Self {
+        Self::default().flag("rotate-keystores", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -394,3 +416,187 @@ pub fn validator_move_count() {
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn validator_rotate_keystores_defaults() {
+    CommandLineTest::validators_rotate_keystores()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_success(|config| {
+            let expected = RotateConfig {
+                target: RotateTarget::ValidatorClient {
+                    vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                    vc_token_path: PathBuf::from("./token.json"),
+                },
+                validators: rotate_keystores::Validators::All,
+                kdf: KeystoreKdf::default(),
+                new_password_path: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_rotate_keystores_misc_flags() {
+    CommandLineTest::validators_rotate_keystores()
+        .flag("--validators-dir", Some("./validators"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--kdf", Some("pbkdf2"))
+        .flag("--kdf-cost", Some("42"))
+        .flag("--new-password-file", Some("./password.txt"))
+        .assert_success(|config| {
+            let expected = RotateConfig {
+                target: RotateTarget::ValidatorsDir(PathBuf::from("./validators")),
+                validators: rotate_keystores::Validators::Specific(vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ]),
+                kdf: KeystoreKdf::Pbkdf2 { c: 42 },
+                new_password_path: Some(PathBuf::from("./password.txt")),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_rotate_keystores_invalid_scrypt_cost() {
+    CommandLineTest::validators_rotate_keystores()
+        .flag("--validators-dir", Some("./validators"))
+        .flag("--validators", Some("all"))
+        .flag("--kdf-cost", Some("1000"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_rotate_keystores_vc_url_and_validators_dir() {
+    CommandLineTest::validators_rotate_keystores()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators-dir", Some("./validators"))
+        .flag("--validators", Some("all"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_exit_defaults() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: exit_validators::Validators::All,
+                exit_epoch: None,
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_misc_flags() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--exit-epoch", Some("1024"))
+        .flag("--no-confirmation", None)
+        .flag("--stdin-inputs", None)
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: exit_validators::Validators::Specific(vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ]),
+                exit_epoch: Some(Epoch::new(1024)),
+                no_confirmation: true,
+                stdin_inputs: true,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_without_validators() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_bls_to_execution_change_defaults() {
+    CommandLineTest::validators_bls_to_execution_change()
+        .flag("--validator-indices", Some("1,2"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--beacon-node", Some("http://localhost:1001"))
+        .assert_success(|config| {
+            let expected = BlsToExecutionChangeConfig {
+                mnemonic_path: None,
+                first_index: 0,
+                validator_indices: vec![1, 2],
+                execution_address: Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap(),
+                bn_url: SensitiveUrl::parse("http://localhost:1001").unwrap(),
+                batch_size: 64,
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_bls_to_execution_change_misc_flags() {
+    CommandLineTest::validators_bls_to_execution_change()
+        .flag("--mnemonic-path", Some("./woof"))
+        .flag("--first-index", Some("12"))
+        .flag("--validator-indices", Some("42"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--beacon-node", Some("http://localhost:1001"))
+        .flag("--batch-size", Some("8"))
+        .flag("--no-confirmation", None)
+        .flag("--stdin-inputs", None)
+        .assert_success(|config| {
+            let expected = BlsToExecutionChangeConfig {
+                mnemonic_path: Some(PathBuf::from("./woof")),
+                first_index: 12,
+                validator_indices: vec![42],
+                execution_address: Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap(),
+                bn_url: SensitiveUrl::parse("http://localhost:1001").unwrap(),
+                batch_size: 8,
+                no_confirmation: true,
+                stdin_inputs: true,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_bls_to_execution_change_without_execution_address() {
+    CommandLineTest::validators_bls_to_execution_change()
+        .flag("--validator-indices", Some("1"))
+        .flag("--beacon-node", Some("http://localhost:1001"))
+        .assert_failed();
+}
+
+#[test]
+pub fn validator_bls_to_execution_change_zero_batch_size() {
+    CommandLineTest::validators_bls_to_execution_change()
+        .flag("--validator-indices", Some("1"))
+        .flag("--execution-address", Some(EXAMPLE_ETH1_ADDRESS))
+        .flag("--beacon-node", Some("http://localhost:1001"))
+        .flag("--batch-size", Some("0"))
+        .assert_failed();
+}
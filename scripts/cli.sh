@@ -29,6 +29,9 @@ vm_cli=$($CMD vm --help)
 vm_cli_create=$($CMD vm create --help)
 vm_cli_import=$($CMD vm import --help)
 vm_cli_move=$($CMD vm move --help)
+vm_cli_rotate_keystores=$($CMD vm rotate-keystores --help)
+vm_cli_exit=$($CMD vm exit --help)
+vm_cli_bls_to_execution_change=$($CMD vm bls-to-execution-change --help)
 
 general=./help_general.md
 bn=./help_bn.md
@@ -38,6 +41,9 @@ vm=./help_vm.md
 vm_create=./help_vm_create.md
 vm_import=./help_vm_import.md
 vm_move=./help_vm_move.md
+vm_rotate_keystores=./help_vm_rotate_keystores.md
+vm_exit=./help_vm_exit.md
+vm_bls_to_execution_change=./help_vm_bls_to_execution_change.md
 
 # create .md files
 write_to_file "$general_cli" "$general" "Lighthouse General Commands"
@@ -47,10 +53,13 @@ write_to_file "$vm_cli" "$vm" "Validator Manager"
 write_to_file "$vm_cli_create" "$vm_create" "Validator Manager Create"
 write_to_file "$vm_cli_import" "$vm_import" "Validator Manager Import"
 write_to_file "$vm_cli_move" "$vm_move" "Validator Manager Move"
+write_to_file "$vm_cli_rotate_keystores" "$vm_rotate_keystores" "Validator Manager Rotate Keystores"
+write_to_file "$vm_cli_exit" "$vm_exit" "Validator Manager Exit"
+write_to_file "$vm_cli_bls_to_execution_change" "$vm_bls_to_execution_change" "Validator Manager BLS To Execution Change"
 
 #input 1 = $1 = files; input 2 = $2 = new files
-files=(./book/src/help_general.md ./book/src/help_bn.md ./book/src/help_vc.md ./book/src/help_vm.md ./book/src/help_vm_create.md ./book/src/help_vm_import.md ./book/src/help_vm_move.md)
-new_files=($general $bn $vc $vm $vm_create $vm_import $vm_move)
+files=(./book/src/help_general.md ./book/src/help_bn.md ./book/src/help_vc.md ./book/src/help_vm.md ./book/src/help_vm_create.md ./book/src/help_vm_import.md ./book/src/help_vm_move.md ./book/src/help_vm_rotate_keystores.md ./book/src/help_vm_exit.md ./book/src/help_vm_bls_to_execution_change.md)
+new_files=($general $bn $vc $vm $vm_create $vm_import $vm_move $vm_rotate_keystores $vm_exit $vm_bls_to_execution_change)
 
 # function to check
 check() {
@@ -84,9 +93,10 @@ check ${files[3]} ${new_files[3]}
 check ${files[4]} ${new_files[4]}
 check ${files[5]} ${new_files[5]}
 check ${files[6]} ${new_files[6]}
+check ${files[7]} ${new_files[7]}
 
 # remove help files
-rm -f help_general.md help_bn.md help_vc.md help_am.md help_vm.md help_vm_create.md help_vm_import.md help_vm_move.md
+rm -f help_general.md help_bn.md help_vc.md help_am.md help_vm.md help_vm_create.md help_vm_import.md help_vm_move.md help_vm_rotate_keystores.md help_vm_exit.md help_vm_bls_to_execution_change.md
 
 # only exit at the very end
 if [[ $changes == true ]]; then
@@ -31,6 +31,10 @@ impl Case for BlsEthAggregatePubkeys {
             {
                 return Ok(());
             }
+            #[cfg(feature = "zkcrypto")]
+            Err(bls::Error::ZkcryptoError(_)) if self.output.is_none() => {
+                return Ok(());
+            }
             Err(e) => return Err(Error::FailedToParseTest(format!("{:?}", e))),
         };
 
@@ -343,11 +343,7 @@ pub async fn ensure_node_synced_up_to_slot<E: EthSpec>(
     slot_duration: Duration,
 ) -> Result<(), String> {
     slot_delay(upto_slot, slot_duration).await;
-    let node = &network
-        .remote_nodes()?
-        .get(node_index)
-        .expect("Should get node")
-        .clone();
+    let node = network.remote_node(node_index)?;
 
     let head = node
         .get_beacon_blocks::<E>(BlockId::Head)
@@ -416,6 +412,25 @@ pub async fn disconnect_from_execution_layer<E: EthSpec>(
     Ok(())
 }
 
+// Causes the execution node at `node_index` to delay each payload it produces by `delay`, which
+// delays the blocks proposed by its beacon node. A `delay` of `None` removes the delay.
+pub async fn delay_block_proposals<E: EthSpec>(
+    network: LocalNetwork<E>,
+    node_index: usize,
+    delay: Option<Duration>,
+) -> Result<(), String> {
+    eprintln!("Delaying proposals of Execution Node {node_index} by {delay:?}");
+
+    network
+        .execution_nodes
+        .read()
+        .get(node_index)
+        .ok_or_else(|| format!("No execution node for index {node_index}"))?
+        .server
+        .set_get_payload_delay(delay);
+    Ok(())
+}
+
 // Causes the beacon node at `node_index` to reconnect from the execution layer.
 pub async fn reconnect_to_execution_layer<E: EthSpec>(
     network: LocalNetwork<E>,
@@ -123,4 +123,45 @@ pub fn cli_app() -> Command {
                         .help("Continue after checks (default false)"),
                 ),
         )
+        .subcommand(
+            Command::new("scenario-sim")
+                .about(
+                    "Runs a Beacon Chain simulation described by a YAML scenario file. \
+                    The scenario specifies the topology of the network, the fork schedule, faults \
+                    to inject (such as killing a node or disconnecting an execution layer) and \
+                    assertions to make at given epochs. If any assertion fails, the simulation \
+                    will exit immediately.",
+                )
+                .arg(
+                    Arg::new("scenario")
+                        .long("scenario")
+                        .value_name("FILE")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the YAML scenario file."),
+                )
+                .arg(
+                    Arg::new("speed-up-factor")
+                        .short('s')
+                        .long("speed-up-factor")
+                        .action(ArgAction::Set)
+                        .default_value("3")
+                        .help("Speed up factor. Please use a divisor of 12."),
+                )
+                .arg(
+                    Arg::new("debug-level")
+                        .short('d')
+                        .long("debug-level")
+                        .action(ArgAction::Set)
+                        .default_value("debug")
+                        .help("Set the severity level of the logs."),
+                )
+                .arg(
+                    Arg::new("continue-after-checks")
+                        .short('c')
+                        .long("continue_after_checks")
+                        .action(ArgAction::SetTrue)
+                        .help("Continue after checks (default false)"),
+                ),
+        )
 }
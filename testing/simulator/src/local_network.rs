@@ -1,5 +1,6 @@
 use crate::checks::epoch_delay;
 use eth2_network_config::TRUSTED_SETUP_BYTES;
+use lighthouse_network::{LinkConditioner, LinkConditions};
 use node_test_rig::{
     environment::RuntimeContext,
     eth2::{types::StateId, BeaconNodeHttpClient},
@@ -14,6 +15,7 @@ use std::{
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
+use task_executor::TaskExecutor;
 use types::{ChainSpec, Epoch, EthSpec};
 
 const BOOTNODE_PORT: u16 = 42424;
@@ -98,6 +100,12 @@ pub struct Inner<E: EthSpec> {
     pub proposer_nodes: RwLock<Vec<LocalBeaconNode<E>>>,
     pub validator_clients: RwLock<Vec<LocalValidatorClient<E>>>,
     pub execution_nodes: RwLock<Vec<LocalExecutionNode<E>>>,
+    /// Closing the sender at index `i` stops the beacon node at index `i` of `beacon_nodes`.
+    beacon_node_exits: RwLock<Vec<async_channel::Sender<()>>>,
+    /// Closing the sender at index `i` stops the beacon node at index `i` of `proposer_nodes`.
+    proposer_node_exits: RwLock<Vec<async_channel::Sender<()>>>,
+    /// Controls latency, loss and partitions between all nodes in the network.
+    link_conditioner: LinkConditioner,
 }
 
 /// Represents a set of interconnected `LocalBeaconNode` and `LocalValidatorClient`.
@@ -155,6 +163,9 @@ impl<E: EthSpec> LocalNetwork<E> {
                 proposer_nodes: RwLock::new(vec![]),
                 execution_nodes: RwLock::new(vec![]),
                 validator_clients: RwLock::new(vec![]),
+                beacon_node_exits: RwLock::new(vec![]),
+                proposer_node_exits: RwLock::new(vec![]),
+                link_conditioner: LinkConditioner::default(),
             }),
         };
 
@@ -185,11 +196,52 @@ impl<E: EthSpec> LocalNetwork<E> {
         self.validator_clients.read().len()
     }
 
+    /// Returns a context for a beacon node which can be stopped independently of the rest of the
+    /// network by closing the returned sender.
+    ///
+    /// The beacon node is also stopped when the network shuts down.
+    fn node_context(
+        &self,
+        service_name: String,
+    ) -> Result<(RuntimeContext<E>, async_channel::Sender<()>), String> {
+        let mut context = self.context.service_context(service_name);
+        let handle = context
+            .executor
+            .handle()
+            .ok_or("Runtime is shutting down")?;
+        let (exit_tx, exit_rx) = async_channel::bounded(1);
+
+        let network_exit = self.context.executor.exit();
+        let node_exit_tx = exit_tx.clone();
+        self.context.executor.spawn_without_exit(
+            async move {
+                network_exit.await;
+                node_exit_tx.close();
+            },
+            "node_exit",
+        );
+
+        context.executor = TaskExecutor::new(
+            handle,
+            exit_rx,
+            context.log().clone(),
+            context.executor.shutdown_sender(),
+        );
+        Ok((context, exit_tx))
+    }
+
     async fn construct_boot_node(
         &self,
         mut beacon_config: ClientConfig,
         mock_execution_config: MockExecutionConfig,
-    ) -> Result<(LocalBeaconNode<E>, LocalExecutionNode<E>), String> {
+    ) -> Result<
+        (
+            LocalBeaconNode<E>,
+            LocalExecutionNode<E>,
+            async_channel::Sender<()>,
+        ),
+        String,
+    > {
         beacon_config.network.set_ipv4_listening_address(
             std::net::Ipv4Addr::UNSPECIFIED,
             BOOTNODE_PORT,
@@ -200,6 +252,8 @@ impl<E: EthSpec> LocalNetwork<E> {
         beacon_config.network.enr_udp4_port = Some(BOOTNODE_PORT.try_into().expect("non zero"));
         beacon_config.network.enr_tcp4_port = Some(BOOTNODE_PORT.try_into().expect("non zero"));
         beacon_config.network.discv5_config.table_filter = |_| true;
+        beacon_config.network.link_conditioner =
+            Some(self.link_conditioner.for_node(BOOTNODE_PORT));
 
         let execution_node = LocalExecutionNode::new(
             self.context.service_context("boot_node_el".into()),
@@ -213,13 +267,10 @@ impl<E: EthSpec> LocalNetwork<E> {
             ..Default::default()
         });
 
-        let beacon_node = LocalBeaconNode::production(
-            self.context.service_context("boot_node".into()),
-            beacon_config,
-        )
-        .await?;
+        let (context, exit_tx) = self.node_context("boot_node".into())?;
+        let beacon_node = LocalBeaconNode::production(context, beacon_config).await?;
 
-        Ok((beacon_node, execution_node))
+        Ok((beacon_node, execution_node, exit_tx))
     }
 
     async fn construct_beacon_node(
@@ -227,7 +278,14 @@ impl<E: EthSpec> LocalNetwork<E> {
         mut beacon_config: ClientConfig,
         mut mock_execution_config: MockExecutionConfig,
         is_proposer: bool,
-    ) -> Result<(LocalBeaconNode<E>, LocalExecutionNode<E>), String> {
+    ) -> Result<
+        (
+            LocalBeaconNode<E>,
+            LocalExecutionNode<E>,
+            async_channel::Sender<()>,
+        ),
+        String,
+    > {
         let count = (self.beacon_node_count() + self.proposer_node_count()) as u16;
 
         // Set config.
@@ -243,6 +301,8 @@ impl<E: EthSpec> LocalNetwork<E> {
         beacon_config.network.enr_tcp4_port = Some(libp2p_tcp_port.try_into().unwrap());
         beacon_config.network.discv5_config.table_filter = |_| true;
         beacon_config.network.proposer_only = is_proposer;
+        beacon_config.network.link_conditioner =
+            Some(self.link_conditioner.for_node(libp2p_tcp_port));
 
         mock_execution_config.server_config.listen_port = EXECUTION_PORT + count;
 
@@ -261,13 +321,10 @@ impl<E: EthSpec> LocalNetwork<E> {
         });
 
         // Construct beacon node using the config,
-        let beacon_node = LocalBeaconNode::production(
-            self.context.service_context(format!("node_{}", count)),
-            beacon_config,
-        )
-        .await?;
+        let (context, exit_tx) = self.node_context(format!("node_{}", count))?;
+        let beacon_node = LocalBeaconNode::production(context, beacon_config).await?;
 
-        Ok((beacon_node, execution_node))
+        Ok((beacon_node, execution_node, exit_tx))
     }
 
     /// Adds a beacon node to the network, connecting to the 0'th beacon node via ENR.
@@ -293,7 +350,7 @@ impl<E: EthSpec> LocalNetwork<E> {
                 );
             }
         }
-        let (beacon_node, execution_node) = if first_bn_exists {
+        let (beacon_node, execution_node, exit_tx) = if first_bn_exists {
             // Network already exists. We construct a new node.
             self.construct_beacon_node(beacon_config, mock_execution_config, is_proposer)
                 .await?
@@ -306,12 +363,92 @@ impl<E: EthSpec> LocalNetwork<E> {
         self.execution_nodes.write().push(execution_node);
         if is_proposer {
             self.proposer_nodes.write().push(beacon_node);
+            self.proposer_node_exits.write().push(exit_tx);
         } else {
             self.beacon_nodes.write().push(beacon_node);
+            self.beacon_node_exits.write().push(exit_tx);
         }
         Ok(())
     }
 
+    /// Stops the beacon node with index `beacon_node`, as if its process had been killed.
+    ///
+    /// The node retains its index, but is no longer included in `Self::remote_nodes`.
+    pub fn kill_beacon_node(&self, beacon_node: usize) -> Result<(), String> {
+        self.beacon_node_exits
+            .read()
+            .get(beacon_node)
+            .ok_or_else(|| format!("No beacon node for index {}", beacon_node))?
+            .close();
+        Ok(())
+    }
+
+    /// Returns `true` if the beacon node with index `beacon_node` has not been killed.
+    pub fn is_beacon_node_running(&self, beacon_node: usize) -> bool {
+        self.beacon_node_exits
+            .read()
+            .get(beacon_node)
+            .is_some_and(|exit_tx| !exit_tx.is_closed())
+    }
+
+    /// Returns the libp2p TCP port of the beacon node with index `beacon_node`, which identifies it
+    /// to the link conditioner.
+    fn beacon_node_port(&self, beacon_node: usize) -> Result<u16, String> {
+        self.beacon_nodes
+            .read()
+            .get(beacon_node)
+            .ok_or_else(|| format!("No beacon node for index {}", beacon_node))?
+            .client
+            .enr()
+            .and_then(|enr| enr.tcp4())
+            .ok_or_else(|| format!("Beacon node {} has no TCP port", beacon_node))
+    }
+
+    /// Partitions each group of beacon nodes from the beacon nodes in every other group, closing
+    /// any connections between them. Nodes which are not in any group, including proposer nodes,
+    /// are unaffected.
+    pub fn partition(&self, groups: &[Vec<usize>]) -> Result<(), String> {
+        let port_groups = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|&beacon_node| self.beacon_node_port(beacon_node))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.link_conditioner.partition(&port_groups);
+        Ok(())
+    }
+
+    /// Removes all partitions between beacon nodes.
+    pub fn heal_partitions(&self) {
+        self.link_conditioner.heal_partitions();
+    }
+
+    /// Adds `latency` and `packet_loss` to the link between two beacon nodes, in both directions.
+    pub fn set_link_conditions(
+        &self,
+        beacon_node_a: usize,
+        beacon_node_b: usize,
+        latency: Duration,
+        packet_loss: f64,
+    ) -> Result<(), String> {
+        let a = self.beacon_node_port(beacon_node_a)?;
+        let b = self.beacon_node_port(beacon_node_b)?;
+        let partitioned = self.link_conditioner.link_conditions(a, b).partitioned;
+        self.link_conditioner.set_link_conditions(
+            a,
+            b,
+            LinkConditions {
+                latency,
+                packet_loss,
+                partitioned,
+            },
+        );
+        Ok(())
+    }
+
     // Add a new node with a delay. This node will not have validators and is only used to test
     // sync.
     pub async fn add_beacon_node_with_delay(
@@ -435,18 +572,42 @@ impl<E: EthSpec> LocalNetwork<E> {
         Ok(())
     }
 
-    /// For all beacon nodes in `Self`, return a HTTP client to access each nodes HTTP API.
+    /// For all running beacon nodes in `Self`, return a HTTP client to access each nodes HTTP API.
     pub fn remote_nodes(&self) -> Result<Vec<BeaconNodeHttpClient>, String> {
         let beacon_nodes = self.beacon_nodes.read();
         let proposer_nodes = self.proposer_nodes.read();
+        let beacon_node_exits = self.beacon_node_exits.read();
+        let proposer_node_exits = self.proposer_node_exits.read();
 
         beacon_nodes
             .iter()
-            .chain(proposer_nodes.iter())
-            .map(|beacon_node| beacon_node.remote_node())
+            .zip(beacon_node_exits.iter())
+            .chain(proposer_nodes.iter().zip(proposer_node_exits.iter()))
+            .filter(|(_, exit_tx)| !exit_tx.is_closed())
+            .map(|(beacon_node, _)| beacon_node.remote_node())
             .collect()
     }
 
+    /// Return a HTTP client to access the HTTP API of the node at `index`, where the indices of
+    /// proposer nodes follow those of the beacon nodes.
+    ///
+    /// Unlike `Self::remote_nodes`, the indices are unaffected by killing a node.
+    pub fn remote_node(&self, index: usize) -> Result<BeaconNodeHttpClient, String> {
+        let beacon_node_count = self.beacon_node_count();
+        if index < beacon_node_count {
+            if !self.is_beacon_node_running(index) {
+                return Err(format!("Beacon node {} has been killed", index));
+            }
+            self.beacon_nodes.read()[index].remote_node()
+        } else {
+            self.proposer_nodes
+                .read()
+                .get(index - beacon_node_count)
+                .ok_or_else(|| format!("No beacon node for index {}", index))?
+                .remote_node()
+        }
+    }
+
     /// Return current epoch of bootnode.
     pub async fn _bootnode_epoch(&self) -> Result<Epoch, String> {
         let nodes = self.remote_nodes().expect("Failed to get remote nodes");
@@ -16,6 +16,8 @@ mod cli;
 mod fallback_sim;
 mod local_network;
 mod retry;
+mod scenario;
+mod scenario_sim;
 
 use cli::cli_app;
 use env_logger::{Builder, Env};
@@ -44,6 +46,13 @@ fn main() {
                 std::process::exit(1)
             }
         },
+        Some(("scenario-sim", matches)) => match scenario_sim::run_scenario_sim(matches) {
+            Ok(()) => println!("Simulation exited successfully"),
+            Err(e) => {
+                eprintln!("Simulation exited with error: {}", e);
+                std::process::exit(1)
+            }
+        },
         _ => {
             eprintln!("Invalid subcommand. Use --help to see available options");
             std::process::exit(1)
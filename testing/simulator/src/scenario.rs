@@ -0,0 +1,388 @@
+//! Declarative scenarios for the `scenario-sim` subcommand.
+//!
+//! A scenario is a YAML file describing the topology of a local network, its fork schedule, the
+//! faults to inject and the assertions to make at given epochs. For example:
+//!
+//! ```yaml
+//! name: el-outage
+//! topology:
+//!   nodes: 4
+//!   validators_per_node: 20
+//! end_epoch: 10
+//! faults:
+//!   - epoch: 3
+//!     fault: disconnect_execution_layer
+//!     node: 1
+//!   - epoch: 5
+//!     fault: reconnect_execution_layer
+//!     node: 1
+//! assertions:
+//!   - epoch: 4
+//!     check: finalized
+//!     finalized_epoch: 2
+//!   - epoch: 10
+//!     check: node_synced
+//!     node: 1
+//! ```
+//!
+//! Beacon nodes are indexed in the order they are added to the network: `0..topology.nodes` at
+//! genesis, followed by any nodes started by an `add_node` fault in order of epoch. Node `0` is the
+//! boot node and cannot be killed. Proposer nodes cannot be the target of a fault or assertion.
+//! Partitions are not supported alongside proposer nodes, as a proposer node connects to every
+//! beacon node and would relay messages across the partition.
+//!
+//! All faults and assertions are scheduled relative to genesis and run concurrently. The
+//! simulation fails as soon as any of them fails.
+
+use serde::Deserialize;
+use std::fs::File;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    pub name: String,
+    pub topology: Topology,
+    #[serde(default)]
+    pub forks: ForkEpochs,
+    /// The epoch at which the simulation ends, once all assertions have passed.
+    pub end_epoch: u64,
+    #[serde(default)]
+    pub faults: Vec<ScheduledFault>,
+    #[serde(default)]
+    pub assertions: Vec<ScheduledAssertion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Topology {
+    /// The number of beacon nodes, each with a validator client.
+    pub nodes: usize,
+    /// The number of proposer-only beacon nodes, used by the validator client of the beacon node
+    /// with the same index.
+    #[serde(default)]
+    pub proposer_nodes: usize,
+    pub validators_per_node: usize,
+}
+
+/// The fork schedule. Genesis is always post-merge, so Altair and Bellatrix must be at epoch `0`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForkEpochs {
+    pub altair: u64,
+    pub bellatrix: u64,
+    pub capella: Option<u64>,
+    pub deneb: Option<u64>,
+    pub electra: Option<u64>,
+}
+
+impl Default for ForkEpochs {
+    fn default() -> Self {
+        Self {
+            altair: 0,
+            bellatrix: 0,
+            capella: Some(1),
+            deneb: Some(2),
+            electra: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledFault {
+    /// The fault is injected half a slot after the start of this epoch.
+    pub epoch: u64,
+    #[serde(flatten)]
+    pub fault: Fault,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "fault", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Fault {
+    /// Stop the beacon node, as if its process had been killed.
+    KillNode { node: usize },
+    /// Start a new beacon node without validators, e.g. to test sync.
+    AddNode,
+    /// Make the execution node of the beacon node respond `SYNCING` to all payloads.
+    DisconnectExecutionLayer { node: usize },
+    /// Make the execution node of the beacon node respond `VALID` to all payloads.
+    ReconnectExecutionLayer { node: usize },
+    /// Delay each payload produced for the beacon node by `delay_ms`, delaying its proposals. A
+    /// delay of `0` removes the delay.
+    DelayProposer { node: usize, delay_ms: u64 },
+    /// Disconnect each group of beacon nodes from the beacon nodes in every other group. Nodes
+    /// which are not in any group remain connected to all nodes.
+    Partition { groups: Vec<Vec<usize>> },
+    /// Remove all partitions, leaving the latency and loss of each link unchanged.
+    HealPartition,
+    /// Add `latency_ms` of latency and a probability of `packet_loss` that each message is
+    /// delayed by a retransmission to the link between two beacon nodes, in both directions. A
+    /// latency and loss of `0` restore the link.
+    DegradeLink {
+        between: [usize; 2],
+        latency_ms: u64,
+        #[serde(default)]
+        packet_loss: f64,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledAssertion {
+    /// The assertion is checked half a slot after the start of this epoch.
+    pub epoch: u64,
+    #[serde(flatten)]
+    pub assertion: Assertion,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Assertion {
+    /// All running beacon nodes have finalized `finalized_epoch`.
+    Finalized { finalized_epoch: u64 },
+    /// A block has been produced at every slot since genesis.
+    FullBlockProduction,
+    /// Blobs have been produced for every block since the start of `from_epoch`.
+    FullBlobProduction { from_epoch: u64 },
+    /// Every sync aggregate since the start of `from_epoch` is full.
+    FullSyncAggregates { from_epoch: u64 },
+    /// The head of the beacon node is at or beyond the start of the epoch.
+    NodeSynced { node: usize },
+    /// At least `min_percent` of attestations since `from_epoch` were correct, as observed by
+    /// the beacon node.
+    AttestationPerformance {
+        node: usize,
+        from_epoch: u64,
+        min_percent: f64,
+    },
+}
+
+impl Scenario {
+    /// Read and validate the scenario in the YAML file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|e| format!("Unable to open scenario {:?}: {}", path, e))?;
+        let scenario: Self = serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse scenario {:?}: {}", path, e))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    #[cfg(test)]
+    fn from_yaml(yaml: &str) -> Result<Self, String> {
+        let scenario: Self = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// The total number of beacon nodes which are started during the scenario, excluding proposer
+    /// nodes.
+    pub fn beacon_node_count(&self) -> usize {
+        self.topology.nodes
+            + self
+                .faults
+                .iter()
+                .filter(|scheduled| matches!(scheduled.fault, Fault::AddNode))
+                .count()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.topology.nodes == 0 || self.topology.validators_per_node == 0 {
+            return Err("The topology must have at least one node and validator".into());
+        }
+        if self.topology.proposer_nodes > self.topology.nodes {
+            return Err("There cannot be more proposer nodes than nodes".into());
+        }
+
+        let forks = &self.forks;
+        if forks.altair != 0 || forks.bellatrix != 0 {
+            return Err("Altair and Bellatrix must be at epoch 0".into());
+        }
+        let fork_epochs = [forks.capella, forks.deneb, forks.electra];
+        if fork_epochs
+            .windows(2)
+            .any(|pair| pair[1].is_some() && (pair[0].is_none() || pair[1] < pair[0]))
+        {
+            return Err("Fork epochs must be scheduled in order".into());
+        }
+
+        let node_count = self.beacon_node_count();
+        let check_node = |node: usize| {
+            if node < node_count {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Node {} does not exist, there are {} nodes",
+                    node, node_count
+                ))
+            }
+        };
+
+        for ScheduledFault { epoch, fault } in &self.faults {
+            if *epoch > self.end_epoch {
+                return Err(format!("Fault {:?} is after the end epoch", fault));
+            }
+            match fault {
+                Fault::KillNode { node: 0 } => {
+                    return Err("The boot node (node 0) cannot be killed".into())
+                }
+                Fault::KillNode { node }
+                | Fault::DisconnectExecutionLayer { node }
+                | Fault::ReconnectExecutionLayer { node }
+                | Fault::DelayProposer { node, .. } => check_node(*node)?,
+                Fault::AddNode | Fault::HealPartition => {}
+                Fault::Partition { groups } => {
+                    if self.topology.proposer_nodes > 0 {
+                        return Err("Partitions are not supported with proposer nodes".into());
+                    }
+                    if groups.len() < 2 {
+                        return Err("A partition must have at least two groups".into());
+                    }
+                    let mut nodes = groups.iter().flatten().copied().collect::<Vec<_>>();
+                    nodes.iter().try_for_each(|node| check_node(*node))?;
+                    nodes.sort_unstable();
+                    if nodes.windows(2).any(|pair| pair[0] == pair[1]) {
+                        return Err("A node cannot be in more than one partition group".into());
+                    }
+                }
+                Fault::DegradeLink {
+                    between,
+                    packet_loss,
+                    ..
+                } => {
+                    between.iter().try_for_each(|node| check_node(*node))?;
+                    if between[0] == between[1] {
+                        return Err("A link must be between two different nodes".into());
+                    }
+                    if !(0.0..=1.0).contains(packet_loss) {
+                        return Err("Packet loss must be between 0 and 1".into());
+                    }
+                }
+            }
+        }
+
+        for ScheduledAssertion { epoch, assertion } in &self.assertions {
+            if *epoch > self.end_epoch {
+                return Err(format!("Assertion {:?} is after the end epoch", assertion));
+            }
+            match assertion {
+                Assertion::NodeSynced { node } | Assertion::AttestationPerformance { node, .. } => {
+                    check_node(*node)?
+                }
+                Assertion::Finalized { .. }
+                | Assertion::FullBlockProduction
+                | Assertion::FullBlobProduction { .. }
+                | Assertion::FullSyncAggregates { .. } => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn scenario_with_fault(fault: &str) -> String {
+        format!(
+            "name: test
+topology:
+  nodes: 3
+  validators_per_node: 8
+end_epoch: 8
+faults:
+  - epoch: 2
+{fault}
+"
+        )
+    }
+
+    #[test]
+    fn example_scenarios_are_valid() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let mut count = 0;
+        for entry in fs::read_dir(dir).unwrap() {
+            Scenario::from_file(&entry.unwrap().path()).unwrap();
+            count += 1;
+        }
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn parse_faults_and_assertions() {
+        let scenario = Scenario::from_yaml(
+            "name: test
+topology:
+  nodes: 2
+  validators_per_node: 8
+forks:
+  altair: 0
+  bellatrix: 0
+  capella: 1
+  deneb: 3
+  electra: null
+end_epoch: 8
+faults:
+  - epoch: 2
+    fault: add_node
+  - epoch: 3
+    fault: delay_proposer
+    node: 2
+    delay_ms: 500
+assertions:
+  - epoch: 8
+    check: node_synced
+    node: 2
+  - epoch: 8
+    check: full_block_production
+",
+        )
+        .unwrap();
+
+        assert_eq!(scenario.forks.deneb, Some(3));
+        assert_eq!(scenario.beacon_node_count(), 3);
+        assert!(matches!(scenario.faults[0].fault, Fault::AddNode));
+        assert!(matches!(
+            scenario.faults[1].fault,
+            Fault::DelayProposer {
+                node: 2,
+                delay_ms: 500
+            }
+        ));
+        assert!(matches!(
+            scenario.assertions[0].assertion,
+            Assertion::NodeSynced { node: 2 }
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_faults() {
+        for fault in [
+            "    fault: kill_node\n    node: 0",
+            "    fault: kill_node\n    node: 3",
+            "    fault: disconnect_execution_layer\n    node: 5",
+            "    fault: kill_node\n    node: 1\n    delay_ms: 5",
+            "    fault: partition\n    groups: [[0, 1]]",
+            "    fault: partition\n    groups: [[0, 1], [1, 2]]",
+            "    fault: partition\n    groups: [[0], [3]]",
+            "    fault: degrade_link\n    between: [1, 1]\n    latency_ms: 100",
+            "    fault: degrade_link\n    between: [0, 1]\n    latency_ms: 0\n    packet_loss: 2",
+            "    fault: unknown",
+        ] {
+            assert!(
+                Scenario::from_yaml(&scenario_with_fault(fault)).is_err(),
+                "{fault}"
+            );
+        }
+        for fault in [
+            "    fault: kill_node\n    node: 2",
+            "    fault: heal_partition",
+            "    fault: partition\n    groups: [[0, 1], [2]]",
+            "    fault: degrade_link\n    between: [0, 2]\n    latency_ms: 100",
+        ] {
+            Scenario::from_yaml(&scenario_with_fault(fault)).unwrap();
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use crate::local_network::LocalNetworkParams;
+use crate::retry::with_retry;
+use crate::scenario::{Assertion, Fault, Scenario, ScheduledAssertion, ScheduledFault};
+use crate::{checks, LocalNetwork};
+use clap::ArgMatches;
+use futures::future::{try_join_all, LocalBoxFuture};
+use futures::prelude::*;
+use node_test_rig::{
+    environment::{EnvironmentBuilder, LoggerConfig},
+    testing_validator_config, ClientConfig, MockExecutionConfig, ValidatorFiles,
+};
+use rayon::prelude::*;
+use std::cmp::max;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::{Epoch, EthSpec, MinimalEthSpec};
+
+const GENESIS_DELAY: u64 = 32;
+
+const SUGGESTED_FEE_RECIPIENT: [u8; 20] =
+    [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+pub fn run_scenario_sim(matches: &ArgMatches) -> Result<(), String> {
+    let scenario_path = matches
+        .get_one::<String>("scenario")
+        .map(PathBuf::from)
+        .expect("missing scenario");
+    let speed_up_factor = matches
+        .get_one::<String>("speed-up-factor")
+        .expect("missing speed-up-factor default")
+        .parse::<u64>()
+        .expect("missing speed-up-factor default");
+    let log_level = matches
+        .get_one::<String>("debug-level")
+        .expect("missing debug-level default");
+
+    let continue_after_checks = matches.get_flag("continue-after-checks");
+
+    let scenario = Scenario::from_file(&scenario_path)?;
+    let topology = &scenario.topology;
+
+    println!("Scenario Simulator:");
+    println!(" scenario: {}", scenario.name);
+    println!(" nodes: {}", topology.nodes);
+    println!(" proposer-nodes: {}", topology.proposer_nodes);
+    println!(" validators-per-node: {}", topology.validators_per_node);
+    println!(" faults: {}", scenario.faults.len());
+    println!(" assertions: {}", scenario.assertions.len());
+    println!(" speed-up-factor: {}", speed_up_factor);
+    println!(" continue-after-checks: {}", continue_after_checks);
+
+    // Generate the directories and keystores required for the validator clients.
+    let validators_per_node = topology.validators_per_node;
+    let validator_files = (0..topology.nodes)
+        .into_par_iter()
+        .map(|i| {
+            println!(
+                "Generating keystores for validator {} of {}",
+                i + 1,
+                topology.nodes
+            );
+
+            let indices =
+                (i * validators_per_node..(i + 1) * validators_per_node).collect::<Vec<_>>();
+            ValidatorFiles::with_keystores(&indices).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut env = EnvironmentBuilder::minimal()
+        .initialize_logger(LoggerConfig {
+            path: None,
+            debug_level: log_level.clone(),
+            logfile_debug_level: log_level.clone(),
+            log_format: None,
+            logfile_format: None,
+            log_color: false,
+            disable_log_timestamp: false,
+            max_log_size: 0,
+            max_log_number: 0,
+            compression: false,
+            is_restricted: true,
+            sse_logging: false,
+        })?
+        .multi_threaded_tokio_runtime()?
+        .build()?;
+
+    let spec = &mut env.eth2_config.spec;
+
+    let total_validator_count = validators_per_node * topology.nodes;
+    let genesis_delay = GENESIS_DELAY;
+
+    spec.seconds_per_slot /= speed_up_factor;
+    spec.seconds_per_slot = max(1, spec.seconds_per_slot);
+    spec.genesis_delay = genesis_delay;
+    spec.min_genesis_time = 0;
+    spec.min_genesis_active_validator_count = total_validator_count as u64;
+    spec.altair_fork_epoch = Some(Epoch::new(scenario.forks.altair));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(scenario.forks.bellatrix));
+    spec.capella_fork_epoch = scenario.forks.capella.map(Epoch::new);
+    spec.deneb_fork_epoch = scenario.forks.deneb.map(Epoch::new);
+    spec.electra_fork_epoch = scenario.forks.electra.map(Epoch::new);
+
+    let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+
+    let context = env.core_context();
+
+    let main_future = async {
+        /*
+         * Create a new `LocalNetwork` with one beacon node.
+         */
+        let max_retries = 3;
+        let (network, beacon_config, mock_execution_config) = with_retry(max_retries, || {
+            Box::pin(LocalNetwork::create_local_network(
+                None,
+                None,
+                LocalNetworkParams {
+                    validator_count: total_validator_count,
+                    node_count: topology.nodes,
+                    proposer_nodes: topology.proposer_nodes,
+                    genesis_delay,
+                },
+                context.clone(),
+            ))
+        })
+        .await?;
+
+        // Add nodes to the network.
+        for _ in 0..topology.nodes {
+            network
+                .add_beacon_node(beacon_config.clone(), mock_execution_config.clone(), false)
+                .await?;
+        }
+
+        for _ in 0..topology.proposer_nodes {
+            println!("Adding a proposer node");
+            network
+                .add_beacon_node(beacon_config.clone(), mock_execution_config.clone(), true)
+                .await?;
+        }
+
+        /*
+         * One by one, add validators to the network.
+         */
+        let executor = context.executor.clone();
+        for (i, files) in validator_files.into_iter().enumerate() {
+            let network_1 = network.clone();
+            executor.spawn(
+                async move {
+                    let mut validator_config = testing_validator_config();
+                    validator_config.fee_recipient = Some(SUGGESTED_FEE_RECIPIENT.into());
+                    println!("Adding validator client {}", i);
+                    network_1
+                        .add_validator_client(validator_config, i, files)
+                        .await
+                        .expect("should add validator");
+                },
+                "vc",
+            );
+        }
+
+        // Set all payloads as valid. Faults may change this for individual nodes.
+        network.execution_nodes.write().iter().for_each(|node| {
+            node.server.all_payloads_valid();
+        });
+
+        let duration_to_genesis = network.duration_to_genesis().await;
+        println!("Duration to genesis: {}", duration_to_genesis.as_secs());
+        sleep(duration_to_genesis).await;
+
+        /*
+         * Schedule all faults and assertions relative to genesis, failing as soon as any of them
+         * fails.
+         */
+        let runner = ScenarioRunner {
+            network: network.clone(),
+            beacon_config,
+            mock_execution_config,
+            slot_duration,
+            nodes: topology.nodes,
+            proposer_nodes: topology.proposer_nodes,
+        };
+        let faults = scenario
+            .faults
+            .iter()
+            .map(|scheduled| runner.inject_fault(scheduled));
+        let assertions = scenario
+            .assertions
+            .iter()
+            .map(|scheduled| runner.check_assertion(scheduled));
+        let end_epoch = Epoch::new(scenario.end_epoch);
+        let end = async move {
+            checks::epoch_delay(end_epoch, slot_duration, MinimalEthSpec::slots_per_epoch()).await;
+            Ok::<(), String>(())
+        }
+        .boxed_local();
+
+        try_join_all(faults.chain(assertions).chain(std::iter::once(end))).await?;
+
+        // The `final_future` either completes immediately or never completes, depending on the value
+        // of `continue_after_checks`.
+
+        if continue_after_checks {
+            future::pending::<()>().await;
+        }
+        /*
+         * End the simulation by dropping the network. This will kill all running beacon nodes and
+         * validator clients.
+         */
+        println!(
+            "Simulation complete. Finished with {} beacon nodes and {} validator clients",
+            network.beacon_node_count() + network.proposer_node_count(),
+            network.validator_client_count()
+        );
+
+        // Be explicit about dropping the network, as this kills all the nodes. This ensures
+        // all the checks have adequate time to pass.
+        drop(network);
+        Ok::<(), String>(())
+    };
+
+    let result = env.runtime().block_on(main_future);
+
+    env.fire_signal();
+    env.shutdown_on_idle();
+
+    result
+}
+
+/// Translates the faults and assertions of a scenario into operations on a `LocalNetwork`.
+struct ScenarioRunner<E: EthSpec> {
+    network: LocalNetwork<E>,
+    beacon_config: ClientConfig,
+    mock_execution_config: MockExecutionConfig,
+    slot_duration: Duration,
+    nodes: usize,
+    proposer_nodes: usize,
+}
+
+impl<E: EthSpec> ScenarioRunner<E> {
+    /// Returns the index of the execution node paired with the beacon node at `node`.
+    ///
+    /// Execution nodes are created in the same order as beacon nodes, which includes the proposer
+    /// nodes that are started between the initial nodes and any nodes added later.
+    fn execution_node(&self, node: usize) -> usize {
+        if node < self.nodes {
+            node
+        } else {
+            node + self.proposer_nodes
+        }
+    }
+
+    fn inject_fault(
+        &self,
+        scheduled: &ScheduledFault,
+    ) -> LocalBoxFuture<'static, Result<(), String>> {
+        let network = self.network.clone();
+        let beacon_config = self.beacon_config.clone();
+        let mock_execution_config = self.mock_execution_config.clone();
+        let slot_duration = self.slot_duration;
+        let epoch = Epoch::new(scheduled.epoch);
+        let fault = scheduled.fault.clone();
+        let execution_node = match fault {
+            Fault::DisconnectExecutionLayer { node }
+            | Fault::ReconnectExecutionLayer { node }
+            | Fault::DelayProposer { node, .. } => self.execution_node(node),
+            _ => 0,
+        };
+        let description = format!("Fault {:?} at epoch {}", fault, epoch);
+
+        async move {
+            checks::epoch_delay(epoch, slot_duration, E::slots_per_epoch()).await;
+            println!("Injecting {}", description);
+
+            match fault {
+                Fault::KillNode { node } => network.kill_beacon_node(node),
+                Fault::AddNode => {
+                    network
+                        .add_beacon_node(beacon_config, mock_execution_config, false)
+                        .await
+                }
+                Fault::DisconnectExecutionLayer { .. } => {
+                    checks::disconnect_from_execution_layer(network, execution_node).await
+                }
+                Fault::ReconnectExecutionLayer { .. } => {
+                    checks::reconnect_to_execution_layer(network, execution_node).await
+                }
+                Fault::DelayProposer { delay_ms, .. } => {
+                    let delay = (delay_ms > 0).then(|| Duration::from_millis(delay_ms));
+                    checks::delay_block_proposals(network, execution_node, delay).await
+                }
+                Fault::Partition { groups } => network.partition(&groups),
+                Fault::HealPartition => {
+                    network.heal_partitions();
+                    Ok(())
+                }
+                Fault::DegradeLink {
+                    between: [a, b],
+                    latency_ms,
+                    packet_loss,
+                } => network.set_link_conditions(
+                    a,
+                    b,
+                    Duration::from_millis(latency_ms),
+                    packet_loss,
+                ),
+            }
+            .map_err(|e| format!("{} failed: {}", description, e))
+        }
+        .boxed_local()
+    }
+
+    fn check_assertion(
+        &self,
+        scheduled: &ScheduledAssertion,
+    ) -> LocalBoxFuture<'static, Result<(), String>> {
+        let network = self.network.clone();
+        let slot_duration = self.slot_duration;
+        let slots_per_epoch = E::slots_per_epoch();
+        let epoch = Epoch::new(scheduled.epoch);
+        let slot = epoch.start_slot(slots_per_epoch);
+        let description = format!("Assertion {:?} at epoch {}", scheduled.assertion, epoch);
+
+        // Each check delays until `epoch` itself, relative to genesis.
+        let check = match scheduled.assertion {
+            Assertion::Finalized { finalized_epoch } => async move {
+                checks::epoch_delay(epoch, slot_duration, slots_per_epoch).await;
+                checks::verify_all_finalized_at(network, Epoch::new(finalized_epoch)).await
+            }
+            .boxed_local(),
+            Assertion::FullBlockProduction => {
+                checks::verify_full_block_production_up_to(network, slot, slot_duration)
+                    .boxed_local()
+            }
+            Assertion::FullBlobProduction { from_epoch } => {
+                checks::verify_full_blob_production_up_to(
+                    network,
+                    Epoch::new(from_epoch).start_slot(slots_per_epoch),
+                    slot,
+                    slot_duration,
+                )
+                .boxed_local()
+            }
+            Assertion::FullSyncAggregates { from_epoch } => {
+                checks::verify_full_sync_aggregates_up_to(
+                    network,
+                    Epoch::new(from_epoch).start_slot(slots_per_epoch),
+                    slot,
+                    slot_duration,
+                )
+                .boxed_local()
+            }
+            Assertion::NodeSynced { node } => {
+                checks::ensure_node_synced_up_to_slot(network, node, slot, slot_duration)
+                    .boxed_local()
+            }
+            Assertion::AttestationPerformance {
+                node,
+                from_epoch,
+                min_percent,
+            } => checks::check_attestation_correctness(
+                network,
+                from_epoch,
+                epoch.as_u64(),
+                slot_duration,
+                node,
+                min_percent,
+            )
+            .boxed_local(),
+        };
+
+        async move {
+            check.await?;
+            println!("Passed {}", description);
+            Ok(())
+        }
+        .map_err(|e: String| format!("{} failed: {}", description, e))
+        .boxed_local()
+    }
+}
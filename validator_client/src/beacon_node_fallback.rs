@@ -408,6 +408,17 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         n
     }
 
+    /// The first synced and ready candidate, which is the first to receive requests, or `None` if
+    /// no candidate is synced.
+    pub async fn first_synced_candidate(&self) -> Option<String> {
+        for candidate in &self.candidates {
+            if candidate.status(RequireSynced::Yes).await.is_ok() {
+                return Some(candidate.beacon_node.to_string());
+            }
+        }
+        None
+    }
+
     /// Loop through ALL candidates in `self.candidates` and update their sync status.
     ///
     /// It is possible for a node to return an unsynced status while continuing to serve
@@ -8,6 +8,7 @@ use crate::{
 use crate::{
     http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
+    webhook::{WebhookEvent, WebhookNotifier},
 };
 use bls::SignatureBytes;
 use environment::RuntimeContext;
@@ -59,6 +60,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     context: Option<RuntimeContext<E>>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    webhook: Option<Arc<WebhookNotifier>>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -71,6 +73,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             context: None,
             graffiti: None,
             graffiti_file: None,
+            webhook: None,
         }
     }
 
@@ -109,6 +112,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn webhook(mut self, webhook: Option<Arc<WebhookNotifier>>) -> Self {
+        self.webhook = webhook;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -127,6 +135,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 proposer_nodes: self.proposer_nodes,
                 graffiti: self.graffiti,
                 graffiti_file: self.graffiti_file,
+                webhook: self.webhook,
             }),
         })
     }
@@ -216,6 +225,7 @@ pub struct Inner<T, E: EthSpec> {
     context: RuntimeContext<E>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    webhook: Option<Arc<WebhookNotifier>>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -344,6 +354,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                     "block_slot" => ?slot,
                                     "info" => "block v3 proposal failed, this error may or may not result in a missed block"
                                 );
+                                service.notify_proposal_failed(slot, validator_pubkey, e);
                             }
                         }
                     },
@@ -386,6 +397,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                             "block_slot" => ?slot,
                                             "info" => "full block attempted after a blinded failure",
                                         );
+                                        service.notify_proposal_failed(
+                                            slot,
+                                            validator_pubkey,
+                                            format!("{:?}", e),
+                                        );
                                     }
                                 }
                                 Err(BlockError::Irrecoverable(e)) => {
@@ -398,7 +414,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                         "error" => ?e,
                                         "block_slot" => ?slot,
                                         "info" => "this error may or may not result in a missed block",
-                                    )
+                                    );
+                                    service.notify_proposal_failed(slot, validator_pubkey, e);
                                 }
                                 Ok(_) => {}
                             };
@@ -415,6 +432,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                                     "block_slot" => ?slot,
                                     "info" => "proposal did not use a builder",
                                 );
+                                service.notify_proposal_failed(
+                                    slot,
+                                    validator_pubkey,
+                                    format!("{:?}", e),
+                                );
                             }
                     },
                     "block service",
@@ -425,6 +447,17 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok(())
     }
 
+    /// Notifies the webhook, if any, that `validator_pubkey` failed to propose at `slot`.
+    fn notify_proposal_failed(&self, slot: Slot, validator_pubkey: PublicKeyBytes, error: String) {
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(WebhookEvent::ProposalFailed {
+                slot,
+                validator_pubkey,
+                error,
+            });
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     async fn sign_and_publish_block(
         &self,
@@ -1,4 +1,4 @@
-use clap::{builder::ArgPredicate, Arg, ArgAction, Command};
+use clap::{builder::ArgPredicate, Arg, ArgAction, ArgGroup, Command};
 use clap_utils::{get_color_style, FLAG_HEADER};
 
 pub fn cli_app() -> Command {
@@ -118,6 +118,20 @@ pub fn cli_app() -> Command {
             )
             .display_order(0)
         )
+        .arg(
+            Arg::new("reload-validator-definitions")
+            .long("reload-validator-definitions")
+            .action(ArgAction::SetTrue)
+            .help_heading(FLAG_HEADER)
+            .help(
+                "If present, watch the validator_definitions.yml file and reload it whenever it \
+                changes, enabling, disabling, adding or removing validators without a restart. \
+                Unless --disable-auto-discover is set, new keystores in the validators-dir and \
+                new passwords in the secrets-dir are also detected. Newly enabled validators are \
+                subject to doppelganger protection, if enabled."
+            )
+            .display_order(0)
+        )
         .arg(
             Arg::new("use-long-timeouts")
                 .long("use-long-timeouts")
@@ -350,6 +364,47 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("monitoring-file")
+                .long("monitoring-file")
+                .value_name("PATH")
+                .help("Enables the monitoring service for appending the metrics that would be sent to \
+                the monitoring-endpoint to a local file, one JSON object per line. The file is \
+                rotated once it reaches 10MB, keeping the 5 most recent rotated files.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-file-period")
+                .long("monitoring-file-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each write to \
+                       the monitoring-file. Default: 60s")
+                .requires("monitoring-file")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-remote-write")
+                .long("monitoring-remote-write")
+                .value_name("ADDRESS")
+                .help("Enables the monitoring service for pushing the metrics that would be sent to \
+                the monitoring-endpoint to a Prometheus remote-write endpoint, e.g. \
+                http://localhost:9090/api/v1/write. Metrics which cannot be sent are retried \
+                once the endpoint is available again.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("monitoring-remote-write-period")
+                .long("monitoring-remote-write-period")
+                .value_name("SECONDS")
+                .help("Defines how many seconds to wait between each message sent to \
+                       the monitoring-remote-write endpoint. Default: 60s")
+                .requires("monitoring-remote-write")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("enable-doppelganger-protection")
                 .long("enable-doppelganger-protection")
@@ -367,6 +422,41 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("doppelganger-peer-vcs")
+                .long("doppelganger-peer-vcs")
+                .value_name("NETWORK_ADDRESSES")
+                .help("Comma-separated addresses to the keymanager APIs of peer validator \
+                    clients. Doppelganger protection will not enable a Web3Signer validator \
+                    whilst it is listed by any of these peers, or whilst any of them cannot be \
+                    reached. Requires --doppelganger-peer-vc-tokens.")
+                .requires("enable-doppelganger-protection")
+                .requires("doppelganger-peer-vc-tokens")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("doppelganger-peer-vc-tokens")
+                .long("doppelganger-peer-vc-tokens")
+                .value_name("TOKEN_PATHS")
+                .help("Comma-separated paths to files containing the API tokens of the \
+                    validator clients given by --doppelganger-peer-vcs, in the same order.")
+                .requires("doppelganger-peer-vcs")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("doppelganger-lock-dir")
+                .long("doppelganger-lock-dir")
+                .value_name("DIR")
+                .help("A directory shared by all validator clients using the same Web3Signer. \
+                    Doppelganger protection will not enable a Web3Signer validator until it \
+                    holds an exclusive lock for that validator in this directory. The lock is \
+                    held until the validator is disabled or the process exits.")
+                .requires("enable-doppelganger-protection")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-proposals")
                 .long("builder-proposals")
@@ -438,6 +528,75 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposer-config-file")
+                .long("proposer-config-file")
+                .value_name("PATH")
+                .help("Path to a JSON file containing the fee recipient, gas limit, builder \
+                    preferences and graffiti for individual validators, as well as defaults \
+                    for all validators. The file is reloaded periodically. Values for a \
+                    specific validator take priority over the validator definitions, which \
+                    take priority over the defaults in this file.")
+                .conflicts_with("proposer-config-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("proposer-config-url")
+                .long("proposer-config-url")
+                .value_name("URL")
+                .help("URL from which to periodically fetch a proposer config. The format is the \
+                    same as for --proposer-config-file.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("proposer-config-refresh-interval")
+                .long("proposer-config-refresh-interval")
+                .value_name("SECONDS")
+                .help("The number of seconds between loads of the proposer config. Defaults \
+                    to 60 seconds.")
+                .requires("proposer-config-source")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .group(
+            ArgGroup::new("proposer-config-source")
+                .args(["proposer-config-file", "proposer-config-url"])
+                .multiple(false)
+        )
+        .arg(
+            Arg::new("webhook-url")
+                .long("webhook-url")
+                .value_name("URL")
+                .help("URL to which JSON notifications are POSTed when a local validator is \
+                    about to propose, fails to propose or misses attestations, when a \
+                    doppelganger is detected and when the validator client switches to a \
+                    different beacon node.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("webhook-events")
+                .long("webhook-events")
+                .value_name("EVENTS")
+                .help("Comma-separated list of events to send to the webhook. Possible values \
+                    are: proposal-upcoming, proposal-failed, attestation-missed, \
+                    doppelganger-detected, beacon-node-switched. Defaults to all events.")
+                .requires("webhook-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("webhook-max-retries")
+                .long("webhook-max-retries")
+                .value_name("INTEGER")
+                .help("The number of times a failed webhook notification is retried, with an \
+                    exponential backoff, before it is dropped. Defaults to 3.")
+                .requires("webhook-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("prefer-builder-proposals")
                 .long("prefer-builder-proposals")
@@ -1,5 +1,7 @@
 use crate::beacon_node_fallback::ApiTopic;
 use crate::graffiti_file::GraffitiFile;
+use crate::proposer_config::{ProposerConfigSource, DEFAULT_REFRESH_INTERVAL};
+use crate::webhook::{WebhookConfig, WebhookEventKind, DEFAULT_MAX_RETRIES};
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -38,6 +40,8 @@ pub struct Config {
     pub allow_unsynced_beacon_node: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
+    /// If true, reload the validator definitions whenever they change on disk.
+    pub reload_validator_definitions: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
     /// If true, use longer timeouts for requests made to the beacon node.
@@ -57,6 +61,14 @@ pub struct Config {
     /// If true, enable functionality that monitors the network for attestations or proposals from
     /// any of the validators managed by this client before starting up.
     pub enable_doppelganger_protection: bool,
+    /// The keymanager API endpoints of peer validator clients. Web3Signer validators will not be
+    /// enabled by doppelganger protection whilst they are enabled on any of these peers.
+    pub doppelganger_peer_vcs: Vec<SensitiveUrl>,
+    /// Paths to the API tokens of `doppelganger_peer_vcs`, in the same order.
+    pub doppelganger_peer_vc_tokens: Vec<PathBuf>,
+    /// A directory shared between validator clients in which doppelganger protection holds a lock
+    /// for each enabled Web3Signer validator.
+    pub doppelganger_lock_dir: Option<PathBuf>,
     /// If true, then we publish validator specific metrics (e.g next attestation duty slot)
     /// for all our managed validators.
     /// Note: We publish validator specific metrics for low validator counts without this flag
@@ -89,6 +101,12 @@ pub struct Config {
     pub distributed: bool,
     pub web3_signer_keep_alive_timeout: Option<Duration>,
     pub web3_signer_max_idle_connections: Option<usize>,
+    /// A file or URL from which to periodically load the proposer config.
+    pub proposer_config: Option<ProposerConfigSource>,
+    /// The interval between loads of the proposer config.
+    pub proposer_config_refresh_interval: Duration,
+    /// A webhook to which notifications about duties and beacon node health are sent.
+    pub webhook: Option<WebhookConfig>,
 }
 
 impl Default for Config {
@@ -112,6 +130,7 @@ impl Default for Config {
             proposer_nodes: Vec::new(),
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
+            reload_validator_definitions: false,
             init_slashing_protection: false,
             use_long_timeouts: false,
             graffiti: None,
@@ -121,6 +140,9 @@ impl Default for Config {
             http_metrics: <_>::default(),
             monitoring_api: None,
             enable_doppelganger_protection: false,
+            doppelganger_peer_vcs: vec![],
+            doppelganger_peer_vc_tokens: vec![],
+            doppelganger_lock_dir: None,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
             builder_proposals: false,
@@ -136,6 +158,9 @@ impl Default for Config {
             distributed: false,
             web3_signer_keep_alive_timeout: DEFAULT_WEB3SIGNER_KEEP_ALIVE,
             web3_signer_max_idle_connections: None,
+            proposer_config: None,
+            proposer_config_refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            webhook: None,
         }
     }
 }
@@ -196,6 +221,7 @@ impl Config {
         }
 
         config.disable_auto_discover = cli_args.get_flag("disable-auto-discover");
+        config.reload_validator_definitions = cli_args.get_flag("reload-validator-definitions");
         config.init_slashing_protection = cli_args.get_flag("init-slashing-protection");
         config.use_long_timeouts = cli_args.get_flag("use-long-timeouts");
 
@@ -361,14 +387,36 @@ impl Config {
         /*
          * Explorer metrics
          */
-        if let Some(monitoring_endpoint) = cli_args.get_one::<String>("monitoring-endpoint") {
+        let monitoring_endpoint = parse_optional(cli_args, "monitoring-endpoint")?;
+        let monitoring_file = parse_optional(cli_args, "monitoring-file")?
+            .map(|path| {
+                Ok::<_, String>(monitoring_api::FileConfig {
+                    path,
+                    update_period_secs: parse_optional(cli_args, "monitoring-file-period")?,
+                })
+            })
+            .transpose()?;
+        let monitoring_remote_write = parse_optional(cli_args, "monitoring-remote-write")?
+            .map(|endpoint| {
+                Ok::<_, String>(monitoring_api::RemoteWriteConfig {
+                    endpoint,
+                    update_period_secs: parse_optional(cli_args, "monitoring-remote-write-period")?,
+                })
+            })
+            .transpose()?;
+        if monitoring_endpoint.is_some()
+            || monitoring_file.is_some()
+            || monitoring_remote_write.is_some()
+        {
             let update_period_secs =
                 clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
             config.monitoring_api = Some(monitoring_api::Config {
                 db_path: None,
                 freezer_db_path: None,
                 update_period_secs,
-                monitoring_endpoint: monitoring_endpoint.to_string(),
+                monitoring_endpoint,
+                file: monitoring_file,
+                remote_write: monitoring_remote_write,
             });
         }
 
@@ -376,6 +424,28 @@ impl Config {
             config.enable_doppelganger_protection = true;
         }
 
+        if let Some(peer_vcs) = parse_optional::<String>(cli_args, "doppelganger-peer-vcs")? {
+            config.doppelganger_peer_vcs = peer_vcs
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Unable to parse peer validator client URL: {:?}", e))?;
+        }
+
+        if let Some(tokens) = parse_optional::<String>(cli_args, "doppelganger-peer-vc-tokens")? {
+            config.doppelganger_peer_vc_tokens = tokens.split(',').map(PathBuf::from).collect();
+        }
+
+        if config.doppelganger_peer_vcs.len() != config.doppelganger_peer_vc_tokens.len() {
+            return Err(
+                "--doppelganger-peer-vc-tokens must contain one token path for each of the \
+                 --doppelganger-peer-vcs"
+                    .into(),
+            );
+        }
+
+        config.doppelganger_lock_dir = parse_optional(cli_args, "doppelganger-lock-dir")?;
+
         if cli_args.get_flag("builder-proposals") {
             config.builder_proposals = true;
         }
@@ -409,6 +479,45 @@ impl Config {
 
         config.builder_boost_factor = parse_optional(cli_args, "builder-boost-factor")?;
 
+        if let Some(path) = parse_optional(cli_args, "proposer-config-file")? {
+            config.proposer_config = Some(ProposerConfigSource::File(path));
+        } else if let Some(url) = cli_args.get_one::<String>("proposer-config-url") {
+            config.proposer_config = Some(ProposerConfigSource::Url(
+                SensitiveUrl::parse(url)
+                    .map_err(|e| format!("Unable to parse proposer config URL: {:?}", e))?,
+            ));
+        }
+
+        if let Some(seconds) = parse_optional::<u64>(cli_args, "proposer-config-refresh-interval")?
+        {
+            if seconds == 0 {
+                return Err("proposer-config-refresh-interval cannot be 0".to_string());
+            }
+            config.proposer_config_refresh_interval = Duration::from_secs(seconds);
+        }
+
+        if let Some(url) = cli_args.get_one::<String>("webhook-url") {
+            let events = if let Some(events) = cli_args.get_one::<String>("webhook-events") {
+                events
+                    .split(',')
+                    .map(|e| {
+                        e.trim()
+                            .parse::<WebhookEventKind>()
+                            .map_err(|_| format!("Unknown webhook event: {e}"))
+                    })
+                    .collect::<Result<_, _>>()?
+            } else {
+                WebhookEventKind::all()
+            };
+            config.webhook = Some(WebhookConfig {
+                url: SensitiveUrl::parse(url)
+                    .map_err(|e| format!("Unable to parse webhook URL: {:?}", e))?,
+                events,
+                max_retries: parse_optional(cli_args, "webhook-max-retries")?
+                    .unwrap_or(DEFAULT_MAX_RETRIES),
+            });
+        }
+
         config.enable_latency_measurement_service =
             !cli_args.get_flag("disable-latency-measurement-service");
 
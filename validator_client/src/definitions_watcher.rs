@@ -0,0 +1,139 @@
+//! Watches the `validator_definitions.yml` file (and, if auto-discovery is enabled, the validators
+//! and secrets directories) for changes, reloading the validator definitions whenever they occur.
+//!
+//! This allows an external configuration-management system to add, remove, enable and disable
+//! validators without restarting the validator client.
+//!
+//! Changes are detected by polling the modification time of each path once per slot. Writes made
+//! by the validator client itself (e.g., via the keymanager API) will also be detected, but the
+//! resulting reload is a no-op since the definitions in memory will already match those on disk.
+
+use crate::http_metrics::metrics;
+use crate::ValidatorStore;
+use account_utils::validator_definitions::CONFIG_FILENAME;
+use environment::RuntimeContext;
+use slog::{error, info};
+use slot_clock::SlotClock;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The watcher will run 1/2 of the way through the slot, between attestation production and
+/// aggregation.
+pub const SLOT_DELAY_MULTIPLIER: u32 = 1;
+pub const SLOT_DELAY_DENOMINATOR: u32 = 2;
+
+/// The modification times of the paths being watched.
+///
+/// A `None` value indicates that the path does not exist or its modification time could not be
+/// read.
+#[derive(PartialEq)]
+struct Fingerprint(Vec<Option<SystemTime>>);
+
+impl Fingerprint {
+    fn new(paths: &[PathBuf]) -> Self {
+        Self(paths.iter().map(|path| modified(path)).collect())
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Starts a service that reloads the validator definitions whenever they change on disk.
+pub fn start_definitions_watcher<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    slot_clock: T,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    validator_dir: PathBuf,
+    secrets_dir: Option<PathBuf>,
+) {
+    let log = context.log().clone();
+
+    // New keystores and password files are detected via the modification times of their parent
+    // directories.
+    let mut paths = vec![validator_dir.join(CONFIG_FILENAME)];
+    if let Some(secrets_dir) = secrets_dir {
+        paths.push(validator_dir);
+        paths.push(secrets_dir);
+    }
+
+    let future = async move {
+        let mut fingerprint = Fingerprint::new(&paths);
+
+        loop {
+            let sleep_time = slot_clock
+                .duration_to_next_slot()
+                .map(|next_slot| {
+                    next_slot
+                        + (slot_clock.slot_duration() / SLOT_DELAY_DENOMINATOR)
+                            * SLOT_DELAY_MULTIPLIER
+                })
+                .unwrap_or_else(|| slot_clock.slot_duration());
+
+            sleep(sleep_time).await;
+
+            let new_fingerprint = Fingerprint::new(&paths);
+            if new_fingerprint == fingerprint {
+                continue;
+            }
+
+            match validator_store.reload_validator_definitions().await {
+                Ok(diff) => {
+                    // Only record the new fingerprint once the reload has succeeded, so that a
+                    // failed reload is retried at the next slot.
+                    fingerprint = new_fingerprint;
+                    if diff.is_empty() {
+                        continue;
+                    }
+
+                    metrics::inc_counter_vec(
+                        &metrics::VALIDATOR_DEFINITIONS_RELOADS_TOTAL,
+                        &[metrics::SUCCESS],
+                    );
+                    for (change, pubkeys) in [
+                        ("added", &diff.added),
+                        ("removed", &diff.removed),
+                        ("enabled", &diff.enabled),
+                        ("disabled", &diff.disabled),
+                        ("updated", &diff.updated),
+                    ] {
+                        metrics::inc_counter_vec_by(
+                            &metrics::VALIDATOR_DEFINITIONS_CHANGES_TOTAL,
+                            &[change],
+                            pubkeys.len() as u64,
+                        );
+                    }
+                    info!(
+                        log,
+                        "Reloaded validator definitions";
+                        "updated" => ?diff.updated,
+                        "disabled" => ?diff.disabled,
+                        "enabled" => ?diff.enabled,
+                        "removed" => ?diff.removed,
+                        "added" => ?diff.added,
+                    );
+                }
+                Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::VALIDATOR_DEFINITIONS_RELOADS_TOTAL,
+                        &[metrics::ERROR],
+                    );
+                    error!(
+                        log,
+                        "Failed to reload validator definitions";
+                        "msg" => "the previous definitions remain in use where possible",
+                        "error" => e,
+                    );
+                }
+            }
+        }
+    };
+
+    context.executor.spawn(future, "definitions_watcher");
+}
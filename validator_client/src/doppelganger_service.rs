@@ -28,22 +28,36 @@
 //! detect each other.
 //!
 //! Doppelganger protection is a best-effort, last-line-of-defence mitigation. Do not rely upon it.
+//!
+//! ## Web3Signer validators
+//!
+//! Validators which sign via Web3Signer may additionally be subject to the checks in the `remote`
+//! module, which consult peer validator clients and a shared lock directory before a key is
+//! enabled. See that module for details.
+
+pub mod remote;
 
 use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
 use crate::validator_store::ValidatorStore;
+use crate::webhook::{WebhookEvent, WebhookNotifier};
 use crate::OfflineOnFailure;
 use environment::RuntimeContext;
 use eth2::types::LivenessResponseData;
 use parking_lot::RwLock;
-use slog::{crit, error, info, Logger};
+use remote::{RemoteCheckError, RemoteChecks};
+use slog::{crit, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 use task_executor::ShutdownReason;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 
+/// The maximum time to wait for the webhook to be notified of a doppelganger before shutting down.
+const WEBHOOK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// A wrapper around `PublicKeyBytes` which encodes information about the status of a validator
 /// pubkey with regards to doppelganger protection.
 #[derive(Debug, PartialEq)]
@@ -270,6 +284,12 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
 
 pub struct DoppelgangerService {
     doppelganger_states: RwLock<HashMap<PublicKeyBytes, DoppelgangerState>>,
+    /// Validators which must pass the `remote_checks` before they are permitted to sign.
+    ///
+    /// The value is `true` if a check has previously failed for that validator.
+    remote_check_states: RwLock<HashMap<PublicKeyBytes, bool>>,
+    remote_checks: Option<RemoteChecks>,
+    webhook: Option<Arc<WebhookNotifier>>,
     log: Logger,
 }
 
@@ -277,10 +297,26 @@ impl DoppelgangerService {
     pub fn new(log: Logger) -> Self {
         Self {
             doppelganger_states: <_>::default(),
+            remote_check_states: <_>::default(),
+            remote_checks: None,
+            webhook: None,
             log,
         }
     }
 
+    /// Require Web3Signer validators to pass the given `remote_checks` before they are permitted
+    /// to sign.
+    pub fn with_remote_checks(mut self, remote_checks: RemoteChecks) -> Self {
+        self.remote_checks = Some(remote_checks);
+        self
+    }
+
+    /// Notify the `webhook` before shutting down when a doppelganger is detected.
+    pub fn with_webhook(mut self, webhook: Arc<WebhookNotifier>) -> Self {
+        self.webhook = Some(webhook);
+        self
+    }
+
     /// Starts a reoccurring future which will try to keep the doppelganger service updated each
     /// slot.
     pub fn start_update_service<E: EthSpec, T: 'static + SlotClock>(
@@ -291,7 +327,8 @@ impl DoppelgangerService {
         slot_clock: T,
     ) -> Result<(), String> {
         // Define the `get_index` function as one that uses the validator store.
-        let get_index = move |pubkey| validator_store.validator_index(&pubkey);
+        let index_validator_store = validator_store.clone();
+        let get_index = move |pubkey| index_validator_store.validator_index(&pubkey);
 
         // Define the `get_liveness` function as one that queries the beacon node API.
         let log = service.log.clone();
@@ -304,18 +341,42 @@ impl DoppelgangerService {
             )
         };
 
-        let mut shutdown_sender = context.executor.shutdown_sender();
+        let shutdown_sender = context.executor.shutdown_sender();
         let log = service.log.clone();
-        let mut shutdown_func = move || {
-            if let Err(e) =
-                shutdown_sender.try_send(ShutdownReason::Failure("Doppelganger detected."))
-            {
-                crit!(
-                    log,
-                    "Failed to send shutdown signal";
-                    "msg" => "terminate this process immediately",
-                    "error" => ?e
+        let webhook = service.webhook.clone();
+        let executor = context.executor.clone();
+        let mut shutdown_func = move |violators: &HashSet<u64>| {
+            let mut shutdown_sender = shutdown_sender.clone();
+            let log = log.clone();
+            let mut shutdown = move || {
+                if let Err(e) =
+                    shutdown_sender.try_send(ShutdownReason::Failure("Doppelganger detected."))
+                {
+                    crit!(
+                        log,
+                        "Failed to send shutdown signal";
+                        "msg" => "terminate this process immediately",
+                        "error" => ?e
+                    );
+                }
+            };
+
+            if let Some(webhook) = webhook.clone() {
+                let mut validator_indices = violators.iter().copied().collect::<Vec<_>>();
+                validator_indices.sort_unstable();
+                // Shutting down would discard a queued notification, so deliver it first. Signing
+                // was disabled for every validator before this function was called, so the delay
+                // cannot result in any messages being signed.
+                executor.spawn(
+                    async move {
+                        let event = WebhookEvent::DoppelgangerDetected { validator_indices };
+                        let _ = timeout(WEBHOOK_SHUTDOWN_TIMEOUT, webhook.send(event)).await;
+                        shutdown();
+                    },
+                    "doppelganger_webhook",
                 );
+            } else {
+                shutdown();
             }
         };
 
@@ -342,6 +403,20 @@ impl DoppelgangerService {
                     }
 
                     if let Some(slot) = slot_clock.now() {
+                        if let Err(e) = service
+                            .run_remote_checks::<E, _>(
+                                &slot_clock,
+                                &validator_store.web3signer_voting_pubkeys(),
+                            )
+                            .await
+                        {
+                            error!(
+                                service.log,
+                                "Error during remote doppelganger checks";
+                                "error" => ?e
+                            );
+                        }
+
                         if let Err(e) = service
                             .detect_doppelgangers::<E, _, _, _, _>(
                                 slot,
@@ -367,11 +442,13 @@ impl DoppelgangerService {
 
     /// Returns the current status of the `validator` in the doppelganger protection process.
     pub fn validator_status(&self, validator: PublicKeyBytes) -> DoppelgangerStatus {
+        let remote_checks_pending = self.remote_check_states.read().contains_key(&validator);
+
         self.doppelganger_states
             .read()
             .get(&validator)
             .map(|v| {
-                if v.requires_further_checks() {
+                if v.requires_further_checks() || remote_checks_pending {
                     DoppelgangerStatus::SigningDisabled(validator)
                 } else {
                     DoppelgangerStatus::SigningEnabled(validator)
@@ -428,6 +505,112 @@ impl DoppelgangerService {
         Ok(())
     }
 
+    /// Require a validator which has been registered via `Self::register_new_validator` to pass
+    /// the remote checks before it is permitted to sign.
+    ///
+    /// This function has no effect if remote checks are not configured.
+    pub fn register_remote_checks(&self, validator: PublicKeyBytes) {
+        if self.remote_checks.is_some() {
+            self.remote_check_states.write().insert(validator, false);
+        }
+    }
+
+    /// Run the remote checks for all validators in `active` which have not yet passed them.
+    ///
+    /// `active` should contain all enabled Web3Signer validators. Locks held for validators not
+    /// in `active` are released, and those validators must pass the checks again before they are
+    /// next permitted to sign.
+    ///
+    /// If a validator passes the checks after having previously failed them, its liveness-based
+    /// doppelganger detection is restarted, since it may have been signing elsewhere until now.
+    async fn run_remote_checks<E: EthSpec, T: SlotClock>(
+        &self,
+        slot_clock: &T,
+        active: &HashSet<PublicKeyBytes>,
+    ) -> Result<(), String> {
+        let Some(remote_checks) = &self.remote_checks else {
+            return Ok(());
+        };
+
+        let released = remote_checks.release_inactive_locks(active);
+        if !released.is_empty() {
+            let mut remote_check_states = self.remote_check_states.write();
+            for pubkey in released {
+                remote_check_states.insert(pubkey, true);
+            }
+        }
+
+        let pending = self
+            .remote_check_states
+            .read()
+            .keys()
+            .filter(|pubkey| active.contains(pubkey))
+            .copied()
+            .collect::<Vec<_>>();
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        for (pubkey, result) in remote_checks.check(&pending).await {
+            match result {
+                Ok(()) => {
+                    let previously_failed = self
+                        .remote_check_states
+                        .write()
+                        .remove(&pubkey)
+                        .unwrap_or(false);
+
+                    if previously_failed {
+                        self.register_new_validator::<E, _>(pubkey, slot_clock)?;
+                    }
+
+                    info!(
+                        self.log,
+                        "Remote doppelganger checks passed";
+                        "restarted_detection" => previously_failed,
+                        "pubkey" => ?pubkey,
+                    );
+                }
+                Err(e) => {
+                    if let Some(previously_failed) =
+                        self.remote_check_states.write().get_mut(&pubkey)
+                    {
+                        *previously_failed = true;
+                    }
+
+                    match e {
+                        RemoteCheckError::EnabledOnPeer(url) => crit!(
+                            self.log,
+                            "Validator is enabled on a peer validator client";
+                            "msg" => "this validator will remain disabled until it is removed \
+                                from the peer",
+                            "peer" => url,
+                            "pubkey" => ?pubkey,
+                        ),
+                        RemoteCheckError::LockHeld(path) => crit!(
+                            self.log,
+                            "Validator is locked by another validator client";
+                            "msg" => "this validator will remain disabled until the lock is \
+                                released",
+                            "path" => ?path,
+                            "pubkey" => ?pubkey,
+                        ),
+                        e => warn!(
+                            self.log,
+                            "Unable to complete remote doppelganger checks";
+                            "msg" => "this validator will remain disabled until the checks pass",
+                            "error" => ?e,
+                            "pubkey" => ?pubkey,
+                        ),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Contact the beacon node and try to detect if there are any doppelgangers, updating the state
     /// of `self`.
     ///
@@ -448,7 +631,7 @@ impl DoppelgangerService {
         I: Fn(PublicKeyBytes) -> Option<u64>,
         L: Fn(Epoch, Vec<u64>) -> F,
         F: Future<Output = LivenessResponses>,
-        S: FnMut(),
+        S: FnMut(&HashSet<u64>),
     {
         // Get all validators with active doppelganger protection.
         let indices_map = self.compute_detection_indices_map(get_index);
@@ -482,12 +665,21 @@ impl DoppelgangerService {
     where
         F: Fn(PublicKeyBytes) -> Option<u64>,
     {
+        // Validators awaiting remote checks are excluded, otherwise a key which is still being
+        // moved from another validator client would be detected as a doppelganger.
+        let remote_checks_pending = self
+            .remote_check_states
+            .read()
+            .keys()
+            .copied()
+            .collect::<HashSet<_>>();
+
         let detection_pubkeys = self
             .doppelganger_states
             .read()
             .iter()
             .filter_map(|(pubkey, state)| {
-                if state.requires_further_checks() {
+                if state.requires_further_checks() && !remote_checks_pending.contains(pubkey) {
                     Some(*pubkey)
                 } else {
                     None
@@ -510,6 +702,17 @@ impl DoppelgangerService {
         indices_map
     }
 
+    /// Disable signing for every validator, including those which have already completed
+    /// doppelganger detection.
+    ///
+    /// This is used once a doppelganger has been detected, so that no validator signs any messages
+    /// whilst the VC is shutting down.
+    fn disable_all_validators(&self) {
+        for state in self.doppelganger_states.write().values_mut() {
+            state.remaining_epochs = u64::MAX;
+        }
+    }
+
     /// Process the liveness responses from the BN, potentially updating doppelganger states or
     /// shutting down the VC.
     fn process_liveness_responses<E: EthSpec, S>(
@@ -520,7 +723,7 @@ impl DoppelgangerService {
         shutdown_func: &mut S,
     ) -> Result<(), String>
     where
-        S: FnMut(),
+        S: FnMut(&HashSet<u64>),
     {
         let request_epoch = request_slot.epoch(E::slots_per_epoch());
         let previous_epoch = request_epoch.saturating_sub(1_u64);
@@ -677,7 +880,8 @@ impl DoppelgangerService {
 
         // Attempt to shutdown the validator client if there are any detected duplicate validators.
         if violators_exist {
-            shutdown_func();
+            self.disable_all_validators();
+            shutdown_func(&violators);
         }
 
         Ok(())
@@ -1016,7 +1220,7 @@ mod test {
         {
             // Create a simulated shutdown sender.
             let mut did_shutdown = false;
-            let mut shutdown_func = || did_shutdown = true;
+            let mut shutdown_func = |_: &HashSet<u64>| did_shutdown = true;
 
             // Create a simulated validator store that can resolve pubkeys to indices.
             let pubkey_to_index = self.pubkey_to_index_map();
@@ -1398,6 +1602,64 @@ mod test {
             });
     }
 
+    #[test]
+    fn detection_disables_enabled_validators() {
+        let early_epoch = genesis_epoch() + 42;
+        let early_slot = early_epoch.start_slot(E::slots_per_epoch());
+        let early_activation_slot =
+            (early_epoch + DEFAULT_REMAINING_DETECTION_EPOCHS + 1).end_slot(E::slots_per_epoch());
+
+        let early_validators: Vec<u64> = (0..DEFAULT_VALIDATORS as u64 / 2).collect();
+        let late_validators: Vec<u64> =
+            (DEFAULT_VALIDATORS as u64 / 2..DEFAULT_VALIDATORS as u64).collect();
+
+        let mut scenario = TestBuilder::default()
+            .build()
+            .set_slot(early_slot)
+            .register_validators(&early_validators);
+
+        // Allow the early validators to complete doppelganger detection.
+        for slot in early_slot.as_u64()..=early_activation_slot.as_u64() {
+            scenario = scenario.simulate_detect_doppelgangers(
+                Slot::new(slot),
+                ShouldShutdown::No,
+                |current_epoch, detection_indices: Vec<_>| {
+                    future::ready(get_false_responses(current_epoch, &detection_indices))
+                },
+            );
+        }
+        for &index in &early_validators {
+            let pubkey = scenario.validators[index as usize];
+            assert_eq!(
+                scenario.doppelganger.validator_status(pubkey),
+                DoppelgangerStatus::SigningEnabled(pubkey)
+            );
+        }
+
+        // A doppelganger of one of the late validators should disable every validator, not only
+        // those which are still being checked.
+        let late_epoch = early_activation_slot.epoch(E::slots_per_epoch()) + 1;
+        let detection_slot = (late_epoch + 1).start_slot(E::slots_per_epoch());
+        let doppelganger_index = late_validators[0];
+        scenario
+            .set_slot(late_epoch.start_slot(E::slots_per_epoch()))
+            .register_validators(&late_validators)
+            .set_slot(detection_slot)
+            .simulate_detect_doppelgangers(
+                detection_slot,
+                ShouldShutdown::Yes,
+                |current_epoch, detection_indices: Vec<_>| {
+                    assert!(detection_indices.contains(&doppelganger_index));
+                    let mut responses = get_false_responses(current_epoch, &detection_indices);
+                    for response in &mut responses.current_epoch_responses {
+                        response.is_live = response.index == doppelganger_index;
+                    }
+                    future::ready(responses)
+                },
+            )
+            .assert_all_disabled();
+    }
+
     #[test]
     fn staggered_entry() {
         let early_epoch = genesis_epoch() + 42;
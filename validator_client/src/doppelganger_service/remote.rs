@@ -0,0 +1,236 @@
+//! Additional doppelganger checks for validators which sign via Web3Signer.
+//!
+//! When a fleet of validator clients shares a Web3Signer, moving a key from one validator client
+//! to another is a matter of adding it to one and removing it from the other. If the operator adds
+//! the key to the new host before removing it from the old one, liveness-based doppelganger
+//! detection may not notice in time (e.g., if the key has no duties during the detection period).
+//!
+//! These checks close that gap. A Web3Signer validator is not permitted to sign until:
+//!
+//! - None of the configured peer validator clients lists the key via the standard keymanager API
+//!   (`GET /eth/v1/remotekeys` and `GET /eth/v1/keystores`).
+//! - An exclusive lock has been obtained on `<lock_dir>/<pubkey>.lock`, if a lock directory is
+//!   configured. The lock is held for as long as the key is enabled in this validator client and
+//!   is released automatically by the OS if the process exits.
+//!
+//! If a check fails, or cannot be completed because a peer is offline, the validator remains
+//! disabled and the checks are retried in the next slot.
+
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
+use futures::future::join_all;
+use lockfile::{Lockfile, LockfileError};
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::PublicKeyBytes;
+
+/// The timeout applied to each request to a peer validator client.
+const PEER_VALIDATOR_CLIENT_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// The reason a validator did not pass the remote doppelganger checks.
+#[derive(Debug)]
+pub enum RemoteCheckError {
+    /// The validator is enabled on the peer validator client at the given URL.
+    EnabledOnPeer(String),
+    /// The peer validator client at the given URL could not be queried.
+    PeerUnavailable(String, String),
+    /// The lock for the validator is held by another process.
+    LockHeld(PathBuf),
+    /// The lock for the validator could not be obtained for some other reason.
+    LockError(LockfileError),
+}
+
+struct PeerValidatorClient {
+    url: SensitiveUrl,
+    client: ValidatorClientHttpClient,
+}
+
+pub struct RemoteChecks {
+    peers: Vec<PeerValidatorClient>,
+    lock_dir: Option<PathBuf>,
+    locks: Mutex<HashMap<PublicKeyBytes, Lockfile>>,
+}
+
+impl RemoteChecks {
+    /// Create a new instance, reading the API token for each of `peer_urls` from the file at the
+    /// corresponding index of `peer_token_paths`.
+    pub fn new(
+        peer_urls: &[SensitiveUrl],
+        peer_token_paths: &[PathBuf],
+        lock_dir: Option<PathBuf>,
+    ) -> Result<Self, String> {
+        if peer_urls.len() != peer_token_paths.len() {
+            return Err(format!(
+                "{} peer validator clients were supplied with {} API tokens",
+                peer_urls.len(),
+                peer_token_paths.len()
+            ));
+        }
+
+        let peers = peer_urls
+            .iter()
+            .zip(peer_token_paths)
+            .map(|(url, token_path)| {
+                let secret = ValidatorClientHttpClient::load_api_token_from_file(token_path)
+                    .map_err(|e| format!("Unable to load API token for {}: {:?}", url, e))?;
+                let http_client = reqwest::Client::builder()
+                    .timeout(PEER_VALIDATOR_CLIENT_TIMEOUT)
+                    .build()
+                    .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+                let client = ValidatorClientHttpClient::from_components(
+                    url.clone(),
+                    http_client,
+                    secret.as_str().to_string(),
+                )
+                .map_err(|e| format!("Invalid API token for {}: {:?}", url, e))?;
+                Ok(PeerValidatorClient {
+                    url: url.clone(),
+                    client,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if let Some(lock_dir) = &lock_dir {
+            fs::create_dir_all(lock_dir).map_err(|e| {
+                format!(
+                    "Unable to create doppelganger lock directory {:?}: {:?}",
+                    lock_dir, e
+                )
+            })?;
+        }
+
+        Ok(Self {
+            peers,
+            lock_dir,
+            locks: <_>::default(),
+        })
+    }
+
+    /// Run the checks for each of `pubkeys`, returning a result for each.
+    ///
+    /// Each peer validator client is queried once, regardless of the number of `pubkeys`.
+    pub async fn check(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Vec<(PublicKeyBytes, Result<(), RemoteCheckError>)> {
+        let peer_pubkeys = join_all(self.peers.iter().map(|peer| async move {
+            let pubkeys = peer_enabled_pubkeys(&peer.client)
+                .await
+                .map_err(|e| format!("{:?}", e));
+            (peer.url.to_string(), pubkeys)
+        }))
+        .await;
+
+        pubkeys
+            .iter()
+            .map(|pubkey| {
+                let result = peer_pubkeys
+                    .iter()
+                    .try_for_each(|(url, peer_pubkeys)| match peer_pubkeys {
+                        Ok(peer_pubkeys) if peer_pubkeys.contains(pubkey) => {
+                            Err(RemoteCheckError::EnabledOnPeer(url.clone()))
+                        }
+                        Ok(_) => Ok(()),
+                        Err(e) => Err(RemoteCheckError::PeerUnavailable(url.clone(), e.clone())),
+                    })
+                    .and_then(|()| self.acquire_lock(*pubkey));
+                (*pubkey, result)
+            })
+            .collect()
+    }
+
+    /// Release the locks of all validators which are not in `active`, returning their pubkeys.
+    pub fn release_inactive_locks(&self, active: &HashSet<PublicKeyBytes>) -> Vec<PublicKeyBytes> {
+        let mut locks = self.locks.lock();
+        let inactive = locks
+            .keys()
+            .filter(|pubkey| !active.contains(pubkey))
+            .copied()
+            .collect::<Vec<_>>();
+        for pubkey in &inactive {
+            locks.remove(pubkey);
+        }
+        inactive
+    }
+
+    /// Obtain the lock for `pubkey`, if a lock directory is configured.
+    fn acquire_lock(&self, pubkey: PublicKeyBytes) -> Result<(), RemoteCheckError> {
+        let Some(lock_dir) = &self.lock_dir else {
+            return Ok(());
+        };
+
+        let mut locks = self.locks.lock();
+        if locks.contains_key(&pubkey) {
+            return Ok(());
+        }
+
+        let lockfile = Lockfile::new(lock_path(lock_dir, &pubkey)).map_err(|e| match e {
+            LockfileError::FileLocked(path, _) => RemoteCheckError::LockHeld(path),
+            e => RemoteCheckError::LockError(e),
+        })?;
+        locks.insert(pubkey, lockfile);
+
+        Ok(())
+    }
+}
+
+/// Returns the path of the lock file for `pubkey` within `lock_dir`.
+fn lock_path(lock_dir: &Path, pubkey: &PublicKeyBytes) -> PathBuf {
+    lock_dir.join(format!("{}.lock", pubkey.as_hex_string()))
+}
+
+/// Returns the pubkeys of all validators enabled on the peer validator client, including both
+/// local keystores and remote keys.
+async fn peer_enabled_pubkeys(
+    client: &ValidatorClientHttpClient,
+) -> Result<HashSet<PublicKeyBytes>, eth2::Error> {
+    let remotekeys = client.get_remotekeys().await?;
+    let keystores = client.get_keystores().await?;
+
+    Ok(remotekeys
+        .data
+        .into_iter()
+        .map(|remotekey| remotekey.pubkey)
+        .chain(
+            keystores
+                .data
+                .into_iter()
+                .map(|keystore| keystore.validating_pubkey),
+        )
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn lock_is_exclusive_and_released_when_inactive() {
+        let dir = tempdir().unwrap();
+        let pubkey = PublicKeyBytes::empty();
+
+        let first = RemoteChecks::new(&[], &[], Some(dir.path().to_path_buf())).unwrap();
+        let second = RemoteChecks::new(&[], &[], Some(dir.path().to_path_buf())).unwrap();
+
+        first.acquire_lock(pubkey).unwrap();
+        // Re-acquiring a lock which is already held by this instance is a no-op.
+        first.acquire_lock(pubkey).unwrap();
+
+        if cfg!(unix) {
+            assert!(matches!(
+                second.acquire_lock(pubkey),
+                Err(RemoteCheckError::LockHeld(_))
+            ));
+        }
+
+        assert!(first.release_inactive_locks(&[pubkey].into()).is_empty());
+        assert_eq!(first.release_inactive_locks(&HashSet::new()), vec![pubkey]);
+        assert!(!lock_path(dir.path(), &pubkey).exists());
+
+        second.acquire_lock(pubkey).unwrap();
+    }
+}
@@ -0,0 +1,650 @@
+//! Publishes signed voluntary exits, holding back exits which are signed for a future epoch.
+//!
+//! A voluntary exit is only valid once the current epoch is greater than or equal to the epoch of
+//! the exit. Exits for a future epoch are persisted to the validators directory so that they
+//! survive a restart, and are published once per epoch until the beacon node accepts them.
+//!
+//! The service also projects the exit and withdrawable epochs of exits from the exit queue of the
+//! head state, following `initiate_validator_exit`. Before Electra the exit queue is limited by the
+//! number of exiting validators, from Electra it is limited by their effective balance.
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use eth2::lighthouse_vc::types::{VoluntaryExitData, VoluntaryExitStatus};
+use eth2::types::{StateId, ValidatorId};
+use filesystem::create_with_600_perms;
+use parking_lot::Mutex;
+use safe_arith::{ArithError, SafeArith};
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::cmp::max;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{
+    BeaconState, ChainSpec, Epoch, EthSpec, ForkName, PublicKeyBytes, SignedVoluntaryExit,
+    Validator,
+};
+
+/// The file in the validators directory which contains the exits that have not been published.
+pub const SCHEDULED_EXITS_FILENAME: &str = "scheduled_exits.json";
+
+/// A projection of the exit queue of a beacon state, prior to Electra.
+///
+/// The projection assumes the churn limit remains constant, so it becomes less accurate the
+/// further into the future an exit is initiated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitQueue {
+    max_exit_epoch: Option<Epoch>,
+    max_exit_epoch_churn: u64,
+    churn_limit: u64,
+}
+
+impl ExitQueue {
+    /// Builds the exit queue from the validators which are active in the head state.
+    pub fn new<'a>(
+        active_validators: impl IntoIterator<Item = &'a Validator>,
+        spec: &ChainSpec,
+    ) -> Result<Self, ArithError> {
+        let mut queue = Self {
+            max_exit_epoch: None,
+            max_exit_epoch_churn: 0,
+            churn_limit: 0,
+        };
+        let mut active_validator_count = 0_u64;
+        for validator in active_validators {
+            active_validator_count.safe_add_assign(1)?;
+            if validator.exit_epoch != spec.far_future_epoch {
+                queue.record_exit(validator.exit_epoch)?;
+            }
+        }
+        queue.churn_limit = max(
+            spec.min_per_epoch_churn_limit,
+            active_validator_count.safe_div(spec.churn_limit_quotient)?,
+        );
+        Ok(queue)
+    }
+
+    /// Adds an exit which is initiated at `epoch` to the queue, returning its exit epoch.
+    pub fn initiate_exit(&mut self, epoch: Epoch, spec: &ChainSpec) -> Result<Epoch, ArithError> {
+        let delayed_epoch = spec.compute_activation_exit_epoch(epoch)?;
+        let mut exit_queue_epoch = self
+            .max_exit_epoch
+            .map_or(delayed_epoch, |epoch| max(epoch, delayed_epoch));
+        if self.max_exit_epoch == Some(exit_queue_epoch)
+            && self.max_exit_epoch_churn >= self.churn_limit
+        {
+            exit_queue_epoch.safe_add_assign(1)?;
+        }
+        self.record_exit(exit_queue_epoch)?;
+        Ok(exit_queue_epoch)
+    }
+
+    fn record_exit(&mut self, exit_epoch: Epoch) -> Result<(), ArithError> {
+        match self.max_exit_epoch {
+            Some(max_exit_epoch) if exit_epoch == max_exit_epoch => {
+                self.max_exit_epoch_churn.safe_add_assign(1)?;
+            }
+            Some(max_exit_epoch) if exit_epoch < max_exit_epoch => (),
+            _ => {
+                self.max_exit_epoch = Some(exit_epoch);
+                self.max_exit_epoch_churn = 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A projection of the exit queue of a beacon state, from Electra onwards.
+///
+/// This follows `BeaconState::compute_exit_epoch_and_update_churn`, and likewise assumes the churn
+/// limit remains constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceExitQueue {
+    earliest_exit_epoch: Epoch,
+    exit_balance_to_consume: u64,
+    per_epoch_churn: u64,
+}
+
+impl BalanceExitQueue {
+    /// Adds an exit of `exit_balance` which is initiated at `epoch` to the queue, returning its
+    /// exit epoch.
+    pub fn initiate_exit(
+        &mut self,
+        epoch: Epoch,
+        exit_balance: u64,
+        spec: &ChainSpec,
+    ) -> Result<Epoch, ArithError> {
+        let mut earliest_exit_epoch = max(
+            self.earliest_exit_epoch,
+            spec.compute_activation_exit_epoch(epoch)?,
+        );
+        let mut exit_balance_to_consume = if self.earliest_exit_epoch < earliest_exit_epoch {
+            self.per_epoch_churn
+        } else {
+            self.exit_balance_to_consume
+        };
+
+        if exit_balance > exit_balance_to_consume {
+            let balance_to_process = exit_balance.safe_sub(exit_balance_to_consume)?;
+            let additional_epochs = balance_to_process
+                .safe_sub(1)?
+                .safe_div(self.per_epoch_churn)?
+                .safe_add(1)?;
+            earliest_exit_epoch.safe_add_assign(additional_epochs)?;
+            exit_balance_to_consume
+                .safe_add_assign(additional_epochs.safe_mul(self.per_epoch_churn)?)?;
+        }
+        self.exit_balance_to_consume = exit_balance_to_consume.safe_sub(exit_balance)?;
+        self.earliest_exit_epoch = earliest_exit_epoch;
+
+        Ok(earliest_exit_epoch)
+    }
+}
+
+/// The exit queue of the head state, for the fork of the head state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadExitQueue {
+    Validators(ExitQueue),
+    Balance(BalanceExitQueue),
+}
+
+impl HeadExitQueue {
+    /// Builds the exit queue of `state`.
+    pub fn from_state<E: EthSpec>(
+        state: &mut BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<Self, String> {
+        if state.fork_name_unchecked() >= ForkName::Electra {
+            state
+                .build_total_active_balance_cache(spec)
+                .map_err(|e| format!("Unable to compute total active balance: {:?}", e))?;
+            let queue = (|| {
+                Ok::<_, types::BeaconStateError>(BalanceExitQueue {
+                    earliest_exit_epoch: state.earliest_exit_epoch()?,
+                    exit_balance_to_consume: state.exit_balance_to_consume()?,
+                    per_epoch_churn: state.get_activation_exit_churn_limit(spec)?,
+                })
+            })()
+            .map_err(|e| format!("Unable to read exit queue: {:?}", e))?;
+            Ok(Self::Balance(queue))
+        } else {
+            let current_epoch = state.current_epoch();
+            let active_validators = state
+                .validators()
+                .iter()
+                .filter(|validator| validator.is_active_at(current_epoch));
+            ExitQueue::new(active_validators, spec)
+                .map(Self::Validators)
+                .map_err(|e| format!("Unable to build exit queue: {:?}", e))
+        }
+    }
+
+    /// Adds an exit of `validator` which is initiated at `epoch` to the queue, returning its exit
+    /// epoch.
+    pub fn initiate_exit(
+        &mut self,
+        epoch: Epoch,
+        validator: &Validator,
+        spec: &ChainSpec,
+    ) -> Result<Epoch, ArithError> {
+        match self {
+            Self::Validators(queue) => queue.initiate_exit(epoch, spec),
+            Self::Balance(queue) => queue.initiate_exit(epoch, validator.effective_balance, spec),
+        }
+    }
+}
+
+pub struct ExitService<T: SlotClock, E: EthSpec> {
+    /// The exits which have not yet been published, by validator.
+    scheduled: Mutex<HashMap<PublicKeyBytes, SignedVoluntaryExit>>,
+    path: PathBuf,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    /// The exit queue of the head state, along with the epoch in which it was fetched.
+    ///
+    /// Fetching the head state is expensive, so it is fetched at most once per epoch.
+    head_exit_queue: tokio::sync::Mutex<Option<(Epoch, HeadExitQueue)>>,
+    slot_clock: T,
+    spec: ChainSpec,
+    log: Logger,
+}
+
+impl<T: SlotClock + 'static, E: EthSpec> ExitService<T, E> {
+    /// Creates the service, loading any scheduled exits from `validator_dir`.
+    pub fn new(
+        validator_dir: &Path,
+        beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+        slot_clock: T,
+        spec: ChainSpec,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let path = validator_dir.join(SCHEDULED_EXITS_FILENAME);
+        let scheduled = if path.exists() {
+            let bytes = fs::read(&path)
+                .map_err(|e| format!("Unable to read {}: {:?}", path.display(), e))?;
+            serde_json::from_slice(&bytes)
+                .map_err(|e| format!("Unable to parse {}: {:?}", path.display(), e))?
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self {
+            scheduled: Mutex::new(scheduled),
+            path,
+            beacon_nodes,
+            head_exit_queue: <_>::default(),
+            slot_clock,
+            spec,
+            log,
+        })
+    }
+
+    /// Returns the exits which have not yet been published.
+    pub async fn scheduled_exits(&self) -> Vec<VoluntaryExitData> {
+        let exits = self
+            .scheduled
+            .lock()
+            .iter()
+            .map(|(pubkey, exit)| (*pubkey, exit.clone()))
+            .collect::<Vec<_>>();
+        self.with_projections(exits, VoluntaryExitStatus::Scheduled)
+            .await
+    }
+
+    /// Adds `exits` to the scheduled exits, then publishes any which are valid in the current
+    /// epoch.
+    ///
+    /// An exit replaces any scheduled exit for the same validator.
+    pub async fn add_exits(
+        &self,
+        exits: Vec<(PublicKeyBytes, SignedVoluntaryExit)>,
+    ) -> Result<Vec<VoluntaryExitData>, String> {
+        // Project the epochs before publishing, since the projection places the new exits after
+        // the existing scheduled exits.
+        let mut data = self
+            .with_projections(exits.clone(), VoluntaryExitStatus::Scheduled)
+            .await;
+
+        {
+            let mut scheduled = self.scheduled.lock();
+            scheduled.extend(exits);
+            self.persist(&scheduled)?;
+        }
+
+        self.publish_due_exits().await;
+
+        let scheduled = self.scheduled.lock();
+        for exit in &mut data {
+            if !scheduled.contains_key(&exit.pubkey) {
+                exit.status = VoluntaryExitStatus::Published;
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Publishes each scheduled exit which is valid in the current epoch, removing it from the
+    /// scheduled exits if it is accepted by a beacon node.
+    pub async fn publish_due_exits(&self) {
+        let Some(current_epoch) = self.current_epoch() else {
+            return;
+        };
+        let due = self
+            .scheduled
+            .lock()
+            .iter()
+            .filter(|(_, exit)| exit.message.epoch <= current_epoch)
+            .map(|(pubkey, exit)| (*pubkey, exit.clone()))
+            .collect::<Vec<_>>();
+        if due.is_empty() {
+            return;
+        }
+
+        let mut published = vec![];
+        for (pubkey, exit) in due {
+            let exit_ref = &exit;
+            match self
+                .beacon_nodes
+                .first_success(
+                    RequireSynced::No,
+                    OfflineOnFailure::Yes,
+                    |beacon_node| async move {
+                        beacon_node.post_beacon_pool_voluntary_exits(exit_ref).await
+                    },
+                )
+                .await
+            {
+                Ok(()) => {
+                    info!(
+                        self.log,
+                        "Published voluntary exit";
+                        "validator" => ?pubkey,
+                        "epoch" => exit.message.epoch,
+                    );
+                    published.push(pubkey);
+                }
+                Err(e) => warn!(
+                    self.log,
+                    "Failed to publish voluntary exit";
+                    "msg" => "the exit will be retried next epoch",
+                    "validator" => ?pubkey,
+                    "error" => %e,
+                ),
+            }
+        }
+
+        let mut scheduled = self.scheduled.lock();
+        for pubkey in &published {
+            scheduled.remove(pubkey);
+        }
+        if let Err(e) = self.persist(&scheduled) {
+            error!(
+                self.log,
+                "Failed to persist scheduled exits";
+                "error" => e,
+            );
+        }
+    }
+
+    /// Starts a service which publishes scheduled exits at the start of each epoch.
+    pub fn start_update_service(self: Arc<Self>, executor: &TaskExecutor) {
+        let future = async move {
+            loop {
+                match self.slot_clock.duration_to_next_epoch(E::slots_per_epoch()) {
+                    Some(duration) => sleep(duration).await,
+                    None => {
+                        error!(self.log, "Failed to read slot clock");
+                        // If we can't read the slot clock, just wait another slot.
+                        sleep(self.slot_clock.slot_duration()).await;
+                        continue;
+                    }
+                }
+
+                self.publish_due_exits().await;
+            }
+        };
+
+        executor.spawn(future, "exit_service");
+    }
+
+    fn current_epoch(&self) -> Option<Epoch> {
+        self.slot_clock
+            .now()
+            .map(|slot| slot.epoch(E::slots_per_epoch()))
+    }
+
+    fn persist(
+        &self,
+        scheduled: &HashMap<PublicKeyBytes, SignedVoluntaryExit>,
+    ) -> Result<(), String> {
+        let bytes = serde_json::to_vec_pretty(scheduled)
+            .map_err(|e| format!("Unable to serialize scheduled exits: {:?}", e))?;
+        // The exits are irrevocable once published, so only the owner may read them.
+        create_with_600_perms(&self.path, &bytes)
+            .map_err(|e| format!("Unable to write {}: {:?}", self.path.display(), e))
+    }
+
+    /// Returns `exits` with the exit and withdrawable epochs projected from the exit queue of the
+    /// head state, after any earlier scheduled exits.
+    ///
+    /// The projections are omitted if the head state cannot be fetched.
+    async fn with_projections(
+        &self,
+        exits: Vec<(PublicKeyBytes, SignedVoluntaryExit)>,
+        status: VoluntaryExitStatus,
+    ) -> Vec<VoluntaryExitData> {
+        let projections = match self.project_exit_epochs(&exits).await {
+            Ok(projections) => projections,
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Unable to project exit epochs";
+                    "error" => e,
+                );
+                vec![None; exits.len()]
+            }
+        };
+
+        exits
+            .into_iter()
+            .zip(projections)
+            .map(|((pubkey, signed_voluntary_exit), projection)| {
+                let projected_exit_epoch = projection;
+                let projected_withdrawable_epoch = projection.and_then(|epoch| {
+                    epoch
+                        .safe_add(self.spec.min_validator_withdrawability_delay)
+                        .ok()
+                });
+                VoluntaryExitData {
+                    pubkey,
+                    status,
+                    signed_voluntary_exit,
+                    projected_exit_epoch,
+                    projected_withdrawable_epoch,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the exit queue of the head state, fetching the head state if the queue has not
+    /// been fetched during `current_epoch`.
+    async fn head_exit_queue(&self, current_epoch: Epoch) -> Result<HeadExitQueue, String> {
+        // Hold the lock whilst fetching so that concurrent requests only fetch the state once.
+        let mut cache = self.head_exit_queue.lock().await;
+        if let Some((epoch, queue)) = cache.as_ref() {
+            if *epoch == current_epoch {
+                return Ok(queue.clone());
+            }
+        }
+
+        let mut state = self
+            .beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::Yes,
+                |beacon_node| async move {
+                    beacon_node
+                        .get_debug_beacon_states_ssz::<E>(StateId::Head, &self.spec)
+                        .await
+                },
+            )
+            .await
+            .map_err(|e| format!("Unable to fetch head state: {}", e))?
+            .ok_or("Head state not found")?;
+        let queue = HeadExitQueue::from_state(&mut state, &self.spec)?;
+
+        *cache = Some((current_epoch, queue.clone()));
+        Ok(queue)
+    }
+
+    /// Projects the exit epoch of each of `exits`.
+    ///
+    /// The exits are added to the exit queue along with the scheduled exits, in order of epoch.
+    /// Validators which have already initiated an exit have their actual exit epoch, and inactive
+    /// validators have no exit epoch.
+    async fn project_exit_epochs(
+        &self,
+        exits: &[(PublicKeyBytes, SignedVoluntaryExit)],
+    ) -> Result<Vec<Option<Epoch>>, String> {
+        let current_epoch = self
+            .current_epoch()
+            .ok_or("Unable to determine current epoch")?;
+
+        let mut queue = self.head_exit_queue(current_epoch).await?;
+
+        let mut queued = self
+            .scheduled
+            .lock()
+            .iter()
+            .filter(|(pubkey, _)| !exits.iter().any(|(exit_pubkey, _)| exit_pubkey == *pubkey))
+            .map(|(_, exit)| exit.message.clone())
+            .collect::<Vec<_>>();
+        queued.extend(exits.iter().map(|(_, exit)| exit.message.clone()));
+        queued.sort_by_key(|exit| (exit.epoch, exit.validator_index));
+
+        // Only fetch the validators which are exiting, rather than the entire validator set.
+        let ids = queued
+            .iter()
+            .map(|exit| ValidatorId::Index(exit.validator_index))
+            .collect::<Vec<_>>();
+        let ids_ref = &ids;
+        let active_validators = self
+            .beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::Yes,
+                |beacon_node| async move {
+                    beacon_node
+                        .post_beacon_states_validators(StateId::Head, Some(ids_ref.clone()), None)
+                        .await
+                },
+            )
+            .await
+            .map_err(|e| format!("Unable to fetch validators: {}", e))?
+            .ok_or("Head state not found")?
+            .data
+            .into_iter()
+            .filter(|data| data.validator.is_active_at(current_epoch))
+            .map(|data| (data.index, data.validator))
+            .collect::<HashMap<_, _>>();
+
+        let mut exit_epochs = HashMap::new();
+        for exit in queued {
+            let Some(validator) = active_validators.get(&exit.validator_index) else {
+                continue;
+            };
+            let exit_epoch = if validator.exit_epoch == self.spec.far_future_epoch {
+                queue
+                    .initiate_exit(max(exit.epoch, current_epoch), validator, &self.spec)
+                    .map_err(|e| format!("Unable to project exit epoch: {:?}", e))?
+            } else {
+                validator.exit_epoch
+            };
+            exit_epochs.insert(exit.validator_index, exit_epoch);
+        }
+
+        debug!(
+            self.log,
+            "Projected exit epochs";
+            "exits" => exit_epochs.len(),
+        );
+
+        Ok(exits
+            .iter()
+            .map(|(_, exit)| exit_epochs.get(&exit.message.validator_index).copied())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MinimalEthSpec;
+
+    fn validator(exit_epoch: Epoch) -> Validator {
+        Validator {
+            exit_epoch,
+            ..Validator::default()
+        }
+    }
+
+    #[test]
+    fn exit_queue_without_exits() {
+        let spec = MinimalEthSpec::default_spec();
+        let validators = vec![validator(spec.far_future_epoch); 16];
+        let mut queue = ExitQueue::new(&validators, &spec).unwrap();
+
+        // The minimum churn limit applies to a small validator set.
+        let delayed_epoch = spec.compute_activation_exit_epoch(Epoch::new(10)).unwrap();
+        for _ in 0..spec.min_per_epoch_churn_limit {
+            assert_eq!(
+                queue.initiate_exit(Epoch::new(10), &spec).unwrap(),
+                delayed_epoch
+            );
+        }
+        assert_eq!(
+            queue.initiate_exit(Epoch::new(10), &spec).unwrap(),
+            delayed_epoch + 1
+        );
+
+        // Exits initiated later are not placed before earlier exits.
+        assert_eq!(
+            queue.initiate_exit(Epoch::new(5), &spec).unwrap(),
+            delayed_epoch + 1
+        );
+    }
+
+    #[test]
+    fn exit_queue_with_exits() {
+        let spec = MinimalEthSpec::default_spec();
+        let churn_limit = 6;
+        let validator_count = churn_limit * spec.churn_limit_quotient as usize;
+        let mut validators = vec![validator(spec.far_future_epoch); validator_count];
+        // Fill the exit queue up to epoch 100.
+        for (i, validator) in validators.iter_mut().take(churn_limit * 3).enumerate() {
+            validator.exit_epoch = Epoch::new(98 + (i / churn_limit) as u64);
+        }
+        let mut queue = ExitQueue::new(&validators, &spec).unwrap();
+        assert_eq!(queue.churn_limit, churn_limit as u64);
+
+        assert_eq!(
+            queue.initiate_exit(Epoch::new(10), &spec).unwrap(),
+            Epoch::new(101)
+        );
+
+        // Exits initiated after the end of the queue are only delayed by the activation exit
+        // delay.
+        assert_eq!(
+            queue.initiate_exit(Epoch::new(200), &spec).unwrap(),
+            spec.compute_activation_exit_epoch(Epoch::new(200)).unwrap()
+        );
+    }
+
+    #[test]
+    fn balance_exit_queue() {
+        let spec = MinimalEthSpec::default_spec();
+        let balance = 32_000_000_000;
+        let per_epoch_churn = 4 * balance;
+        let mut queue = BalanceExitQueue {
+            earliest_exit_epoch: Epoch::new(100),
+            exit_balance_to_consume: 2 * balance,
+            per_epoch_churn,
+        };
+
+        // The remaining balance of the earliest exit epoch is consumed first.
+        for _ in 0..2 {
+            assert_eq!(
+                queue.initiate_exit(Epoch::new(10), balance, &spec).unwrap(),
+                Epoch::new(100)
+            );
+        }
+        assert_eq!(queue.exit_balance_to_consume, 0);
+        assert_eq!(
+            queue.initiate_exit(Epoch::new(10), balance, &spec).unwrap(),
+            Epoch::new(101)
+        );
+        assert_eq!(queue.exit_balance_to_consume, per_epoch_churn - balance);
+
+        // An exit larger than the churn spans multiple epochs.
+        assert_eq!(
+            queue
+                .initiate_exit(Epoch::new(10), 2 * per_epoch_churn, &spec)
+                .unwrap(),
+            Epoch::new(103)
+        );
+        assert_eq!(queue.exit_balance_to_consume, per_epoch_churn - balance);
+
+        // Exits initiated after the end of the queue are only delayed by the activation exit
+        // delay.
+        let delayed_epoch = spec.compute_activation_exit_epoch(Epoch::new(200)).unwrap();
+        assert_eq!(
+            queue
+                .initiate_exit(Epoch::new(200), balance, &spec)
+                .unwrap(),
+            delayed_epoch
+        );
+        assert_eq!(queue.exit_balance_to_consume, per_epoch_churn - balance);
+    }
+}
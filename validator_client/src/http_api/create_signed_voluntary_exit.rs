@@ -1,8 +1,11 @@
+use crate::exit_service::ExitService;
 use crate::validator_store::ValidatorStore;
 use bls::{PublicKey, PublicKeyBytes};
+use eth2::lighthouse_vc::types::{VoluntaryExitData, VoluntaryExitsRequest};
 use eth2::types::GenericResponse;
 use slog::{info, Logger};
 use slot_clock::SlotClock;
+use std::collections::HashSet;
 use std::sync::Arc;
 use types::{Epoch, EthSpec, SignedVoluntaryExit, VoluntaryExit};
 
@@ -21,23 +24,7 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
     };
 
     let pubkey_bytes = PublicKeyBytes::from(pubkey);
-    if !validator_store.has_validator(&pubkey_bytes) {
-        return Err(warp_utils::reject::custom_not_found(format!(
-            "{} is disabled or not managed by this validator client",
-            pubkey_bytes.as_hex_string()
-        )));
-    }
-
-    let validator_index = validator_store
-        .validator_index(&pubkey_bytes)
-        .ok_or_else(|| {
-            warp_utils::reject::custom_not_found(format!(
-                "The validator index for {} is not known. The validator client \
-                may still be initializing or the validator has not yet had a \
-                deposit processed.",
-                pubkey_bytes.as_hex_string()
-            ))
-        })?;
+    let validator_index = get_validator_index(&pubkey_bytes, &validator_store)?;
 
     let voluntary_exit = VoluntaryExit {
         epoch,
@@ -64,6 +51,98 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
     Ok(GenericResponse::from(signed_voluntary_exit))
 }
 
+/// Signs a voluntary exit for each of the validators in `request`, passing them to the
+/// `exit_service` to be published immediately or held until their epoch.
+///
+/// Every validator is checked before any exits are signed.
+pub async fn create_signed_voluntary_exits<T: 'static + SlotClock + Clone, E: EthSpec>(
+    request: VoluntaryExitsRequest,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    exit_service: Arc<ExitService<T, E>>,
+    slot_clock: T,
+    log: Logger,
+) -> Result<GenericResponse<Vec<VoluntaryExitData>>, warp::Rejection> {
+    if request.pubkeys.is_empty() {
+        return Err(warp_utils::reject::custom_bad_request(
+            "No validators were specified".to_string(),
+        ));
+    }
+    if request.pubkeys.iter().collect::<HashSet<_>>().len() != request.pubkeys.len() {
+        return Err(warp_utils::reject::custom_bad_request(
+            "Duplicate validators were specified".to_string(),
+        ));
+    }
+
+    let epoch = match request.epoch {
+        Some(epoch) => epoch,
+        None => get_current_epoch::<T, E>(slot_clock).ok_or_else(|| {
+            warp_utils::reject::custom_server_error("Unable to determine current epoch".to_string())
+        })?,
+    };
+
+    let validator_indices = request
+        .pubkeys
+        .iter()
+        .map(|pubkey| get_validator_index(pubkey, &validator_store))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    info!(
+        log,
+        "Signing voluntary exits";
+        "count" => request.pubkeys.len(),
+        "epoch" => epoch
+    );
+
+    let mut exits = Vec::with_capacity(request.pubkeys.len());
+    for (pubkey, validator_index) in request.pubkeys.into_iter().zip(validator_indices) {
+        let voluntary_exit = VoluntaryExit {
+            epoch,
+            validator_index,
+        };
+        let signed_voluntary_exit = validator_store
+            .sign_voluntary_exit(pubkey, voluntary_exit)
+            .await
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "Failed to sign voluntary exit for {}: {:?}",
+                    pubkey.as_hex_string(),
+                    e
+                ))
+            })?;
+        exits.push((pubkey, signed_voluntary_exit));
+    }
+
+    exit_service
+        .add_exits(exits)
+        .await
+        .map(GenericResponse::from)
+        .map_err(warp_utils::reject::custom_server_error)
+}
+
+/// Returns the index of a validator which is enabled and managed by this validator client.
+fn get_validator_index<T: 'static + SlotClock + Clone, E: EthSpec>(
+    pubkey_bytes: &PublicKeyBytes,
+    validator_store: &ValidatorStore<T, E>,
+) -> Result<u64, warp::Rejection> {
+    if !validator_store.has_validator(pubkey_bytes) {
+        return Err(warp_utils::reject::custom_not_found(format!(
+            "{} is disabled or not managed by this validator client",
+            pubkey_bytes.as_hex_string()
+        )));
+    }
+
+    validator_store
+        .validator_index(pubkey_bytes)
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(format!(
+                "The validator index for {} is not known. The validator client \
+            may still be initializing or the validator has not yet had a \
+            deposit processed.",
+                pubkey_bytes.as_hex_string()
+            ))
+        })
+}
+
 /// Calculates the current epoch from the genesis time and current time.
 fn get_current_epoch<T: 'static + SlotClock + Clone, E: EthSpec>(slot_clock: T) -> Option<Epoch> {
     slot_clock.now().map(|s| s.epoch(E::slots_per_epoch()))
@@ -10,7 +10,11 @@ pub mod test_utils;
 
 use crate::http_api::graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
-use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
+use crate::exit_service::ExitService;
+use crate::http_api::create_signed_voluntary_exit::{
+    create_signed_voluntary_exit, create_signed_voluntary_exits,
+};
+use crate::initialized_validators::Error as InitializedValidatorsError;
 use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
 use account_utils::{
     mnemonic_from_phrase,
@@ -80,6 +84,7 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub task_executor: TaskExecutor,
     pub api_secret: ApiSecret,
     pub validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    pub exit_service: Option<Arc<ExitService<T, E>>>,
     pub validator_dir: Option<PathBuf>,
     pub secrets_dir: Option<PathBuf>,
     pub graffiti_file: Option<GraffitiFile>,
@@ -221,6 +226,15 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     let inner_graffiti_flag = ctx.graffiti_flag;
     let graffiti_flag_filter = warp::any().map(move || inner_graffiti_flag);
 
+    let inner_exit_service = ctx.exit_service.clone();
+    let exit_service_filter = warp::any()
+        .map(move || inner_exit_service.clone())
+        .and_then(|exit_service: Option<_>| async move {
+            exit_service.ok_or_else(|| {
+                warp_utils::reject::custom_not_found("exit service is not initialized.".to_string())
+            })
+        });
+
     let inner_ctx = ctx.clone();
     let log_filter = warp::any().map(move || inner_ctx.log.clone());
 
@@ -781,6 +795,118 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST /lighthouse/validators/{validator_pubkey}/reencrypt
+    let post_validators_reencrypt = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("reencrypt"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             body: api_types::KeystoreReencryptRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    let initialized_validators_rw_lock = validator_store.initialized_validators();
+                    // Only hold the lock whilst reading and replacing the keystore, since the
+                    // encryption itself is slow.
+                    let reencryption = initialized_validators_rw_lock
+                        .read()
+                        .prepare_keystore_reencryption(&validator_pubkey, body.password, body.kdf);
+                    reencryption
+                        .and_then(|reencryption| reencryption.encrypt())
+                        .and_then(|reencrypted| {
+                            initialized_validators_rw_lock
+                                .write()
+                                .replace_keystore(reencrypted)
+                        })
+                        .map_err(|e| match e {
+                            InitializedValidatorsError::ValidatorNotInitialized(_) => {
+                                warp_utils::reject::custom_not_found(format!(
+                                    "no validator for {:?}",
+                                    validator_pubkey
+                                ))
+                            }
+                            InitializedValidatorsError::InvalidActionOnValidator
+                            | InitializedValidatorsError::ReencryptionPasswordRequired(_) => {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "unable to re-encrypt keystore: {:?}",
+                                    e
+                                ))
+                            }
+                            e => warp_utils::reject::custom_server_error(format!(
+                                "unable to re-encrypt keystore: {:?}",
+                                e
+                            )),
+                        })
+                })
+            },
+        );
+
+    // GET /lighthouse/validators/exits
+    let get_lighthouse_validators_exits = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("exits"))
+        .and(warp::path::end())
+        .and(exit_service_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |exit_service: Arc<ExitService<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let exits = handle.block_on(exit_service.scheduled_exits());
+                        Ok(api_types::GenericResponse::from(exits))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
+    // POST /lighthouse/validators/exits
+    let post_lighthouse_validators_exits = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("exits"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .and(exit_service_filter)
+        .and(slot_clock_filter.clone())
+        .and(log_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |body: api_types::VoluntaryExitsRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             exit_service: Arc<ExitService<T, E>>,
+             slot_clock: T,
+             log,
+             signer,
+             task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        handle.block_on(create_signed_voluntary_exits(
+                            body,
+                            validator_store,
+                            exit_service,
+                            slot_clock,
+                            log,
+                        ))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // GET /lighthouse/auth
     let get_auth = warp::path("lighthouse").and(warp::path("auth").and(warp::path::end()));
     let get_auth = get_auth
@@ -1310,6 +1436,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_health)
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
+                        .or(get_lighthouse_validators_exits)
                         .or(get_lighthouse_validators_pubkey)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
@@ -1326,6 +1453,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_validators_mnemonic)
                         .or(post_validators_web3signer)
                         .or(post_validators_voluntary_exits)
+                        .or(post_validators_reencrypt)
+                        .or(post_lighthouse_validators_exits)
                         .or(post_fee_recipient)
                         .or(post_gas_limit)
                         .or(post_std_keystores)
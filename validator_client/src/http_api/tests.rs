@@ -6,12 +6,16 @@ mod keystores;
 use crate::doppelganger_service::DoppelgangerService;
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
-    initialized_validators::InitializedValidators,
+    initialized_validators::{DefinitionsDiff, InitializedValidators},
+    proposer_config::{ProposerConfig, ProposerOptions},
+    signing_method::SigningMethod,
+    validator_store::DEFAULT_GAS_LIMIT,
     Config, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
-    eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, ZeroizeString,
+    eth2_wallet::WalletBuilder,
+    keystore_rotation::{backup_path, KeystoreKdf},
+    mnemonic_from_phrase, random_mnemonic, random_password, random_password_string, ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
 use eth2::{
@@ -19,7 +23,7 @@ use eth2::{
     types::ErrorMessage as ApiErrorMessage,
     Error as ApiError,
 };
-use eth2_keystore::KeystoreBuilder;
+use eth2_keystore::{json_keystore::Kdf, Keystore, KeystoreBuilder};
 use logging::test_logger;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
@@ -118,6 +122,7 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            exit_service: None,
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -264,6 +269,39 @@ impl ApiTester {
         self
     }
 
+    /// Modify the validator definitions file directly, as an external tool would.
+    pub fn edit_definitions_on_disk<F>(self, func: F) -> Self
+    where
+        F: FnOnce(&mut ValidatorDefinitions),
+    {
+        let mut definitions = ValidatorDefinitions::open(self._validator_dir.path()).unwrap();
+        func(&mut definitions);
+        definitions.save(self._validator_dir.path()).unwrap();
+        self
+    }
+
+    /// Reload the validator definitions from disk, passing the pubkeys of all definitions on disk
+    /// and the resulting diff to `func`.
+    pub async fn reload_definitions<F>(self, func: F) -> Self
+    where
+        F: FnOnce(&[PublicKeyBytes], DefinitionsDiff),
+    {
+        let pubkeys = ValidatorDefinitions::open(self._validator_dir.path())
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|def| def.voting_public_key.compress())
+            .collect::<Vec<_>>();
+
+        let diff = self
+            .validator_store
+            .reload_validator_definitions()
+            .await
+            .unwrap();
+        func(&pubkeys, diff);
+        self
+    }
+
     pub async fn create_hd_validators(self, s: HdValidatorScenario) -> Self {
         let initial_vals = self.vals_total();
         let initial_enabled_vals = self.vals_enabled();
@@ -607,6 +645,37 @@ impl ApiTester {
         self
     }
 
+    pub async fn set_proposer_config_gas_limits(
+        self,
+        validator_gas_limits: Vec<(usize, u64)>,
+        default_gas_limit: Option<u64>,
+    ) -> Self {
+        let validators = self.client.get_lighthouse_validators().await.unwrap().data;
+
+        let proposer_config = ProposerConfig {
+            proposer_config: validator_gas_limits
+                .into_iter()
+                .map(|(index, gas_limit)| {
+                    let options = ProposerOptions {
+                        gas_limit: Some(gas_limit),
+                        ..<_>::default()
+                    };
+                    (validators[index].voting_pubkey, options)
+                })
+                .collect(),
+            default_config: ProposerOptions {
+                gas_limit: default_gas_limit,
+                ..<_>::default()
+            },
+        };
+        assert!(self
+            .validator_store
+            .set_proposer_config(proposer_config.clone()));
+        assert!(!self.validator_store.set_proposer_config(proposer_config));
+
+        self
+    }
+
     pub async fn set_builder_proposals(self, index: usize, builder_proposals: bool) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
 
@@ -757,6 +826,88 @@ impl ApiTester {
         self
     }
 
+    pub async fn reencrypt_keystore(self, index: usize, password: Option<ZeroizeString>) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let old_password = self.keystore_password(&validator.voting_pubkey);
+
+        self.client
+            .post_lighthouse_validators_reencrypt(
+                &validator.voting_pubkey,
+                &KeystoreReencryptRequest {
+                    password: password.clone(),
+                    kdf: KeystoreKdf::Pbkdf2 { c: 2 },
+                },
+            )
+            .await
+            .unwrap();
+
+        let new_password = self.keystore_password(&validator.voting_pubkey);
+        assert!(new_password != old_password);
+        if let Some(password) = password {
+            assert!(new_password == password);
+        }
+
+        // The keystore on disk is encrypted with the new password and KDF, with the old keystore
+        // kept as a backup.
+        let signing_method = self
+            .initialized_validators
+            .read()
+            .signing_method(&validator.voting_pubkey)
+            .unwrap();
+        let SigningMethod::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore,
+            ..
+        } = signing_method.as_ref()
+        else {
+            panic!("validator should have a local keystore");
+        };
+        let keystore = Keystore::from_json_file(voting_keystore_path).unwrap();
+        assert_eq!(&keystore, voting_keystore);
+        assert!(matches!(keystore.kdf(), Kdf::Pbkdf2(_)));
+        assert_eq!(
+            keystore
+                .decrypt_keypair(new_password.as_ref())
+                .unwrap()
+                .pk
+                .compress(),
+            validator.voting_pubkey
+        );
+        assert!(backup_path(voting_keystore_path).exists());
+
+        self
+    }
+
+    pub async fn reencrypt_keystore_fails(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        self.client
+            .post_lighthouse_validators_reencrypt(
+                &validator.voting_pubkey,
+                &KeystoreReencryptRequest {
+                    password: None,
+                    kdf: KeystoreKdf::default(),
+                },
+            )
+            .await
+            .unwrap_err();
+
+        self
+    }
+
+    fn keystore_password(&self, voting_pubkey: &PublicKeyBytes) -> ZeroizeString {
+        self.initialized_validators
+            .read()
+            .validator_definitions()
+            .iter()
+            .find(|def| def.voting_public_key.compress() == *voting_pubkey)
+            .unwrap()
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .unwrap()
+    }
+
     pub async fn assert_graffiti(self, index: usize, graffiti: &str) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         let graffiti_str = GraffitiString::from_str(graffiti).unwrap();
@@ -768,6 +919,33 @@ impl ApiTester {
         self
     }
 
+    /// Set the validator index of the validator with the `index`-th definition on disk.
+    pub fn set_validator_index(self, index: usize, validator_index: u64) -> Self {
+        let pubkey = self.definition_pubkey(index);
+        self.initialized_validators
+            .write()
+            .set_index(&pubkey, validator_index);
+        self
+    }
+
+    /// Assert the validator index of the validator with the `index`-th definition on disk.
+    pub fn assert_validator_index(self, index: usize, validator_index: Option<u64>) -> Self {
+        let pubkey = self.definition_pubkey(index);
+        assert_eq!(
+            self.initialized_validators.read().get_index(&pubkey),
+            validator_index
+        );
+        self
+    }
+
+    fn definition_pubkey(&self, index: usize) -> PublicKeyBytes {
+        ValidatorDefinitions::open(self._validator_dir.path())
+            .unwrap()
+            .as_slice()[index]
+            .voting_public_key
+            .compress()
+    }
+
     pub async fn test_set_graffiti(self, index: usize, graffiti: &str) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         let graffiti_str = GraffitiString::from_str(graffiti).unwrap();
@@ -920,6 +1098,31 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_validators_reencrypt(
+                    &PublicKeyBytes::empty(),
+                    &KeystoreReencryptRequest {
+                        password: None,
+                        kdf: <_>::default(),
+                    },
+                )
+                .await
+        })
+        .await
+        .test_with_invalid_auth(
+            |client| async move { client.get_lighthouse_validators_exits().await },
+        )
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_validators_exits(&VoluntaryExitsRequest {
+                    pubkeys: vec![PublicKeyBytes::empty()],
+                    epoch: None,
+                })
+                .await
+        })
+        .await
         .test_with_invalid_auth(|client| async move { client.get_keystores().await })
         .await
         .test_with_invalid_auth(|client| async move {
@@ -1084,6 +1287,42 @@ async fn validator_gas_limit() {
         .await;
 }
 
+#[tokio::test]
+async fn validator_gas_limit_proposer_config() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .set_gas_limit(0, 500)
+        .await
+        // The validator definitions take priority over the proposer config defaults.
+        .set_proposer_config_gas_limits(vec![], Some(700))
+        .await
+        .assert_gas_limit(0, 500)
+        .await
+        .assert_gas_limit(1, 700)
+        .await
+        // The proposer config for a specific validator takes priority over the definitions.
+        .set_proposer_config_gas_limits(vec![(0, 900)], Some(700))
+        .await
+        .assert_gas_limit(0, 900)
+        .await
+        .assert_gas_limit(1, 700)
+        .await
+        // Without a proposer config, the definitions and process defaults apply.
+        .set_proposer_config_gas_limits(vec![], None)
+        .await
+        .assert_gas_limit(0, 500)
+        .await
+        .assert_gas_limit(1, DEFAULT_GAS_LIMIT)
+        .await;
+}
+
 #[tokio::test]
 async fn validator_builder_proposals() {
     ApiTester::new()
@@ -1358,3 +1597,79 @@ async fn web3signer_validator_creation() {
         .assert_enabled_validators_count(1)
         .assert_validators_count(1);
 }
+
+#[tokio::test]
+async fn keystore_reencryption() {
+    ApiTester::new()
+        .await
+        .create_keystore_validators(KeystoreValidatorScenario {
+            correct_password: true,
+            enabled: true,
+        })
+        .await
+        .create_web3signer_validators(Web3SignerValidatorScenario {
+            count: 1,
+            enabled: true,
+        })
+        .await
+        .reencrypt_keystore(0, None)
+        .await
+        .reencrypt_keystore(0, Some(random_password_string()))
+        .await
+        .reencrypt_keystore_fails(1)
+        .await
+        .assert_enabled_validators_count(2);
+}
+
+#[tokio::test]
+async fn reload_validator_definitions() {
+    let mut removed_def = None;
+
+    ApiTester::new()
+        .await
+        .create_web3signer_validators(Web3SignerValidatorScenario {
+            count: 3,
+            enabled: true,
+        })
+        .await
+        .reload_definitions(|_, diff| assert_eq!(diff, DefinitionsDiff::default()))
+        .await
+        .edit_definitions_on_disk(|defs| {
+            defs.as_mut_slice()[0].enabled = false;
+            let def = defs.as_slice()[2].clone();
+            defs.retain(|d| d.voting_public_key != def.voting_public_key);
+            removed_def = Some(def);
+        })
+        .reload_definitions(|pubkeys, diff| {
+            assert_eq!(diff.disabled, vec![pubkeys[0]]);
+            assert_eq!(diff.removed.len(), 1);
+            assert!(diff.added.is_empty() && diff.enabled.is_empty() && diff.updated.is_empty());
+        })
+        .await
+        .assert_enabled_validators_count(1)
+        .assert_validators_count(2)
+        .set_validator_index(1, 42)
+        .edit_definitions_on_disk(|defs| {
+            defs.as_mut_slice()[0].enabled = true;
+            defs.as_mut_slice()[1].graffiti = Some(GraffitiString::from_str("reloaded").unwrap());
+            defs.push(removed_def.take().unwrap());
+        })
+        .reload_definitions(|pubkeys, diff| {
+            assert_eq!(
+                diff,
+                DefinitionsDiff {
+                    added: vec![pubkeys[2]],
+                    enabled: vec![pubkeys[0]],
+                    updated: vec![pubkeys[1]],
+                    ..<_>::default()
+                }
+            );
+        })
+        .await
+        .assert_enabled_validators_count(3)
+        .assert_validators_count(3)
+        // The updated validator was modified in place, rather than being re-initialized.
+        .assert_validator_index(1, Some(42))
+        .assert_graffiti(1, "reloaded")
+        .await;
+}
@@ -5,6 +5,8 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use types::EthSpec;
 
 pub const SUCCESS: &str = "success";
+pub const ERROR: &str = "error";
+pub const DROPPED: &str = "dropped";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
@@ -53,6 +55,28 @@ lazy_static::lazy_static! {
         "vc_validators_total_count",
         "Number of total validators (enabled and disabled)"
     );
+    pub static ref VALIDATOR_DEFINITIONS_RELOADS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_validator_definitions_reloads_total",
+        "Total count of reloads of the validator definitions file after it changed on disk",
+        &["status"]
+    );
+    pub static ref VALIDATOR_DEFINITIONS_CHANGES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_validator_definitions_changes_total",
+        "Total count of validators changed by reloads of the validator definitions file",
+        &["change"]
+    );
+
+    pub static ref PROPOSER_CONFIG_LOADS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_proposer_config_loads_total",
+        "Total count of attempts to load the proposer config",
+        &["status"]
+    );
+
+    pub static ref WEBHOOK_NOTIFICATIONS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_webhook_notifications_total",
+        "Total count of webhook notifications by delivery status",
+        &["status"]
+    );
 
     pub static ref SIGNED_BLOCKS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "vc_signed_beacon_blocks_total",
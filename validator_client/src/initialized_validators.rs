@@ -8,7 +8,8 @@
 
 use crate::signing_method::SigningMethod;
 use account_utils::{
-    read_password, read_password_from_user, read_password_string,
+    keystore_rotation::{self, KeystoreKdf},
+    random_password_string, read_password, read_password_from_user, read_password_string,
     validator_definitions::{
         self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
         CONFIG_FILENAME,
@@ -117,6 +118,22 @@ pub enum Error {
     UnableToSaveKeyCache(key_cache::Error),
     UnableToDecryptKeyCache(key_cache::Error),
     UnableToDeletePasswordFile(PathBuf, io::Error),
+    /// There was an error reading the on-disk validator definitions file.
+    UnableToOpenDefinitions(validator_definitions::Error),
+    /// There was an error searching the validators directory for new keystores.
+    UnableToDiscoverKeystores(validator_definitions::Error),
+    /// A reload would enable a local keystore whose password is not available on disk, which
+    /// would require prompting the user.
+    PasswordUnavailableForReload(PublicKey),
+    /// The keystore password is not stored by the validator client, so a new password must be
+    /// supplied in order to re-encrypt the keystore.
+    ReencryptionPasswordRequired(PublicKey),
+    /// The keystore could not be re-encrypted.
+    UnableToReencryptKeystore(keystore_rotation::Error),
+    /// The keystore of the validator was replaced whilst it was being re-encrypted.
+    KeystoreChangedDuringReencryption(PublicKey),
+    /// The validator definitions were modified whilst they were being reloaded.
+    DefinitionsChangedDuringReload,
 }
 
 impl From<LockfileError> for Error {
@@ -125,6 +142,47 @@ impl From<LockfileError> for Error {
     }
 }
 
+/// A local keystore which is ready to be re-encrypted, see
+/// `InitializedValidators::prepare_keystore_reencryption`.
+pub struct KeystoreReencryption {
+    voting_public_key: PublicKey,
+    voting_keypair: Keypair,
+    voting_keystore: Keystore,
+    password: ZeroizeString,
+    kdf: KeystoreKdf,
+}
+
+impl KeystoreReencryption {
+    /// Encrypts the keystore with the new password and KDF.
+    ///
+    /// This is slow, so it must be called without holding a lock on `InitializedValidators`.
+    pub fn encrypt(self) -> Result<ReencryptedKeystore, Error> {
+        let keystore = keystore_rotation::encrypt_keystore(
+            &self.voting_keypair,
+            &self.voting_keystore,
+            &self.password,
+            self.kdf,
+        )
+        .map_err(Error::UnableToReencryptKeystore)?;
+
+        Ok(ReencryptedKeystore {
+            voting_public_key: self.voting_public_key,
+            previous_keystore: self.voting_keystore,
+            keystore,
+            password: self.password,
+        })
+    }
+}
+
+/// A keystore which has been re-encrypted but not yet written to disk, see
+/// `InitializedValidators::replace_keystore`.
+pub struct ReencryptedKeystore {
+    voting_public_key: PublicKey,
+    previous_keystore: Keystore,
+    keystore: Keystore,
+    password: ZeroizeString,
+}
+
 /// A validator that is ready to sign messages.
 pub struct InitializedValidator {
     signing_method: Arc<SigningMethod>,
@@ -357,6 +415,16 @@ impl InitializedValidator {
         })
     }
 
+    /// Updates the fields of this validator which do not affect how it signs from `def`.
+    fn update_from_definition(&mut self, def: &ValidatorDefinition) {
+        self.graffiti = def.graffiti.clone().map(Into::into);
+        self.suggested_fee_recipient = def.suggested_fee_recipient;
+        self.gas_limit = def.gas_limit;
+        self.builder_proposals = def.builder_proposals;
+        self.builder_boost_factor = def.builder_boost_factor;
+        self.prefer_builder_proposals = def.prefer_builder_proposals;
+    }
+
     /// Returns the voting public key for this validator.
     pub fn voting_public_key(&self) -> &PublicKey {
         match self.signing_method.as_ref() {
@@ -471,6 +539,151 @@ fn unlock_keystore_via_stdin_password(
     }
 }
 
+/// The changes between two sets of validator definitions, as applied by
+/// `InitializedValidators::reload_definitions`.
+///
+/// Each validator appears in at most one of the lists.
+#[derive(Debug, Default, PartialEq)]
+pub struct DefinitionsDiff {
+    /// Validators with a new definition, which may or may not be enabled.
+    pub added: Vec<PublicKeyBytes>,
+    /// Validators whose definition was removed.
+    pub removed: Vec<PublicKeyBytes>,
+    /// Validators whose existing definition was enabled.
+    pub enabled: Vec<PublicKeyBytes>,
+    /// Validators whose existing definition was disabled.
+    pub disabled: Vec<PublicKeyBytes>,
+    /// Validators whose definition was modified without changing whether it is enabled (e.g., a
+    /// new fee recipient).
+    pub updated: Vec<PublicKeyBytes>,
+}
+
+impl DefinitionsDiff {
+    /// Compute the changes required to go from the `old` definitions to the `new` ones.
+    ///
+    /// Only the first definition for each public key is considered, matching the behaviour of
+    /// `InitializedValidators::update_validators`.
+    pub fn new(old: &[ValidatorDefinition], new: &[ValidatorDefinition]) -> Self {
+        let first_by_pubkey = |defs: &[ValidatorDefinition]| {
+            let mut map = HashMap::with_capacity(defs.len());
+            for def in defs {
+                map.entry(def.voting_public_key.compress()).or_insert(def);
+            }
+            map
+        };
+        let old_defs = first_by_pubkey(old);
+        let new_defs = first_by_pubkey(new);
+
+        let mut diff = Self::default();
+        for (pubkey, new_def) in &new_defs {
+            match old_defs.get(pubkey) {
+                None => diff.added.push(*pubkey),
+                Some(old_def) if old_def.enabled != new_def.enabled => {
+                    if new_def.enabled {
+                        diff.enabled.push(*pubkey)
+                    } else {
+                        diff.disabled.push(*pubkey)
+                    }
+                }
+                Some(old_def) if old_def != new_def => diff.updated.push(*pubkey),
+                Some(_) => (),
+            }
+        }
+        diff.removed = old_defs
+            .keys()
+            .filter(|pubkey| !new_defs.contains_key(pubkey))
+            .copied()
+            .collect();
+
+        diff
+    }
+
+    /// Returns `true` if there are no changes.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.enabled.is_empty()
+            && self.disabled.is_empty()
+            && self.updated.is_empty()
+    }
+}
+
+/// Returns `true` if both signing definitions use the same keystore or remote signer, so a
+/// validator does not need to be re-initialized when changing from `old` to `new`.
+///
+/// A local keystore is identified by its path, since changing only its password does not change
+/// the keypair.
+fn same_signer(old: &SigningDefinition, new: &SigningDefinition) -> bool {
+    match (old, new) {
+        (
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path: old_path,
+                ..
+            },
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path: new_path,
+                ..
+            },
+        ) => old_path == new_path,
+        (old, new) => old == new,
+    }
+}
+
+/// A reload of the validator definitions, see `InitializedValidators::prepare_reload`.
+pub struct DefinitionsReload {
+    /// The definitions which the changes were computed from.
+    previous_definitions: Vec<ValidatorDefinition>,
+    /// The definitions read from disk.
+    definitions: ValidatorDefinitions,
+    diff: DefinitionsDiff,
+    /// The definitions of validators which must be initialized.
+    initialize_definitions: Vec<ValidatorDefinition>,
+    /// The validators which have been initialized by `Self::initialize`.
+    initialized: HashMap<PublicKeyBytes, InitializedValidator>,
+    web3_signer_client_map: Option<HashMap<Web3SignerDefinition, Client>>,
+    config: Config,
+}
+
+impl DefinitionsReload {
+    /// Initializes the validators which were added, enabled or changed their signer, decrypting
+    /// any local keystores.
+    ///
+    /// This is slow, so it must be called without holding a lock on `InitializedValidators`. The
+    /// key cache is not used, so validators initialized by a reload are added to the key cache
+    /// the next time the validator client starts.
+    pub async fn initialize(mut self) -> Result<Self, Error> {
+        let mut key_cache = KeyCache::new();
+        let mut key_stores = HashMap::new();
+        for def in std::mem::take(&mut self.initialize_definitions) {
+            let pubkey = def.voting_public_key.compress();
+            let validator = InitializedValidator::from_definition(
+                def,
+                &mut key_cache,
+                &mut key_stores,
+                &mut self.web3_signer_client_map,
+                &self.config,
+            )
+            .await?;
+            self.initialized.insert(pubkey, validator);
+        }
+        Ok(self)
+    }
+
+    /// Returns the validators which were added or enabled and have been initialized by
+    /// `Self::initialize`, i.e., those which will be able to sign once the reload is applied.
+    pub fn newly_enabled(&self) -> impl Iterator<Item = (&PublicKeyBytes, &SigningMethod)> {
+        self.diff
+            .added
+            .iter()
+            .chain(&self.diff.enabled)
+            .filter_map(|pubkey| {
+                self.initialized
+                    .get(pubkey)
+                    .map(|validator| (pubkey, validator.signing_method.as_ref()))
+            })
+    }
+}
+
 /// A set of `InitializedValidator` objects which is initialized from a list of
 /// `ValidatorDefinition`. The `ValidatorDefinition` file is maintained as `self` is modified.
 ///
@@ -723,6 +936,161 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Prepares the keystore of the local keystore validator with `voting_public_key` to be
+    /// re-encrypted using `kdf` and either `password` or a new random password.
+    ///
+    /// ## Notes
+    ///
+    /// Re-encryption is split into three steps so that the slow encryption does not block signing:
+    ///
+    /// 1. This function copies the keystore whilst holding a read lock.
+    /// 2. `KeystoreReencryption::encrypt` encrypts the keystore without holding any lock.
+    /// 3. `Self::replace_keystore` writes the new keystore to disk and swaps it in memory, whilst
+    ///    holding a write lock.
+    ///
+    /// The password is stored in the same manner as before: either in the password file or in the
+    /// validator definition. If the password is not stored by the validator client then `password`
+    /// must be provided.
+    pub fn prepare_keystore_reencryption(
+        &self,
+        voting_public_key: &PublicKey,
+        password: Option<ZeroizeString>,
+        kdf: KeystoreKdf,
+    ) -> Result<KeystoreReencryption, Error> {
+        let def = self
+            .definitions
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == *voting_public_key)
+            .ok_or_else(|| Error::ValidatorNotInitialized(voting_public_key.clone()))?;
+        let validator = self
+            .validators
+            .get(&PublicKeyBytes::from(voting_public_key))
+            .ok_or_else(|| Error::ValidatorNotInitialized(voting_public_key.clone()))?;
+
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_password_path,
+            voting_keystore_password,
+            ..
+        } = &def.signing_definition
+        else {
+            return Err(Error::InvalidActionOnValidator);
+        };
+        let SigningMethod::LocalKeystore {
+            voting_keystore,
+            voting_keypair,
+            ..
+        } = validator.signing_method.as_ref()
+        else {
+            return Err(Error::InvalidActionOnValidator);
+        };
+
+        let password = match password {
+            Some(password) => password,
+            None if voting_keystore_password_path.is_some()
+                || voting_keystore_password.is_some() =>
+            {
+                random_password_string()
+            }
+            None => {
+                return Err(Error::ReencryptionPasswordRequired(
+                    voting_public_key.clone(),
+                ))
+            }
+        };
+
+        Ok(KeystoreReencryption {
+            voting_public_key: voting_public_key.clone(),
+            voting_keypair: voting_keypair.clone(),
+            voting_keystore: voting_keystore.clone(),
+            password,
+            kdf,
+        })
+    }
+
+    /// Replaces the keystore of a validator with a keystore which was re-encrypted by
+    /// `KeystoreReencryption::encrypt`.
+    ///
+    /// The existing keystore and password file are backed up before being replaced on disk. The
+    /// in-memory keystore is then swapped for the new one, so the validator continues to perform
+    /// its duties without interruption. If the password is stored in the validator definition, the
+    /// definitions are saved to disk.
+    ///
+    /// An error is returned if the keystore of the validator changed after the re-encryption was
+    /// prepared.
+    pub fn replace_keystore(&mut self, reencrypted: ReencryptedKeystore) -> Result<(), Error> {
+        let ReencryptedKeystore {
+            voting_public_key,
+            previous_keystore,
+            keystore: new_keystore,
+            password,
+        } = reencrypted;
+
+        let def = self
+            .definitions
+            .as_mut_slice()
+            .iter_mut()
+            .find(|def| def.voting_public_key == voting_public_key)
+            .ok_or_else(|| Error::ValidatorNotInitialized(voting_public_key.clone()))?;
+        let validator = self
+            .validators
+            .get_mut(&PublicKeyBytes::from(&voting_public_key))
+            .ok_or_else(|| Error::ValidatorNotInitialized(voting_public_key.clone()))?;
+
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_password_path,
+            voting_keystore_password,
+            ..
+        } = &mut def.signing_definition
+        else {
+            return Err(Error::InvalidActionOnValidator);
+        };
+        let SigningMethod::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore_lockfile,
+            voting_keystore,
+            voting_keypair,
+        } = validator.signing_method.as_ref()
+        else {
+            return Err(Error::InvalidActionOnValidator);
+        };
+        if *voting_keystore != previous_keystore {
+            return Err(Error::KeystoreChangedDuringReencryption(voting_public_key));
+        }
+
+        keystore_rotation::replace_keystore(
+            &new_keystore,
+            voting_keystore_path,
+            &password,
+            voting_keystore_password_path.as_deref(),
+        )
+        .map_err(Error::UnableToReencryptKeystore)?;
+
+        // Hand the lockfile over to the new signing method so that it is held throughout.
+        let signing_method = Arc::new(SigningMethod::LocalKeystore {
+            voting_keystore_path: voting_keystore_path.clone(),
+            voting_keystore_lockfile: Mutex::new(voting_keystore_lockfile.lock().take()),
+            voting_keystore: new_keystore,
+            voting_keypair: voting_keypair.clone(),
+        });
+        validator.signing_method = signing_method;
+
+        if voting_keystore_password.is_some() {
+            *voting_keystore_password = Some(password);
+            self.definitions
+                .save(&self.validators_dir)
+                .map_err(Error::UnableToSaveDefinitions)?;
+        }
+
+        info!(
+            self.log,
+            "Re-encrypted validator keystore";
+            "voting_pubkey" => format!("{:?}", voting_public_key)
+        );
+
+        Ok(())
+    }
+
     /// Returns a slice of all defined validators (regardless of their enabled state).
     pub fn validator_definitions(&self) -> &[ValidatorDefinition] {
         self.definitions.as_slice()
@@ -1183,6 +1551,177 @@ impl InitializedValidators {
         .map_err(Error::UnableToDecryptKeyCache)
     }
 
+    /// Re-reads the validator definitions file from disk and determines the changes required for
+    /// `self` to match it. If auto-discovery is enabled, any new keystores in the validators
+    /// directory are added to the definitions first.
+    ///
+    /// Reloading is split into three steps so that no lock is held whilst keystores are decrypted:
+    ///
+    /// 1. This function reads the definitions whilst holding a read lock.
+    /// 2. `DefinitionsReload::initialize` initializes new validators without holding any lock.
+    /// 3. `Self::apply_reload` enables, disables and updates validators whilst holding a write lock.
+    ///
+    /// Returns an error if a newly enabled local keystore would require the user to be prompted
+    /// for its password.
+    pub fn prepare_reload(&self) -> Result<DefinitionsReload, Error> {
+        let mut definitions = ValidatorDefinitions::open(&self.validators_dir)
+            .map_err(Error::UnableToOpenDefinitions)?;
+
+        if !self.config.disable_auto_discover {
+            let new_validators = definitions
+                .discover_local_keystores(
+                    self.validators_dir.as_path(),
+                    self.config.secrets_dir.as_path(),
+                    &self.log,
+                )
+                .map_err(Error::UnableToDiscoverKeystores)?;
+            if new_validators > 0 {
+                definitions
+                    .save(&self.validators_dir)
+                    .map_err(Error::UnableToSaveDefinitions)?;
+            }
+        }
+
+        let diff = DefinitionsDiff::new(self.definitions.as_slice(), definitions.as_slice());
+
+        // Updated validators are only re-initialized if their keystore or remote signer changed,
+        // other changes are applied in place.
+        let old_signing_definitions = self
+            .definitions
+            .as_slice()
+            .iter()
+            .rev()
+            .map(|def| (def.voting_public_key.compress(), &def.signing_definition))
+            .collect::<HashMap<_, _>>();
+        let updated = diff.updated.iter().collect::<HashSet<_>>();
+        let mut to_initialize = diff
+            .added
+            .iter()
+            .chain(&diff.enabled)
+            .chain(&diff.updated)
+            .collect::<HashSet<_>>();
+        let mut initialize_definitions = vec![];
+        for def in definitions.as_slice() {
+            let pubkey = def.voting_public_key.compress();
+            if !def.enabled || !to_initialize.remove(&pubkey) {
+                continue;
+            }
+            let signing_changed = old_signing_definitions
+                .get(&pubkey)
+                .map_or(true, |old| !same_signer(old, &def.signing_definition));
+            if !updated.contains(&pubkey) || signing_changed {
+                initialize_definitions.push(def.clone());
+            }
+        }
+
+        for def in &initialize_definitions {
+            if let SigningDefinition::LocalKeystore {
+                voting_keystore_password: None,
+                voting_keystore_password_path,
+                ..
+            } = &def.signing_definition
+            {
+                if voting_keystore_password_path
+                    .as_ref()
+                    .map_or(true, |path| !path.exists())
+                {
+                    return Err(Error::PasswordUnavailableForReload(
+                        def.voting_public_key.clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(DefinitionsReload {
+            previous_definitions: self.definitions.as_slice().to_vec(),
+            definitions,
+            diff,
+            initialize_definitions,
+            initialized: HashMap::new(),
+            web3_signer_client_map: self.web3_signer_client_map.clone(),
+            config: self.config.clone(),
+        })
+    }
+
+    /// Applies a reload which was prepared by `Self::prepare_reload` and initialized by
+    /// `DefinitionsReload::initialize`.
+    ///
+    /// Validators whose definition has not changed are left untouched, so they continue to
+    /// perform duties throughout the reload. Validators whose definition changed without changing
+    /// their keystore or remote signer are updated in place.
+    ///
+    /// Returns an error without making any changes if `self.definitions` was modified after the
+    /// reload was prepared.
+    pub fn apply_reload(&mut self, reload: DefinitionsReload) -> Result<DefinitionsDiff, Error> {
+        if self.definitions.as_slice() != reload.previous_definitions.as_slice() {
+            return Err(Error::DefinitionsChangedDuringReload);
+        }
+        let DefinitionsReload {
+            definitions,
+            diff,
+            initialized,
+            web3_signer_client_map,
+            ..
+        } = reload;
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+
+        for pubkey in diff.removed.iter().chain(&diff.disabled) {
+            if self.validators.remove(pubkey).is_some() {
+                info!(
+                    self.log,
+                    "Disabled validator";
+                    "voting_pubkey" => ?pubkey
+                );
+            }
+        }
+
+        let mut to_update = diff.updated.iter().collect::<HashSet<_>>();
+        for def in definitions.as_slice() {
+            let pubkey = def.voting_public_key.compress();
+            if !to_update.remove(&pubkey) {
+                continue;
+            }
+            if !def.enabled {
+                self.validators.remove(&pubkey);
+            } else if let Some(validator) = self.validators.get_mut(&pubkey) {
+                validator.update_from_definition(def);
+            }
+        }
+
+        for (pubkey, mut validator) in initialized {
+            let signing_method = match validator.signing_method.as_ref() {
+                SigningMethod::LocalKeystore { .. } => "local_keystore",
+                SigningMethod::Web3Signer { .. } => "remote_signer",
+            };
+            if let Some(previous) = self.validators.remove(&pubkey) {
+                validator.index = previous.index;
+            }
+            self.validators.insert(pubkey, validator);
+            info!(
+                self.log,
+                "Enabled validator";
+                "signing_method" => signing_method,
+                "voting_pubkey" => ?pubkey,
+            );
+        }
+
+        self.web3_signer_client_map = web3_signer_client_map;
+        self.definitions = definitions;
+
+        set_gauge(
+            &crate::http_metrics::metrics::ENABLED_VALIDATORS_COUNT,
+            self.num_enabled() as i64,
+        );
+        set_gauge(
+            &crate::http_metrics::metrics::TOTAL_VALIDATORS_COUNT,
+            self.num_total() as i64,
+        );
+
+        Ok(diff)
+    }
+
     /// Scans `self.definitions` and attempts to initialize and validators which are not already
     /// initialized.
     ///
@@ -3,7 +3,9 @@ mod beacon_node_fallback;
 mod block_service;
 mod check_synced;
 mod cli;
+mod definitions_watcher;
 mod duties_service;
+mod exit_service;
 mod graffiti_file;
 mod http_metrics;
 mod key_cache;
@@ -17,14 +19,16 @@ pub mod config;
 mod doppelganger_service;
 pub mod http_api;
 pub mod initialized_validators;
+pub mod proposer_config;
 pub mod validator_store;
+pub mod webhook;
 
 pub use beacon_node_fallback::ApiTopic;
 pub use cli::cli_app;
 pub use config::Config;
 use initialized_validators::InitializedValidators;
 use lighthouse_metrics::set_gauge;
-use monitoring_api::{MonitoringHttpClient, ProcessType};
+use monitoring_api::{MonitoringClient, ProcessType};
 use sensitive_url::SensitiveUrl;
 pub use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 
@@ -32,7 +36,7 @@ use crate::beacon_node_fallback::{
     start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode, OfflineOnFailure,
     RequireSynced,
 };
-use crate::doppelganger_service::DoppelgangerService;
+use crate::doppelganger_service::{remote::RemoteChecks, DoppelgangerService};
 use crate::graffiti_file::GraffitiFile;
 use crate::initialized_validators::Error::UnableToOpenVotingKeystore;
 use account_utils::validator_definitions::ValidatorDefinitions;
@@ -42,6 +46,7 @@ use clap::ArgMatches;
 use duties_service::{sync::SyncDutiesMap, DutiesService};
 use environment::RuntimeContext;
 use eth2::{reqwest::ClientBuilder, types::Graffiti, BeaconNodeHttpClient, StatusCode, Timeouts};
+use exit_service::ExitService;
 use http_api::ApiSecret;
 use notifier::spawn_notifier;
 use parking_lot::RwLock;
@@ -64,6 +69,7 @@ use tokio::{
 };
 use types::{EthSpec, Hash256, PublicKeyBytes};
 use validator_store::ValidatorStore;
+use webhook::WebhookNotifier;
 
 /// The interval between attempts to contact the beacon node during startup.
 const RETRY_DELAY: Duration = Duration::from_secs(2);
@@ -96,6 +102,8 @@ pub struct ProductionValidatorClient<E: EthSpec> {
     sync_committee_service: SyncCommitteeService<SystemTimeSlotClock, E>,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
     preparation_service: PreparationService<SystemTimeSlotClock, E>,
+    exit_service: Arc<ExitService<SystemTimeSlotClock, E>>,
+    webhook: Option<Arc<WebhookNotifier>>,
     validator_store: Arc<ValidatorStore<SystemTimeSlotClock, E>>,
     slot_clock: SystemTimeSlotClock,
     http_api_listen_addr: Option<SocketAddr>,
@@ -180,16 +188,29 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         };
 
         // Start the explorer client which periodically sends validator process
-        // and system metrics to the configured endpoint.
+        // and system metrics to the configured destinations.
         if let Some(monitoring_config) = &config.monitoring_api {
             let monitoring_client =
-                MonitoringHttpClient::new(monitoring_config, context.log().clone())?;
+                MonitoringClient::new(monitoring_config, context.log().clone())?;
             monitoring_client.auto_update(
                 context.executor.clone(),
                 vec![ProcessType::Validator, ProcessType::System],
             );
         };
 
+        // Optionally send notifications about duties and beacon node health to a webhook.
+        let webhook = config
+            .webhook
+            .clone()
+            .map(|webhook_config| {
+                WebhookNotifier::new(
+                    webhook_config,
+                    &context.executor,
+                    context.service_context("webhook".into()).log().clone(),
+                )
+            })
+            .transpose()?;
+
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.validator_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
@@ -430,12 +451,23 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         start_fallback_updater_service(context.clone(), proposer_nodes.clone())?;
 
         let doppelganger_service = if config.enable_doppelganger_protection {
-            Some(Arc::new(DoppelgangerService::new(
+            let mut doppelganger_service = DoppelgangerService::new(
                 context
                     .service_context(DOPPELGANGER_SERVICE_NAME.into())
                     .log()
                     .clone(),
-            )))
+            );
+            if !config.doppelganger_peer_vcs.is_empty() || config.doppelganger_lock_dir.is_some() {
+                doppelganger_service = doppelganger_service.with_remote_checks(RemoteChecks::new(
+                    &config.doppelganger_peer_vcs,
+                    &config.doppelganger_peer_vc_tokens,
+                    config.doppelganger_lock_dir.clone(),
+                )?);
+            }
+            if let Some(webhook) = &webhook {
+                doppelganger_service = doppelganger_service.with_webhook(webhook.clone());
+            }
+            Some(Arc::new(doppelganger_service))
         } else {
             None
         };
@@ -495,7 +527,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
-            .graffiti_file(config.graffiti_file.clone());
+            .graffiti_file(config.graffiti_file.clone())
+            .webhook(webhook.clone());
 
         // If we have proposer nodes, add them to the block service builder.
         if proposer_nodes_num > 0 {
@@ -529,6 +562,14 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             context.service_context("sync_committee".into()),
         );
 
+        let exit_service = Arc::new(ExitService::new(
+            &config.validator_dir,
+            beacon_nodes.clone(),
+            slot_clock.clone(),
+            context.eth2_config.spec.clone(),
+            context.service_context("exit".into()).log().clone(),
+        )?);
+
         Ok(Self {
             context,
             duties_service,
@@ -537,6 +578,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             sync_committee_service,
             doppelganger_service,
             preparation_service,
+            exit_service,
+            webhook,
             validator_store,
             config,
             slot_clock,
@@ -561,6 +604,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 task_executor: self.context.executor.clone(),
                 api_secret,
                 validator_store: Some(self.validator_store.clone()),
+                exit_service: Some(self.exit_service.clone()),
                 validator_dir: Some(self.config.validator_dir.clone()),
                 secrets_dir: Some(self.config.secrets_dir.clone()),
                 graffiti_file: self.config.graffiti_file.clone(),
@@ -589,6 +633,16 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             None
         };
 
+        if let Some(source) = self.config.proposer_config.clone() {
+            proposer_config::start_proposer_config_service(
+                self.context.clone(),
+                self.validator_store.clone(),
+                source,
+                self.config.proposer_config_refresh_interval,
+            )
+            .map_err(|e| format!("Unable to start proposer config service: {}", e))?;
+        }
+
         // Wait until genesis has occurred.
         wait_for_genesis(&self.beacon_nodes, self.genesis_time, &self.context).await?;
 
@@ -638,6 +692,20 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             );
         }
 
+        self.exit_service
+            .clone()
+            .start_update_service(&self.context.executor);
+
+        if self.config.reload_validator_definitions {
+            definitions_watcher::start_definitions_watcher(
+                self.context.clone(),
+                self.duties_service.slot_clock.clone(),
+                self.validator_store.clone(),
+                self.config.validator_dir.clone(),
+                (!self.config.disable_auto_discover).then(|| self.config.secrets_dir.clone()),
+            );
+        }
+
         Ok(())
     }
 }
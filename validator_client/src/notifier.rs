@@ -1,26 +1,35 @@
+use crate::beacon_node_fallback::RequireSynced;
+use crate::doppelganger_service::DoppelgangerStatus;
 use crate::http_metrics;
-use crate::{DutiesService, ProductionValidatorClient};
+use crate::webhook::{WebhookEvent, WebhookEventKind, WebhookNotifier};
+use crate::{DutiesService, OfflineOnFailure, ProductionValidatorClient};
 use lighthouse_metrics::set_gauge;
-use slog::{error, info, Logger};
+use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::collections::HashSet;
 use tokio::time::{sleep, Duration};
-use types::EthSpec;
+use types::{Epoch, EthSpec};
 
 /// Spawns a notifier service which periodically logs information about the node.
 pub fn spawn_notifier<E: EthSpec>(client: &ProductionValidatorClient<E>) -> Result<(), String> {
     let context = client.context.service_context("notifier".into());
     let executor = context.executor.clone();
     let duties_service = client.duties_service.clone();
+    let webhook = client.webhook.clone();
 
     let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
 
     let interval_fut = async move {
         let log = context.log();
+        let mut webhook_state = WebhookState::default();
 
         loop {
             if let Some(duration_to_next_slot) = duties_service.slot_clock.duration_to_next_slot() {
                 sleep(duration_to_next_slot + slot_duration / 2).await;
                 notify(&duties_service, log).await;
+                if let Some(webhook) = &webhook {
+                    notify_webhook(&duties_service, webhook, &mut webhook_state, log).await;
+                }
             } else {
                 error!(log, "Failed to read slot clock");
                 // If we can't read the slot clock, just wait another slot.
@@ -129,3 +138,120 @@ async fn notify<T: SlotClock + 'static, E: EthSpec>(
         error!(log, "Unable to read slot clock");
     }
 }
+
+/// Tracks the checks made by `notify_webhook` so that each event is only sent once.
+#[derive(Default)]
+struct WebhookState {
+    /// The beacon node in use at the previous check, or `None` before the first check.
+    beacon_node: Option<Option<String>>,
+    /// The epoch in which the attestations of the previous epoch were last checked.
+    attestations_checked_epoch: Option<Epoch>,
+}
+
+/// Sends the events which are detected by polling to the webhook.
+async fn notify_webhook<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    webhook: &WebhookNotifier,
+    state: &mut WebhookState,
+    log: &Logger,
+) {
+    if webhook.is_enabled(WebhookEventKind::BeaconNodeSwitched) {
+        let current = duties_service.beacon_nodes.first_synced_candidate().await;
+        if let Some(previous) = state.beacon_node.replace(current.clone()) {
+            if previous != current {
+                webhook.notify(WebhookEvent::BeaconNodeSwitched { previous, current });
+            }
+        }
+    }
+
+    let Some(slot) = duties_service.slot_clock.now() else {
+        return;
+    };
+
+    if webhook.is_enabled(WebhookEventKind::ProposalUpcoming) {
+        let next_slot = slot + 1;
+        for validator_pubkey in duties_service.block_proposers(next_slot) {
+            webhook.notify(WebhookEvent::ProposalUpcoming {
+                slot: next_slot,
+                validator_pubkey,
+            });
+        }
+    }
+
+    // Check the previous epoch once per epoch, skipping the epoch in which the validator client
+    // started since its duties may not have been known for the whole of the previous epoch.
+    let epoch = slot.epoch(E::slots_per_epoch());
+    if webhook.is_enabled(WebhookEventKind::AttestationMissed)
+        && state
+            .attestations_checked_epoch
+            .replace(epoch)
+            .is_some_and(|checked_epoch| checked_epoch < epoch)
+    {
+        notify_missed_attestations(duties_service, webhook, epoch - 1, log).await;
+    }
+}
+
+/// Notifies the webhook of the local validators with attestation duties in `epoch` which the beacon
+/// node has not seen attest or propose during `epoch`.
+async fn notify_missed_attestations<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    webhook: &WebhookNotifier,
+    epoch: Epoch,
+    log: &Logger,
+) {
+    // Only check validators that are considered safe in terms of doppelganger protection, since
+    // the others are not permitted to attest.
+    let signing_pubkeys: HashSet<_> = duties_service
+        .validator_store
+        .voting_pubkeys(DoppelgangerStatus::only_safe);
+    let validator_indices = duties_service
+        .attesters
+        .read()
+        .values()
+        .filter_map(|map| map.get(&epoch))
+        .map(|(_, duty_and_proof)| &duty_and_proof.duty)
+        .filter(|duty| signing_pubkeys.contains(&duty.pubkey))
+        .map(|duty| duty.validator_index)
+        .collect::<Vec<_>>();
+
+    if validator_indices.is_empty() {
+        return;
+    }
+
+    let result = duties_service
+        .beacon_nodes
+        .first_success(
+            RequireSynced::Yes,
+            OfflineOnFailure::Yes,
+            |beacon_node| async {
+                beacon_node
+                    .post_validator_liveness_epoch(epoch, &validator_indices)
+                    .await
+            },
+        )
+        .await;
+
+    match result {
+        Ok(response) => {
+            let mut missed = response
+                .data
+                .into_iter()
+                .filter(|liveness| !liveness.is_live)
+                .map(|liveness| liveness.index)
+                .collect::<Vec<_>>();
+            if !missed.is_empty() {
+                missed.sort_unstable();
+                webhook.notify(WebhookEvent::AttestationMissed {
+                    epoch,
+                    validator_indices: missed,
+                });
+            }
+        }
+        Err(e) => warn!(
+            log,
+            "Unable to check for missed attestations";
+            "epoch" => %epoch,
+            "error" => %e,
+        ),
+    }
+}
@@ -0,0 +1,258 @@
+//! Periodically loads a proposer config from a file or URL, allowing an external system to set the
+//! fee recipient, gas limit, builder preferences and graffiti of many validators at once.
+//!
+//! The proposer config is a JSON document of the form:
+//!
+//! ```json
+//! {
+//!     "proposer_config": {
+//!         "0xa1d1...": {
+//!             "fee_recipient": "0x...",
+//!             "gas_limit": 30000000,
+//!             "builder_proposals": true,
+//!             "builder_boost_factor": 90,
+//!             "graffiti": "..."
+//!         }
+//!     },
+//!     "default_config": {
+//!         "fee_recipient": "0x..."
+//!     }
+//! }
+//! ```
+//!
+//! Every field is optional. A config that fails to load or validate is rejected in its entirety,
+//! in which case the previous config remains in use. Valid configs are applied to the
+//! `ValidatorStore` atomically.
+
+use crate::http_metrics::metrics;
+use crate::ValidatorStore;
+use environment::RuntimeContext;
+use reqwest::Client;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slog::{info, warn};
+use slot_clock::SlotClock;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::{graffiti::GraffitiString, Address, EthSpec, PublicKeyBytes};
+
+/// The default interval between loads of the proposer config.
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The timeout for requests to a proposer config URL.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The location from which the proposer config is loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProposerConfigSource {
+    File(PathBuf),
+    Url(SensitiveUrl),
+}
+
+impl ProposerConfigSource {
+    /// Loads and validates the proposer config.
+    async fn load(&self, client: &Client) -> Result<ProposerConfig, String> {
+        let bytes = match self {
+            ProposerConfigSource::File(path) => {
+                fs::read(path).map_err(|e| format!("unable to read {:?}: {:?}", path, e))?
+            }
+            ProposerConfigSource::Url(url) => client
+                .get(url.full.clone())
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| format!("unable to fetch {}: {:?}", url, e))?
+                .bytes()
+                .await
+                .map_err(|e| format!("unable to fetch {}: {:?}", url, e))?
+                .to_vec(),
+        };
+
+        let config: ProposerConfig =
+            serde_json::from_slice(&bytes).map_err(|e| format!("invalid JSON: {:?}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+/// The proposal settings for a single validator, or the defaults for all validators.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerOptions {
+    pub fee_recipient: Option<Address>,
+    pub gas_limit: Option<u64>,
+    pub builder_proposals: Option<bool>,
+    pub builder_boost_factor: Option<u64>,
+    pub graffiti: Option<GraffitiString>,
+}
+
+impl ProposerOptions {
+    fn validate(&self) -> Result<(), String> {
+        if self.fee_recipient == Some(Address::zero()) {
+            return Err("fee_recipient must not be the zero address".to_string());
+        }
+        if self.gas_limit == Some(0) {
+            return Err("gas_limit must not be zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Proposal settings for individual validators, along with defaults for all validators.
+///
+/// For each setting, the value for a validator in `proposer_config` takes precedence over the
+/// value in `validator_definitions.yml`, which in turn takes precedence over the value in
+/// `default_config` and then the process-level flags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerConfig {
+    #[serde(default)]
+    pub proposer_config: HashMap<PublicKeyBytes, ProposerOptions>,
+    #[serde(default)]
+    pub default_config: ProposerOptions,
+}
+
+impl ProposerConfig {
+    /// Checks the settings of every validator, returning an error describing the first invalid
+    /// setting.
+    pub fn validate(&self) -> Result<(), String> {
+        for (pubkey, options) in &self.proposer_config {
+            options
+                .validate()
+                .map_err(|e| format!("invalid config for {:?}: {}", pubkey, e))?;
+        }
+        self.default_config
+            .validate()
+            .map_err(|e| format!("invalid default_config: {}", e))
+    }
+}
+
+/// Starts a service that loads the proposer config from `source` every `refresh_interval`,
+/// applying it to the `validator_store` whenever it changes.
+pub fn start_proposer_config_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    source: ProposerConfigSource,
+    refresh_interval: Duration,
+) -> Result<(), String> {
+    let log = context.log().clone();
+    let client = Client::builder()
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Unable to build proposer config HTTP client: {:?}", e))?;
+
+    let future = async move {
+        loop {
+            match source.load(&client).await {
+                Ok(config) => {
+                    metrics::inc_counter_vec(
+                        &metrics::PROPOSER_CONFIG_LOADS_TOTAL,
+                        &[metrics::SUCCESS],
+                    );
+                    let num_validators = config.proposer_config.len();
+                    if validator_store.set_proposer_config(config) {
+                        info!(
+                            log,
+                            "Updated proposer config";
+                            "validators" => num_validators,
+                        );
+                    }
+                }
+                Err(e) => {
+                    metrics::inc_counter_vec(
+                        &metrics::PROPOSER_CONFIG_LOADS_TOTAL,
+                        &[metrics::ERROR],
+                    );
+                    warn!(
+                        log,
+                        "Failed to load proposer config";
+                        "msg" => "the previous proposer config remains in use",
+                        "source" => ?source,
+                        "error" => e,
+                    );
+                }
+            }
+
+            sleep(refresh_interval).await;
+        }
+    };
+
+    context.executor.spawn(future, "proposer_config");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const PK1: &str = "0x800012708dc03f611751aad7a43a082142832b5c1aceed07ff9b543cf836381861352aa923c70eeb02018b638aa306aa";
+
+    #[test]
+    fn parse_proposer_config() {
+        let json = format!(
+            r#"{{
+                "proposer_config": {{
+                    "{PK1}": {{
+                        "fee_recipient": "0x00000000219ab540356cbb839cbe05303d7705fa",
+                        "gas_limit": 30000000,
+                        "builder_proposals": true,
+                        "builder_boost_factor": 90,
+                        "graffiti": "custom"
+                    }}
+                }},
+                "default_config": {{
+                    "gas_limit": 36000000
+                }}
+            }}"#
+        );
+        let config: ProposerConfig = serde_json::from_str(&json).unwrap();
+        config.validate().unwrap();
+
+        let options = &config.proposer_config[&PublicKeyBytes::from_str(PK1).unwrap()];
+        assert_eq!(
+            options.fee_recipient,
+            Some(Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa").unwrap())
+        );
+        assert_eq!(options.gas_limit, Some(30_000_000));
+        assert_eq!(options.builder_proposals, Some(true));
+        assert_eq!(options.builder_boost_factor, Some(90));
+        assert_eq!(
+            options.graffiti,
+            Some(GraffitiString::from_str("custom").unwrap())
+        );
+        assert_eq!(
+            config.default_config,
+            ProposerOptions {
+                gas_limit: Some(36_000_000),
+                ..<_>::default()
+            }
+        );
+    }
+
+    #[test]
+    fn reject_invalid_proposer_config() {
+        // Unknown fields are rejected, rather than silently ignored.
+        assert!(
+            serde_json::from_str::<ProposerConfig>(r#"{"default_config": {"gaslimit": 1}}"#)
+                .is_err()
+        );
+
+        let config: ProposerConfig = serde_json::from_str(&format!(
+            r#"{{"proposer_config": {{"{PK1}": {{"gas_limit": 0}}}}}}"#
+        ))
+        .unwrap();
+        assert!(config.validate().is_err());
+
+        let config: ProposerConfig = serde_json::from_str(
+            r#"{"default_config": {"fee_recipient": "0x0000000000000000000000000000000000000000"}}"#,
+        )
+        .unwrap();
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
     doppelganger_service::DoppelgangerService,
     http_metrics::metrics,
-    initialized_validators::InitializedValidators,
+    initialized_validators::{DefinitionsDiff, InitializedValidators},
+    proposer_config::{ProposerConfig, ProposerOptions},
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
     Config,
 };
-use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use account_utils::validator_definitions::{
+    PasswordStorage, SigningDefinition, ValidatorDefinition,
+};
 use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
     interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
@@ -74,6 +77,7 @@ pub struct ValidatorStore<T, E: EthSpec> {
     produce_block_v3: bool,
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
+    proposer_config: RwLock<ProposerConfig>,
     task_executor: TaskExecutor,
     _phantom: PhantomData<E>,
 }
@@ -109,6 +113,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             produce_block_v3: config.produce_block_v3,
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
+            proposer_config: RwLock::new(ProposerConfig::default()),
             task_executor,
             _phantom: PhantomData,
         }
@@ -123,6 +128,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             for pubkey in self.validators.read().iter_voting_pubkeys() {
                 doppelganger_service.register_new_validator::<E, _>(*pubkey, &self.slot_clock)?
             }
+            for pubkey in self.web3signer_voting_pubkeys::<Vec<_>>() {
+                doppelganger_service.register_remote_checks(pubkey);
+            }
         }
 
         Ok(())
@@ -199,6 +207,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         if let Some(doppelganger_service) = &self.doppelganger_service {
             doppelganger_service
                 .register_new_validator::<E, _>(validator_pubkey, &self.slot_clock)?;
+            if let SigningDefinition::Web3Signer(_) = validator_def.signing_definition {
+                doppelganger_service.register_remote_checks(validator_pubkey);
+            }
         }
 
         self.validators
@@ -210,23 +221,102 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Reload the validator definitions from disk, as per
+    /// `InitializedValidators::prepare_reload`.
+    ///
+    /// New keystores are decrypted without holding the validators lock. Validators which become
+    /// enabled are registered with the slashing protection database and the doppelganger service
+    /// before the reload is applied, so they cannot sign until doppelganger protection permits it.
+    /// If any registration fails, the reload is abandoned without enabling any validators.
+    pub async fn reload_validator_definitions(&self) -> Result<DefinitionsDiff, String> {
+        let reload = self.validators.read().prepare_reload();
+        let reload = reload
+            .map_err(|e| format!("Unable to reload validator definitions: {:?}", e))?
+            .initialize()
+            .await
+            .map_err(|e| format!("Unable to reload validator definitions: {:?}", e))?;
+
+        for (pubkey, signing_method) in reload.newly_enabled() {
+            self.slashing_protection
+                .register_validator(*pubkey)
+                .map_err(|e| format!("failed to register validator: {:?}", e))?;
+
+            if let Some(doppelganger_service) = &self.doppelganger_service {
+                doppelganger_service.register_new_validator::<E, _>(*pubkey, &self.slot_clock)?;
+                if let SigningMethod::Web3Signer { .. } = signing_method {
+                    doppelganger_service.register_remote_checks(*pubkey);
+                }
+            }
+        }
+
+        self.validators
+            .write()
+            .apply_reload(reload)
+            .map_err(|e| format!("Unable to reload validator definitions: {:?}", e))
+    }
+
     /// Returns `ProposalData` for the provided `pubkey` if it exists in `InitializedValidators`.
     /// `ProposalData` fields include defaulting logic described in `get_fee_recipient_defaulting`,
-    /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`.
+    /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`, as well as any values
+    /// from the proposer config.
     pub fn proposal_data(&self, pubkey: &PublicKeyBytes) -> Option<ProposalData> {
         self.validators
             .read()
             .validator(pubkey)
             .map(|validator| ProposalData {
                 validator_index: validator.get_index(),
-                fee_recipient: self
-                    .get_fee_recipient_defaulting(validator.get_suggested_fee_recipient()),
-                gas_limit: self.get_gas_limit_defaulting(validator.get_gas_limit()),
-                builder_proposals: self
-                    .get_builder_proposals_defaulting(validator.get_builder_proposals()),
+                fee_recipient: self.get_fee_recipient_defaulting(self.with_proposer_config(
+                    pubkey,
+                    validator.get_suggested_fee_recipient(),
+                    |options| options.fee_recipient,
+                )),
+                gas_limit: self.get_gas_limit_defaulting(self.with_proposer_config(
+                    pubkey,
+                    validator.get_gas_limit(),
+                    |options| options.gas_limit,
+                )),
+                builder_proposals: self.get_builder_proposals_defaulting(
+                    self.with_proposer_config(
+                        pubkey,
+                        validator.get_builder_proposals(),
+                        |options| options.builder_proposals,
+                    ),
+                ),
             })
     }
 
+    /// Replaces the proposer config, returning `false` if it is unchanged.
+    ///
+    /// The new config takes effect for all validators at once.
+    pub fn set_proposer_config(&self, proposer_config: ProposerConfig) -> bool {
+        let mut current = self.proposer_config.write();
+        if *current == proposer_config {
+            return false;
+        }
+        *current = proposer_config;
+        true
+    }
+
+    /// Resolves a per-validator setting using the proposer config. The priority order is:
+    ///
+    /// 1. the entry for `validator_pubkey` in the proposer config
+    /// 2. `validator_value`, from validator_definitions.yml
+    /// 3. the default in the proposer config
+    fn with_proposer_config<V>(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        validator_value: Option<V>,
+        field: impl Fn(&ProposerOptions) -> Option<V>,
+    ) -> Option<V> {
+        let proposer_config = self.proposer_config.read();
+        proposer_config
+            .proposer_config
+            .get(validator_pubkey)
+            .and_then(&field)
+            .or(validator_value)
+            .or_else(|| field(&proposer_config.default_config))
+    }
+
     /// Attempts to resolve the pubkey to a validator index.
     ///
     /// It may return `None` if the `pubkey` is:
@@ -274,6 +364,25 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .collect()
     }
 
+    /// Returns the voting pubkeys of all enabled validators which sign via Web3Signer.
+    pub fn web3signer_voting_pubkeys<I>(&self) -> I
+    where
+        I: FromIterator<PublicKeyBytes>,
+    {
+        let validators = self.validators.read();
+        validators
+            .iter_voting_pubkeys()
+            .filter(|pubkey| {
+                validators
+                    .signing_method(pubkey)
+                    .map_or(false, |signing_method| {
+                        matches!(*signing_method, SigningMethod::Web3Signer { .. })
+                    })
+            })
+            .copied()
+            .collect()
+    }
+
     /// Returns doppelganger statuses for all enabled validators.
     #[allow(clippy::needless_collect)] // Collect is required to avoid holding a lock.
     pub fn doppelganger_statuses(&self) -> Vec<DoppelgangerStatus> {
@@ -410,18 +519,29 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(signature)
     }
 
+    /// Returns the graffiti for the given public key, as described in `with_proposer_config`.
     pub fn graffiti(&self, validator_pubkey: &PublicKeyBytes) -> Option<Graffiti> {
-        self.validators.read().graffiti(validator_pubkey)
+        let graffiti = self.validators.read().graffiti(validator_pubkey);
+        self.with_proposer_config(validator_pubkey, graffiti, |options| {
+            options.graffiti.clone().map(Into::into)
+        })
     }
 
     /// Returns the fee recipient for the given public key. The priority order for fetching
     /// the fee recipient is:
-    /// 1. validator_definitions.yml
-    /// 2. process level fee recipient
+    /// 1. proposer config for the validator
+    /// 2. validator_definitions.yml
+    /// 3. proposer config default
+    /// 4. process level fee recipient
     pub fn get_fee_recipient(&self, validator_pubkey: &PublicKeyBytes) -> Option<Address> {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_fee_recipient_defaulting(self.suggested_fee_recipient(validator_pubkey))
+        let fee_recipient = self.suggested_fee_recipient(validator_pubkey);
+        self.get_fee_recipient_defaulting(self.with_proposer_config(
+            validator_pubkey,
+            fee_recipient,
+            |options| options.fee_recipient,
+        ))
     }
 
     pub fn get_fee_recipient_defaulting(&self, fee_recipient: Option<Address>) -> Option<Address> {
@@ -440,11 +560,18 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns the gas limit for the given public key. The priority order for fetching
     /// the gas limit is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level gas limit
-    /// 3. `DEFAULT_GAS_LIMIT`
+    /// 1. proposer config for the validator
+    /// 2. validator_definitions.yml
+    /// 3. proposer config default
+    /// 4. process level gas limit
+    /// 5. `DEFAULT_GAS_LIMIT`
     pub fn get_gas_limit(&self, validator_pubkey: &PublicKeyBytes) -> u64 {
-        self.get_gas_limit_defaulting(self.validators.read().gas_limit(validator_pubkey))
+        let gas_limit = self.validators.read().gas_limit(validator_pubkey);
+        self.get_gas_limit_defaulting(self.with_proposer_config(
+            validator_pubkey,
+            gas_limit,
+            |options| options.gas_limit,
+        ))
     }
 
     fn get_gas_limit_defaulting(&self, gas_limit: Option<u64>) -> u64 {
@@ -460,27 +587,48 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns a `bool` for the given public key that denotes whether this validator should use the
     /// builder API. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level flag
+    /// 1. proposer config for the validator
+    /// 2. validator_definitions.yml
+    /// 3. proposer config default
+    /// 4. process level flag
     pub fn get_builder_proposals(&self, validator_pubkey: &PublicKeyBytes) -> bool {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_builder_proposals_defaulting(
-            self.validators.read().builder_proposals(validator_pubkey),
-        )
+        self.get_builder_proposals_defaulting(self.validator_builder_proposals(validator_pubkey))
     }
 
     /// Returns a `u64` for the given public key that denotes the builder boost factor. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
-    /// 2. process level flag
+    /// 1. proposer config for the validator
+    /// 2. validator_definitions.yml
+    /// 3. proposer config default
+    /// 4. process level flag
     pub fn get_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
-        self.validators
-            .read()
-            .builder_boost_factor(validator_pubkey)
+        self.validator_builder_boost_factor(validator_pubkey)
             .or(self.builder_boost_factor)
     }
 
+    /// Returns the `builder_proposals` for the given public key, from the proposer config or
+    /// validator_definitions.yml.
+    fn validator_builder_proposals(&self, validator_pubkey: &PublicKeyBytes) -> Option<bool> {
+        let builder_proposals = self.validators.read().builder_proposals(validator_pubkey);
+        self.with_proposer_config(validator_pubkey, builder_proposals, |options| {
+            options.builder_proposals
+        })
+    }
+
+    /// Returns the `builder_boost_factor` for the given public key, from the proposer config or
+    /// validator_definitions.yml.
+    fn validator_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
+        let builder_boost_factor = self
+            .validators
+            .read()
+            .builder_boost_factor(validator_pubkey);
+        self.with_proposer_config(validator_pubkey, builder_boost_factor, |options| {
+            options.builder_boost_factor
+        })
+    }
+
     /// Returns a `bool` for the given public key that denotes whether this validator should prefer a
     /// builder payload. The priority order for fetching this value is:
     ///
@@ -520,12 +668,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return Some(u64::MAX);
         }
 
-        self.validators
-            .read()
-            .builder_boost_factor(validator_pubkey)
+        self.validator_builder_boost_factor(validator_pubkey)
             .or_else(|| {
                 if matches!(
-                    self.validators.read().builder_proposals(validator_pubkey),
+                    self.validator_builder_proposals(validator_pubkey),
                     Some(false)
                 ) {
                     return Some(0);
@@ -0,0 +1,371 @@
+//! Sends JSON notifications to a webhook when something happens that an operator may need to act
+//! upon, such as an upcoming or failed proposal, missed attestations, a doppelganger or the
+//! validator client switching to a different beacon node.
+//!
+//! Each notification is a JSON object of the form:
+//!
+//! ```json
+//! {
+//!     "event": "proposal_failed",
+//!     "timestamp": 1700000000,
+//!     "slot": "8000000",
+//!     "validator_pubkey": "0xa1d1...",
+//!     "error": "..."
+//! }
+//! ```
+//!
+//! Notifications are queued and delivered in order by a single task, which retries each failed
+//! delivery with an exponential backoff. Notifications are dropped if the queue is full, so that an
+//! unavailable webhook never holds up validator duties.
+
+use crate::http_metrics::metrics;
+use reqwest::Client;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slog::{debug, warn, Logger};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use strum::{EnumString, EnumVariantNames};
+use task_executor::TaskExecutor;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use types::{Epoch, PublicKeyBytes, Slot};
+
+/// The default number of times a failed delivery is retried.
+pub const DEFAULT_MAX_RETRIES: usize = 3;
+
+/// The timeout for each request to the webhook.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The delay before the first retry of a failed delivery, which doubles with each retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The maximum number of notifications waiting to be delivered.
+const QUEUE_SIZE: usize = 1_024;
+
+/// The kinds of notification which may be sent to the webhook.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, EnumString, EnumVariantNames,
+)]
+#[strum(serialize_all = "kebab-case")]
+pub enum WebhookEventKind {
+    ProposalUpcoming,
+    ProposalFailed,
+    AttestationMissed,
+    DoppelgangerDetected,
+    BeaconNodeSwitched,
+}
+
+impl WebhookEventKind {
+    pub fn all() -> Vec<WebhookEventKind> {
+        use WebhookEventKind::*;
+        vec![
+            ProposalUpcoming,
+            ProposalFailed,
+            AttestationMissed,
+            DoppelgangerDetected,
+            BeaconNodeSwitched,
+        ]
+    }
+}
+
+/// A notification to be sent to the webhook.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A local validator will propose in the next slot.
+    ProposalUpcoming {
+        slot: Slot,
+        validator_pubkey: PublicKeyBytes,
+    },
+    /// A local validator failed to propose a block.
+    ProposalFailed {
+        slot: Slot,
+        validator_pubkey: PublicKeyBytes,
+        error: String,
+    },
+    /// The beacon node has not seen attestations or blocks from local validators which had
+    /// attestation duties in the given epoch.
+    AttestationMissed {
+        epoch: Epoch,
+        validator_indices: Vec<u64>,
+    },
+    /// Another instance of local validators was detected on the network. The validator client will
+    /// shut down.
+    DoppelgangerDetected { validator_indices: Vec<u64> },
+    /// Requests are now being sent to a different beacon node. A `None` value indicates that no
+    /// beacon node is synced.
+    BeaconNodeSwitched {
+        previous: Option<String>,
+        current: Option<String>,
+    },
+}
+
+impl WebhookEvent {
+    pub fn kind(&self) -> WebhookEventKind {
+        match self {
+            WebhookEvent::ProposalUpcoming { .. } => WebhookEventKind::ProposalUpcoming,
+            WebhookEvent::ProposalFailed { .. } => WebhookEventKind::ProposalFailed,
+            WebhookEvent::AttestationMissed { .. } => WebhookEventKind::AttestationMissed,
+            WebhookEvent::DoppelgangerDetected { .. } => WebhookEventKind::DoppelgangerDetected,
+            WebhookEvent::BeaconNodeSwitched { .. } => WebhookEventKind::BeaconNodeSwitched,
+        }
+    }
+}
+
+/// The body of a request to the webhook.
+#[derive(Serialize)]
+struct Payload<'a> {
+    #[serde(flatten)]
+    event: &'a WebhookEvent,
+    /// The UNIX time at which the event occurred, in seconds.
+    timestamp: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: SensitiveUrl,
+    /// Only events of these kinds are sent to the webhook.
+    pub events: Vec<WebhookEventKind>,
+    /// The number of times a failed delivery is retried before the notification is dropped.
+    pub max_retries: usize,
+}
+
+/// Sends notifications to the webhook in `config`.
+pub struct WebhookNotifier {
+    client: Client,
+    config: WebhookConfig,
+    retry_delay: Duration,
+    queue_tx: mpsc::Sender<(WebhookEvent, u64)>,
+    log: Logger,
+}
+
+impl WebhookNotifier {
+    /// Creates a notifier and spawns the task which delivers its queued notifications.
+    pub fn new(
+        config: WebhookConfig,
+        executor: &TaskExecutor,
+        log: Logger,
+    ) -> Result<Arc<Self>, String> {
+        Self::with_retry_delay(config, INITIAL_RETRY_DELAY, executor, log)
+    }
+
+    fn with_retry_delay(
+        config: WebhookConfig,
+        retry_delay: Duration,
+        executor: &TaskExecutor,
+        log: Logger,
+    ) -> Result<Arc<Self>, String> {
+        let client = Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Unable to build webhook HTTP client: {:?}", e))?;
+        let (queue_tx, mut queue_rx) = mpsc::channel(QUEUE_SIZE);
+
+        let notifier = Arc::new(Self {
+            client,
+            config,
+            retry_delay,
+            queue_tx,
+            log,
+        });
+
+        let inner = notifier.clone();
+        executor.spawn(
+            async move {
+                while let Some((event, timestamp)) = queue_rx.recv().await {
+                    inner.deliver(&event, timestamp).await;
+                }
+            },
+            "webhook",
+        );
+
+        Ok(notifier)
+    }
+
+    /// Returns `true` if events of this `kind` are sent to the webhook.
+    pub fn is_enabled(&self, kind: WebhookEventKind) -> bool {
+        self.config.events.contains(&kind)
+    }
+
+    /// Queues `event` for delivery, unless its kind is filtered out.
+    pub fn notify(&self, event: WebhookEvent) {
+        if !self.is_enabled(event.kind()) {
+            return;
+        }
+
+        if let Err(e) = self.queue_tx.try_send((event, unix_timestamp())) {
+            metrics::inc_counter_vec(&metrics::WEBHOOK_NOTIFICATIONS_TOTAL, &[metrics::DROPPED]);
+            warn!(
+                self.log,
+                "Dropped webhook notification";
+                "msg" => "the webhook notification queue is full",
+                "error" => %e,
+            );
+        }
+    }
+
+    /// Delivers `event` immediately, bypassing the queue, unless its kind is filtered out.
+    ///
+    /// This should be used for events which are followed by a shutdown, which would otherwise
+    /// discard the queue.
+    pub async fn send(&self, event: WebhookEvent) {
+        if self.is_enabled(event.kind()) {
+            self.deliver(&event, unix_timestamp()).await;
+        }
+    }
+
+    /// Sends `event` to the webhook, retrying up to `max_retries` times.
+    async fn deliver(&self, event: &WebhookEvent, timestamp: u64) {
+        let payload = Payload { event, timestamp };
+        let mut retry_delay = self.retry_delay;
+
+        for attempt in 0..=self.config.max_retries {
+            if attempt > 0 {
+                sleep(retry_delay).await;
+                retry_delay *= 2;
+            }
+
+            match self
+                .client
+                .post(self.config.url.full.clone())
+                .json(&payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+            {
+                Ok(_) => {
+                    metrics::inc_counter_vec(
+                        &metrics::WEBHOOK_NOTIFICATIONS_TOTAL,
+                        &[metrics::SUCCESS],
+                    );
+                    return;
+                }
+                Err(e) => debug!(
+                    self.log,
+                    "Webhook request failed";
+                    "attempt" => attempt + 1,
+                    "event" => ?event.kind(),
+                    "error" => %e,
+                ),
+            }
+        }
+
+        metrics::inc_counter_vec(&metrics::WEBHOOK_NOTIFICATIONS_TOTAL, &[metrics::ERROR]);
+        warn!(
+            self.log,
+            "Failed to send webhook notification";
+            "attempts" => self.config.max_retries + 1,
+            "event" => ?event.kind(),
+            "url" => %self.config.url,
+        );
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use strum::VariantNames;
+    use task_executor::test_utils::TestRuntime;
+    use warp::Filter;
+
+    #[test]
+    fn event_kind_all() {
+        let all = WebhookEventKind::all();
+        assert_eq!(all.len(), WebhookEventKind::VARIANTS.len());
+        assert!(WebhookEventKind::VARIANTS
+            .iter()
+            .map(|kind| WebhookEventKind::from_str(kind).unwrap())
+            .eq(all.into_iter()));
+    }
+
+    /// Starts a local webhook receiver which fails the first `failures` requests, returning its
+    /// address and a channel of the successfully received payloads.
+    fn start_receiver(failures: usize) -> (SocketAddr, mpsc::UnboundedReceiver<serde_json::Value>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |payload: serde_json::Value| {
+                if requests.fetch_add(1, Ordering::SeqCst) < failures {
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR
+                } else {
+                    tx.send(payload).unwrap();
+                    warp::http::StatusCode::OK
+                }
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (addr, rx)
+    }
+
+    /// The `TestRuntime` must be kept alive for the notifier to deliver queued notifications.
+    fn notifier(
+        addr: SocketAddr,
+        events: Vec<WebhookEventKind>,
+    ) -> (TestRuntime, Arc<WebhookNotifier>) {
+        let runtime = TestRuntime::default();
+        let config = WebhookConfig {
+            url: SensitiveUrl::parse(&format!("http://{}", addr)).unwrap(),
+            events,
+            max_retries: 2,
+        };
+        let notifier = WebhookNotifier::with_retry_delay(
+            config,
+            Duration::from_millis(10),
+            &runtime.task_executor,
+            runtime.log.clone(),
+        )
+        .unwrap();
+        (runtime, notifier)
+    }
+
+    #[tokio::test]
+    async fn delivers_with_retries() {
+        let (addr, mut rx) = start_receiver(2);
+        let (_runtime, notifier) = notifier(addr, WebhookEventKind::all());
+
+        notifier.notify(WebhookEvent::AttestationMissed {
+            epoch: Epoch::new(7),
+            validator_indices: vec![1, 2],
+        });
+
+        let payload = rx.recv().await.unwrap();
+        assert_eq!(payload["event"], "attestation_missed");
+        assert_eq!(payload["epoch"], "7");
+        assert_eq!(payload["validator_indices"], serde_json::json!([1, 2]));
+        assert!(payload["timestamp"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn filters_events() {
+        let (addr, mut rx) = start_receiver(0);
+        let (_runtime, notifier) = notifier(addr, vec![WebhookEventKind::BeaconNodeSwitched]);
+
+        notifier
+            .send(WebhookEvent::DoppelgangerDetected {
+                validator_indices: vec![3],
+            })
+            .await;
+        notifier
+            .send(WebhookEvent::BeaconNodeSwitched {
+                previous: Some("http://localhost:5052/".into()),
+                current: None,
+            })
+            .await;
+
+        let payload = rx.recv().await.unwrap();
+        assert_eq!(payload["event"], "beacon_node_switched");
+        assert_eq!(payload["previous"], "http://localhost:5052/");
+        assert_eq!(payload["current"], serde_json::Value::Null);
+        assert!(rx.try_recv().is_err());
+    }
+}
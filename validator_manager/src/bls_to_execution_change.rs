@@ -0,0 +1,424 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::{eth2_keystore::keypair_from_secret, read_mnemonic_from_cli};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
+use eth2_wallet::{bip39::Seed, recover_validator_secret_from_mnemonic, KeyType};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use types::*;
+
+pub const CMD: &str = "bls-to-execution-change";
+pub const MNEMONIC_FLAG: &str = "mnemonic-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
+pub const VALIDATOR_INDICES_FLAG: &str = "validator-indices";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const BATCH_SIZE_FLAG: &str = "batch-size";
+pub const NO_CONFIRMATION_FLAG: &str = "no-confirmation";
+
+pub const WEBSITE_URL: &str =
+    "https://lighthouse-book.sigmaprime.io/validator-manager-bls-change.html";
+
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Changes the BLS withdrawal credentials of one or more validators to an execution \
+                address. The withdrawal keys are derived from a BIP-39 mnemonic and the current \
+                withdrawal credentials of each validator are checked against a beacon node \
+                before the signed changes are published to it.",
+        )
+        .arg(
+            Arg::new("help")
+                .long("help")
+                .short('h')
+                .help("Prints help information")
+                .action(ArgAction::HelpLong)
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+        .arg(
+            Arg::new(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help(
+                    "The key index of the first validator in --validator-indices. Subsequent \
+                    validators are assumed to use consecutive key indexes.",
+                )
+                .action(ArgAction::Set)
+                .required(false)
+                .default_value("0")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATOR_INDICES_FLAG)
+                .long(VALIDATOR_INDICES_FLAG)
+                .value_name("INDICES")
+                .help(
+                    "A comma-separated list of validator indices, in the same order as the \
+                    key indexes of the validators in the mnemonic.",
+                )
+                .required(true)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("ETH1_ADDRESS")
+                .help(
+                    "The execution address to which the withdrawal credentials of the \
+                    validators will be changed. This change is irreversible.",
+                )
+                .required(true)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. The current \
+                    withdrawal credentials of the validators are read from this node and the \
+                    signed changes are published to it.",
+                )
+                .required(true)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(BATCH_SIZE_FLAG)
+                .long(BATCH_SIZE_FLAG)
+                .value_name("INTEGER")
+                .help("The maximum number of changes to publish to the beacon node per request.")
+                .action(ArgAction::Set)
+                .default_value("64")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(NO_CONFIRMATION_FLAG)
+                .long(NO_CONFIRMATION_FLAG)
+                .help(
+                    "Publishes the changes without prompting to confirm the execution address. \
+                    This should be used with caution.",
+                )
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(STDIN_INPUTS_FLAG)
+                .action(ArgAction::SetTrue)
+                .hide(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty.")
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BlsToExecutionChangeConfig {
+    pub mnemonic_path: Option<PathBuf>,
+    pub first_index: u32,
+    pub validator_indices: Vec<u64>,
+    pub execution_address: Address,
+    pub bn_url: SensitiveUrl,
+    pub batch_size: usize,
+    pub no_confirmation: bool,
+    pub stdin_inputs: bool,
+}
+
+impl BlsToExecutionChangeConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validator_indices = matches
+            .get_one::<String>(VALIDATOR_INDICES_FLAG)
+            .ok_or_else(|| format!("Must supply --{VALIDATOR_INDICES_FLAG}."))?
+            .split(',')
+            .map(|index| {
+                index
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid validator index {}: {:?}", index, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let batch_size = clap_utils::parse_required(matches, BATCH_SIZE_FLAG)?;
+        if batch_size == 0 {
+            return Err(format!("--{} cannot be 0", BATCH_SIZE_FLAG));
+        }
+
+        Ok(Self {
+            mnemonic_path: clap_utils::parse_optional(matches, MNEMONIC_FLAG)?,
+            first_index: clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?,
+            validator_indices,
+            execution_address: clap_utils::parse_required(matches, EXECUTION_ADDRESS_FLAG)?,
+            bn_url: clap_utils::parse_required(matches, BEACON_NODE_FLAG)?,
+            batch_size,
+            no_confirmation: matches.get_flag(NO_CONFIRMATION_FLAG),
+            stdin_inputs: cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG),
+        })
+    }
+}
+
+pub async fn cli_run(
+    matches: &ArgMatches,
+    spec: &ChainSpec,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = BlsToExecutionChangeConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config, spec).await
+    }
+}
+
+/// Returns `Ok(true)` if `withdrawal_credentials` are the BLS credentials of
+/// `withdrawal_pubkey` and `Ok(false)` if they have already been changed to `execution_address`.
+///
+/// An error is returned if the credentials do not belong to `withdrawal_pubkey` or have been
+/// changed to some other execution address.
+fn requires_change(
+    withdrawal_credentials: Hash256,
+    withdrawal_pubkey: &PublicKey,
+    execution_address: Address,
+    spec: &ChainSpec,
+) -> Result<bool, String> {
+    if withdrawal_credentials == WithdrawalCredentials::eth1(execution_address, spec).into() {
+        Ok(false)
+    } else if withdrawal_credentials.as_bytes()[0] != spec.bls_withdrawal_prefix_byte {
+        Err(format!(
+            "withdrawal credentials {:?} have already been changed to a different address",
+            withdrawal_credentials
+        ))
+    } else if withdrawal_credentials != WithdrawalCredentials::bls(withdrawal_pubkey, spec).into() {
+        Err(format!(
+            "withdrawal credentials {:?} do not match the withdrawal key {:?} derived from the \
+            mnemonic. Check that --{} is correct.",
+            withdrawal_credentials, withdrawal_pubkey, FIRST_INDEX_FLAG
+        ))
+    } else {
+        Ok(true)
+    }
+}
+
+async fn run(config: BlsToExecutionChangeConfig, spec: &ChainSpec) -> Result<(), String> {
+    let BlsToExecutionChangeConfig {
+        mnemonic_path,
+        first_index,
+        validator_indices,
+        execution_address,
+        bn_url,
+        batch_size,
+        no_confirmation,
+        stdin_inputs,
+    } = config;
+
+    if validator_indices.is_empty() {
+        return Err(format!("--{} cannot be empty", VALIDATOR_INDICES_FLAG));
+    }
+
+    let bn_http_client =
+        BeaconNodeHttpClient::new(bn_url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT));
+
+    /*
+     * Ensure that the beacon node is on the same network, since the changes are signed over the
+     * genesis fork version and genesis validators root.
+     */
+    let genesis = bn_http_client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get genesis from beacon node: {:?}", e))?
+        .data;
+    if genesis.genesis_fork_version != spec.genesis_fork_version {
+        return Err("Beacon node appears to be on the wrong network".to_string());
+    }
+
+    let mut validators = Vec::with_capacity(validator_indices.len());
+    for indices in validator_indices.chunks(batch_size) {
+        let ids = indices
+            .iter()
+            .copied()
+            .map(ValidatorId::Index)
+            .collect::<Vec<_>>();
+        let mut data = bn_http_client
+            .get_beacon_states_validators(StateId::Head, Some(&ids), None)
+            .await
+            .map_err(|e| format!("Failed to get validators from beacon node: {:?}", e))?
+            .ok_or("Beacon node does not have a head state")?
+            .data;
+        for index in indices {
+            let position = data
+                .iter()
+                .position(|validator| validator.index == *index)
+                .ok_or_else(|| format!("Validator {} is unknown to the beacon node", index))?;
+            validators.push(data.swap_remove(position).validator);
+        }
+    }
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    /*
+     * Check the withdrawal credentials of every validator before signing or publishing anything.
+     */
+    let mut changes = vec![];
+    for (i, (validator_index, validator)) in validator_indices
+        .iter()
+        .copied()
+        .zip(&validators)
+        .enumerate()
+    {
+        let key_index = first_index
+            .checked_add(i as u32)
+            .ok_or("Key index overflow")?;
+        let (secret, _) =
+            recover_validator_secret_from_mnemonic(seed.as_bytes(), key_index, KeyType::Withdrawal)
+                .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+
+        let needs_change = requires_change(
+            validator.withdrawal_credentials,
+            &keypair.pk,
+            execution_address,
+            spec,
+        )
+        .map_err(|e| format!("Validator {}: {}", validator_index, e))?;
+
+        if needs_change {
+            let change = BlsToExecutionChange {
+                validator_index,
+                from_bls_pubkey: keypair.pk.compress(),
+                to_execution_address: execution_address,
+            };
+            changes.push(change.sign(&keypair.sk, genesis.genesis_validators_root, spec));
+        } else {
+            eprintln!(
+                "Validator {} already withdraws to {:?}, skipping",
+                validator_index, execution_address
+            );
+        }
+    }
+
+    if changes.is_empty() {
+        eprintln!("No withdrawal credentials need changing");
+        return Ok(());
+    }
+
+    eprintln!(
+        "Changing the withdrawal credentials of {} validators to {:?}",
+        changes.len(),
+        execution_address
+    );
+
+    if !no_confirmation {
+        eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+        eprintln!(
+            "PLEASE VISIT {} TO MAKE SURE YOU UNDERSTAND THE IMPLICATIONS OF A WITHDRAWAL \
+            CREDENTIALS CHANGE.",
+            WEBSITE_URL
+        );
+        eprintln!("Enter the execution address again to confirm the changes: ");
+        let confirmation = account_utils::read_input_from_user(stdin_inputs)?;
+        if confirmation.parse::<Address>().ok() != Some(execution_address) {
+            return Err(
+                "Did not publish withdrawal credential changes. The entered address does not \
+                match --execution-address."
+                    .to_string(),
+            );
+        }
+    }
+
+    let mut num_published = 0;
+    for batch in changes.chunks(batch_size) {
+        bn_http_client
+            .post_beacon_pool_bls_to_execution_changes(batch)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to publish changes after {} of {} were published: {:?}",
+                    num_published,
+                    changes.len(),
+                    e
+                )
+            })?;
+        num_published += batch.len();
+        eprintln!(
+            "Published {} of {} withdrawal credential changes",
+            num_published,
+            changes.len()
+        );
+    }
+
+    eprintln!(
+        "The changes will be applied once they are included in a block. The withdrawal \
+        credentials can be checked with the beacon node's /eth/v1/beacon/states/head/validators \
+        endpoint."
+    );
+
+    Ok(())
+}
+
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2_wallet::bip39::{Language, Mnemonic};
+
+    const TEST_VECTOR_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn withdrawal_pubkey(index: u32) -> PublicKey {
+        let mnemonic = Mnemonic::from_phrase(TEST_VECTOR_MNEMONIC, Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "");
+        let (secret, _) =
+            recover_validator_secret_from_mnemonic(seed.as_bytes(), index, KeyType::Withdrawal)
+                .unwrap();
+        keypair_from_secret(secret.as_bytes()).unwrap().pk
+    }
+
+    #[test]
+    fn check_withdrawal_credentials() {
+        let spec = MainnetEthSpec::default_spec();
+        let pubkey = withdrawal_pubkey(0);
+        let address = Address::repeat_byte(0x42);
+
+        let bls_credentials = WithdrawalCredentials::bls(&pubkey, &spec).into();
+        assert_eq!(
+            requires_change(bls_credentials, &pubkey, address, &spec),
+            Ok(true)
+        );
+
+        let eth1_credentials = WithdrawalCredentials::eth1(address, &spec).into();
+        assert_eq!(
+            requires_change(eth1_credentials, &pubkey, address, &spec),
+            Ok(false)
+        );
+
+        // Changed to a different address.
+        let other_credentials =
+            WithdrawalCredentials::eth1(Address::repeat_byte(0x01), &spec).into();
+        assert!(requires_change(other_credentials, &pubkey, address, &spec).is_err());
+
+        // Derived from the wrong key index.
+        assert!(requires_change(bls_credentials, &withdrawal_pubkey(1), address, &spec).is_err());
+    }
+}
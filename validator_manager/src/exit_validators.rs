@@ -0,0 +1,272 @@
+use super::common::*;
+use crate::DumpConfig;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
+use eth2::{
+    lighthouse_vc::types::{VoluntaryExitStatus, VoluntaryExitsRequest},
+    SensitiveUrl,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+use types::{Epoch, PublicKeyBytes};
+
+pub const CMD: &str = "exit";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const EXIT_EPOCH_FLAG: &str = "exit-epoch";
+pub const NO_CONFIRMATION_FLAG: &str = "no-confirmation";
+
+pub const CONFIRMATION_PHRASE: &str = "Exit my validator";
+pub const WEBSITE_URL: &str = "https://lighthouse-book.sigmaprime.io/voluntary-exit.html";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Signs voluntary exits for one or more validators on a validator client via its \
+                HTTP API. Exits for the current epoch are published immediately. Exits for a \
+                future epoch are held by the validator client and published once that epoch \
+                arrives. The projected exit and withdrawable epochs of each validator are \
+                reported, based on the exit queue of the beacon node's head state.",
+        )
+        .arg(
+            Arg::new("help")
+                .long("help")
+                .short('h')
+                .help("Prints help information")
+                .action(ArgAction::HelpLong)
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+        .arg(
+            Arg::new(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a validator client using the keymanager-API. \
+                    The validator client must be connected to a beacon node.",
+                )
+                .required(true)
+                .requires(VC_TOKEN_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to exit. Either a list of 0x-prefixed validator pubkeys \
+                    or the keyword \"all\" for all enabled validators.",
+                )
+                .required(true)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(EXIT_EPOCH_FLAG)
+                .long(EXIT_EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch at which the exits become valid. If this is a future epoch, \
+                    the validator client will publish the exits once it arrives. Defaults \
+                    to the current epoch.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(NO_CONFIRMATION_FLAG)
+                .long(NO_CONFIRMATION_FLAG)
+                .help(
+                    "Exits without prompting for confirmation that you understand the \
+                    implications of a voluntary exit. This should be used with caution.",
+                )
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(STDIN_INPUTS_FLAG)
+                .action(ArgAction::SetTrue)
+                .hide(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty.")
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Validators {
+    All,
+    Specific(Vec<PublicKeyBytes>),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExitConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: Validators,
+    pub exit_epoch: Option<Epoch>,
+    pub no_confirmation: bool,
+    pub stdin_inputs: bool,
+}
+
+impl ExitConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = match matches
+            .get_one::<String>(VALIDATORS_FLAG)
+            .map(String::as_str)
+        {
+            Some("all") => Validators::All,
+            Some(pubkeys) => pubkeys
+                .split(',')
+                .map(PublicKeyBytes::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Validators::Specific)?,
+            None => Err(format!("Must supply --{VALIDATORS_FLAG}."))?,
+        };
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators,
+            exit_epoch: clap_utils::parse_optional(matches, EXIT_EPOCH_FLAG)?,
+            no_confirmation: matches.get_flag(NO_CONFIRMATION_FLAG),
+            stdin_inputs: cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG),
+        })
+    }
+}
+
+pub async fn cli_run(matches: &ArgMatches, dump_config: DumpConfig) -> Result<(), String> {
+    let config = ExitConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: ExitConfig) -> Result<(), String> {
+    let ExitConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        exit_epoch,
+        no_confirmation,
+        stdin_inputs,
+    } = config;
+
+    let (http_client, _keystores) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    // Remote signers are included, since they are able to sign exits.
+    let enabled_pubkeys = http_client
+        .get_lighthouse_validators()
+        .await
+        .map_err(|e| format!("Failed to list validators on VC: {:?}", e))?
+        .data
+        .into_iter()
+        .filter(|validator| validator.enabled)
+        .map(|validator| validator.voting_pubkey)
+        .collect::<Vec<_>>();
+
+    let pubkeys = match validators {
+        Validators::All => enabled_pubkeys,
+        Validators::Specific(pubkeys) => {
+            if let Some(pubkey) = pubkeys
+                .iter()
+                .find(|pubkey| !enabled_pubkeys.contains(pubkey))
+            {
+                return Err(format!(
+                    "Validator {:?} is not enabled on the validator client",
+                    pubkey
+                ));
+            }
+            pubkeys
+        }
+    };
+
+    if pubkeys.is_empty() {
+        return Err("No validators to exit".to_string());
+    }
+
+    match exit_epoch {
+        Some(epoch) => eprintln!(
+            "Signing voluntary exits for {} validators at epoch {}",
+            pubkeys.len(),
+            epoch
+        ),
+        None => eprintln!(
+            "Signing voluntary exits for {} validators at the current epoch",
+            pubkeys.len()
+        ),
+    }
+
+    if !no_confirmation {
+        eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+        eprintln!(
+            "PLEASE VISIT {} TO MAKE SURE YOU UNDERSTAND THE IMPLICATIONS OF A VOLUNTARY EXIT.",
+            WEBSITE_URL
+        );
+        eprintln!("Enter the exit phrase from the above URL to confirm the voluntary exits: ");
+        let confirmation = account_utils::read_input_from_user(stdin_inputs)?;
+        if confirmation != CONFIRMATION_PHRASE {
+            return Err(
+                "Did not sign voluntary exits. Please check that you entered the correct \
+                exit phrase."
+                    .to_string(),
+            );
+        }
+    }
+
+    let exits = http_client
+        .post_lighthouse_validators_exits(&VoluntaryExitsRequest {
+            pubkeys,
+            epoch: exit_epoch,
+        })
+        .await
+        .map_err(|e| format!("Failed to sign voluntary exits: {:?}", e))?
+        .data;
+
+    for exit in &exits {
+        let status = match exit.status {
+            VoluntaryExitStatus::Published => "published",
+            VoluntaryExitStatus::Scheduled => "scheduled",
+        };
+        let projection = match (exit.projected_exit_epoch, exit.projected_withdrawable_epoch) {
+            (Some(exit_epoch), Some(withdrawable_epoch)) => format!(
+                "projected exit epoch {}, withdrawable epoch {}",
+                exit_epoch, withdrawable_epoch
+            ),
+            _ => "projected exit epoch unknown".to_string(),
+        };
+        eprintln!(
+            "Voluntary exit for validator {:?} at epoch {} {} ({})",
+            exit.pubkey, exit.signed_voluntary_exit.message.epoch, status, projection
+        );
+    }
+
+    let num_scheduled = exits
+        .iter()
+        .filter(|exit| exit.status == VoluntaryExitStatus::Scheduled)
+        .count();
+    if num_scheduled > 0 {
+        eprintln!(
+            "{} exits are held by the validator client, which will publish them at the start \
+            of the epoch in which they become valid",
+            num_scheduled
+        );
+    }
+
+    Ok(())
+}
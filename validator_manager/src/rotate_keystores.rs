@@ -0,0 +1,618 @@
+use super::common::*;
+use crate::DumpConfig;
+use account_utils::{
+    eth2_keystore::Keystore,
+    keystore_rotation::{reencrypt_keystore, KeystoreKdf, DEFAULT_PBKDF2_C, DEFAULT_SCRYPT_N},
+    random_password_string, read_password_string,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+    ZeroizeString,
+};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
+use eth2::{lighthouse_vc::types::KeystoreReencryptRequest, SensitiveUrl};
+use lockfile::Lockfile;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use types::PublicKeyBytes;
+
+pub const CMD: &str = "rotate-keystores";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_DIR_FLAG: &str = "validators-dir";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const KDF_FLAG: &str = "kdf";
+pub const KDF_COST_FLAG: &str = "kdf-cost";
+pub const NEW_PASSWORD_FILE_FLAG: &str = "new-password-file";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Re-encrypts validator keystores with a new password and/or key derivation \
+                function. Keystores can be re-encrypted whilst the validator client is running \
+                via its HTTP API, or directly in a validators directory whilst the validator \
+                client is stopped. The existing keystores and password files are backed up \
+                alongside the originals with a \".backup\" suffix. This command only supports \
+                validators signing via a keystore on the local file system (i.e., not \
+                Web3Signer validators).",
+        )
+        .arg(
+            Arg::new("help")
+                .long("help")
+                .short('h')
+                .help("Prints help information")
+                .action(ArgAction::HelpLong)
+                .display_order(0)
+                .help_heading(FLAG_HEADER),
+        )
+        .arg(
+            Arg::new(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a validator client using the keymanager-API. \
+                    The keystores of this validator client will be re-encrypted without \
+                    interrupting its duties.",
+                )
+                .requires(VC_TOKEN_FLAG)
+                .conflicts_with(VALIDATORS_DIR_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATORS_DIR_FLAG)
+                .long(VALIDATORS_DIR_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The validators directory of a validator client which is not running. \
+                    The keystores listed in its validator_definitions.yml file will be \
+                    re-encrypted in place.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators whose keystores will be re-encrypted. Either a list of \
+                    0x-prefixed validator pubkeys or the keyword \"all\".",
+                )
+                .required(true)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help("The key derivation function used to encrypt the new keystores.")
+                .value_parser(["scrypt", "pbkdf2"])
+                .default_value("scrypt")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("UINT32")
+                .help(
+                    "The cost parameter of the key derivation function. This is \"n\" for \
+                    scrypt, which must be a power of two, and the iteration count \"c\" for \
+                    pbkdf2. Defaults to 262144 for both.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(NEW_PASSWORD_FILE_FLAG)
+                .long(NEW_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "A file containing the new password for all re-encrypted keystores. If \
+                    this flag is not supplied then each keystore receives a new random \
+                    password, which is stored in the same manner as its existing password.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Validators {
+    All,
+    Specific(Vec<PublicKeyBytes>),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum RotateTarget {
+    /// Re-encrypt the keystores of a running validator client via its HTTP API.
+    ValidatorClient {
+        vc_url: SensitiveUrl,
+        vc_token_path: PathBuf,
+    },
+    /// Re-encrypt the keystores in the validators directory of a stopped validator client.
+    ValidatorsDir(PathBuf),
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RotateConfig {
+    pub target: RotateTarget,
+    pub validators: Validators,
+    pub kdf: KeystoreKdf,
+    pub new_password_path: Option<PathBuf>,
+}
+
+impl RotateConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let target = match (
+            clap_utils::parse_optional(matches, VC_URL_FLAG)?,
+            clap_utils::parse_optional(matches, VALIDATORS_DIR_FLAG)?,
+        ) {
+            (Some(vc_url), None) => RotateTarget::ValidatorClient {
+                vc_url,
+                vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            },
+            (None, Some(validators_dir)) => RotateTarget::ValidatorsDir(validators_dir),
+            _ => Err(format!(
+                "Must supply exactly one of --{VC_URL_FLAG} or --{VALIDATORS_DIR_FLAG}."
+            ))?,
+        };
+
+        let validators = match matches
+            .get_one::<String>(VALIDATORS_FLAG)
+            .map(String::as_str)
+        {
+            Some("all") => Validators::All,
+            Some(pubkeys) => pubkeys
+                .split(',')
+                .map(PublicKeyBytes::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Validators::Specific)?,
+            None => Err(format!("Must supply --{VALIDATORS_FLAG}."))?,
+        };
+
+        let kdf_cost = clap_utils::parse_optional(matches, KDF_COST_FLAG)?;
+        let kdf = match matches.get_one::<String>(KDF_FLAG).map(String::as_str) {
+            Some("pbkdf2") => KeystoreKdf::Pbkdf2 {
+                c: kdf_cost.unwrap_or(DEFAULT_PBKDF2_C),
+            },
+            _ => {
+                let n = kdf_cost.unwrap_or(DEFAULT_SCRYPT_N);
+                if n < 2 || !n.is_power_of_two() {
+                    return Err(format!(
+                        "--{KDF_COST_FLAG} must be a power of two greater than 1 for scrypt."
+                    ));
+                }
+                KeystoreKdf::Scrypt { n }
+            }
+        };
+
+        Ok(Self {
+            target,
+            validators,
+            kdf,
+            new_password_path: clap_utils::parse_optional(matches, NEW_PASSWORD_FILE_FLAG)?,
+        })
+    }
+}
+
+pub async fn cli_run(matches: &ArgMatches, dump_config: DumpConfig) -> Result<(), String> {
+    let config = RotateConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: RotateConfig) -> Result<(), String> {
+    let RotateConfig {
+        target,
+        validators,
+        kdf,
+        new_password_path,
+    } = config;
+
+    let new_password = new_password_path
+        .map(|path| {
+            read_password_string(&path)
+                .map_err(|e| format!("Unable to read password from {:?}: {}", path, e))
+        })
+        .transpose()?;
+
+    let count = match target {
+        RotateTarget::ValidatorClient {
+            vc_url,
+            vc_token_path,
+        } => rotate_via_vc(vc_url, &vc_token_path, validators, kdf, new_password).await?,
+        RotateTarget::ValidatorsDir(validators_dir) => {
+            rotate_in_validators_dir(&validators_dir, validators, kdf, new_password)?
+        }
+    };
+
+    eprintln!("Re-encrypted {} keystores", count);
+
+    Ok(())
+}
+
+/// Re-encrypts keystores via the HTTP API of a running validator client, which swaps in the new
+/// keystores without interrupting its duties.
+async fn rotate_via_vc(
+    vc_url: SensitiveUrl,
+    vc_token_path: &Path,
+    validators: Validators,
+    kdf: KeystoreKdf,
+    new_password: Option<ZeroizeString>,
+) -> Result<usize, String> {
+    let (http_client, keystores) = vc_http_client(vc_url, vc_token_path).await?;
+
+    let pubkeys = match validators {
+        Validators::All => keystores.iter().map(|k| k.validating_pubkey).collect(),
+        Validators::Specific(pubkeys) => {
+            if let Some(pubkey) = pubkeys
+                .iter()
+                .find(|pubkey| !keystores.iter().any(|k| k.validating_pubkey == **pubkey))
+            {
+                return Err(format!(
+                    "Validator {:?} does not have a keystore on the validator client",
+                    pubkey
+                ));
+            }
+            pubkeys
+        }
+    };
+
+    for pubkey in &pubkeys {
+        let request = KeystoreReencryptRequest {
+            password: new_password.clone(),
+            kdf,
+        };
+        http_client
+            .post_lighthouse_validators_reencrypt(pubkey, &request)
+            .await
+            .map_err(|e| format!("Failed to re-encrypt keystore for {:?}: {:?}", pubkey, e))?;
+        eprintln!("Re-encrypted keystore for validator {:?}", pubkey);
+    }
+
+    Ok(pubkeys.len())
+}
+
+/// Re-encrypts keystores in a validators directory, updating any passwords stored in the
+/// validator definitions file.
+///
+/// The keystore lockfiles are held during re-encryption, which prevents this from running whilst
+/// a validator client is using the keystores.
+fn rotate_in_validators_dir(
+    validators_dir: &Path,
+    validators: Validators,
+    kdf: KeystoreKdf,
+    new_password: Option<ZeroizeString>,
+) -> Result<usize, String> {
+    let mut definitions = ValidatorDefinitions::open(validators_dir).map_err(|e| {
+        format!(
+            "Unable to open {} in {:?}: {:?}",
+            CONFIG_FILENAME, validators_dir, e
+        )
+    })?;
+
+    let pubkeys = match validators {
+        Validators::All => definitions
+            .as_slice()
+            .iter()
+            .filter(|def| def.signing_definition.is_local_keystore())
+            .map(|def| def.voting_public_key.compress())
+            .collect(),
+        Validators::Specific(pubkeys) => pubkeys,
+    };
+
+    for pubkey in &pubkeys {
+        let def = definitions
+            .as_mut_slice()
+            .iter_mut()
+            .find(|def| def.voting_public_key.compress() == *pubkey)
+            .ok_or_else(|| format!("Validator {:?} is not in {}", pubkey, CONFIG_FILENAME))?;
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore_password_path,
+            voting_keystore_password,
+        } = &mut def.signing_definition
+        else {
+            return Err(format!(
+                "Validator {:?} does not use a local keystore",
+                pubkey
+            ));
+        };
+
+        let old_password = match (&voting_keystore_password, &voting_keystore_password_path) {
+            (Some(password), _) => password.clone(),
+            (None, Some(path)) => read_password_string(path)
+                .map_err(|e| format!("Unable to read password from {:?}: {}", path, e))?,
+            (None, None) => {
+                return Err(format!(
+                    "The password for validator {:?} is not stored by the validator client",
+                    pubkey
+                ))
+            }
+        };
+
+        let _lockfile = Lockfile::new(lockfile_path(voting_keystore_path)).map_err(|e| {
+            format!(
+                "Unable to lock {:?}, ensure the validator client is not running: {:?}",
+                voting_keystore_path, e
+            )
+        })?;
+
+        let keystore = Keystore::from_json_file(&voting_keystore_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", voting_keystore_path, e))?;
+        let keypair = keystore
+            .decrypt_keypair(old_password.as_ref())
+            .map_err(|e| format!("Unable to decrypt {:?}: {:?}", voting_keystore_path, e))?;
+
+        let password = new_password.clone().unwrap_or_else(random_password_string);
+        reencrypt_keystore(
+            &keypair,
+            &keystore,
+            voting_keystore_path,
+            &password,
+            voting_keystore_password_path.as_deref(),
+            kdf,
+        )
+        .map_err(|e| format!("Unable to re-encrypt {:?}: {:?}", voting_keystore_path, e))?;
+
+        // Save the definitions after each keystore so that they never disagree with the files on
+        // disk.
+        if voting_keystore_password.is_some() {
+            *voting_keystore_password = Some(password);
+            definitions.save(validators_dir).map_err(|e| {
+                format!(
+                    "Unable to save {} in {:?}: {:?}",
+                    CONFIG_FILENAME, validators_dir, e
+                )
+            })?;
+        }
+
+        eprintln!("Re-encrypted keystore for validator {:?}", pubkey);
+    }
+
+    Ok(pubkeys.len())
+}
+
+/// Returns the path of the lockfile used by the validator client for the keystore at `path`.
+fn lockfile_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".lock");
+    path.with_file_name(file_name)
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
+    use account_utils::{
+        eth2_keystore::{json_keystore::Kdf, KeystoreBuilder},
+        keystore_rotation::backup_path,
+        validator_definitions::{PasswordStorage, ValidatorDefinition},
+    };
+    use std::fs;
+    use tempfile::{tempdir, TempDir};
+    use types::Keypair;
+    use validator_client::http_api::test_utils::ApiTester;
+
+    const VC_TOKEN_FILE_NAME: &str = "vc_token.json";
+
+    /// Asserts that each local keystore in `definitions` is encrypted with PBKDF2 and its stored
+    /// password, returning the keystore paths.
+    fn assert_reencrypted(definitions: &[ValidatorDefinition]) -> Vec<PathBuf> {
+        definitions
+            .iter()
+            .map(|def| {
+                let SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } = &def.signing_definition
+                else {
+                    panic!("validator should have a local keystore");
+                };
+                let password = def
+                    .signing_definition
+                    .voting_keystore_password()
+                    .unwrap()
+                    .unwrap();
+                let keystore = Keystore::from_json_file(voting_keystore_path).unwrap();
+                assert!(matches!(keystore.kdf(), Kdf::Pbkdf2(_)));
+                assert_eq!(
+                    keystore.decrypt_keypair(password.as_ref()).unwrap().pk,
+                    def.voting_public_key
+                );
+                assert!(backup_path(voting_keystore_path).exists());
+                voting_keystore_path.clone()
+            })
+            .collect()
+    }
+
+    async fn rotate_via_vc_test(vc: &ApiTester, dir: &TempDir) -> Result<(), String> {
+        let vc_token_path = dir.path().join(VC_TOKEN_FILE_NAME);
+        fs::write(&vc_token_path, &vc.api_token).unwrap();
+
+        run(RotateConfig {
+            target: RotateTarget::ValidatorClient {
+                vc_url: vc.url.clone(),
+                vc_token_path,
+            },
+            validators: Validators::All,
+            kdf: KeystoreKdf::Pbkdf2 { c: 2 },
+            new_password_path: None,
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn rotate_via_vc_with_passwords_in_definitions() {
+        let dir = tempdir().unwrap();
+        let import_test_result = ImportTestBuilder::new()
+            .await
+            .create_validators(2, 0)
+            .await
+            .run_test()
+            .await;
+        assert!(import_test_result.result.is_ok());
+        let vc = import_test_result.vc;
+
+        rotate_via_vc_test(&vc, &dir).await.unwrap();
+
+        let definitions = vc
+            .initialized_validators
+            .read()
+            .validator_definitions()
+            .to_vec();
+        assert_eq!(assert_reencrypted(&definitions).len(), 2);
+        assert_eq!(vc.initialized_validators.read().num_enabled(), 2);
+    }
+
+    #[tokio::test]
+    async fn rotate_via_vc_with_password_files() {
+        let dir = tempdir().unwrap();
+        let mut http_config = ApiTester::default_http_config();
+        http_config.store_passwords_in_secrets_dir = true;
+        let import_test_result = ImportTestBuilder::new_with_http_config(http_config)
+            .await
+            .create_validators(2, 0)
+            .await
+            .run_test()
+            .await;
+        assert!(import_test_result.result.is_ok());
+        let vc = import_test_result.vc;
+
+        rotate_via_vc_test(&vc, &dir).await.unwrap();
+
+        let definitions = vc
+            .initialized_validators
+            .read()
+            .validator_definitions()
+            .to_vec();
+        assert_eq!(assert_reencrypted(&definitions).len(), 2);
+        for def in &definitions {
+            let password_path = def.signing_definition.voting_keystore_password_path();
+            assert!(backup_path(password_path.unwrap()).exists());
+        }
+    }
+
+    /// Creates a validators directory containing `count` keystores, with their passwords stored
+    /// in the validator definitions.
+    fn validators_dir(count: usize) -> TempDir {
+        let dir = tempdir().unwrap();
+        let definitions = (0..count)
+            .map(|i| {
+                let password = random_password_string();
+                let keystore = KeystoreBuilder::new(
+                    &Keypair::random(),
+                    password.as_ref(),
+                    format!("m/12381/3600/{}/0/0", i),
+                )
+                .unwrap()
+                .kdf(KeystoreKdf::Scrypt { n: 2 }.to_kdf())
+                .build()
+                .unwrap();
+                let keystore_path = dir.path().join(format!("keystore-{}.json", i));
+                fs::write(&keystore_path, keystore.to_json_string().unwrap()).unwrap();
+                ValidatorDefinition::new_keystore_with_password(
+                    keystore_path,
+                    PasswordStorage::ValidatorDefinitions(password),
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        ValidatorDefinitions::from(definitions)
+            .save(dir.path())
+            .unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn rotate_in_validators_dir_with_new_password() {
+        let dir = validators_dir(2);
+        let new_password_path = dir.path().join("new-password");
+        fs::write(&new_password_path, "a new password").unwrap();
+        let pubkeys = ValidatorDefinitions::open(dir.path())
+            .unwrap()
+            .as_slice()
+            .iter()
+            .map(|def| def.voting_public_key.compress())
+            .collect::<Vec<_>>();
+
+        run(RotateConfig {
+            target: RotateTarget::ValidatorsDir(dir.path().into()),
+            validators: Validators::Specific(vec![pubkeys[1]]),
+            kdf: KeystoreKdf::Pbkdf2 { c: 2 },
+            new_password_path: Some(new_password_path),
+        })
+        .await
+        .unwrap();
+
+        let definitions = ValidatorDefinitions::open(dir.path()).unwrap();
+        let password = definitions.as_slice()[1]
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .unwrap();
+        assert_eq!(password.as_str(), "a new password");
+        assert_reencrypted(&definitions.as_slice()[1..]);
+
+        // The other keystore is unchanged.
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } = &definitions.as_slice()[0].signing_definition
+        else {
+            panic!("validator should have a local keystore");
+        };
+        assert!(!backup_path(voting_keystore_path).exists());
+    }
+
+    #[tokio::test]
+    async fn rotate_in_validators_dir_fails_when_locked() {
+        let dir = validators_dir(1);
+        let definitions = ValidatorDefinitions::open(dir.path()).unwrap();
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } = &definitions.as_slice()[0].signing_definition
+        else {
+            panic!("validator should have a local keystore");
+        };
+        let _lockfile = Lockfile::new(lockfile_path(voting_keystore_path)).unwrap();
+
+        let result = run(RotateConfig {
+            target: RotateTarget::ValidatorsDir(dir.path().into()),
+            validators: Validators::All,
+            kdf: KeystoreKdf::Pbkdf2 { c: 2 },
+            new_password_path: None,
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert!(!backup_path(voting_keystore_path).exists());
+        assert_eq!(
+            ValidatorDefinitions::open(dir.path()).unwrap().as_slice(),
+            definitions.as_slice()
+        );
+    }
+}
@@ -0,0 +1,4 @@
+CREATE TABLE missed_slots (
+    slot integer PRIMARY KEY REFERENCES canonical_slots(slot) ON DELETE CASCADE,
+    proposer_index integer NOT NULL
+)
@@ -0,0 +1,11 @@
+CREATE TABLE sync_committee_participation (
+    slot integer NOT NULL REFERENCES beacon_blocks(slot) ON DELETE CASCADE,
+    index integer NOT NULL,
+    period integer NOT NULL,
+    participated boolean NOT NULL,
+    reward integer NOT NULL,
+    PRIMARY KEY(slot, index)
+);
+
+CREATE INDEX sync_committee_participation_index_period ON sync_committee_participation (index, period);
+CREATE INDEX sync_committee_participation_period ON sync_committee_participation (period);
@@ -0,0 +1,18 @@
+CREATE TABLE block_blobs (
+    slot integer PRIMARY KEY REFERENCES beacon_blocks(slot) ON DELETE CASCADE,
+    blob_count integer NOT NULL,
+    blob_gas_used bigint NOT NULL,
+    excess_blob_gas bigint NOT NULL
+);
+
+CREATE TABLE blobs (
+    slot integer NOT NULL REFERENCES beacon_blocks(slot) ON DELETE CASCADE,
+    "index" integer NOT NULL,
+    kzg_commitment text NOT NULL,
+    versioned_hash bytea NOT NULL,
+    size integer,
+    submitter text,
+    PRIMARY KEY(slot, "index")
+);
+
+CREATE INDEX blobs_submitter ON blobs (submitter);
@@ -0,0 +1 @@
+DROP TABLE proposer_info
@@ -0,0 +1 @@
+DROP TABLE suboptimal_attestations
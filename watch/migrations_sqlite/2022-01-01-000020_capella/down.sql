@@ -0,0 +1,2 @@
+ALTER TABLE beacon_blocks
+DROP COLUMN withdrawal_count;
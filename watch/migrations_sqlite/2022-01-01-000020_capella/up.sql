@@ -0,0 +1,3 @@
+ALTER TABLE beacon_blocks
+ADD COLUMN withdrawal_count integer;
+
@@ -0,0 +1,10 @@
+CREATE TABLE reorgs (
+    old_head_root bytea PRIMARY KEY,
+    old_head_slot integer NOT NULL,
+    old_head_proposer_index integer,
+    new_head_root bytea NOT NULL,
+    new_head_slot integer NOT NULL,
+    new_head_proposer_index integer NOT NULL,
+    common_ancestor_slot integer NOT NULL,
+    depth integer NOT NULL
+)
@@ -0,0 +1,2 @@
+DROP TABLE blobs;
+DROP TABLE block_blobs
\ No newline at end of file
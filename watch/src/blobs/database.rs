@@ -0,0 +1,228 @@
+use crate::database::{
+    max_size_batch_insert,
+    schema::{beacon_blocks, blobs, block_blobs},
+    watch_types::{WatchHash, WatchSlot},
+    Error, WatchConn,
+};
+
+use diesel::dsl::{count_star, sum};
+use diesel::prelude::*;
+use diesel::{Insertable, Queryable};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// The blob usage of a single block.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = block_blobs)]
+pub struct WatchBlockBlobs {
+    pub slot: WatchSlot,
+    pub blob_count: i32,
+    pub blob_gas_used: i64,
+    pub excess_blob_gas: i64,
+}
+
+/// A single blob included in a block.
+///
+/// `size` is the number of bytes up to and including the last non-zero byte of the blob. It is
+/// `None` if the blob was not available from the beacon node (e.g. it has been pruned).
+///
+/// `submitter` is the sender of the type 3 transaction which committed to the blob. It is `None`
+/// if the sender could not be determined from the execution payload.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = blobs)]
+pub struct WatchBlob {
+    pub slot: WatchSlot,
+    pub index: i32,
+    pub kzg_commitment: String,
+    pub versioned_hash: WatchHash,
+    pub size: Option<i32>,
+    pub submitter: Option<String>,
+}
+
+/// The blob usage of a single submitter, aggregated over a range of slots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchBlobSubmitter {
+    pub submitter: Option<String>,
+    pub blob_count: i64,
+    pub total_size: i64,
+}
+
+/// Insert a batch of values into the `block_blobs` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_batch_block_blobs(
+    conn: &mut WatchConn,
+    usage: Vec<WatchBlockBlobs>,
+) -> Result<(), Error> {
+    use self::block_blobs::dsl::*;
+
+    let mut count = 0;
+    let timer = Instant::now();
+
+    for chunk in usage.chunks(max_size_batch_insert(conn)) {
+        count += diesel::insert_into(block_blobs)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    let time_taken = timer.elapsed();
+    debug!("Block blobs inserted, count: {count}, time_taken: {time_taken:?}");
+    Ok(())
+}
+
+/// Insert a batch of values into the `blobs` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_batch_blobs(conn: &mut WatchConn, blob_list: Vec<WatchBlob>) -> Result<(), Error> {
+    use self::blobs::dsl::*;
+
+    let mut count = 0;
+    let timer = Instant::now();
+
+    for chunk in blob_list.chunks(max_size_batch_insert(conn)) {
+        count += diesel::insert_into(blobs)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    let time_taken = timer.elapsed();
+    debug!("Blobs inserted, count: {count}, time_taken: {time_taken:?}");
+    Ok(())
+}
+
+/// Selects the lowest `slot` in the `block_blobs` table.
+pub fn get_lowest_block_blobs(conn: &mut WatchConn) -> Result<Option<WatchSlot>, Error> {
+    use self::block_blobs::dsl::*;
+    let timer = Instant::now();
+
+    let result = block_blobs
+        .select(slot)
+        .order_by(slot.asc())
+        .limit(1)
+        .first::<WatchSlot>(conn)
+        .optional()?;
+
+    let time_taken = timer.elapsed();
+    debug!("Block blobs requested: lowest, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects the highest `slot` in the `block_blobs` table.
+pub fn get_highest_block_blobs(conn: &mut WatchConn) -> Result<Option<WatchSlot>, Error> {
+    use self::block_blobs::dsl::*;
+    let timer = Instant::now();
+
+    let result = block_blobs
+        .select(slot)
+        .order_by(slot.desc())
+        .limit(1)
+        .first::<WatchSlot>(conn)
+        .optional()?;
+
+    let time_taken = timer.elapsed();
+    debug!("Block blobs requested: highest, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `block_blobs` table where `slot >= start_slot && slot <= end_slot`.
+pub fn get_block_blobs_by_range(
+    conn: &mut WatchConn,
+    start_slot: WatchSlot,
+    end_slot: WatchSlot,
+) -> Result<Vec<WatchBlockBlobs>, Error> {
+    use self::block_blobs::dsl::*;
+    let timer = Instant::now();
+
+    let result = block_blobs
+        .filter(slot.ge(start_slot))
+        .filter(slot.le(end_slot))
+        .order_by(slot.asc())
+        .load::<WatchBlockBlobs>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!(
+        "Block blobs requested for range: {start_slot} - {end_slot}, time_taken: {time_taken:?}"
+    );
+    Ok(result)
+}
+
+/// Selects all rows of the `blobs` table corresponding to a given `root_query`.
+pub fn get_blobs_by_root(
+    conn: &mut WatchConn,
+    root_query: WatchHash,
+) -> Result<Vec<WatchBlob>, Error> {
+    use self::beacon_blocks::dsl::{beacon_blocks, root};
+    use self::blobs::dsl::*;
+    let timer = Instant::now();
+
+    let join = beacon_blocks.inner_join(blobs);
+
+    let result = join
+        .select((slot, index, kzg_commitment, versioned_hash, size, submitter))
+        .filter(root.eq(root_query))
+        .order_by(index.asc())
+        .load::<WatchBlob>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Blobs requested: {root_query}, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `blobs` table corresponding to a given `slot_query`.
+pub fn get_blobs_by_slot(
+    conn: &mut WatchConn,
+    slot_query: WatchSlot,
+) -> Result<Vec<WatchBlob>, Error> {
+    use self::blobs::dsl::*;
+    let timer = Instant::now();
+
+    let result = blobs
+        .filter(slot.eq(slot_query))
+        .order_by(index.asc())
+        .load::<WatchBlob>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Blobs requested: {slot_query}, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Summarises the `blobs` table by submitter for all blobs with
+/// `slot >= start_slot && slot <= end_slot`.
+///
+/// Results are ordered by the number of blobs submitted, descending.
+pub fn get_blob_submitters_by_range(
+    conn: &mut WatchConn,
+    start_slot: WatchSlot,
+    end_slot: WatchSlot,
+) -> Result<Vec<WatchBlobSubmitter>, Error> {
+    use self::blobs::dsl::*;
+    let timer = Instant::now();
+
+    let totals = blobs
+        .filter(slot.ge(start_slot))
+        .filter(slot.le(end_slot))
+        .group_by(submitter)
+        .select((submitter, count_star(), sum(size)))
+        .load::<(Option<String>, i64, Option<i64>)>(conn)?;
+
+    let mut result: Vec<WatchBlobSubmitter> = totals
+        .into_iter()
+        .map(|(address, blob_count, total_size)| WatchBlobSubmitter {
+            submitter: address,
+            blob_count,
+            total_size: total_size.unwrap_or(0),
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.blob_count
+            .cmp(&a.blob_count)
+            .then_with(|| a.submitter.cmp(&b.submitter))
+    });
+
+    let time_taken = timer.elapsed();
+    debug!("Blob submitters requested for range: {start_slot} - {end_slot}, time_taken: {time_taken:?}");
+    Ok(result)
+}
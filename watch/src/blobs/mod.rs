@@ -0,0 +1,128 @@
+pub mod database;
+mod server;
+mod updater;
+
+use crate::database::watch_types::{WatchHash, WatchSlot};
+use crate::updater::error::Error;
+
+pub use database::{
+    get_blob_submitters_by_range, get_blobs_by_root, get_blobs_by_slot, get_block_blobs_by_range,
+    get_highest_block_blobs, get_lowest_block_blobs, insert_batch_blobs, insert_batch_block_blobs,
+    WatchBlob, WatchBlobSubmitter, WatchBlockBlobs,
+};
+pub use server::blob_routes;
+
+use eth2::types::BlockId;
+use eth2::BeaconNodeHttpClient;
+use execution_layer::extract_blob_transactions;
+use log::warn;
+use std::collections::HashMap;
+use types::{Address, Epoch, EthSpec, Hash256, Slot};
+
+/// Sends a request to `config/spec`.
+/// Reads the Deneb fork epoch.
+pub async fn get_deneb_fork_epoch(bn: &BeaconNodeHttpClient) -> Result<Epoch, Error> {
+    let config_map = bn.get_config_spec::<HashMap<String, String>>().await?.data;
+
+    let deneb_fork_epoch = config_map
+        .get("DENEB_FORK_EPOCH")
+        .ok_or_else(|| {
+            Error::BeaconNodeNotCompatible("No field DENEB_FORK_EPOCH on beacon node spec".into())
+        })?
+        .parse::<u64>()
+        .map_err(|e| Error::BeaconNodeNotCompatible(format!("Invalid DENEB_FORK_EPOCH: {e}")))?;
+
+    Ok(Epoch::new(deneb_fork_epoch))
+}
+
+/// Sends requests to `beacon/blocks/{slot}` and `beacon/blob_sidecars/{slot}`.
+/// Formats the responses into a `WatchBlockBlobs` and a `WatchBlob` for each KZG commitment in
+/// the block.
+///
+/// The submitter of each blob is determined by matching its versioned hash against the type 3
+/// transactions of the execution payload.
+///
+/// Returns `None` if the block does not exist or predates Deneb.
+pub async fn get_block_blobs<E: EthSpec>(
+    bn: &BeaconNodeHttpClient,
+    slot: Slot,
+) -> Result<Option<(WatchBlockBlobs, Vec<WatchBlob>)>, Error> {
+    let Some(block) = bn
+        .get_beacon_blocks::<E>(BlockId::Slot(slot))
+        .await?
+        .map(|resp| resp.data)
+    else {
+        return Ok(None);
+    };
+    let body = block.message().body();
+    let (Ok(commitments), Ok(payload)) = (body.blob_kzg_commitments(), body.execution_payload())
+    else {
+        return Ok(None);
+    };
+    let payload = payload.execution_payload_ref();
+
+    let submitters: HashMap<Hash256, Address> =
+        match extract_blob_transactions::<E>(payload.transactions()) {
+            Ok(transactions) => transactions
+                .into_iter()
+                .filter_map(|tx| tx.sender.map(|sender| (sender, tx.versioned_hashes)))
+                .flat_map(|(sender, hashes)| hashes.into_iter().map(move |hash| (hash, sender)))
+                .collect(),
+            Err(e) => {
+                warn!("Unable to decode blob transactions at slot {slot}: {e:?}");
+                HashMap::new()
+            }
+        };
+
+    let sizes: HashMap<u64, i32> = if commitments.is_empty() {
+        HashMap::new()
+    } else {
+        match bn.get_blobs::<E>(BlockId::Slot(slot), None).await {
+            Ok(Some(resp)) => resp
+                .data
+                .iter()
+                .map(|sidecar| (sidecar.index, blob_size(&sidecar.blob) as i32))
+                .collect(),
+            Ok(None) => HashMap::new(),
+            Err(e) => {
+                // Blobs outside of the retention period are pruned by the beacon node.
+                warn!("Unable to fetch blobs at slot {slot}: {e:?}");
+                HashMap::new()
+            }
+        }
+    };
+
+    let blobs = commitments
+        .iter()
+        .enumerate()
+        .map(|(index, commitment)| {
+            let versioned_hash = commitment.calculate_versioned_hash();
+            WatchBlob {
+                slot: WatchSlot::from_slot(slot),
+                index: index as i32,
+                kzg_commitment: format!("{commitment:?}"),
+                versioned_hash: WatchHash::from_hash(versioned_hash),
+                size: sizes.get(&(index as u64)).copied(),
+                submitter: submitters
+                    .get(&versioned_hash)
+                    .map(|sender| format!("{sender:?}")),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let block_blobs = WatchBlockBlobs {
+        slot: WatchSlot::from_slot(slot),
+        blob_count: blobs.len() as i32,
+        blob_gas_used: payload.blob_gas_used().unwrap_or(0) as i64,
+        excess_blob_gas: payload.excess_blob_gas().unwrap_or(0) as i64,
+    };
+
+    Ok(Some((block_blobs, blobs)))
+}
+
+/// Returns the number of bytes in `blob` up to and including the last non-zero byte.
+fn blob_size(blob: &[u8]) -> usize {
+    blob.iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |position| position + 1)
+}
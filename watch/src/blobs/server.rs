@@ -0,0 +1,74 @@
+use crate::blobs::database::{
+    get_blob_submitters_by_range, get_blobs_by_root, get_blobs_by_slot, get_block_blobs_by_range,
+    WatchBlob, WatchBlobSubmitter, WatchBlockBlobs,
+};
+use crate::database::{get_connection, WatchHash, WatchPool, WatchSlot};
+use crate::server::Error;
+
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Extension, Json, Router,
+};
+use eth2::types::BlockId;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub async fn get_block_blobs(
+    Path(block_query): Path<String>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchBlob>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
+        BlockId::Root(root) => Ok(Json(get_blobs_by_root(
+            &mut conn,
+            WatchHash::from_hash(root),
+        )?)),
+        BlockId::Slot(slot) => Ok(Json(get_blobs_by_slot(
+            &mut conn,
+            WatchSlot::from_slot(slot),
+        )?)),
+        _ => Err(Error::BadRequest),
+    }
+}
+
+pub async fn get_blob_usage(
+    Query(query): Query<HashMap<String, u64>>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchBlockBlobs>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let start_slot = query.get("start_slot").ok_or(Error::BadRequest)?;
+    let end_slot = query.get("end_slot").ok_or(Error::BadRequest)?;
+    if start_slot > end_slot {
+        return Err(Error::BadRequest);
+    }
+    Ok(Json(get_block_blobs_by_range(
+        &mut conn,
+        WatchSlot::new(*start_slot),
+        WatchSlot::new(*end_slot),
+    )?))
+}
+
+pub async fn get_blob_submitters(
+    Query(query): Query<HashMap<String, u64>>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchBlobSubmitter>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let start_slot = query.get("start_slot").ok_or(Error::BadRequest)?;
+    let end_slot = query.get("end_slot").ok_or(Error::BadRequest)?;
+    if start_slot > end_slot {
+        return Err(Error::BadRequest);
+    }
+    Ok(Json(get_blob_submitters_by_range(
+        &mut conn,
+        WatchSlot::new(*start_slot),
+        WatchSlot::new(*end_slot),
+    )?))
+}
+
+pub fn blob_routes() -> Router {
+    Router::new()
+        .route("/v1/blocks/:block/blobs", get(get_block_blobs))
+        .route("/v1/blobs/usage", get(get_blob_usage))
+        .route("/v1/blobs/submitters", get(get_blob_submitters))
+}
@@ -1,7 +1,8 @@
 use crate::database::{
+    max_size_batch_insert,
     schema::{beacon_blocks, block_packing},
     watch_types::{WatchHash, WatchSlot},
-    Error, PgConn, MAX_SIZE_BATCH_INSERT,
+    Error, WatchConn,
 };
 
 use diesel::prelude::*;
@@ -23,7 +24,7 @@ pub struct WatchBlockPacking {
 ///
 /// On a conflict, it will do nothing, leaving the old value.
 pub fn insert_batch_block_packing(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     packing: Vec<WatchBlockPacking>,
 ) -> Result<(), Error> {
     use self::block_packing::dsl::*;
@@ -31,7 +32,7 @@ pub fn insert_batch_block_packing(
     let mut count = 0;
     let timer = Instant::now();
 
-    for chunk in packing.chunks(MAX_SIZE_BATCH_INSERT) {
+    for chunk in packing.chunks(max_size_batch_insert(conn)) {
         count += diesel::insert_into(block_packing)
             .values(chunk)
             .on_conflict_do_nothing()
@@ -44,7 +45,7 @@ pub fn insert_batch_block_packing(
 }
 
 /// Selects the row from the `block_packing` table where `slot` is minimum.
-pub fn get_lowest_block_packing(conn: &mut PgConn) -> Result<Option<WatchBlockPacking>, Error> {
+pub fn get_lowest_block_packing(conn: &mut WatchConn) -> Result<Option<WatchBlockPacking>, Error> {
     use self::block_packing::dsl::*;
     let timer = Instant::now();
 
@@ -60,7 +61,7 @@ pub fn get_lowest_block_packing(conn: &mut PgConn) -> Result<Option<WatchBlockPa
 }
 
 /// Selects the row from the `block_packing` table where `slot` is maximum.
-pub fn get_highest_block_packing(conn: &mut PgConn) -> Result<Option<WatchBlockPacking>, Error> {
+pub fn get_highest_block_packing(conn: &mut WatchConn) -> Result<Option<WatchBlockPacking>, Error> {
     use self::block_packing::dsl::*;
     let timer = Instant::now();
 
@@ -77,7 +78,7 @@ pub fn get_highest_block_packing(conn: &mut PgConn) -> Result<Option<WatchBlockP
 
 /// Selects a single row of the `block_packing` table corresponding to a given `root_query`.
 pub fn get_block_packing_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchBlockPacking>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root};
@@ -99,7 +100,7 @@ pub fn get_block_packing_by_root(
 
 /// Selects a single row of the `block_packing` table corresponding to a given `slot_query`.
 pub fn get_block_packing_by_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchBlockPacking>, Error> {
     use self::block_packing::dsl::*;
@@ -119,7 +120,7 @@ pub fn get_block_packing_by_slot(
 /// row in `block_packing`.
 #[allow(dead_code)]
 pub fn get_unknown_block_packing(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slots_per_epoch: u64,
 ) -> Result<Vec<Option<WatchSlot>>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root, slot};
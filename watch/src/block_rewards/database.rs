@@ -1,7 +1,8 @@
 use crate::database::{
+    max_size_batch_insert,
     schema::{beacon_blocks, block_rewards},
     watch_types::{WatchHash, WatchSlot},
-    Error, PgConn, MAX_SIZE_BATCH_INSERT,
+    Error, WatchConn,
 };
 
 use diesel::prelude::*;
@@ -23,7 +24,7 @@ pub struct WatchBlockRewards {
 ///
 /// On a conflict, it will do nothing, leaving the old value.
 pub fn insert_batch_block_rewards(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     rewards: Vec<WatchBlockRewards>,
 ) -> Result<(), Error> {
     use self::block_rewards::dsl::*;
@@ -31,7 +32,7 @@ pub fn insert_batch_block_rewards(
     let mut count = 0;
     let timer = Instant::now();
 
-    for chunk in rewards.chunks(MAX_SIZE_BATCH_INSERT) {
+    for chunk in rewards.chunks(max_size_batch_insert(conn)) {
         count += diesel::insert_into(block_rewards)
             .values(chunk)
             .on_conflict_do_nothing()
@@ -44,7 +45,7 @@ pub fn insert_batch_block_rewards(
 }
 
 /// Selects the row from the `block_rewards` table where `slot` is minimum.
-pub fn get_lowest_block_rewards(conn: &mut PgConn) -> Result<Option<WatchBlockRewards>, Error> {
+pub fn get_lowest_block_rewards(conn: &mut WatchConn) -> Result<Option<WatchBlockRewards>, Error> {
     use self::block_rewards::dsl::*;
     let timer = Instant::now();
 
@@ -60,7 +61,7 @@ pub fn get_lowest_block_rewards(conn: &mut PgConn) -> Result<Option<WatchBlockRe
 }
 
 /// Selects the row from the `block_rewards` table where `slot` is maximum.
-pub fn get_highest_block_rewards(conn: &mut PgConn) -> Result<Option<WatchBlockRewards>, Error> {
+pub fn get_highest_block_rewards(conn: &mut WatchConn) -> Result<Option<WatchBlockRewards>, Error> {
     use self::block_rewards::dsl::*;
     let timer = Instant::now();
 
@@ -77,7 +78,7 @@ pub fn get_highest_block_rewards(conn: &mut PgConn) -> Result<Option<WatchBlockR
 
 /// Selects a single row of the `block_rewards` table corresponding to a given `root_query`.
 pub fn get_block_rewards_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchBlockRewards>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root};
@@ -99,7 +100,7 @@ pub fn get_block_rewards_by_root(
 
 /// Selects a single row of the `block_rewards` table corresponding to a given `slot_query`.
 pub fn get_block_rewards_by_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchBlockRewards>, Error> {
     use self::block_rewards::dsl::*;
@@ -118,7 +119,7 @@ pub fn get_block_rewards_by_slot(
 /// Selects `slot` from all rows of the `beacon_blocks` table which do not have a corresponding
 /// row in `block_rewards`.
 #[allow(dead_code)]
-pub fn get_unknown_block_rewards(conn: &mut PgConn) -> Result<Vec<Option<WatchSlot>>, Error> {
+pub fn get_unknown_block_rewards(conn: &mut WatchConn) -> Result<Vec<Option<WatchSlot>>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root, slot};
     use self::block_rewards::dsl::block_rewards;
 
@@ -1,7 +1,7 @@
 use crate::block_rewards::database::{
     get_block_rewards_by_root, get_block_rewards_by_slot, WatchBlockRewards,
 };
-use crate::database::{get_connection, PgPool, WatchHash, WatchSlot};
+use crate::database::{get_connection, WatchHash, WatchPool, WatchSlot};
 use crate::server::Error;
 
 use axum::{extract::Path, routing::get, Extension, Json, Router};
@@ -10,7 +10,7 @@ use std::str::FromStr;
 
 pub async fn get_block_rewards(
     Path(block_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBlockRewards>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
     match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
@@ -1,8 +1,8 @@
 use crate::database::{
-    self,
+    self, max_size_batch_insert,
     schema::{beacon_blocks, blockprint},
     watch_types::{WatchHash, WatchSlot},
-    Error, PgConn, MAX_SIZE_BATCH_INSERT,
+    Error, WatchConn,
 };
 
 use diesel::prelude::*;
@@ -47,7 +47,7 @@ pub struct WatchValidatorBlockprint {
 ///
 /// On a conflict, it will do nothing, leaving the old value.
 pub fn insert_batch_blockprint(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     prints: Vec<WatchBlockprint>,
 ) -> Result<(), Error> {
     use self::blockprint::dsl::*;
@@ -55,7 +55,7 @@ pub fn insert_batch_blockprint(
     let mut count = 0;
     let timer = Instant::now();
 
-    for chunk in prints.chunks(MAX_SIZE_BATCH_INSERT) {
+    for chunk in prints.chunks(max_size_batch_insert(conn)) {
         count += diesel::insert_into(blockprint)
             .values(chunk)
             .on_conflict_do_nothing()
@@ -68,7 +68,7 @@ pub fn insert_batch_blockprint(
 }
 
 /// Selects the row from the `blockprint` table where `slot` is minimum.
-pub fn get_lowest_blockprint(conn: &mut PgConn) -> Result<Option<WatchBlockprint>, Error> {
+pub fn get_lowest_blockprint(conn: &mut WatchConn) -> Result<Option<WatchBlockprint>, Error> {
     use self::blockprint::dsl::*;
     let timer = Instant::now();
 
@@ -84,7 +84,7 @@ pub fn get_lowest_blockprint(conn: &mut PgConn) -> Result<Option<WatchBlockprint
 }
 
 /// Selects the row from the `blockprint` table where `slot` is maximum.
-pub fn get_highest_blockprint(conn: &mut PgConn) -> Result<Option<WatchBlockprint>, Error> {
+pub fn get_highest_blockprint(conn: &mut WatchConn) -> Result<Option<WatchBlockprint>, Error> {
     use self::blockprint::dsl::*;
     let timer = Instant::now();
 
@@ -101,7 +101,7 @@ pub fn get_highest_blockprint(conn: &mut PgConn) -> Result<Option<WatchBlockprin
 
 /// Selects a single row of the `blockprint` table corresponding to a given `root_query`.
 pub fn get_blockprint_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchBlockprint>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root};
@@ -123,7 +123,7 @@ pub fn get_blockprint_by_root(
 
 /// Selects a single row of the `blockprint` table corresponding to a given `slot_query`.
 pub fn get_blockprint_by_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchBlockprint>, Error> {
     use self::blockprint::dsl::*;
@@ -142,7 +142,7 @@ pub fn get_blockprint_by_slot(
 /// Selects `slot` from all rows of the `beacon_blocks` table which do not have a corresponding
 /// row in `blockprint`.
 #[allow(dead_code)]
-pub fn get_unknown_blockprint(conn: &mut PgConn) -> Result<Vec<Option<WatchSlot>>, Error> {
+pub fn get_unknown_blockprint(conn: &mut WatchConn) -> Result<Vec<Option<WatchSlot>>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root, slot};
     use self::blockprint::dsl::blockprint;
 
@@ -162,7 +162,7 @@ pub fn get_unknown_blockprint(conn: &mut PgConn) -> Result<Vec<Option<WatchSlot>
 /// `target_slot`.
 /// Inserts `"Unknown" if no prior proposals exist.
 pub fn construct_validator_blockprints_at_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     target_slot: WatchSlot,
     slots_per_epoch: u64,
 ) -> Result<HashMap<i32, WatchConsensusClient>, Error> {
@@ -203,7 +203,7 @@ pub fn construct_validator_blockprints_at_slot(
 /// Counts the number of occurances of each `client` present in the `validators` table at or before some
 /// `target_slot`.
 pub fn get_validators_clients_at_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     target_slot: WatchSlot,
     slots_per_epoch: u64,
 ) -> Result<HashMap<WatchConsensusClient, usize>, Error> {
@@ -1,9 +1,15 @@
+use crate::blobs::{WatchBlob, WatchBlobSubmitter, WatchBlockBlobs};
 use crate::block_packing::WatchBlockPacking;
 use crate::block_rewards::WatchBlockRewards;
 use crate::database::models::{
     WatchBeaconBlock, WatchCanonicalSlot, WatchProposerInfo, WatchValidator,
 };
+use crate::missed_slots::WatchMissedSlot;
+use crate::reorgs::WatchReorg;
 use crate::suboptimal_attestations::WatchAttestation;
+use crate::sync_committee_participation::{
+    WatchSyncCommitteeParticipation, WatchSyncCommitteeSummary,
+};
 
 use eth2::types::BlockId;
 use reqwest::Client;
@@ -175,4 +181,129 @@ impl WatchHttpClient {
 
         self.get_opt(url).await
     }
+
+    pub async fn get_reorgs(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Option<Vec<WatchReorg>>, Error> {
+        let mut url = self.server.join("v1/")?.join("reorgs")?;
+        url.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_missed_slots(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Option<Vec<WatchMissedSlot>>, Error> {
+        let mut url = self.server.join("v1/")?.join("missed_slots")?;
+        url.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_validator_missed_slots(
+        &self,
+        index: i32,
+    ) -> Result<Option<Vec<WatchMissedSlot>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("validators/")?
+            .join(&format!("{index}/"))?
+            .join("missed_slots")?;
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_block_sync_committee_participation(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<WatchSyncCommitteeParticipation>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("blocks/")?
+            .join(&format!("{block_id}/"))?
+            .join("sync_committee_participation")?;
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_validator_sync_committee_participation(
+        &self,
+        index: i32,
+    ) -> Result<Option<Vec<WatchSyncCommitteeSummary>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("validators/")?
+            .join(&format!("{index}/"))?
+            .join("sync_committee_participation")?;
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_sync_committee_participation(
+        &self,
+        period: u64,
+    ) -> Result<Option<Vec<WatchSyncCommitteeSummary>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("sync_committee_participation/")?
+            .join(&period.to_string())?;
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_block_blobs(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<WatchBlob>>, Error> {
+        let url = self
+            .server
+            .join("v1/")?
+            .join("blocks/")?
+            .join(&format!("{block_id}/"))?
+            .join("blobs")?;
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_blob_usage(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Option<Vec<WatchBlockBlobs>>, Error> {
+        let mut url = self.server.join("v1/")?.join("blobs/")?.join("usage")?;
+        url.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get_opt(url).await
+    }
+
+    pub async fn get_blob_submitters(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> Result<Option<Vec<WatchBlobSubmitter>>, Error> {
+        let mut url = self
+            .server
+            .join("v1/")?
+            .join("blobs/")?
+            .join("submitters")?;
+        url.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get_opt(url).await
+    }
 }
@@ -1,9 +1,12 @@
-//! Implementations of PostgreSQL compatibility traits.
+//! Implementations of PostgreSQL and SQLite compatibility traits.
 use crate::database::watch_types::{WatchHash, WatchPK, WatchSlot};
+use crate::database::MultiBackend;
+use diesel::backend::Backend;
 use diesel::deserialize::{self, FromSql};
 use diesel::pg::{Pg, PgValue};
-use diesel::serialize::{self, Output, ToSql};
+use diesel::serialize::{self, IsNull, Output, ToSql};
 use diesel::sql_types::{Binary, Integer};
+use diesel::sqlite::Sqlite;
 
 macro_rules! impl_to_from_sql_int {
     ($type:ty) => {
@@ -22,6 +25,24 @@ macro_rules! impl_to_from_sql_int {
                 Ok(Self::new(i32::from_sql(bytes)? as u64))
             }
         }
+
+        impl ToSql<Integer, Sqlite> for $type {
+            fn to_sql<'a>(&'a self, out: &mut Output<'a, '_, Sqlite>) -> serialize::Result {
+                let v = i32::try_from(self.as_u64()).map_err(|e| Box::new(e))?;
+                out.set_value(v);
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<Integer, Sqlite> for $type {
+            fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+                Ok(Self::new(
+                    <i32 as FromSql<Integer, Sqlite>>::from_sql(bytes)? as u64,
+                ))
+            }
+        }
+
+        impl_to_from_sql_multi!($type, Integer);
     };
 }
 
@@ -39,6 +60,42 @@ macro_rules! impl_to_from_sql_binary {
                 Self::from_bytes(bytes.as_bytes()).map_err(|e| e.to_string().into())
             }
         }
+
+        impl ToSql<Binary, Sqlite> for $type {
+            fn to_sql<'a>(&'a self, out: &mut Output<'a, '_, Sqlite>) -> serialize::Result {
+                out.set_value(self.as_bytes());
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<Binary, Sqlite> for $type {
+            fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+                let bytes = <Vec<u8> as FromSql<Binary, Sqlite>>::from_sql(bytes)?;
+                Self::from_bytes(&bytes).map_err(|e| e.to_string().into())
+            }
+        }
+
+        impl_to_from_sql_multi!($type, Binary);
+    };
+}
+
+/// Dispatches to the implementation of the backend which the `WatchConnection` is connected to.
+macro_rules! impl_to_from_sql_multi {
+    ($type:ty, $sql_type:ident) => {
+        impl ToSql<$sql_type, MultiBackend> for $type {
+            fn to_sql<'a>(&'a self, out: &mut Output<'a, '_, MultiBackend>) -> serialize::Result {
+                out.set_value(($sql_type, self));
+                Ok(IsNull::No)
+            }
+        }
+
+        impl FromSql<$sql_type, MultiBackend> for $type {
+            fn from_sql(
+                bytes: <MultiBackend as Backend>::RawValue<'_>,
+            ) -> deserialize::Result<Self> {
+                bytes.from_sql::<Self, $sql_type>()
+            }
+        }
     };
 }
 
@@ -1,10 +1,12 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 pub const USER: &str = "postgres";
 pub const PASSWORD: &str = "postgres";
 pub const DBNAME: &str = "dev";
 pub const DEFAULT_DBNAME: &str = "postgres";
 pub const HOST: &str = "localhost";
+pub const SQLITE_PATH: &str = "watch.sqlite";
 pub const fn port() -> u16 {
     5432
 }
@@ -32,8 +34,27 @@ fn host() -> String {
     HOST.to_string()
 }
 
+fn sqlite_path() -> PathBuf {
+    PathBuf::from(SQLITE_PATH)
+}
+
+/// The database engine used to store the data collected by the updater.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// A PostgreSQL server, configured with `user`, `password`, `dbname`, `host` and `port`.
+    #[default]
+    Postgres,
+    /// A SQLite database file located at `sqlite_path`.
+    Sqlite,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    #[serde(default)]
+    pub backend: Backend,
+    #[serde(default = "sqlite_path")]
+    pub sqlite_path: PathBuf,
     #[serde(default = "user")]
     pub user: String,
     #[serde(default = "password")]
@@ -53,6 +74,8 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            backend: Backend::default(),
+            sqlite_path: sqlite_path(),
             user: user(),
             password: password(),
             dbname: dbname(),
@@ -66,9 +89,12 @@ impl Default for Config {
 
 impl Config {
     pub fn build_database_url(&self) -> String {
-        format!(
-            "postgres://{}:{}@{}:{}/{}",
-            self.user, self.password, self.host, self.port, self.dbname
-        )
+        match self.backend {
+            Backend::Postgres => format!(
+                "postgres://{}:{}@{}:{}/{}",
+                self.user, self.password, self.host, self.port, self.dbname
+            ),
+            Backend::Sqlite => self.sqlite_path.to_string_lossy().into_owned(),
+        }
     }
 }
@@ -15,6 +15,7 @@ pub enum Error {
     Pool(PoolError),
     SensitiveUrl(SensitiveError),
     InvalidRoot,
+    Migration(String),
     Other(String),
 }
 
@@ -12,10 +12,14 @@ use self::schema::{
     validators,
 };
 
+use diesel::connection::SimpleConnection;
 use diesel::dsl::max;
 use diesel::prelude::*;
-use diesel::r2d2::{Builder, ConnectionManager, Pool, PooledConnection};
+use diesel::r2d2::{
+    Builder, ConnectionManager, CustomizeConnection, Error as R2D2Error, Pool, PooledConnection,
+};
 use diesel::upsert::excluded;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use log::{debug, info};
 use std::collections::HashMap;
 use std::time::Instant;
@@ -47,6 +51,13 @@ pub use crate::suboptimal_attestations::{
     WatchAttestation, WatchSuboptimalAttestation,
 };
 
+#[allow(unused_imports)]
+pub use crate::blobs::{
+    get_blob_submitters_by_range, get_blobs_by_root, get_blobs_by_slot, get_block_blobs_by_range,
+    get_highest_block_blobs, get_lowest_block_blobs, insert_batch_blobs, insert_batch_block_blobs,
+    WatchBlob, WatchBlobSubmitter, WatchBlockBlobs,
+};
+
 #[allow(unused_imports)]
 pub use crate::blockprint::{
     get_blockprint_by_root, get_blockprint_by_slot, get_highest_blockprint, get_lowest_blockprint,
@@ -54,26 +65,122 @@ pub use crate::blockprint::{
     WatchBlockprint,
 };
 
-pub use config::Config;
+#[allow(unused_imports)]
+pub use crate::reorgs::{
+    get_reorgs_by_old_head_proposer, get_reorgs_by_range, insert_reorg, WatchReorg,
+};
+
+#[allow(unused_imports)]
+pub use crate::missed_slots::{
+    get_missed_slots_by_proposer, get_missed_slots_by_range, get_unknown_missed_slots,
+    insert_batch_missed_slots, WatchMissedSlot,
+};
+
+#[allow(unused_imports)]
+pub use crate::sync_committee_participation::{
+    get_highest_sync_committee_participation, get_lowest_sync_committee_participation,
+    get_sync_committee_participation_by_root, get_sync_committee_participation_by_slot,
+    get_sync_committee_summaries_by_index, get_sync_committee_summaries_by_period,
+    insert_batch_sync_committee_participation, WatchSyncCommitteeParticipation,
+    WatchSyncCommitteeSummary,
+};
+
+pub use config::{Backend, Config};
 
 /// Batch inserts cannot exceed a certain size.
 /// See https://github.com/diesel-rs/diesel/issues/2414.
 /// For some reason, this seems to translate to 65535 / 5 (13107) records.
 pub const MAX_SIZE_BATCH_INSERT: usize = 13107;
 
-pub type PgPool = Pool<ConnectionManager<PgConnection>>;
-pub type PgConn = PooledConnection<ConnectionManager<PgConnection>>;
+/// SQLite limits a statement to 32766 bind parameters, which translates to 32766 / 6 (5461)
+/// records for the widest tables.
+pub const MAX_SIZE_BATCH_INSERT_SQLITE: usize = 5461;
+
+/// The PostgreSQL migrations in `watch/migrations`.
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+/// The SQLite migrations in `watch/migrations_sqlite`. These mirror `MIGRATIONS` without the
+/// PostgreSQL-only syntax.
+pub const SQLITE_MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
+
+/// A connection to any of the supported database backends.
+///
+/// All queries are written once against this type and are dispatched to the backend selected by
+/// `Config::backend`.
+#[derive(diesel::MultiConnection)]
+pub enum WatchConnection {
+    Postgres(PgConnection),
+    Sqlite(SqliteConnection),
+}
+
+pub type WatchPool = Pool<ConnectionManager<WatchConnection>>;
+pub type WatchConn = PooledConnection<ConnectionManager<WatchConnection>>;
+
+/// Configures each new SQLite connection. Foreign keys must be enabled for the `ON DELETE CASCADE`
+/// clauses in the migrations to take effect, and the updater and server share the database file.
+#[derive(Debug)]
+struct SqliteCustomizer;
+
+impl CustomizeConnection<WatchConnection, R2D2Error> for SqliteCustomizer {
+    fn on_acquire(&self, conn: &mut WatchConnection) -> Result<(), R2D2Error> {
+        if let WatchConnection::Sqlite(conn) = conn {
+            conn.batch_execute(
+                "PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;",
+            )
+            .map_err(R2D2Error::QueryError)?;
+        }
+        Ok(())
+    }
+}
 
-/// Connect to a Postgresql database and build a connection pool.
-pub fn build_connection_pool(config: &Config) -> Result<PgPool, Error> {
+/// Connect to the configured database and build a connection pool.
+///
+/// A SQLite database is created if it does not exist and any pending migrations are applied,
+/// since there is no separate server to manage with `diesel_cli`.
+pub fn build_connection_pool(config: &Config) -> Result<WatchPool, Error> {
     let database_url = config.clone().build_database_url();
     info!("Building connection pool at: {database_url}");
-    let pg = ConnectionManager::<PgConnection>::new(&database_url);
-    Builder::new().build(pg).map_err(Error::Pool)
+    let manager = ConnectionManager::<WatchConnection>::new(&database_url);
+    match config.backend {
+        Backend::Postgres => Builder::new().build(manager).map_err(Error::Pool),
+        Backend::Sqlite => {
+            run_pending_migrations(config)?;
+            Builder::new()
+                .connection_customizer(Box::new(SqliteCustomizer))
+                .build(manager)
+                .map_err(Error::Pool)
+        }
+    }
+}
+
+/// Applies any migrations which have not yet been run against the configured database.
+pub fn run_pending_migrations(config: &Config) -> Result<(), Error> {
+    let database_url = config.clone().build_database_url();
+    match config.backend {
+        Backend::Postgres => {
+            let mut conn = PgConnection::establish(&database_url)?;
+            conn.run_pending_migrations(MIGRATIONS)
+                .map_err(|e| Error::Migration(e.to_string()))?;
+        }
+        Backend::Sqlite => {
+            let mut conn = SqliteConnection::establish(&database_url)?;
+            conn.run_pending_migrations(SQLITE_MIGRATIONS)
+                .map_err(|e| Error::Migration(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// The number of records which can be inserted by a single statement on the backend of `conn`.
+pub fn max_size_batch_insert(conn: &WatchConn) -> usize {
+    match &**conn {
+        WatchConnection::Postgres(_) => MAX_SIZE_BATCH_INSERT,
+        WatchConnection::Sqlite(_) => MAX_SIZE_BATCH_INSERT_SQLITE,
+    }
 }
 
 /// Retrieve an idle connection from the pool.
-pub fn get_connection(pool: &PgPool) -> Result<PgConn, Error> {
+pub fn get_connection(pool: &WatchPool) -> Result<WatchConn, Error> {
     pool.get().map_err(Error::Pool)
 }
 
@@ -81,7 +188,7 @@ pub fn get_connection(pool: &PgPool) -> Result<PgConn, Error> {
 /// is compatible with the database. These values will not change (except
 /// `current_blockprint_checkpoint`).
 pub fn insert_active_config(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     new_config_name: String,
     new_slots_per_epoch: u64,
 ) -> Result<(), Error> {
@@ -100,7 +207,7 @@ pub fn insert_active_config(
 }
 
 /// Get the active config from the database.
-pub fn get_active_config(conn: &mut PgConn) -> Result<Option<(String, i32)>, Error> {
+pub fn get_active_config(conn: &mut WatchConn) -> Result<Option<(String, i32)>, Error> {
     use self::active_config::dsl::*;
     Ok(active_config
         .select((config_name, slots_per_epoch))
@@ -117,7 +224,10 @@ pub fn get_active_config(conn: &mut PgConn) -> Result<Option<(String, i32)>, Err
 /// If `new_slot.beacon_block` is `None`, the value in the row will be `null`.
 ///
 /// On a conflict, it will do nothing, leaving the old value.
-pub fn insert_canonical_slot(conn: &mut PgConn, new_slot: WatchCanonicalSlot) -> Result<(), Error> {
+pub fn insert_canonical_slot(
+    conn: &mut WatchConn,
+    new_slot: WatchCanonicalSlot,
+) -> Result<(), Error> {
     diesel::insert_into(canonical_slots::table)
         .values(&new_slot)
         .on_conflict_do_nothing()
@@ -128,7 +238,7 @@ pub fn insert_canonical_slot(conn: &mut PgConn, new_slot: WatchCanonicalSlot) ->
 }
 
 pub fn insert_beacon_block<E: EthSpec>(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     block: SignedBeaconBlock<E>,
     root: WatchHash,
 ) -> Result<(), Error> {
@@ -199,7 +309,7 @@ pub fn insert_beacon_block<E: EthSpec>(
 /// Insert a validator into the `validators` table
 ///
 /// On a conflict, it will only overwrite `status`, `activation_epoch` and `exit_epoch`.
-pub fn insert_validator(conn: &mut PgConn, validator: WatchValidator) -> Result<(), Error> {
+pub fn insert_validator(conn: &mut WatchConn, validator: WatchValidator) -> Result<(), Error> {
     use self::validators::dsl::*;
     let new_index = validator.index;
     let new_public_key = validator.public_key;
@@ -227,7 +337,7 @@ pub fn insert_validator(conn: &mut PgConn, validator: WatchValidator) -> Result<
 /// Validators should be updated through the `insert_validator` function which contains the correct
 /// `on_conflict` clauses.
 pub fn insert_batch_validators(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     all_validators: Vec<WatchValidator>,
 ) -> Result<(), Error> {
     use self::validators::dsl::*;
@@ -251,7 +361,7 @@ pub fn insert_batch_validators(
 
 /// Selects a single row of the `canonical_slots` table corresponding to a given `slot_query`.
 pub fn get_canonical_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
@@ -270,7 +380,7 @@ pub fn get_canonical_slot(
 /// Selects a single row of the `canonical_slots` table corresponding to a given `root_query`.
 /// Only returns the non-skipped slot which matches `root`.
 pub fn get_canonical_slot_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
@@ -291,7 +401,7 @@ pub fn get_canonical_slot_by_root(
 /// `slot_query`.
 #[allow(dead_code)]
 pub fn get_root_at_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchHash>, Error> {
     use self::canonical_slots::dsl::*;
@@ -310,7 +420,9 @@ pub fn get_root_at_slot(
 
 /// Selects `slot` from the row of the `canonical_slots` table corresponding to the minimum value
 /// of `slot`.
-pub fn get_lowest_canonical_slot(conn: &mut PgConn) -> Result<Option<WatchCanonicalSlot>, Error> {
+pub fn get_lowest_canonical_slot(
+    conn: &mut WatchConn,
+) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
     let timer = Instant::now();
 
@@ -328,7 +440,7 @@ pub fn get_lowest_canonical_slot(conn: &mut PgConn) -> Result<Option<WatchCanoni
 /// Selects `slot` from the row of the `canonical_slots` table corresponding to the minimum value
 /// of `slot` and where `skipped == false`.
 pub fn get_lowest_non_skipped_canonical_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
 ) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
     let timer = Instant::now();
@@ -347,7 +459,9 @@ pub fn get_lowest_non_skipped_canonical_slot(
 
 /// Select 'slot' from the row of the `canonical_slots` table corresponding to the maximum value
 /// of `slot`.
-pub fn get_highest_canonical_slot(conn: &mut PgConn) -> Result<Option<WatchCanonicalSlot>, Error> {
+pub fn get_highest_canonical_slot(
+    conn: &mut WatchConn,
+) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
     let timer = Instant::now();
 
@@ -365,7 +479,7 @@ pub fn get_highest_canonical_slot(conn: &mut PgConn) -> Result<Option<WatchCanon
 /// Select 'slot' from the row of the `canonical_slots` table corresponding to the maximum value
 /// of `slot` and where `skipped == false`.
 pub fn get_highest_non_skipped_canonical_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
 ) -> Result<Option<WatchCanonicalSlot>, Error> {
     use self::canonical_slots::dsl::*;
     let timer = Instant::now();
@@ -385,7 +499,7 @@ pub fn get_highest_non_skipped_canonical_slot(
 /// Select all rows of the `canonical_slots` table where `slot >= `start_slot && slot <=
 /// `end_slot`.
 pub fn get_canonical_slots_by_range(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     start_slot: WatchSlot,
     end_slot: WatchSlot,
 ) -> Result<Option<Vec<WatchCanonicalSlot>>, Error> {
@@ -410,7 +524,7 @@ pub fn get_canonical_slots_by_range(
 
 /// Selects `root` from all rows of the `canonical_slots` table which have `beacon_block == null`
 /// and `skipped == false`
-pub fn get_unknown_canonical_blocks(conn: &mut PgConn) -> Result<Vec<WatchHash>, Error> {
+pub fn get_unknown_canonical_blocks(conn: &mut WatchConn) -> Result<Vec<WatchHash>, Error> {
     use self::canonical_slots::dsl::*;
 
     let result = canonical_slots
@@ -424,7 +538,7 @@ pub fn get_unknown_canonical_blocks(conn: &mut PgConn) -> Result<Vec<WatchHash>,
 }
 
 /// Selects the row from the `beacon_blocks` table where `slot` is minimum.
-pub fn get_lowest_beacon_block(conn: &mut PgConn) -> Result<Option<WatchBeaconBlock>, Error> {
+pub fn get_lowest_beacon_block(conn: &mut WatchConn) -> Result<Option<WatchBeaconBlock>, Error> {
     use self::beacon_blocks::dsl::*;
     let timer = Instant::now();
 
@@ -440,7 +554,7 @@ pub fn get_lowest_beacon_block(conn: &mut PgConn) -> Result<Option<WatchBeaconBl
 }
 
 /// Selects the row from the `beacon_blocks` table where `slot` is maximum.
-pub fn get_highest_beacon_block(conn: &mut PgConn) -> Result<Option<WatchBeaconBlock>, Error> {
+pub fn get_highest_beacon_block(conn: &mut WatchConn) -> Result<Option<WatchBeaconBlock>, Error> {
     use self::beacon_blocks::dsl::*;
     let timer = Instant::now();
 
@@ -457,7 +571,7 @@ pub fn get_highest_beacon_block(conn: &mut PgConn) -> Result<Option<WatchBeaconB
 
 /// Selects a single row from the `beacon_blocks` table corresponding to a given `root_query`.
 pub fn get_beacon_block_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchBeaconBlock>, Error> {
     use self::beacon_blocks::dsl::*;
@@ -474,7 +588,7 @@ pub fn get_beacon_block_by_root(
 
 /// Selects a single row from the `beacon_blocks` table corresponding to a given `slot_query`.
 pub fn get_beacon_block_by_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchBeaconBlock>, Error> {
     use self::beacon_blocks::dsl::*;
@@ -494,7 +608,7 @@ pub fn get_beacon_block_by_slot(
 ///
 /// Will return `Ok(None)` if there are no matching blocks (e.g. the tip of the chain).
 pub fn get_beacon_block_with_parent(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     parent: WatchHash,
 ) -> Result<Option<WatchBeaconBlock>, Error> {
     use self::beacon_blocks::dsl::*;
@@ -513,7 +627,7 @@ pub fn get_beacon_block_with_parent(
 /// Select all rows of the `beacon_blocks` table where `slot >= `start_slot && slot <=
 /// `end_slot`.
 pub fn get_beacon_blocks_by_range(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     start_slot: WatchSlot,
     end_slot: WatchSlot,
 ) -> Result<Option<Vec<WatchBeaconBlock>>, Error> {
@@ -533,7 +647,7 @@ pub fn get_beacon_blocks_by_range(
 
 /// Selects a single row of the `proposer_info` table corresponding to a given `root_query`.
 pub fn get_proposer_info_by_root(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     root_query: WatchHash,
 ) -> Result<Option<WatchProposerInfo>, Error> {
     use self::beacon_blocks::dsl::{beacon_blocks, root};
@@ -555,7 +669,7 @@ pub fn get_proposer_info_by_root(
 
 /// Selects a single row of the `proposer_info` table corresponding to a given `slot_query`.
 pub fn get_proposer_info_by_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<Option<WatchProposerInfo>, Error> {
     use self::proposer_info::dsl::*;
@@ -575,7 +689,7 @@ pub fn get_proposer_info_by_slot(
 /// Selects a single row of the `proposer_info` table corresponding to a given `slot_query`.
 #[allow(dead_code)]
 pub fn get_proposer_info_by_range(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     start_slot: WatchSlot,
     end_slot: WatchSlot,
 ) -> Result<Option<Vec<WatchProposerInfo>>, Error> {
@@ -596,7 +710,7 @@ pub fn get_proposer_info_by_range(
 }
 
 pub fn get_validators_latest_proposer_info(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     indices_query: Vec<i32>,
 ) -> Result<HashMap<i32, WatchProposerInfo>, Error> {
     use self::proposer_info::dsl::*;
@@ -628,7 +742,7 @@ pub fn get_validators_latest_proposer_info(
 /// Ideally, this would return the full row, but I have not found a way to do that without using
 /// a much more expensive SQL query.
 pub fn get_all_validators_latest_proposer_info_at_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     target_slot: WatchSlot,
 ) -> Result<HashMap<WatchSlot, i32>, Error> {
     use self::proposer_info::dsl::*;
@@ -653,7 +767,7 @@ pub fn get_all_validators_latest_proposer_info_at_slot(
 /// Selects a single row from the `validators` table corresponding to a given
 /// `validator_index_query`.
 pub fn get_validator_by_index(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     validator_index_query: i32,
 ) -> Result<Option<WatchValidator>, Error> {
     use self::validators::dsl::*;
@@ -672,7 +786,7 @@ pub fn get_validator_by_index(
 /// Selects a single row from the `validators` table corresponding to a given
 /// `public_key_query`.
 pub fn get_validator_by_public_key(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     public_key_query: WatchPK,
 ) -> Result<Option<WatchValidator>, Error> {
     use self::validators::dsl::*;
@@ -692,7 +806,7 @@ pub fn get_validator_by_public_key(
 /// the `indices_query`.
 #[allow(dead_code)]
 pub fn get_validators_by_indices(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     indices_query: Vec<i32>,
 ) -> Result<Vec<WatchValidator>, Error> {
     use self::validators::dsl::*;
@@ -709,7 +823,7 @@ pub fn get_validators_by_indices(
 }
 
 // Selects all rows from the `validators` table.
-pub fn get_all_validators(conn: &mut PgConn) -> Result<Vec<WatchValidator>, Error> {
+pub fn get_all_validators(conn: &mut WatchConn) -> Result<Vec<WatchValidator>, Error> {
     use self::validators::dsl::*;
     let timer = Instant::now();
 
@@ -722,7 +836,7 @@ pub fn get_all_validators(conn: &mut PgConn) -> Result<Vec<WatchValidator>, Erro
 
 /// Counts the number of rows in the `validators` table.
 #[allow(dead_code)]
-pub fn count_validators(conn: &mut PgConn) -> Result<i64, Error> {
+pub fn count_validators(conn: &mut WatchConn) -> Result<i64, Error> {
     use self::validators::dsl::*;
 
     validators.count().get_result(conn).map_err(Error::Database)
@@ -731,7 +845,7 @@ pub fn count_validators(conn: &mut PgConn) -> Result<i64, Error> {
 /// Counts the number of rows in the `validators` table where
 /// `activation_epoch <= target_slot.epoch()`.
 pub fn count_validators_activated_before_slot(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     target_slot: WatchSlot,
     slots_per_epoch: u64,
 ) -> Result<i64, Error> {
@@ -754,9 +868,9 @@ pub fn count_validators_activated_before_slot(
 ///
 /// Due to the ON DELETE CASCADE clause present in the database migration SQL, deleting rows from
 /// `canonical_slots` will delete all corresponding rows in `beacon_blocks, `block_rewards`,
-/// `block_packing` and `proposer_info`.
+/// `block_packing`, `proposer_info` and `missed_slots`.
 pub fn delete_canonical_slots_above(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     slot_query: WatchSlot,
 ) -> Result<usize, Error> {
     use self::canonical_slots::dsl::*;
@@ -772,7 +886,7 @@ pub fn delete_canonical_slots_above(
 /// Deletes all rows of the `suboptimal_attestations` table which have `epoch_start_slot` greater
 /// than `epoch_start_slot_query`.
 pub fn delete_suboptimal_attestations_above(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     epoch_start_slot_query: WatchSlot,
 ) -> Result<usize, Error> {
     use self::suboptimal_attestations::dsl::*;
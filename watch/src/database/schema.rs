@@ -19,6 +19,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    block_blobs (slot) {
+        slot -> Int4,
+        blob_count -> Int4,
+        blob_gas_used -> Int8,
+        excess_blob_gas -> Int8,
+    }
+}
+
 diesel::table! {
     block_packing (slot) {
         slot -> Int4,
@@ -44,6 +53,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    blobs (slot, index) {
+        slot -> Int4,
+        index -> Int4,
+        kzg_commitment -> Text,
+        versioned_hash -> Bytea,
+        size -> Nullable<Int4>,
+        submitter -> Nullable<Text>,
+    }
+}
+
 diesel::table! {
     canonical_slots (slot) {
         slot -> Int4,
@@ -53,6 +73,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    missed_slots (slot) {
+        slot -> Int4,
+        proposer_index -> Int4,
+    }
+}
+
 diesel::table! {
     proposer_info (slot) {
         slot -> Int4,
@@ -61,6 +88,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    reorgs (old_head_root) {
+        old_head_root -> Bytea,
+        old_head_slot -> Int4,
+        old_head_proposer_index -> Nullable<Int4>,
+        new_head_root -> Bytea,
+        new_head_slot -> Int4,
+        new_head_proposer_index -> Int4,
+        common_ancestor_slot -> Int4,
+        depth -> Int4,
+    }
+}
+
 diesel::table! {
     suboptimal_attestations (epoch_start_slot, index) {
         epoch_start_slot -> Int4,
@@ -71,6 +111,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    sync_committee_participation (slot, index) {
+        slot -> Int4,
+        index -> Int4,
+        period -> Int4,
+        participated -> Bool,
+        reward -> Int4,
+    }
+}
+
 diesel::table! {
     validators (index) {
         index -> Int4,
@@ -81,22 +131,31 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(blobs -> beacon_blocks (slot));
+diesel::joinable!(block_blobs -> beacon_blocks (slot));
 diesel::joinable!(block_packing -> beacon_blocks (slot));
 diesel::joinable!(block_rewards -> beacon_blocks (slot));
 diesel::joinable!(blockprint -> beacon_blocks (slot));
+diesel::joinable!(missed_slots -> canonical_slots (slot));
 diesel::joinable!(proposer_info -> beacon_blocks (slot));
 diesel::joinable!(proposer_info -> validators (proposer_index));
 diesel::joinable!(suboptimal_attestations -> canonical_slots (epoch_start_slot));
 diesel::joinable!(suboptimal_attestations -> validators (index));
+diesel::joinable!(sync_committee_participation -> beacon_blocks (slot));
 
 diesel::allow_tables_to_appear_in_same_query!(
     active_config,
     beacon_blocks,
+    blobs,
+    block_blobs,
     block_packing,
     block_rewards,
     blockprint,
     canonical_slots,
+    missed_slots,
     proposer_info,
+    reorgs,
     suboptimal_attestations,
+    sync_committee_participation,
     validators,
 );
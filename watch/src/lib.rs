@@ -1,4 +1,5 @@
 #![cfg(unix)]
+pub mod blobs;
 pub mod block_packing;
 pub mod block_rewards;
 pub mod blockprint;
@@ -7,6 +8,9 @@ pub mod client;
 pub mod config;
 pub mod database;
 pub mod logger;
+pub mod missed_slots;
+pub mod reorgs;
 pub mod server;
 pub mod suboptimal_attestations;
+pub mod sync_committee_participation;
 pub mod updater;
@@ -0,0 +1,103 @@
+use crate::database::{
+    max_size_batch_insert,
+    schema::{canonical_slots, missed_slots},
+    watch_types::WatchSlot,
+    Error, WatchConn,
+};
+
+use diesel::prelude::*;
+use diesel::{Insertable, Queryable};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// A skipped slot, attributed to the validator which was scheduled to propose in it.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = missed_slots)]
+pub struct WatchMissedSlot {
+    pub slot: WatchSlot,
+    pub proposer_index: i32,
+}
+
+/// Insert a batch of values into the `missed_slots` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_batch_missed_slots(
+    conn: &mut WatchConn,
+    slots: Vec<WatchMissedSlot>,
+) -> Result<(), Error> {
+    use self::missed_slots::dsl::*;
+
+    let mut count = 0;
+    let timer = Instant::now();
+
+    for chunk in slots.chunks(max_size_batch_insert(conn)) {
+        count += diesel::insert_into(missed_slots)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    let time_taken = timer.elapsed();
+    debug!("Missed slots inserted, count: {count}, time_taken: {time_taken:?}");
+    Ok(())
+}
+
+/// Selects all rows of the `missed_slots` table where `slot >= start_slot && slot <= end_slot`.
+pub fn get_missed_slots_by_range(
+    conn: &mut WatchConn,
+    start_slot: WatchSlot,
+    end_slot: WatchSlot,
+) -> Result<Vec<WatchMissedSlot>, Error> {
+    use self::missed_slots::dsl::*;
+    let timer = Instant::now();
+
+    let result = missed_slots
+        .filter(slot.ge(start_slot))
+        .filter(slot.le(end_slot))
+        .order_by(slot.asc())
+        .load::<WatchMissedSlot>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!(
+        "Missed slots requested for range: {start_slot} to {end_slot}, time taken: {time_taken:?}"
+    );
+    Ok(result)
+}
+
+/// Selects all rows of the `missed_slots` table attributed to `proposer_index_query`.
+pub fn get_missed_slots_by_proposer(
+    conn: &mut WatchConn,
+    proposer_index_query: i32,
+) -> Result<Vec<WatchMissedSlot>, Error> {
+    use self::missed_slots::dsl::*;
+    let timer = Instant::now();
+
+    let result = missed_slots
+        .filter(proposer_index.eq(proposer_index_query))
+        .order_by(slot.asc())
+        .load::<WatchMissedSlot>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!(
+        "Missed slots requested for proposer: {proposer_index_query}, time taken: {time_taken:?}"
+    );
+    Ok(result)
+}
+
+/// Selects `slot` from all skipped rows of the `canonical_slots` table which do not have a
+/// corresponding row in `missed_slots`.
+pub fn get_unknown_missed_slots(conn: &mut WatchConn) -> Result<Vec<WatchSlot>, Error> {
+    use self::canonical_slots::dsl::{canonical_slots, skipped, slot};
+
+    let join = canonical_slots.left_join(missed_slots::table);
+
+    let result = join
+        .select(slot)
+        .filter(skipped.eq(true))
+        .filter(missed_slots::slot.nullable().is_null())
+        .order_by(slot.desc())
+        .load::<WatchSlot>(conn)?;
+
+    Ok(result)
+}
@@ -0,0 +1,30 @@
+pub mod database;
+mod server;
+mod updater;
+
+use crate::updater::error::Error;
+
+pub use database::{
+    get_missed_slots_by_proposer, get_missed_slots_by_range, get_unknown_missed_slots,
+    insert_batch_missed_slots, WatchMissedSlot,
+};
+pub use server::missed_slot_routes;
+
+use eth2::BeaconNodeHttpClient;
+use std::collections::HashMap;
+use types::{Epoch, Slot};
+
+/// Sends a request to `validator/duties/proposer/{epoch}`.
+/// Formats the response into a map from each slot of the epoch to its scheduled proposer.
+pub async fn get_proposers(
+    bn: &BeaconNodeHttpClient,
+    epoch: Epoch,
+) -> Result<HashMap<Slot, u64>, Error> {
+    Ok(bn
+        .get_validator_duties_proposer(epoch)
+        .await?
+        .data
+        .into_iter()
+        .map(|duty| (duty.slot, duty.validator_index))
+        .collect())
+}
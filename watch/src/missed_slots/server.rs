@@ -0,0 +1,55 @@
+use crate::database::{get_connection, get_validator_by_public_key, WatchPK, WatchPool, WatchSlot};
+use crate::missed_slots::database::{
+    get_missed_slots_by_proposer, get_missed_slots_by_range, WatchMissedSlot,
+};
+use crate::server::Error;
+
+use axum::{
+    extract::{Path, Query},
+    routing::get,
+    Extension, Json, Router,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub async fn get_missed_slots(
+    Query(query): Query<HashMap<String, u64>>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchMissedSlot>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let start_slot = query.get("start_slot").ok_or(Error::BadRequest)?;
+    let end_slot = query.get("end_slot").ok_or(Error::BadRequest)?;
+    if start_slot > end_slot {
+        return Err(Error::BadRequest);
+    }
+    Ok(Json(get_missed_slots_by_range(
+        &mut conn,
+        WatchSlot::new(*start_slot),
+        WatchSlot::new(*end_slot),
+    )?))
+}
+
+pub async fn get_validator_missed_slots(
+    Path(validator_query): Path<String>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchMissedSlot>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let index = if validator_query.starts_with("0x") {
+        let pubkey = WatchPK::from_str(&validator_query).map_err(|_| Error::BadRequest)?;
+        get_validator_by_public_key(&mut conn, pubkey)?
+            .ok_or(Error::NotFound)?
+            .index
+    } else {
+        i32::from_str(&validator_query).map_err(|_| Error::BadRequest)?
+    };
+    Ok(Json(get_missed_slots_by_proposer(&mut conn, index)?))
+}
+
+pub fn missed_slot_routes() -> Router {
+    Router::new()
+        .route("/v1/missed_slots", get(get_missed_slots))
+        .route(
+            "/v1/validators/:validator/missed_slots",
+            get(get_validator_missed_slots),
+        )
+}
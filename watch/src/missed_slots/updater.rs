@@ -0,0 +1,57 @@
+use crate::database::{self, WatchSlot};
+use crate::updater::{Error, UpdateHandler};
+
+use crate::missed_slots::{get_proposers, WatchMissedSlot};
+
+use eth2::types::EthSpec;
+use log::debug;
+use std::collections::BTreeMap;
+
+/// The maximum number of epochs of proposer duties requested in a single update.
+const MAX_EPOCHS_PER_UPDATE: usize = 32;
+
+impl<E: EthSpec> UpdateHandler<E> {
+    /// Attributes each skipped slot in the `canonical_slots` table which is not yet present in the
+    /// `missed_slots` table to its scheduled proposer.
+    ///
+    /// Proposer duties are requested once per epoch, starting from the most recent epoch and
+    /// processing at most `MAX_EPOCHS_PER_UPDATE` epochs.
+    pub async fn fill_missed_slots(&mut self) -> Result<(), Error> {
+        let mut conn = database::get_connection(&self.pool)?;
+
+        if !self.config.missed_slots {
+            return Err(Error::NotEnabled("missed_slots".to_string()));
+        }
+
+        let mut slots_by_epoch = BTreeMap::<_, Vec<WatchSlot>>::new();
+        for slot in database::get_unknown_missed_slots(&mut conn)? {
+            slots_by_epoch
+                .entry(slot.epoch(self.slots_per_epoch))
+                .or_default()
+                .push(slot);
+        }
+
+        if slots_by_epoch.is_empty() {
+            debug!("Missed slots are up to date with the database");
+            return Ok(());
+        }
+
+        for (epoch, slots) in slots_by_epoch.into_iter().rev().take(MAX_EPOCHS_PER_UPDATE) {
+            let proposers = get_proposers(&self.bn, epoch).await?;
+            let missed_slots = slots
+                .into_iter()
+                .filter_map(|slot| {
+                    proposers
+                        .get(&slot.as_slot())
+                        .map(|proposer_index| WatchMissedSlot {
+                            slot,
+                            proposer_index: *proposer_index as i32,
+                        })
+                })
+                .collect();
+            database::insert_batch_missed_slots(&mut conn, missed_slots)?;
+        }
+
+        Ok(())
+    }
+}
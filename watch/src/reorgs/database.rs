@@ -0,0 +1,85 @@
+use crate::database::{
+    schema::reorgs,
+    watch_types::{WatchHash, WatchSlot},
+    Error, WatchConn,
+};
+
+use diesel::prelude::*;
+use diesel::{Insertable, Queryable};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// A reorg observed by the updater, where the head it had previously synced to was replaced by a
+/// block which does not descend from it.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = reorgs)]
+pub struct WatchReorg {
+    pub old_head_root: WatchHash,
+    pub old_head_slot: WatchSlot,
+    /// `None` if the old head was reorged out before its block was downloaded.
+    pub old_head_proposer_index: Option<i32>,
+    pub new_head_root: WatchHash,
+    pub new_head_slot: WatchSlot,
+    pub new_head_proposer_index: i32,
+    /// The highest slot at which the old and new chains agree.
+    pub common_ancestor_slot: WatchSlot,
+    /// The number of slots of the old chain which were reorged out.
+    pub depth: i32,
+}
+
+/// Insert a row into the `reorgs` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_reorg(conn: &mut WatchConn, reorg: WatchReorg) -> Result<(), Error> {
+    let old_head_root = reorg.old_head_root;
+    let depth = reorg.depth;
+
+    diesel::insert_into(reorgs::table)
+        .values(reorg)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    debug!("Reorg inserted, old head: {old_head_root}, depth: {depth}");
+    Ok(())
+}
+
+/// Selects all rows of the `reorgs` table where `new_head_slot >= start_slot && new_head_slot <=
+/// end_slot`.
+pub fn get_reorgs_by_range(
+    conn: &mut WatchConn,
+    start_slot: WatchSlot,
+    end_slot: WatchSlot,
+) -> Result<Vec<WatchReorg>, Error> {
+    use self::reorgs::dsl::*;
+    let timer = Instant::now();
+
+    let result = reorgs
+        .filter(new_head_slot.ge(start_slot))
+        .filter(new_head_slot.le(end_slot))
+        .order_by(new_head_slot.asc())
+        .load::<WatchReorg>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Reorgs requested for range: {start_slot} to {end_slot}, time taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `reorgs` table where the orphaned head was proposed by
+/// `proposer_index_query`.
+pub fn get_reorgs_by_old_head_proposer(
+    conn: &mut WatchConn,
+    proposer_index_query: i32,
+) -> Result<Vec<WatchReorg>, Error> {
+    use self::reorgs::dsl::*;
+    let timer = Instant::now();
+
+    let result = reorgs
+        .filter(old_head_proposer_index.eq(proposer_index_query))
+        .order_by(new_head_slot.asc())
+        .load::<WatchReorg>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Reorgs requested for proposer: {proposer_index_query}, time taken: {time_taken:?}");
+    Ok(result)
+}
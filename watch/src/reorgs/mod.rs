@@ -0,0 +1,7 @@
+pub mod database;
+mod server;
+
+pub use database::{
+    get_reorgs_by_old_head_proposer, get_reorgs_by_range, insert_reorg, WatchReorg,
+};
+pub use server::reorg_routes;
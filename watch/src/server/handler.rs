@@ -1,5 +1,5 @@
 use crate::database::{
-    self, Error as DbError, PgPool, WatchBeaconBlock, WatchCanonicalSlot, WatchHash, WatchPK,
+    self, Error as DbError, WatchBeaconBlock, WatchCanonicalSlot, WatchHash, WatchPK, WatchPool,
     WatchProposerInfo, WatchSlot, WatchValidator,
 };
 use crate::server::Error;
@@ -13,7 +13,7 @@ use std::str::FromStr;
 
 pub async fn get_slot(
     Path(slot): Path<u64>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchCanonicalSlot>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_canonical_slot(
@@ -23,14 +23,14 @@ pub async fn get_slot(
 }
 
 pub async fn get_slot_lowest(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchCanonicalSlot>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_lowest_canonical_slot(&mut conn)?))
 }
 
 pub async fn get_slot_highest(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchCanonicalSlot>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_highest_canonical_slot(&mut conn)?))
@@ -38,7 +38,7 @@ pub async fn get_slot_highest(
 
 pub async fn get_slots_by_range(
     Query(query): Query<HashMap<String, u64>>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<Vec<WatchCanonicalSlot>>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     if let Some(start_slot) = query.get("start_slot") {
@@ -62,7 +62,7 @@ pub async fn get_slots_by_range(
 
 pub async fn get_block(
     Path(block_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBeaconBlock>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     let block_id: BlockId = BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)?;
@@ -80,14 +80,14 @@ pub async fn get_block(
 }
 
 pub async fn get_block_lowest(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBeaconBlock>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_lowest_beacon_block(&mut conn)?))
 }
 
 pub async fn get_block_highest(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBeaconBlock>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_highest_beacon_block(&mut conn)?))
@@ -95,7 +95,7 @@ pub async fn get_block_highest(
 
 pub async fn get_block_previous(
     Path(block_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBeaconBlock>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
@@ -119,7 +119,7 @@ pub async fn get_block_previous(
 
 pub async fn get_block_next(
     Path(block_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchBeaconBlock>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
@@ -137,7 +137,7 @@ pub async fn get_block_next(
 
 pub async fn get_blocks_by_range(
     Query(query): Query<HashMap<String, u64>>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<Vec<WatchBeaconBlock>>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     if let Some(start_slot) = query.get("start_slot") {
@@ -161,7 +161,7 @@ pub async fn get_blocks_by_range(
 
 pub async fn get_block_proposer(
     Path(block_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchProposerInfo>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
@@ -179,7 +179,7 @@ pub async fn get_block_proposer(
 
 pub async fn get_validator(
     Path(validator_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Option<WatchValidator>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     if validator_query.starts_with("0x") {
@@ -194,7 +194,7 @@ pub async fn get_validator(
 }
 
 pub async fn get_all_validators(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<Vec<WatchValidator>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     Ok(Json(database::get_all_validators(&mut conn)?))
@@ -202,7 +202,7 @@ pub async fn get_all_validators(
 
 pub async fn get_validator_latest_proposal(
     Path(validator_query): Path<String>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
 ) -> Result<Json<HashMap<i32, WatchProposerInfo>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
     if validator_query.starts_with("0x") {
@@ -223,7 +223,7 @@ pub async fn get_validator_latest_proposal(
 }
 
 pub async fn get_client_breakdown(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, usize>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
@@ -242,7 +242,7 @@ pub async fn get_client_breakdown(
 }
 
 pub async fn get_client_breakdown_percentages(
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, f64>>, Error> {
     let mut conn = database::get_connection(&pool).map_err(Error::Database)?;
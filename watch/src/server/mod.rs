@@ -1,9 +1,13 @@
+use crate::blobs::blob_routes;
 use crate::block_packing::block_packing_routes;
 use crate::block_rewards::block_rewards_routes;
 use crate::blockprint::blockprint_routes;
 use crate::config::Config as FullConfig;
-use crate::database::{self, PgPool};
+use crate::database::{self, WatchPool};
+use crate::missed_slots::missed_slot_routes;
+use crate::reorgs::reorg_routes;
 use crate::suboptimal_attestations::{attestation_routes, blockprint_attestation_routes};
+use crate::sync_committee_participation::sync_committee_participation_routes;
 use axum::{
     http::{StatusCode, Uri},
     routing::get,
@@ -57,7 +61,7 @@ pub async fn serve(config: FullConfig) -> Result<(), Error> {
 pub fn start_server(
     config: &FullConfig,
     slots_per_epoch: u64,
-    pool: PgPool,
+    pool: WatchPool,
 ) -> Result<
     (
         SocketAddr,
@@ -98,7 +102,11 @@ pub fn start_server(
         .merge(attestation_routes())
         .merge(blockprint_routes())
         .merge(block_packing_routes())
-        .merge(block_rewards_routes());
+        .merge(block_rewards_routes())
+        .merge(reorg_routes())
+        .merge(missed_slot_routes())
+        .merge(sync_committee_participation_routes())
+        .merge(blob_routes());
 
     if config.blockprint.enabled && config.updater.attestations {
         routes = routes.merge(blockprint_attestation_routes())
@@ -1,7 +1,8 @@
 use crate::database::{
+    max_size_batch_insert,
     schema::{suboptimal_attestations, validators},
     watch_types::{WatchPK, WatchSlot},
-    Error, PgConn, MAX_SIZE_BATCH_INSERT,
+    Error, WatchConn,
 };
 
 use diesel::prelude::*;
@@ -66,7 +67,7 @@ impl WatchSuboptimalAttestation {
 ///
 /// On a conflict, it will do nothing.
 pub fn insert_batch_suboptimal_attestations(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     attestations: Vec<WatchSuboptimalAttestation>,
 ) -> Result<(), Error> {
     use self::suboptimal_attestations::dsl::*;
@@ -74,7 +75,7 @@ pub fn insert_batch_suboptimal_attestations(
     let mut count = 0;
     let timer = Instant::now();
 
-    for chunk in attestations.chunks(MAX_SIZE_BATCH_INSERT) {
+    for chunk in attestations.chunks(max_size_batch_insert(conn)) {
         count += diesel::insert_into(suboptimal_attestations)
             .values(chunk)
             .on_conflict_do_nothing()
@@ -88,7 +89,7 @@ pub fn insert_batch_suboptimal_attestations(
 
 /// Selects the row from the `suboptimal_attestations` table where `epoch_start_slot` is minimum.
 pub fn get_lowest_attestation(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
 ) -> Result<Option<WatchSuboptimalAttestation>, Error> {
     use self::suboptimal_attestations::dsl::*;
 
@@ -101,7 +102,7 @@ pub fn get_lowest_attestation(
 
 /// Selects the row from the `suboptimal_attestations` table where `epoch_start_slot` is maximum.
 pub fn get_highest_attestation(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
 ) -> Result<Option<WatchSuboptimalAttestation>, Error> {
     use self::suboptimal_attestations::dsl::*;
 
@@ -115,7 +116,7 @@ pub fn get_highest_attestation(
 /// Selects a single row from the `suboptimal_attestations` table corresponding to a given
 /// `index_query` and `epoch_query`.
 pub fn get_attestation_by_index(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     index_query: i32,
     epoch_query: Epoch,
     slots_per_epoch: u64,
@@ -140,7 +141,7 @@ pub fn get_attestation_by_index(
 /// to a given `pubkey_query` and `epoch_query`.
 #[allow(dead_code)]
 pub fn get_attestation_by_pubkey(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     pubkey_query: WatchPK,
     epoch_query: Epoch,
     slots_per_epoch: u64,
@@ -168,7 +169,7 @@ pub fn get_attestation_by_pubkey(
 /// Selects `index` for all validators in the suboptimal_attestations table
 /// that have `source == false` for the corresponding `epoch_start_slot_query`.
 pub fn get_validators_missed_source(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     epoch_start_slot_query: WatchSlot,
 ) -> Result<Vec<i32>, Error> {
     use self::suboptimal_attestations::dsl::*;
@@ -183,7 +184,7 @@ pub fn get_validators_missed_source(
 /// Selects `index` for all validators in the suboptimal_attestations table
 /// that have `head == false` for the corresponding `epoch_start_slot_query`.
 pub fn get_validators_missed_head(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     epoch_start_slot_query: WatchSlot,
 ) -> Result<Vec<i32>, Error> {
     use self::suboptimal_attestations::dsl::*;
@@ -198,7 +199,7 @@ pub fn get_validators_missed_head(
 /// Selects `index` for all validators in the suboptimal_attestations table
 /// that have `target == false` for the corresponding `epoch_start_slot_query`.
 pub fn get_validators_missed_target(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     epoch_start_slot_query: WatchSlot,
 ) -> Result<Vec<i32>, Error> {
     use self::suboptimal_attestations::dsl::*;
@@ -213,7 +214,7 @@ pub fn get_validators_missed_target(
 /// Selects all rows from the `suboptimal_attestations` table for the given
 /// `epoch_start_slot_query`.
 pub fn get_all_suboptimal_attestations_for_epoch(
-    conn: &mut PgConn,
+    conn: &mut WatchConn,
     epoch_start_slot_query: WatchSlot,
 ) -> Result<Vec<WatchSuboptimalAttestation>, Error> {
     use self::suboptimal_attestations::dsl::*;
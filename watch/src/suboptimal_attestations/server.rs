@@ -1,6 +1,6 @@
 use crate::database::{
     get_canonical_slot, get_connection, get_validator_by_index, get_validator_by_public_key,
-    get_validators_clients_at_slot, get_validators_latest_proposer_info, PgPool, WatchPK,
+    get_validators_clients_at_slot, get_validators_latest_proposer_info, WatchPK, WatchPool,
     WatchSlot,
 };
 
@@ -21,7 +21,7 @@ use types::Epoch;
 // In the future it might be worth differentiating these events.
 pub async fn get_validator_attestation(
     Path((validator_query, epoch_query)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<Option<WatchAttestation>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
@@ -85,7 +85,7 @@ pub async fn get_validator_attestation(
 
 pub async fn get_all_validators_attestations(
     Path(epoch): Path<u64>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<Vec<WatchSuboptimalAttestation>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
@@ -100,7 +100,7 @@ pub async fn get_all_validators_attestations(
 
 pub async fn get_validators_missed_vote(
     Path((vote, epoch)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<Vec<i32>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
@@ -125,7 +125,7 @@ pub async fn get_validators_missed_vote(
 
 pub async fn get_validators_missed_vote_graffiti(
     Path((vote, epoch)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, u64>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
@@ -179,7 +179,7 @@ pub fn attestation_routes() -> Router {
 /// disabled.
 pub async fn get_clients_missed_vote(
     Path((vote, epoch)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, u64>>, Error> {
     let mut conn = get_connection(&pool).map_err(Error::Database)?;
@@ -218,7 +218,7 @@ pub async fn get_clients_missed_vote(
 
 pub async fn get_clients_missed_vote_percentages(
     Path((vote, epoch)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, f64>>, Error> {
     let Json(clients_counts) = get_clients_missed_vote(
@@ -253,7 +253,7 @@ pub async fn get_clients_missed_vote_percentages(
 
 pub async fn get_clients_missed_vote_percentages_relative(
     Path((vote, epoch)): Path<(String, u64)>,
-    Extension(pool): Extension<PgPool>,
+    Extension(pool): Extension<WatchPool>,
     Extension(slots_per_epoch): Extension<u64>,
 ) -> Result<Json<HashMap<String, f64>>, Error> {
     let Json(clients_counts) = get_clients_missed_vote(
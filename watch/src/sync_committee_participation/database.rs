@@ -0,0 +1,223 @@
+use crate::database::{
+    max_size_batch_insert,
+    schema::{beacon_blocks, sync_committee_participation},
+    watch_types::{WatchHash, WatchSlot},
+    Error, WatchConn,
+};
+
+use diesel::dsl::{count_star, sum};
+use diesel::prelude::*;
+use diesel::{Insertable, Queryable};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// The participation of a single sync committee member in the `SyncAggregate` of a block.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize, Deserialize)]
+#[diesel(table_name = sync_committee_participation)]
+pub struct WatchSyncCommitteeParticipation {
+    pub slot: WatchSlot,
+    pub index: i32,
+    pub period: i32,
+    pub participated: bool,
+    pub reward: i32,
+}
+
+/// The participation of a single sync committee member, aggregated over a sync committee period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchSyncCommitteeSummary {
+    pub index: i32,
+    pub period: i32,
+    pub participated: i64,
+    pub missed: i64,
+    pub reward: i64,
+}
+
+/// Insert a batch of values into the `sync_committee_participation` table.
+///
+/// On a conflict, it will do nothing, leaving the old value.
+pub fn insert_batch_sync_committee_participation(
+    conn: &mut WatchConn,
+    participation: Vec<WatchSyncCommitteeParticipation>,
+) -> Result<(), Error> {
+    use self::sync_committee_participation::dsl::*;
+
+    let mut count = 0;
+    let timer = Instant::now();
+
+    for chunk in participation.chunks(max_size_batch_insert(conn)) {
+        count += diesel::insert_into(sync_committee_participation)
+            .values(chunk)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+    }
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee participation inserted, count: {count}, time_taken: {time_taken:?}");
+    Ok(())
+}
+
+/// Selects the lowest `slot` in the `sync_committee_participation` table.
+pub fn get_lowest_sync_committee_participation(
+    conn: &mut WatchConn,
+) -> Result<Option<WatchSlot>, Error> {
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let result = sync_committee_participation
+        .select(slot)
+        .order_by(slot.asc())
+        .limit(1)
+        .first::<WatchSlot>(conn)
+        .optional()?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee participation requested: lowest, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects the highest `slot` in the `sync_committee_participation` table.
+pub fn get_highest_sync_committee_participation(
+    conn: &mut WatchConn,
+) -> Result<Option<WatchSlot>, Error> {
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let result = sync_committee_participation
+        .select(slot)
+        .order_by(slot.desc())
+        .limit(1)
+        .first::<WatchSlot>(conn)
+        .optional()?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee participation requested: highest, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `sync_committee_participation` table corresponding to a given
+/// `root_query`.
+pub fn get_sync_committee_participation_by_root(
+    conn: &mut WatchConn,
+    root_query: WatchHash,
+) -> Result<Vec<WatchSyncCommitteeParticipation>, Error> {
+    use self::beacon_blocks::dsl::{beacon_blocks, root};
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let join = beacon_blocks.inner_join(sync_committee_participation);
+
+    let result = join
+        .select((slot, index, period, participated, reward))
+        .filter(root.eq(root_query))
+        .order_by(index.asc())
+        .load::<WatchSyncCommitteeParticipation>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee participation requested: {root_query}, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Selects all rows of the `sync_committee_participation` table corresponding to a given
+/// `slot_query`.
+pub fn get_sync_committee_participation_by_slot(
+    conn: &mut WatchConn,
+    slot_query: WatchSlot,
+) -> Result<Vec<WatchSyncCommitteeParticipation>, Error> {
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let result = sync_committee_participation
+        .filter(slot.eq(slot_query))
+        .order_by(index.asc())
+        .load::<WatchSyncCommitteeParticipation>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee participation requested: {slot_query}, time_taken: {time_taken:?}");
+    Ok(result)
+}
+
+/// Summarises the `sync_committee_participation` table for each sync committee period in which
+/// `index_query` was a member.
+pub fn get_sync_committee_summaries_by_index(
+    conn: &mut WatchConn,
+    index_query: i32,
+) -> Result<Vec<WatchSyncCommitteeSummary>, Error> {
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let totals = sync_committee_participation
+        .filter(index.eq(index_query))
+        .group_by((index, period))
+        .select((index, period, count_star(), sum(reward)))
+        .load::<(i32, i32, i64, Option<i64>)>(conn)?;
+
+    let missed = sync_committee_participation
+        .filter(index.eq(index_query))
+        .filter(participated.eq(false))
+        .group_by((index, period))
+        .select((index, period, count_star()))
+        .load::<(i32, i32, i64)>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!("Sync committee summaries requested for validator: {index_query}, time_taken: {time_taken:?}");
+    Ok(build_summaries(totals, missed))
+}
+
+/// Summarises the `sync_committee_participation` table for each member of the sync committee of
+/// `period_query`.
+pub fn get_sync_committee_summaries_by_period(
+    conn: &mut WatchConn,
+    period_query: i32,
+) -> Result<Vec<WatchSyncCommitteeSummary>, Error> {
+    use self::sync_committee_participation::dsl::*;
+    let timer = Instant::now();
+
+    let totals = sync_committee_participation
+        .filter(period.eq(period_query))
+        .group_by((index, period))
+        .select((index, period, count_star(), sum(reward)))
+        .load::<(i32, i32, i64, Option<i64>)>(conn)?;
+
+    let missed = sync_committee_participation
+        .filter(period.eq(period_query))
+        .filter(participated.eq(false))
+        .group_by((index, period))
+        .select((index, period, count_star()))
+        .load::<(i32, i32, i64)>(conn)?;
+
+    let time_taken = timer.elapsed();
+    debug!(
+        "Sync committee summaries requested for period: {period_query}, time_taken: {time_taken:?}"
+    );
+    Ok(build_summaries(totals, missed))
+}
+
+/// Combines the per-`(index, period)` totals and missed counts into a sorted list of summaries.
+fn build_summaries(
+    totals: Vec<(i32, i32, i64, Option<i64>)>,
+    missed: Vec<(i32, i32, i64)>,
+) -> Vec<WatchSyncCommitteeSummary> {
+    let missed: HashMap<(i32, i32), i64> = missed
+        .into_iter()
+        .map(|(index, period, count)| ((index, period), count))
+        .collect();
+
+    let mut result: Vec<WatchSyncCommitteeSummary> = totals
+        .into_iter()
+        .map(|(index, period, total, reward)| {
+            let missed = missed.get(&(index, period)).copied().unwrap_or(0);
+            WatchSyncCommitteeSummary {
+                index,
+                period,
+                participated: total - missed,
+                missed,
+                reward: reward.unwrap_or(0),
+            }
+        })
+        .collect();
+
+    result.sort_by_key(|summary| (summary.period, summary.index));
+    result
+}
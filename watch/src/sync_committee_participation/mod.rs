@@ -0,0 +1,146 @@
+pub mod database;
+mod server;
+mod updater;
+
+use crate::database::watch_types::WatchSlot;
+use crate::updater::error::Error;
+
+pub use database::{
+    get_highest_sync_committee_participation, get_lowest_sync_committee_participation,
+    get_sync_committee_participation_by_root, get_sync_committee_participation_by_slot,
+    get_sync_committee_summaries_by_index, get_sync_committee_summaries_by_period,
+    insert_batch_sync_committee_participation, WatchSyncCommitteeParticipation,
+    WatchSyncCommitteeSummary,
+};
+pub use server::sync_committee_participation_routes;
+
+use eth2::types::{BlockId, StateId};
+use eth2::BeaconNodeHttpClient;
+use std::collections::HashMap;
+use std::fmt;
+use types::{Epoch, EthSpec, Slot};
+
+/// The values of the beacon node's spec which are required to process sync committees.
+#[derive(Debug, Clone, Copy)]
+pub struct SyncCommitteeSpec {
+    pub altair_fork_epoch: Epoch,
+    pub epochs_per_sync_committee_period: u64,
+}
+
+impl SyncCommitteeSpec {
+    /// Returns the sync committee period of `slot`.
+    pub fn period(&self, slot: Slot, slots_per_epoch: u64) -> u64 {
+        slot.epoch(slots_per_epoch).as_u64() / self.epochs_per_sync_committee_period
+    }
+}
+
+/// Sends a request to `config/spec`.
+/// Reads the Altair fork epoch and the length of a sync committee period.
+pub async fn get_sync_committee_spec(
+    bn: &BeaconNodeHttpClient,
+) -> Result<SyncCommitteeSpec, Error> {
+    let config_map = bn.get_config_spec::<HashMap<String, String>>().await?.data;
+
+    let get_u64 = |key: &str| -> Result<u64, Error> {
+        config_map
+            .get(key)
+            .ok_or_else(|| {
+                Error::BeaconNodeNotCompatible(format!("No field {key} on beacon node spec"))
+            })?
+            .parse::<u64>()
+            .map_err(|e| Error::BeaconNodeNotCompatible(format!("Invalid {key}: {e}")))
+    };
+
+    Ok(SyncCommitteeSpec {
+        altair_fork_epoch: Epoch::new(get_u64("ALTAIR_FORK_EPOCH")?),
+        epochs_per_sync_committee_period: get_u64("EPOCHS_PER_SYNC_COMMITTEE_PERIOD")?,
+    })
+}
+
+/// Sends a request to `beacon/states/{slot}/sync_committees`.
+/// Returns the validator index at each position of the sync committee active at `slot`.
+pub async fn get_sync_committee(
+    bn: &BeaconNodeHttpClient,
+    slot: Slot,
+    slots_per_epoch: u64,
+) -> Result<Vec<u64>, Error> {
+    Ok(bn
+        .get_beacon_states_sync_committees(StateId::Slot(slot), Some(slot.epoch(slots_per_epoch)))
+        .await?
+        .data
+        .validators)
+}
+
+/// Sends requests to `beacon/blocks/{slot}` and `beacon/rewards/sync_committee/{slot}`.
+/// Formats the responses into a `WatchSyncCommitteeParticipation` for each unique member of
+/// `committee`.
+///
+/// A validator is considered to have participated if the `SyncAggregate` of the block contains
+/// its signature at every position it holds in the committee.
+///
+/// Returns an empty vector if the block does not exist or predates Altair.
+pub async fn get_sync_committee_participation<E: EthSpec>(
+    bn: &BeaconNodeHttpClient,
+    slot: Slot,
+    period: u64,
+    committee: &[u64],
+) -> Result<Vec<WatchSyncCommitteeParticipation>, Error> {
+    let Some(block) = bn
+        .get_beacon_blocks::<E>(BlockId::Slot(slot))
+        .await?
+        .map(|resp| resp.data)
+    else {
+        return Ok(vec![]);
+    };
+    let Ok(sync_aggregate) = block.message().body().sync_aggregate() else {
+        return Ok(vec![]);
+    };
+
+    let mut participation: HashMap<u64, bool> = HashMap::new();
+    for (position, validator_index) in committee.iter().enumerate() {
+        let signed = sync_aggregate
+            .sync_committee_bits
+            .get(position)
+            .unwrap_or(false);
+        participation
+            .entry(*validator_index)
+            .and_modify(|participated| *participated &= signed)
+            .or_insert(signed);
+    }
+
+    let rewards: HashMap<u64, i64> = bn
+        .post_beacon_rewards_sync_committee(BlockId::Slot(slot), &[])
+        .await?
+        .data
+        .unwrap_or_default()
+        .into_iter()
+        .map(|reward| (reward.validator_index, reward.reward))
+        .collect();
+
+    let period = to_i32("period", period)?;
+    let mut result = participation
+        .into_iter()
+        .map(|(index, participated)| {
+            Ok(WatchSyncCommitteeParticipation {
+                slot: WatchSlot::from_slot(slot),
+                index: to_i32("validator index", index)?,
+                period,
+                participated,
+                reward: to_i32("reward", rewards.get(&index).copied().unwrap_or(0))?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    result.sort_by_key(|participation| participation.index);
+
+    Ok(result)
+}
+
+/// Converts `value` into the `integer` type used by the `sync_committee_participation` table.
+fn to_i32<T>(name: &str, value: T) -> Result<i32, Error>
+where
+    T: TryInto<i32> + Copy + fmt::Display,
+{
+    value.try_into().map_err(|_| {
+        Error::BeaconNodeNotCompatible(format!("{name} {value} does not fit in the database"))
+    })
+}
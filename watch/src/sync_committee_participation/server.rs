@@ -0,0 +1,76 @@
+use crate::database::{
+    get_connection, get_validator_by_public_key, WatchHash, WatchPK, WatchPool, WatchSlot,
+};
+use crate::server::Error;
+use crate::sync_committee_participation::database::{
+    get_sync_committee_participation_by_root, get_sync_committee_participation_by_slot,
+    get_sync_committee_summaries_by_index, get_sync_committee_summaries_by_period,
+    WatchSyncCommitteeParticipation, WatchSyncCommitteeSummary,
+};
+
+use axum::{extract::Path, routing::get, Extension, Json, Router};
+use eth2::types::BlockId;
+use std::str::FromStr;
+
+pub async fn get_block_sync_committee_participation(
+    Path(block_query): Path<String>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchSyncCommitteeParticipation>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    match BlockId::from_str(&block_query).map_err(|_| Error::BadRequest)? {
+        BlockId::Root(root) => Ok(Json(get_sync_committee_participation_by_root(
+            &mut conn,
+            WatchHash::from_hash(root),
+        )?)),
+        BlockId::Slot(slot) => Ok(Json(get_sync_committee_participation_by_slot(
+            &mut conn,
+            WatchSlot::from_slot(slot),
+        )?)),
+        _ => Err(Error::BadRequest),
+    }
+}
+
+pub async fn get_validator_sync_committee_participation(
+    Path(validator_query): Path<String>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchSyncCommitteeSummary>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    let index = if validator_query.starts_with("0x") {
+        let pubkey = WatchPK::from_str(&validator_query).map_err(|_| Error::BadRequest)?;
+        get_validator_by_public_key(&mut conn, pubkey)?
+            .ok_or(Error::NotFound)?
+            .index
+    } else {
+        i32::from_str(&validator_query).map_err(|_| Error::BadRequest)?
+    };
+    Ok(Json(get_sync_committee_summaries_by_index(
+        &mut conn, index,
+    )?))
+}
+
+pub async fn get_period_sync_committee_participation(
+    Path(period): Path<u64>,
+    Extension(pool): Extension<WatchPool>,
+) -> Result<Json<Vec<WatchSyncCommitteeSummary>>, Error> {
+    let mut conn = get_connection(&pool).map_err(Error::Database)?;
+    Ok(Json(get_sync_committee_summaries_by_period(
+        &mut conn,
+        period as i32,
+    )?))
+}
+
+pub fn sync_committee_participation_routes() -> Router {
+    Router::new()
+        .route(
+            "/v1/blocks/:block/sync_committee_participation",
+            get(get_block_sync_committee_participation),
+        )
+        .route(
+            "/v1/validators/:validator/sync_committee_participation",
+            get(get_validator_sync_committee_participation),
+        )
+        .route(
+            "/v1/sync_committee_participation/:period",
+            get(get_period_sync_committee_participation),
+        )
+}
@@ -0,0 +1,232 @@
+use crate::database::{self, Error as DbError, WatchConn, WatchSlot};
+use crate::updater::{handler::has_beacon_blocks, Error, UpdateHandler};
+
+use crate::sync_committee_participation::{
+    get_sync_committee, get_sync_committee_participation, get_sync_committee_spec,
+    SyncCommitteeSpec,
+};
+
+use eth2::types::EthSpec;
+use log::{debug, error, warn};
+use std::collections::{hash_map::Entry, HashMap};
+use types::Slot;
+
+/// The maximum number of slots processed in a single update. Each block requires a request for
+/// the block itself and a request for its sync committee rewards.
+const MAX_SLOTS_PER_UPDATE: u64 = 64;
+
+impl<E: EthSpec> UpdateHandler<E> {
+    /// Forward fills the `sync_committee_participation` table starting from the entry with the
+    /// highest slot.
+    ///
+    /// Processes blocks with:
+    /// `start_slot` -> highest filled `sync_committee_participation` + 1 (or lowest beacon block)
+    /// `end_slot` -> highest beacon block
+    ///
+    /// Blocks prior to the Altair fork are never processed and the range will not exceed
+    /// `MAX_SLOTS_PER_UPDATE`.
+    pub async fn fill_sync_committee_participation(&mut self) -> Result<(), Error> {
+        let mut conn = database::get_connection(&self.pool)?;
+
+        // Get the slot of the highest entry in the `sync_committee_participation` table.
+        let highest_filled_slot_opt = if self.config.sync_committee_participation {
+            database::get_highest_sync_committee_participation(&mut conn)?
+        } else {
+            return Err(Error::NotEnabled(
+                "sync_committee_participation".to_string(),
+            ));
+        };
+
+        let spec = get_sync_committee_spec(&self.bn).await?;
+        let altair_start_slot = spec.altair_fork_epoch.start_slot(self.slots_per_epoch);
+
+        let start_slot = if let Some(highest_filled_slot) = highest_filled_slot_opt {
+            highest_filled_slot.as_slot() + 1
+        } else {
+            // No entries in the `sync_committee_participation` table. Use `beacon_blocks`
+            // instead.
+            if let Some(lowest_beacon_block) =
+                database::get_lowest_beacon_block(&mut conn)?.map(|block| block.slot)
+            {
+                lowest_beacon_block.as_slot()
+            } else {
+                // There are no blocks in the database, do not fill the table.
+                warn!("Refusing to fill sync committee participation as there are no blocks in the database");
+                return Ok(());
+            }
+        };
+
+        // The genesis block does not contain a meaningful `SyncAggregate`.
+        let mut start_slot =
+            std::cmp::max(start_slot, std::cmp::max(altair_start_slot, Slot::new(1)));
+
+        // A range without any blocks does not advance the highest filled slot, so continue from
+        // the end of the previous update instead. The range is only skipped while it still holds
+        // no blocks, since a reorg may have since inserted some.
+        if let Some(filled_to) = self.sync_committee_participation_filled_to {
+            if filled_to >= start_slot && !has_beacon_blocks(&mut conn, start_slot, filled_to)? {
+                start_slot = filled_to + 1;
+            }
+        }
+
+        if let Some(highest_beacon_block) =
+            database::get_highest_beacon_block(&mut conn)?.map(|block| block.slot)
+        {
+            let mut end_slot = highest_beacon_block.as_slot();
+
+            if start_slot > end_slot {
+                debug!("Sync committee participation is up to date with the head of the database");
+                return Ok(());
+            }
+
+            // Ensure the size of the update does not exceed the maximum allowed value.
+            if start_slot < end_slot.saturating_sub(MAX_SLOTS_PER_UPDATE) {
+                end_slot = start_slot + MAX_SLOTS_PER_UPDATE
+            }
+
+            self.process_sync_committee_participation(&mut conn, spec, start_slot, end_slot)
+                .await?;
+            self.sync_committee_participation_filled_to = Some(end_slot);
+        } else {
+            // There are no blocks in the `beacon_blocks` database, but there are entries in the
+            // `sync_committee_participation` table. This is a critical failure. It usually means
+            // someone has manually tampered with the database tables and should not occur during
+            // normal operation.
+            error!("Database is corrupted. Please re-sync the database");
+            return Err(Error::Database(DbError::DatabaseCorrupted));
+        }
+
+        Ok(())
+    }
+
+    /// Backfill the `sync_committee_participation` table starting from the entry with the lowest
+    /// slot.
+    ///
+    /// Processes blocks with:
+    /// `start_slot` -> lowest_beacon_block
+    /// `end_slot` -> lowest filled `sync_committee_participation` - 1 (or highest beacon block)
+    ///
+    /// Blocks prior to the Altair fork are never processed and the range will not exceed
+    /// `max_backfill_size_epochs` or `MAX_SLOTS_PER_UPDATE`.
+    pub async fn backfill_sync_committee_participation(&mut self) -> Result<(), Error> {
+        let mut conn = database::get_connection(&self.pool)?;
+        let max_backfill = self.config.max_backfill_size_epochs * self.slots_per_epoch;
+
+        // Get the slot of the lowest entry in the `sync_committee_participation` table.
+        let lowest_filled_slot_opt = if self.config.sync_committee_participation {
+            database::get_lowest_sync_committee_participation(&mut conn)?
+        } else {
+            return Err(Error::NotEnabled(
+                "sync_committee_participation".to_string(),
+            ));
+        };
+
+        let spec = get_sync_committee_spec(&self.bn).await?;
+        let altair_start_slot = spec.altair_fork_epoch.start_slot(self.slots_per_epoch);
+
+        let mut end_slot = if let Some(lowest_filled_slot) = lowest_filled_slot_opt {
+            lowest_filled_slot.as_slot().saturating_sub(1_u64)
+        } else {
+            // No entries in the `sync_committee_participation` table. Use `beacon_blocks`
+            // instead.
+            if let Some(highest_beacon_block) =
+                database::get_highest_beacon_block(&mut conn)?.map(|block| block.slot)
+            {
+                highest_beacon_block.as_slot()
+            } else {
+                // There are no blocks in the database, do not backfill the table.
+                warn!("Refusing to backfill sync committee participation as there are no blocks in the database");
+                return Ok(());
+            }
+        };
+
+        // A range without any blocks does not lower the lowest filled slot, so continue from the
+        // start of the previous update instead.
+        if let Some(backfilled_from) = self.sync_committee_participation_backfilled_from {
+            if backfilled_from <= end_slot
+                && !has_beacon_blocks(&mut conn, backfilled_from, end_slot)?
+            {
+                end_slot = backfilled_from.saturating_sub(1_u64);
+            }
+        }
+
+        if end_slot < std::cmp::max(altair_start_slot, Slot::new(1)) {
+            debug!("Sync committee participation backfill is complete");
+            return Ok(());
+        }
+
+        if let Some(lowest_block_slot) = database::get_lowest_beacon_block(&mut conn)? {
+            let mut start_slot = std::cmp::max(
+                lowest_block_slot.slot.as_slot(),
+                std::cmp::max(altair_start_slot, Slot::new(1)),
+            );
+
+            if start_slot > end_slot {
+                debug!("Sync committee participation is up to date with the base of the database");
+                return Ok(());
+            }
+
+            // Ensure that the range does not exceed `max_backfill` or `MAX_SLOTS_PER_UPDATE`.
+            if start_slot < end_slot.saturating_sub(max_backfill) {
+                start_slot = end_slot.saturating_sub(max_backfill)
+            }
+
+            if start_slot < end_slot.saturating_sub(MAX_SLOTS_PER_UPDATE) {
+                start_slot = end_slot.saturating_sub(MAX_SLOTS_PER_UPDATE)
+            }
+
+            self.process_sync_committee_participation(&mut conn, spec, start_slot, end_slot)
+                .await?;
+            self.sync_committee_participation_backfilled_from = Some(start_slot);
+        } else {
+            // There are no blocks in the `beacon_blocks` database, but there are entries in the
+            // `sync_committee_participation` table. This is a critical failure. It usually means
+            // someone has manually tampered with the database tables and should not occur during
+            // normal operation.
+            error!("Database is corrupted. Please re-sync the database");
+            return Err(Error::Database(DbError::DatabaseCorrupted));
+        }
+
+        Ok(())
+    }
+
+    /// Inserts the sync committee participation of every block in the `beacon_blocks` table with
+    /// `start_slot <= slot <= end_slot`.
+    ///
+    /// Each sync committee is requested at most once.
+    async fn process_sync_committee_participation(
+        &mut self,
+        conn: &mut WatchConn,
+        spec: SyncCommitteeSpec,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<(), Error> {
+        let mut blocks = database::get_beacon_blocks_by_range(
+            conn,
+            WatchSlot::from_slot(start_slot),
+            WatchSlot::from_slot(end_slot),
+        )?
+        .unwrap_or_default();
+        blocks.sort_by_key(|block| block.slot);
+
+        let mut committees: HashMap<u64, Vec<u64>> = HashMap::new();
+
+        for block in blocks {
+            let slot = block.slot.as_slot();
+            let period = spec.period(slot, self.slots_per_epoch);
+
+            let committee = match committees.entry(period) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(get_sync_committee(&self.bn, slot, self.slots_per_epoch).await?)
+                }
+            };
+
+            let participation =
+                get_sync_committee_participation::<E>(&self.bn, slot, period, committee).await?;
+            database::insert_batch_sync_committee_participation(conn, participation)?;
+        }
+
+        Ok(())
+    }
+}
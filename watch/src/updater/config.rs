@@ -20,6 +20,15 @@ pub const fn block_rewards() -> bool {
 pub const fn block_packing() -> bool {
     true
 }
+pub const fn missed_slots() -> bool {
+    true
+}
+pub const fn sync_committee_participation() -> bool {
+    true
+}
+pub const fn blobs() -> bool {
+    true
+}
 
 fn beacon_node_url() -> String {
     BEACON_NODE_URL.to_string()
@@ -48,6 +57,15 @@ pub struct Config {
     /// Whether to sync the block_packing table.
     #[serde(default = "block_packing")]
     pub block_packing: bool,
+    /// Whether to sync the missed_slots table.
+    #[serde(default = "missed_slots")]
+    pub missed_slots: bool,
+    /// Whether to sync the sync_committee_participation table.
+    #[serde(default = "sync_committee_participation")]
+    pub sync_committee_participation: bool,
+    /// Whether to sync the block_blobs and blobs tables.
+    #[serde(default = "blobs")]
+    pub blobs: bool,
 }
 
 impl Default for Config {
@@ -60,6 +78,9 @@ impl Default for Config {
             proposer_info: proposer_info(),
             block_rewards: block_rewards(),
             block_packing: block_packing(),
+            missed_slots: missed_slots(),
+            sync_committee_participation: sync_committee_participation(),
+            blobs: blobs(),
         }
     }
 }
@@ -1,6 +1,8 @@
 use crate::blockprint::WatchBlockprintClient;
 use crate::config::Config as FullConfig;
-use crate::database::{self, PgPool, WatchCanonicalSlot, WatchHash, WatchSlot};
+use crate::database::{
+    self, WatchCanonicalSlot, WatchConn, WatchHash, WatchPool, WatchReorg, WatchSlot,
+};
 use crate::updater::{Config, Error, WatchSpec};
 use beacon_node::beacon_chain::BeaconChainError;
 use eth2::{
@@ -19,7 +21,7 @@ const MAX_EXPECTED_REORG_LENGTH: u64 = 32;
 /// Ensure the existing database is valid for this run.
 pub async fn ensure_valid_database<E: EthSpec>(
     spec: &WatchSpec<E>,
-    pool: &mut PgPool,
+    pool: &mut WatchPool,
 ) -> Result<(), Error> {
     let mut conn = database::get_connection(pool)?;
 
@@ -43,11 +45,19 @@ pub async fn ensure_valid_database<E: EthSpec>(
 }
 
 pub struct UpdateHandler<E: EthSpec> {
-    pub pool: PgPool,
+    pub pool: WatchPool,
     pub bn: BeaconNodeHttpClient,
     pub blockprint: Option<WatchBlockprintClient>,
     pub config: Config,
     pub slots_per_epoch: u64,
+    /// The last slot processed by `fill_sync_committee_participation`.
+    pub sync_committee_participation_filled_to: Option<Slot>,
+    /// The first slot processed by `backfill_sync_committee_participation`.
+    pub sync_committee_participation_backfilled_from: Option<Slot>,
+    /// The last slot processed by `fill_blobs`.
+    pub blobs_filled_to: Option<Slot>,
+    /// The first slot processed by `backfill_blobs`.
+    pub blobs_backfilled_from: Option<Slot>,
     pub _phantom: PhantomData<E>,
 }
 
@@ -85,6 +95,10 @@ impl<E: EthSpec> UpdateHandler<E> {
             blockprint,
             config: config.updater,
             slots_per_epoch: spec.slots_per_epoch(),
+            sync_committee_participation_filled_to: None,
+            sync_committee_participation_backfilled_from: None,
+            blobs_filled_to: None,
+            blobs_backfilled_from: None,
             _phantom: PhantomData,
         })
     }
@@ -117,8 +131,9 @@ impl<E: EthSpec> UpdateHandler<E> {
     /// pair.
     /// 3. Go back `MAX_EXPECTED_REORG_LENGTH` slots through the database ensuring it is
     /// consistent with the beacon node. If a re-org occurs beyond this range, we cannot recover.
-    /// 4. Remove any invalid slots from the database.
-    /// 5. Sync all blocks between the first valid block of the database and the head of the beacon
+    /// 4. Record any reorg of the previous head of the database in the `reorgs` table.
+    /// 5. Remove any invalid slots from the database.
+    /// 6. Sync all blocks between the first valid block of the database and the head of the beacon
     /// chain.
     ///
     /// In the event there are no slots present in the database, it will sync from the head block
@@ -136,9 +151,19 @@ impl<E: EthSpec> UpdateHandler<E> {
         if let Some(latest_matching_canonical_slot) =
             self.get_first_matching_block(bn_header.clone()).await?
         {
+            // Load the head of the database before any orphaned slots are removed.
+            let old_head = database::get_highest_non_skipped_canonical_slot(&mut conn)?;
+
             // Check for reorgs.
             let latest_db_slot = self.check_for_reorg(latest_matching_canonical_slot).await?;
 
+            if let Some(old_head) = old_head {
+                if old_head.slot.as_slot() > latest_db_slot {
+                    self.record_reorg(old_head, &bn_header, header_root, latest_db_slot)
+                        .await?;
+                }
+            }
+
             // Remove all slots above `latest_db_slot` from the database.
             let result = database::delete_canonical_slots_above(
                 &mut conn,
@@ -302,6 +327,80 @@ impl<E: EthSpec> UpdateHandler<E> {
         Ok(latest_canonical_slot.slot.as_slot())
     }
 
+    /// Inserts a row into the `reorgs` table if `old_head` is no longer canonical according to the
+    /// beacon node.
+    ///
+    /// `common_ancestor_slot` is the slot below which the database is known to be consistent with
+    /// the beacon node.
+    ///
+    /// Must be called before the slots above `common_ancestor_slot` are removed from the database,
+    /// since the proposer of `old_head` is read from the `proposer_info` table.
+    pub async fn record_reorg(
+        &mut self,
+        old_head: WatchCanonicalSlot,
+        new_head: &BeaconBlockHeader,
+        new_head_root: Hash256,
+        common_ancestor_slot: Slot,
+    ) -> Result<(), Error> {
+        let mut conn = database::get_connection(&self.pool)?;
+
+        // Slots may be removed from the database without a reorg (e.g. if a slot is missing), in
+        // which case the old head is still in the canonical chain.
+        let old_head_canonical = get_header(&self.bn, BlockId::Slot(old_head.slot.as_slot()))
+            .await?
+            .is_some_and(|header| header.canonical_root() == old_head.root.as_hash());
+        if old_head_canonical {
+            return Ok(());
+        }
+
+        // `common_ancestor_slot` is a lower bound, so search the slots above it for the most recent
+        // block which is shared by both chains.
+        let mut common_ancestor_slot = common_ancestor_slot;
+        for slot in (common_ancestor_slot.as_u64() + 1..old_head.slot.as_u64()).rev() {
+            let slot = Slot::new(slot);
+            let Some(db_canonical_slot) =
+                database::get_canonical_slot(&mut conn, WatchSlot::from_slot(slot))?
+            else {
+                continue;
+            };
+            if db_canonical_slot.skipped {
+                continue;
+            }
+            if let Some(header) = get_header(&self.bn, BlockId::Slot(slot)).await? {
+                if header.canonical_root() == db_canonical_slot.root.as_hash() {
+                    common_ancestor_slot = slot;
+                    break;
+                }
+            }
+        }
+
+        let old_head_proposer_index =
+            database::get_proposer_info_by_slot(&mut conn, old_head.slot)?
+                .map(|proposer_info| proposer_info.proposer_index);
+        let depth = old_head.slot.as_slot().saturating_sub(common_ancestor_slot);
+
+        warn!(
+            "Reorg detected at slot {}, old head: {}, new head: {new_head_root:?}, depth: {depth}",
+            new_head.slot, old_head.root
+        );
+
+        database::insert_reorg(
+            &mut conn,
+            WatchReorg {
+                old_head_root: old_head.root,
+                old_head_slot: old_head.slot,
+                old_head_proposer_index,
+                new_head_root: WatchHash::from_hash(new_head_root),
+                new_head_slot: WatchSlot::from_slot(new_head.slot),
+                new_head_proposer_index: new_head.proposer_index as i32,
+                common_ancestor_slot: WatchSlot::from_slot(common_ancestor_slot),
+                depth: depth.as_u64() as i32,
+            },
+        )?;
+
+        Ok(())
+    }
+
     /// Fills the canonical slots table beginning from `start_slot` and ending at `end_slot`.
     /// It fills in reverse order, that is, `start_slot` is higher than `end_slot`.
     ///
@@ -469,3 +568,18 @@ impl<E: EthSpec> UpdateHandler<E> {
         Ok(())
     }
 }
+
+/// Returns `true` if the `beacon_blocks` table contains a block with
+/// `start_slot <= slot <= end_slot`.
+pub fn has_beacon_blocks(
+    conn: &mut WatchConn,
+    start_slot: Slot,
+    end_slot: Slot,
+) -> Result<bool, Error> {
+    Ok(database::get_beacon_blocks_by_range(
+        conn,
+        WatchSlot::from_slot(start_slot),
+        WatchSlot::from_slot(end_slot),
+    )?
+    .map_or(false, |blocks| !blocks.is_empty()))
+}
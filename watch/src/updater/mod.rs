@@ -157,6 +157,32 @@ pub async fn run_once<E: EthSpec>(
         debug!("Block packing update complete, time taken: {packing_timer_elapsed:?}");
     }
 
+    if config.updater.missed_slots {
+        info!("Updating missed slots");
+        let missed_slots_timer = Instant::now();
+        watch.fill_missed_slots().await?;
+        let missed_slots_timer_elapsed = missed_slots_timer.elapsed();
+        debug!("Missed slots update complete, time taken: {missed_slots_timer_elapsed:?}");
+    }
+
+    if config.updater.sync_committee_participation {
+        info!("Updating sync committee participation");
+        let sync_committee_timer = Instant::now();
+        watch.fill_sync_committee_participation().await?;
+        watch.backfill_sync_committee_participation().await?;
+        let sync_committee_timer_elapsed = sync_committee_timer.elapsed();
+        debug!("Sync committee participation update complete, time taken: {sync_committee_timer_elapsed:?}");
+    }
+
+    if config.updater.blobs {
+        info!("Updating blobs");
+        let blobs_timer = Instant::now();
+        watch.fill_blobs().await?;
+        watch.backfill_blobs().await?;
+        let blobs_timer_elapsed = blobs_timer.elapsed();
+        debug!("Blobs update complete, time taken: {blobs_timer_elapsed:?}");
+    }
+
     if config.blockprint.enabled {
         info!("Updating blockprint");
         let blockprint_timer = Instant::now();
@@ -2,7 +2,10 @@
 #![cfg(unix)]
 
 use beacon_chain::{
-    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
+    test_utils::{
+        AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+        SyncCommitteeStrategy,
+    },
     ChainConfig,
 };
 use eth2::{types::BlockId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
@@ -15,16 +18,17 @@ use rand::{thread_rng, Rng};
 use std::collections::HashMap;
 use std::env;
 use std::time::Duration;
-use testcontainers::{clients::Cli, core::WaitFor, Image, RunnableImage};
+use tempfile::TempDir;
+use testcontainers::{clients::Cli, core::WaitFor, Container, Image, RunnableImage};
 use tokio::{runtime, task::JoinHandle};
 use tokio_postgres::{config::Config as PostgresConfig, Client, NoTls};
-use types::{Hash256, MainnetEthSpec, Slot};
+use types::{ChainSpec, EthSpec, ForkName, Hash256, MainnetEthSpec, Slot};
 use unused_port::unused_tcp4_port;
 use url::Url;
 use watch::{
     client::WatchHttpClient,
     config::Config,
-    database::{self, Config as DatabaseConfig, PgPool, WatchSlot},
+    database::{self, Backend, Config as DatabaseConfig, WatchPool, WatchSlot},
     server::{start_server, Config as ServerConfig},
     updater::{handler::*, run_updater, Config as UpdaterConfig, WatchSpec},
 };
@@ -114,24 +118,58 @@ pub fn get_host_from_env() -> String {
     env::var(WATCH_HOST_ENV_VARIABLE).unwrap_or_else(|_| "localhost".to_string())
 }
 
+/// Generates a `postgres` and a `sqlite` test for the test body `$name`, which takes the
+/// `Backend` to run against.
+macro_rules! test_all_backends {
+    ($name:ident) => {
+        mod $name {
+            use super::*;
+
+            #[tokio::test]
+            async fn postgres() {
+                super::$name(Backend::Postgres).await
+            }
+
+            #[tokio::test]
+            async fn sqlite() {
+                super::$name(Backend::Sqlite).await
+            }
+        }
+    };
+}
+
 struct TesterBuilder {
     pub harness: BeaconChainHarness<EphemeralHarnessType<E>>,
     pub config: Config,
     _bn_network_rx: NetworkReceivers<E>,
+    _datadir: TempDir,
 }
 
 impl TesterBuilder {
-    pub async fn new() -> TesterBuilder {
-        let harness = BeaconChainHarness::builder(E::default())
-            .default_spec()
+    pub async fn new(backend: Backend) -> TesterBuilder {
+        Self::new_with_spec(None, backend).await
+    }
+
+    pub async fn new_with_spec(spec: Option<ChainSpec>, backend: Backend) -> TesterBuilder {
+        // Post-merge chains require an execution layer to produce blocks.
+        let post_merge = spec
+            .as_ref()
+            .map_or(false, |spec| spec.bellatrix_fork_epoch.is_some());
+
+        let builder = BeaconChainHarness::builder(E::default())
+            .spec_or_default(spec)
             .chain_config(ChainConfig {
                 reconstruct_historic_states: true,
                 ..ChainConfig::default()
             })
             .logger(test_logger())
             .deterministic_keypairs(VALIDATOR_COUNT)
-            .fresh_ephemeral_store()
-            .build();
+            .fresh_ephemeral_store();
+        let harness = if post_merge {
+            builder.mock_execution_layer().build()
+        } else {
+            builder.build()
+        };
 
         /*
          * Spawn a Beacon Node HTTP API.
@@ -153,12 +191,15 @@ impl TesterBuilder {
          * Create a watch configuration
          */
         let database_port = unused_tcp4_port().expect("Unable to find unused port.");
+        let datadir = TempDir::new().expect("Unable to create temporary directory.");
         let server_port = 0;
         let config = Config {
             database: DatabaseConfig {
+                backend,
                 dbname: random_dbname(),
                 port: database_port,
                 host: get_host_from_env(),
+                sqlite_path: datadir.path().join("watch.sqlite"),
                 ..Default::default()
             },
             server: ServerConfig {
@@ -180,9 +221,10 @@ impl TesterBuilder {
             harness,
             config,
             _bn_network_rx,
+            _datadir: datadir,
         }
     }
-    pub async fn build(self, pool: PgPool) -> Tester {
+    pub async fn build(self, pool: WatchPool) -> Tester {
         /*
          * Spawn a Watch HTTP API.
          */
@@ -217,10 +259,25 @@ impl TesterBuilder {
             config: self.config,
             updater,
             _bn_network_rx: self._bn_network_rx,
+            _datadir: self._datadir,
         }
     }
-    async fn initialize_database(&self) -> PgPool {
-        create_test_database(&self.config.database).await;
+    /// Starts a PostgreSQL docker container if the `Postgres` backend is being tested. The
+    /// SQLite database is created by `initialize_database`.
+    fn start_database<'d>(&self, docker: &'d Cli) -> Option<Container<'d, Postgres>> {
+        match self.config.database.backend {
+            Backend::Postgres => {
+                let image = RunnableImage::from(Postgres::default())
+                    .with_mapped_port((self.config.database.port, 5432));
+                Some(docker.run(image))
+            }
+            Backend::Sqlite => None,
+        }
+    }
+    async fn initialize_database(&self) -> WatchPool {
+        if self.config.database.backend == Backend::Postgres {
+            create_test_database(&self.config.database).await;
+        }
         database::utils::run_migrations(&self.config.database);
         database::build_connection_pool(&self.config.database)
             .expect("Could not build connection pool")
@@ -233,6 +290,7 @@ struct Tester {
     pub config: Config,
     pub updater: UpdateHandler<E>,
     _bn_network_rx: NetworkReceivers<E>,
+    _datadir: TempDir,
 }
 
 impl Tester {
@@ -273,6 +331,51 @@ impl Tester {
         self
     }
 
+    /// Extend the chain with blocks which are signed by the entire sync committee.
+    pub async fn extend_chain_with_sync_committee(&mut self, num_blocks: u64) -> &mut Self {
+        self.harness.advance_slot();
+        self.harness
+            .extend_chain_with_sync(
+                num_blocks as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+                SyncCommitteeStrategy::AllValidators,
+            )
+            .await;
+        self
+    }
+
+    /// Extend the chain with blocks which receive no attestations, so that they can be orphaned.
+    pub async fn extend_chain_without_attestations(&mut self, num_blocks: u64) -> &mut Self {
+        self.harness.advance_slot();
+        self.harness
+            .extend_chain(
+                num_blocks as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::SomeValidators(vec![]),
+            )
+            .await;
+        self
+    }
+
+    // Orphan the head block by building a block on its parent in the next slot.
+    pub async fn orphan_head(&mut self) -> &mut Self {
+        let head_slot = self.harness.chain.head_snapshot().beacon_block.slot();
+        self.harness.advance_slot();
+        let first_slot = self.harness.get_current_slot();
+        self.harness
+            .extend_chain(
+                1,
+                BlockStrategy::ForkCanonicalChainAt {
+                    previous_slot: head_slot - 1,
+                    first_slot,
+                },
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        self
+    }
+
     /// Run the watch updater service.
     pub async fn run_update_service(&mut self, num_runs: usize) -> &mut Self {
         for _ in 0..num_runs {
@@ -340,6 +443,36 @@ impl Tester {
         self
     }
 
+    pub async fn fill_sync_committee_participation(&mut self) -> &mut Self {
+        self.updater
+            .fill_sync_committee_participation()
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn backfill_sync_committee_participation(&mut self) -> &mut Self {
+        self.updater
+            .backfill_sync_committee_participation()
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn fill_blobs(&mut self) -> &mut Self {
+        self.updater.fill_blobs().await.unwrap();
+
+        self
+    }
+
+    pub async fn backfill_blobs(&mut self) -> &mut Self {
+        self.updater.backfill_blobs().await.unwrap();
+
+        self
+    }
+
     pub async fn assert_canonical_slots_empty(&mut self) -> &mut Self {
         let lowest_slot = self
             .client
@@ -403,6 +536,58 @@ impl Tester {
         self
     }
 
+    /// Check that a single reorg of `old_head_slot` onto `new_head_slot` is recorded, and that the
+    /// orphaned slot is attributed to the proposer of the orphaned block.
+    pub async fn assert_single_slot_reorg(
+        &mut self,
+        old_head_slot: u64,
+        new_head_slot: u64,
+    ) -> &mut Self {
+        let reorgs = self
+            .client
+            .get_reorgs(0, new_head_slot)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reorgs.len(), 1);
+        let reorg = &reorgs[0];
+
+        assert_eq!(reorg.old_head_slot, WatchSlot::new(old_head_slot));
+        assert_eq!(reorg.new_head_slot, WatchSlot::new(new_head_slot));
+        assert_eq!(
+            reorg.common_ancestor_slot,
+            WatchSlot::new(old_head_slot - 1)
+        );
+        assert_eq!(reorg.depth, 1);
+        assert_eq!(
+            reorg.new_head_root.as_hash(),
+            self.harness.chain.head_beacon_block_root()
+        );
+
+        let proposer_index = reorg.old_head_proposer_index.unwrap();
+        let missed_slots = self
+            .client
+            .get_missed_slots(old_head_slot, new_head_slot)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(missed_slots.len(), 1);
+        assert_eq!(missed_slots[0].slot, WatchSlot::new(old_head_slot));
+        assert_eq!(missed_slots[0].proposer_index, proposer_index);
+
+        let validator_missed_slots = self
+            .client
+            .get_validator_missed_slots(proposer_index)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(validator_missed_slots
+            .iter()
+            .any(|missed_slot| missed_slot.slot == WatchSlot::new(old_head_slot)));
+
+        self
+    }
+
     pub async fn assert_all_validators_exist(&mut self) -> &mut Self {
         assert_eq!(
             self.client
@@ -545,6 +730,142 @@ impl Tester {
         self
     }
 
+    /// Check that every validator participated in, and was rewarded for, the sync aggregate of the
+    /// highest block.
+    pub async fn assert_highest_block_has_sync_committee_participation(&mut self) -> &mut Self {
+        let block = self
+            .client
+            .get_highest_beacon_block()
+            .await
+            .unwrap()
+            .unwrap();
+
+        let participation = self
+            .client
+            .get_block_sync_committee_participation(BlockId::Root(block.root.as_hash()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(participation.len(), VALIDATOR_COUNT);
+        for member in participation {
+            assert_eq!(member.slot, block.slot);
+            assert!(member.participated);
+            assert!(member.reward > 0);
+        }
+
+        self
+    }
+
+    /// Check that every validator has a summary for `period` covering `num_blocks` blocks.
+    pub async fn assert_sync_committee_summaries(
+        &mut self,
+        period: u64,
+        num_blocks: i64,
+    ) -> &mut Self {
+        let summaries = self
+            .client
+            .get_sync_committee_participation(period)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(summaries.len(), VALIDATOR_COUNT);
+        for summary in &summaries {
+            assert_eq!(summary.period, period as i32);
+            assert_eq!(summary.participated + summary.missed, num_blocks);
+            assert!(summary.participated > 0);
+        }
+
+        let validator_summaries = self
+            .client
+            .get_validator_sync_committee_participation(summaries[0].index)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(validator_summaries, vec![summaries[0].clone()]);
+
+        self
+    }
+
+    /// Check that the blobs recorded for the highest block match the KZG commitments of the head
+    /// block of the harness.
+    pub async fn assert_highest_block_has_blobs(&mut self) -> &mut Self {
+        let head = self.harness.chain.head_snapshot();
+        let commitments = head
+            .beacon_block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .unwrap()
+            .clone();
+
+        let block = self
+            .client
+            .get_highest_beacon_block()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.root.as_hash(), head.beacon_block_root);
+
+        let blobs = self
+            .client
+            .get_block_blobs(BlockId::Root(block.root.as_hash()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(blobs.len(), commitments.len());
+        for (blob, commitment) in blobs.iter().zip(commitments.iter()) {
+            assert_eq!(blob.slot, block.slot);
+            assert_eq!(
+                blob.versioned_hash.as_hash(),
+                commitment.calculate_versioned_hash()
+            );
+            // Blobs are within the retention period, so their sizes must be known.
+            assert!(blob.size.is_some());
+        }
+
+        let usage = self
+            .client
+            .get_blob_usage(block.slot.as_u64(), block.slot.as_u64())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(usage.len(), 1);
+        assert_eq!(usage[0].blob_count as usize, commitments.len());
+
+        self
+    }
+
+    /// Check that the blob counts of all submitters sum to the number of blobs in
+    /// `start_slot..=end_slot`.
+    pub async fn assert_blob_submitters_consistent(
+        &mut self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> &mut Self {
+        let usage = self
+            .client
+            .get_blob_usage(start_slot, end_slot)
+            .await
+            .unwrap()
+            .unwrap();
+        let submitters = self
+            .client
+            .get_blob_submitters(start_slot, end_slot)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let total_blobs: i64 = usage.iter().map(|block| block.blob_count as i64).sum();
+        let submitted_blobs: i64 = submitters.iter().map(|s| s.blob_count).sum();
+        assert_eq!(total_blobs, submitted_blobs);
+
+        self
+    }
+
     /// Check that the canonical chain in watch matches that of the harness. Also check that all
     /// canonical blocks can be retrieved.
     pub async fn assert_canonical_chain_consistent(&mut self, last_slot: u64) -> &mut Self {
@@ -628,15 +949,12 @@ pub fn random_dbname() -> String {
     format!("test_{}", s)
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn short_chain() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(short_chain);
+async fn short_chain(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -656,15 +974,12 @@ async fn short_chain() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn short_chain_sync_starts_on_skip_slot() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(short_chain_sync_starts_on_skip_slot);
+async fn short_chain_sync_starts_on_skip_slot(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -694,15 +1009,12 @@ async fn short_chain_sync_starts_on_skip_slot() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn short_chain_with_skip_slot() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(short_chain_with_skip_slot);
+async fn short_chain_with_skip_slot(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -739,15 +1051,12 @@ async fn short_chain_with_skip_slot() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn short_chain_with_reorg() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(short_chain_with_reorg);
+async fn short_chain_with_reorg(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -786,15 +1095,45 @@ async fn short_chain_with_reorg() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn chain_grows() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(short_chain_with_orphaned_head);
+async fn short_chain_with_orphaned_head(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        .extend_chain(5)
+        .await
+        .extend_chain_without_attestations(1)
+        .await
+        .run_update_service(1)
+        .await
+        .assert_highest_canonical_slot(6)
+        .await
+        .orphan_head()
+        .await
+        .run_update_service(1)
+        .await
+        .assert_highest_canonical_slot(7)
+        .await
+        .assert_slot_is_skipped(6)
+        .await
+        .assert_canonical_chain_consistent(0)
+        .await
+        .assert_single_slot_reorg(6, 7)
+        .await;
+}
+
+test_all_backends!(chain_grows);
+async fn chain_grows(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
+
+    let docker = Cli::default();
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -850,15 +1189,12 @@ async fn chain_grows() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn chain_grows_with_metadata() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(chain_grows_with_metadata);
+async fn chain_grows_with_metadata(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -957,15 +1293,12 @@ async fn chain_grows_with_metadata() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn chain_grows_with_metadata_and_multiple_skip_slots() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(chain_grows_with_metadata_and_multiple_skip_slots);
+async fn chain_grows_with_metadata_and_multiple_skip_slots(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -1071,15 +1404,12 @@ async fn chain_grows_with_metadata_and_multiple_skip_slots() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn chain_grows_to_second_epoch() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(chain_grows_to_second_epoch);
+async fn chain_grows_to_second_epoch(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -1159,15 +1489,12 @@ async fn chain_grows_to_second_epoch() {
         .await;
 }
 
-#[cfg(unix)]
-#[tokio::test]
-async fn large_chain() {
-    let builder = TesterBuilder::new().await;
+test_all_backends!(large_chain);
+async fn large_chain(backend: Backend) {
+    let builder = TesterBuilder::new(backend).await;
 
     let docker = Cli::default();
-    let image = RunnableImage::from(Postgres::default())
-        .with_mapped_port((builder.config.database.port, 5432));
-    let _node = docker.run(image);
+    let _node = builder.start_database(&docker);
 
     let pool = builder.initialize_database().await;
     let mut tester = builder.build(pool).await;
@@ -1290,3 +1617,169 @@ async fn large_chain() {
         .assert_all_blocks_have_metadata()
         .await;
 }
+
+test_all_backends!(altair_chain_with_sync_committee_participation);
+async fn altair_chain_with_sync_committee_participation(backend: Backend) {
+    let spec = ForkName::Altair.make_genesis_spec(E::default_spec());
+    let builder = TesterBuilder::new_with_spec(Some(spec), backend).await;
+
+    let docker = Cli::default();
+    let _node = builder.start_database(&docker);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        // Apply two epochs of blocks signed by the sync committee.
+        .extend_chain_with_sync_committee(SLOTS_PER_EPOCH * 2)
+        .await
+        .perform_head_update()
+        .await
+        .perform_backfill()
+        .await
+        .update_validator_set()
+        .await
+        .update_unknown_blocks()
+        .await
+        // Backfill before forward fill to ensure order is arbitrary.
+        .backfill_sync_committee_participation()
+        .await
+        .fill_sync_committee_participation()
+        .await
+        .assert_highest_block_has_sync_committee_participation()
+        .await
+        // Every block except genesis is included in the first period.
+        .assert_sync_committee_summaries(0, SLOTS_PER_EPOCH as i64 * 2)
+        .await;
+}
+
+test_all_backends!(altair_chain_with_sync_committee_participation_across_skip_slots);
+async fn altair_chain_with_sync_committee_participation_across_skip_slots(backend: Backend) {
+    let spec = ForkName::Altair.make_genesis_spec(E::default_spec());
+    let builder = TesterBuilder::new_with_spec(Some(spec), backend).await;
+
+    let docker = Cli::default();
+    let _node = builder.start_database(&docker);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        .extend_chain_with_sync_committee(8)
+        .await
+        .perform_head_update()
+        .await
+        .perform_backfill()
+        .await
+        .update_validator_set()
+        .await
+        .update_unknown_blocks()
+        .await
+        .fill_sync_committee_participation()
+        .await
+        .assert_highest_block_has_sync_committee_participation()
+        .await;
+
+    // Skip enough slots that the next update only covers slots without blocks.
+    for _ in 0..SLOTS_PER_EPOCH * 3 {
+        tester.skip_slot();
+    }
+
+    tester
+        .extend_chain_with_sync_committee(8)
+        .await
+        .perform_head_update()
+        .await
+        .update_unknown_blocks()
+        .await
+        // The first update covers the skipped slots and the second reaches the new blocks.
+        .fill_sync_committee_participation()
+        .await
+        .fill_sync_committee_participation()
+        .await
+        .assert_highest_block_has_sync_committee_participation()
+        .await
+        .assert_sync_committee_summaries(0, 16)
+        .await;
+}
+
+test_all_backends!(deneb_chain_with_blobs);
+async fn deneb_chain_with_blobs(backend: Backend) {
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let builder = TesterBuilder::new_with_spec(Some(spec), backend).await;
+
+    let docker = Cli::default();
+    let _node = builder.start_database(&docker);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        .extend_chain(SLOTS_PER_EPOCH)
+        .await
+        .perform_head_update()
+        .await
+        .perform_backfill()
+        .await
+        .update_validator_set()
+        .await
+        .update_unknown_blocks()
+        .await
+        // Backfill before forward fill to ensure order is arbitrary.
+        .backfill_blobs()
+        .await
+        .fill_blobs()
+        .await
+        .assert_highest_block_has_blobs()
+        .await
+        .assert_blob_submitters_consistent(0, SLOTS_PER_EPOCH)
+        .await;
+}
+
+test_all_backends!(deneb_chain_with_blobs_across_skip_slots);
+async fn deneb_chain_with_blobs_across_skip_slots(backend: Backend) {
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let builder = TesterBuilder::new_with_spec(Some(spec), backend).await;
+
+    let docker = Cli::default();
+    let _node = builder.start_database(&docker);
+
+    let pool = builder.initialize_database().await;
+    let mut tester = builder.build(pool).await;
+
+    tester
+        .extend_chain(8)
+        .await
+        .perform_head_update()
+        .await
+        .perform_backfill()
+        .await
+        .update_validator_set()
+        .await
+        .update_unknown_blocks()
+        .await
+        .fill_blobs()
+        .await
+        .assert_highest_block_has_blobs()
+        .await;
+
+    // Skip enough slots that the next update only covers slots without blocks.
+    for _ in 0..SLOTS_PER_EPOCH * 3 {
+        tester.skip_slot();
+    }
+
+    tester
+        .extend_chain(8)
+        .await
+        .perform_head_update()
+        .await
+        .update_unknown_blocks()
+        .await
+        // The first update covers the skipped slots and the second reaches the new blocks.
+        .fill_blobs()
+        .await
+        .fill_blobs()
+        .await
+        .assert_highest_block_has_blobs()
+        .await;
+}